@@ -1,12 +1,6 @@
 use std::collections::HashMap;
 
-use ncollide2d::pipeline::CollisionGroups;
-use ncollide2d::shape::ShapeHandle;
-use nphysics2d::object::{
-    BodyPartHandle, BodyStatus, ColliderDesc, DefaultBodyHandle, DefaultColliderHandle,
-    RigidBodyDesc,
-};
-use rustarok_common::common::{v2, EngineTime, Local, Mat4, Remote, Vec2};
+use rustarok_common::common::{EngineTime, Local, Mat4, Remote, Vec2};
 use serde::Deserialize;
 use serde::Serialize;
 use specs::prelude::*;
@@ -20,18 +14,15 @@ use crate::components::status::status::Statuses;
 use crate::grf::SpriteResource;
 use crate::render::render_command::RenderCommandCollector;
 use crate::runtime_assets::ecs::create_ecs_world;
-use crate::runtime_assets::map::PhysicEngine;
 use crate::systems::{Sprites, SystemVariables};
 use crate::GameTime;
-use rand::Rng;
 use rustarok_common::attack::{BasicAttackType, WeaponType};
 use rustarok_common::char_attr::{BonusDurations, CharAttributes, CharAttributesBonuses};
 use rustarok_common::components::char::{
-    create_common_player_entity, CharDir, CharOutlook, CharState, CharType, CollisionGroup,
-    ControllerEntityId, EntityId, EntityTarget, JobId, LocalCharStateComp, MonsterId, Sex,
-    StaticCharDataComponent, Team,
+    create_common_player_entity, CharDir, CharOutlook, CharState, CharType, ControllerEntityId,
+    EntityId, EntityTarget, JobId, LocalCharStateComp, MonsterId, Sex, StaticCharDataComponent,
+    Team,
 };
-use rustarok_common::components::job_ids::JobSpriteId;
 use rustarok_common::config::CommonConfigs;
 
 #[derive(Component, Debug)]
@@ -66,118 +57,6 @@ pub enum MonsterActionIndex {
     Die = 32,
 }
 
-pub struct CharPhysicsEntityBuilder<'a> {
-    pos2d: Vec2,
-    self_group: CollisionGroup,
-    collider_shape: ShapeHandle<f32>,
-    blacklist_groups: &'a [CollisionGroup],
-    body_status: BodyStatus,
-}
-
-impl<'a> CharPhysicsEntityBuilder<'a> {
-    pub fn new(pos2d: Vec2) -> CharPhysicsEntityBuilder<'a> {
-        CharPhysicsEntityBuilder {
-            pos2d,
-            self_group: CollisionGroup::StaticModel,
-            collider_shape: ShapeHandle::new(ncollide2d::shape::Ball::new(1.0)),
-            blacklist_groups: &[],
-            body_status: BodyStatus::Dynamic,
-        }
-    }
-
-    pub fn collision_group(mut self, self_group: CollisionGroup) -> CharPhysicsEntityBuilder<'a> {
-        self.self_group = self_group;
-        self.blacklist_groups = match self_group {
-            CollisionGroup::Guard => &[
-                CollisionGroup::Minion,
-                CollisionGroup::NonCollidablePlayer,
-                CollisionGroup::StaticModel,
-                CollisionGroup::LeftPlayer,
-                CollisionGroup::RightPlayer,
-                CollisionGroup::Guard,
-                CollisionGroup::SkillArea,
-                CollisionGroup::Turret,
-                CollisionGroup::NeutralPlayerPlayer,
-                CollisionGroup::LeftBarricade,
-                CollisionGroup::RightBarricade,
-            ],
-            CollisionGroup::StaticModel => panic!(),
-            CollisionGroup::LeftPlayer | CollisionGroup::RightPlayer => &[
-                CollisionGroup::Minion,
-                CollisionGroup::NonCollidablePlayer,
-                CollisionGroup::Guard,
-                CollisionGroup::Turret,
-            ],
-            CollisionGroup::NonCollidablePlayer => &[
-                CollisionGroup::Minion,
-                CollisionGroup::NonCollidablePlayer,
-                CollisionGroup::StaticModel,
-                CollisionGroup::LeftPlayer,
-                CollisionGroup::RightPlayer,
-                CollisionGroup::Guard,
-                CollisionGroup::Turret,
-                CollisionGroup::NeutralPlayerPlayer,
-            ],
-            CollisionGroup::Minion => &[
-                CollisionGroup::LeftPlayer,
-                CollisionGroup::RightPlayer,
-                CollisionGroup::StaticModel,
-                CollisionGroup::NonCollidablePlayer,
-                CollisionGroup::Turret,
-                CollisionGroup::NeutralPlayerPlayer,
-            ],
-            CollisionGroup::SkillArea => panic!(),
-            CollisionGroup::Turret => &[
-                CollisionGroup::Minion,
-                CollisionGroup::NonCollidablePlayer,
-                CollisionGroup::StaticModel,
-                CollisionGroup::LeftPlayer,
-                CollisionGroup::RightPlayer,
-                CollisionGroup::Guard,
-                CollisionGroup::Turret,
-                CollisionGroup::NeutralPlayerPlayer,
-            ],
-            CollisionGroup::NeutralPlayerPlayer => &[
-                CollisionGroup::Minion,
-                CollisionGroup::NonCollidablePlayer,
-                CollisionGroup::Guard,
-                CollisionGroup::Turret,
-            ],
-            CollisionGroup::LeftBarricade => &[
-                CollisionGroup::LeftPlayer,
-                CollisionGroup::Minion,
-                CollisionGroup::NonCollidablePlayer,
-                CollisionGroup::Guard,
-                CollisionGroup::Turret,
-            ],
-            CollisionGroup::RightBarricade => &[
-                CollisionGroup::RightPlayer,
-                CollisionGroup::Minion,
-                CollisionGroup::NonCollidablePlayer,
-                CollisionGroup::Guard,
-                CollisionGroup::Turret,
-            ],
-        };
-        self
-    }
-
-    pub fn body_status(mut self, body_status: BodyStatus) -> CharPhysicsEntityBuilder<'a> {
-        self.body_status = body_status;
-        self
-    }
-
-    pub fn circle(mut self, radius: f32) -> CharPhysicsEntityBuilder<'a> {
-        self.collider_shape = ShapeHandle::new(ncollide2d::shape::Ball::new(radius));
-        self
-    }
-
-    pub fn rectangle(mut self, w: f32, h: f32) -> CharPhysicsEntityBuilder<'a> {
-        self.collider_shape =
-            ShapeHandle::new(ncollide2d::shape::Cuboid::new(v2(w / 2.0, h / 2.0)));
-        self
-    }
-}
-
 pub fn create_client_entity(
     world: &mut specs::World,
     name: String,
@@ -329,71 +208,9 @@ pub fn create_client_guard_entity(
     );
 }
 
-pub fn create_client_minion_entity(
-    world: &mut specs::World,
-    pos: Vec2,
-    team: Team,
-) -> EntityId<Local> {
-    let mut rng = rand::thread_rng();
-    let sex = if rng.gen::<usize>() % 2 == 0 {
-        Sex::Male
-    } else {
-        Sex::Female
-    };
-
-    let (job_id, job_sprite_id) = if rng.gen::<usize>() % 2 == 0 {
-        (JobId::SWORDMAN, JobSpriteId::SWORDMAN)
-    } else {
-        (JobId::ARCHER, JobSpriteId::ARCHER)
-    };
-    let head_index = {
-        let head_count = world
-            .read_resource::<SystemVariables>()
-            .assets
-            .sprites
-            .head_sprites[Sex::Male as usize]
-            .len();
-        rng.gen::<usize>() % head_count
-    };
-    let outlook = CharOutlook::Human {
-        job_sprite_id,
-        head_index,
-        sex,
-    };
-
-    let base_attrs =
-        CharAttributes::get_base_attributes(job_id, &world.read_resource::<CommonConfigs>())
-            .clone();
-    let builder = create_common_player_entity(
-        "Minion".to_owned(),
-        world,
-        CharType::Minion,
-        job_id,
-        pos,
-        team,
-        outlook.clone(),
-    );
-
-    return EntityId::from(
-        builder
-            .with(SpriteRenderDescriptorComponent::new())
-            .with(NpcComponent)
-            .with(CharacterStateComponent::new(
-                0.0,
-                CharType::Minion,
-                outlook.clone(),
-                job_id,
-                team,
-                base_attrs,
-            ))
-            .build(),
-    );
-}
-
 pub struct CharacterEntityBuilder {
     char_id: EntityId<Local>,
     name: String,
-    pub physics_handles: Option<(DefaultColliderHandle, DefaultBodyHandle)>,
 }
 
 impl CharacterEntityBuilder {
@@ -401,7 +218,6 @@ impl CharacterEntityBuilder {
         CharacterEntityBuilder {
             char_id,
             name: name.to_owned(),
-            physics_handles: None,
         }
     }
 
@@ -415,26 +231,6 @@ impl CharacterEntityBuilder {
 #[derive(Eq, PartialEq, Hash)]
 pub struct ComponentRadius(pub i32);
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct CastingSkillData {
-    pub target_area_pos: Option<Vec2>,
-    pub char_to_skill_dir_when_casted: Vec2,
-    pub target_entity: Option<EntityId<Local>>,
-    pub cast_started: GameTime<Local>,
-    pub cast_ends: GameTime<Local>,
-    pub can_move: bool,
-    pub skill: Skills,
-}
-
-#[derive(Clone, Debug, PartialEq)]
-pub enum ClientCharState {
-    CastingSkill(CastingSkillData),
-}
-
-unsafe impl Sync for ClientCharState {}
-
-unsafe impl Send for ClientCharState {}
-
 pub fn get_sprite_index(state: &CharState<Local>, is_monster: bool) -> usize {
     // TODO2
     match (state, is_monster) {
@@ -444,7 +240,7 @@ pub fn get_sprite_index(state: &CharState<Local>, is_monster: bool) -> usize {
         (CharState::Attacking { .. }, false) => CharActionIndex::Attacking3 as usize,
         (CharState::ReceivingDamage, false) => CharActionIndex::ReceivingDamage as usize,
         (CharState::Dead, false) => CharActionIndex::Dead as usize,
-        //        (CharState::CastingSkill { .. }, false) => CharActionIndex::CastingSpell as usize,
+        (CharState::CastingSkill { .. }, false) => CharActionIndex::CastingSpell as usize,
 
         // monster
         (CharState::Idle, true) => MonsterActionIndex::Idle as usize,
@@ -453,7 +249,7 @@ pub fn get_sprite_index(state: &CharState<Local>, is_monster: bool) -> usize {
         (CharState::Attacking { .. }, true) => MonsterActionIndex::Attack as usize,
         (CharState::ReceivingDamage, true) => MonsterActionIndex::ReceivingDamage as usize,
         (CharState::Dead, true) => MonsterActionIndex::Die as usize,
-        //        (CharState::CastingSkill { .. }, true) => MonsterActionIndex::Attack as usize,
+        (CharState::CastingSkill { .. }, true) => MonsterActionIndex::Attack as usize,
     }
 }
 
@@ -500,18 +296,6 @@ pub fn get_sprite_and_action_index<'a>(
     };
 }
 
-pub struct TurretControllerComponent;
-
-impl Component for TurretControllerComponent {
-    type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
-}
-
-#[derive(Component)]
-pub struct TurretComponent {
-    pub owner_entity_id: EntityId<Local>,
-    pub preferred_target: Option<EntityId<Local>>,
-}
-
 pub struct NpcComponent;
 
 impl Component for NpcComponent {
@@ -526,37 +310,9 @@ pub struct CharacterStateComponent {
     prev_state: CharState<Local>,
     // TODO: the whole Statuses struct needs for simulation but not for state representation. Extract the array from it for serialization
     pub statuses: Statuses,
-    pub body_handle: DefaultBodyHandle,
-    pub collider_handle: DefaultColliderHandle,
 }
 
 impl CharacterStateComponent {
-    pub fn set_noncollidable(&self, physics_world: &mut PhysicEngine) {
-        // TODO2
-        //        if let Some(collider) = physics_world.colliders.get_mut(self.collider_handle) {
-        //            let mut cg = collider.collision_groups().clone();
-        //            cg.modify_membership(self.team.get_collision_group() as usize, false);
-        //            cg.modify_membership(CollisionGroup::NonCollidablePlayer as usize, true);
-        //            collider.set_collision_groups(cg);
-        //        }
-        //        if let Some(body) = physics_world.bodies.get_mut(self.body_handle) {
-        //            body.set_status(BodyStatus::Kinematic);
-        //        }
-    }
-
-    pub fn set_collidable(&self, physics_world: &mut PhysicEngine) {
-        // TODO2
-        //        if let Some(collider) = physics_world.colliders.get_mut(self.collider_handle) {
-        //            let mut cg = collider.collision_groups().clone();
-        //            cg.modify_membership(self.team.get_collision_group() as usize, true);
-        //            cg.modify_membership(CollisionGroup::NonCollidablePlayer as usize, false);
-        //            collider.set_collision_groups(cg);
-        //        }
-        //        if let Some(body) = physics_world.bodies.get_mut(self.body_handle) {
-        //            body.set_status(BodyStatus::Dynamic);
-        //        }
-    }
-
     pub fn new(
         y: f32,
         char_type: CharType,
@@ -570,9 +326,6 @@ impl CharacterStateComponent {
             y,
             prev_state: CharState::Idle,
             statuses,
-            // hack, remove these
-            body_handle: DefaultBodyHandle::from_raw_parts(1, 2),
-            collider_handle: DefaultBodyHandle::from_raw_parts(1, 2),
         }
     }
 
@@ -600,7 +353,6 @@ impl CharacterStateComponent {
         time: &EngineTime,
         entities: &Entities,
         updater: &mut LazyUpdate,
-        phyisics_world: &mut PhysicEngine,
         dev_configs: &CommonConfigs,
     ) {
         // TODO: refactor this
@@ -610,7 +362,6 @@ impl CharacterStateComponent {
         //        let bit_indices_of_changed_statuses = mut_statuses.update(
         //            self_char_id,
         //            self,
-        //            phyisics_world,
         //            sys_vars,
         //            time,
         //            entities,
@@ -654,8 +405,7 @@ impl CharacterStateComponent {
     pub fn went_from_casting_to_idle(&self, current_state: &CharState<Local>) -> bool {
         match current_state {
             CharState::Idle => match self.prev_state {
-                // TODO2
-                //                CharState::CastingSkill(_) => true,
+                CharState::CastingSkill(_) => true,
                 _ => false,
             },
             _ => false,
@@ -6,12 +6,16 @@ use crate::GameTime;
 use rustarok_common::common::{v2, v3, Local, Mat3, Mat4, Vec2, Vec2u};
 use rustarok_common::components::char::{CharDir, ControllerEntityId, EntityId, Team};
 use rustarok_common::components::controller::{ControllerComponent, PlayerIntention};
+use rustarok_common::config::JobDefinition;
 use sdl2::keyboard::Scancode;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
 use strum_macros::Display;
 use strum_macros::EnumCount;
 use strum_macros::EnumIter;
+use strum_macros::EnumString;
 
 #[derive(Default, Copy, Clone)]
 pub struct KeyState {
@@ -36,7 +40,7 @@ impl KeyState {
     }
 }
 
-#[derive(PartialEq, Eq, Copy, Clone, EnumIter, Display, Hash, EnumCount)]
+#[derive(PartialEq, Eq, Copy, Clone, EnumIter, EnumString, Display, Hash, EnumCount)]
 pub enum SkillKey {
     A,
     Q,
@@ -304,6 +308,21 @@ impl HumanInputComponent {
         self.skills_for_keys[skill_key as usize] = Some(skill);
     }
 
+    /// The keys which are not used by the job are cleared, except attack move and mounting
+    pub fn assign_job_skills(&mut self, job: &JobDefinition) {
+        for skill_key in SkillKey::iter() {
+            if skill_key != SkillKey::A && skill_key != SkillKey::Y {
+                self.skills_for_keys[skill_key as usize] = None;
+            }
+        }
+        for job_skill in &job.skills {
+            match SkillKey::from_str(&job_skill.key) {
+                Ok(skill_key) => self.assign_skill(skill_key, job_skill.skill),
+                Err(_) => log::warn!("{}: '{}' is not a skill key", job.job_id, job_skill.key),
+            }
+        }
+    }
+
     pub fn mouse_pos(&self) -> Vec2u {
         Vec2u::new(self.last_mouse_x, self.last_mouse_y)
     }
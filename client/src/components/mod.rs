@@ -1,5 +1,4 @@
 use nalgebra::Isometry2;
-use nphysics2d::object::DefaultBodyHandle;
 use specs::prelude::*;
 
 use crate::audio::sound_sys::SoundId;
@@ -42,11 +41,6 @@ pub struct StrEffectComponent {
     pub play_mode: ActionPlayMode,
 }
 
-#[derive(Component)]
-pub struct MinionComponent {
-    pub fountain_up: bool,
-}
-
 pub enum FlyingNumberType {
     Damage,
     Combo {
@@ -1,21 +1,15 @@
 use crate::components::char::ActionPlayMode;
-use crate::components::skills::skills::{
-    FinishCast, SkillDef, SkillManifestation, SkillTargetType,
-};
-use crate::components::status::status::{
-    ApplyStatusComponent, StatusEnum, StatusStackingResult, StatusUpdateResult,
-};
+use crate::components::skills::skills::{SkillDef, SkillTargetType};
+use crate::components::status::status::{StatusEnum, StatusStackingResult, StatusUpdateResult};
 use crate::effect::StrEffectType;
 use crate::render::render_command::RenderCommandCollector;
 use crate::render::render_sys::RenderDesktopClientSystem;
-use crate::systems::{AssetResources, SystemVariables};
+use crate::systems::AssetResources;
 use rustarok_common::attack::{
     HpModificationRequest, HpModificationResult, HpModificationResultType, HpModificationType,
 };
-use rustarok_common::common::{EngineTime, GameTime, Local, Vec2};
+use rustarok_common::common::{GameTime, Local, Vec2};
 use rustarok_common::components::char::EntityId;
-use rustarok_common::config::CommonConfigs;
-use specs::world::WorldExt;
 
 pub struct AbsorbShieldSkill;
 
@@ -26,32 +20,6 @@ impl SkillDef for AbsorbShieldSkill {
         "data\\texture\\À¯ÀúÀÎÅÍÆäÀÌ½º\\item\\cr_reflectshield.bmp"
     }
 
-    fn finish_cast(
-        &self,
-        params: &FinishCast,
-        ecs_world: &mut specs::world::World,
-    ) -> Option<Box<dyn SkillManifestation>> {
-        let mut sys_vars = ecs_world.write_resource::<SystemVariables>();
-        let now = ecs_world.read_resource::<EngineTime>().now();
-        let duration_seconds = ecs_world
-            .read_resource::<CommonConfigs>()
-            .skills
-            .absorb_shield
-            .duration_seconds;
-        sys_vars
-            .apply_statuses
-            .push(ApplyStatusComponent::from_status(
-                params.caster_entity_id,
-                params.target_entity.unwrap(),
-                StatusEnum::AbsorbStatus(AbsorbStatus::new(
-                    params.caster_entity_id,
-                    now,
-                    duration_seconds,
-                )),
-            ));
-        None
-    }
-
     fn get_skill_target_type(&self) -> SkillTargetType {
         SkillTargetType::OnlyAllyAndSelf
     }
@@ -1,26 +1,14 @@
-use nalgebra::{Isometry2, Vector2};
-
-use crate::components::char::{
-    ActionPlayMode, CharActionIndex, CharacterStateComponent, SpriteRenderDescriptorComponent,
-};
-use crate::components::skills::skills::{
-    FinishCast, SkillDef, SkillManifestation, SkillTargetType,
-};
-use crate::components::status::status::{
-    ApplyStatusComponent, StatusEnum, StatusUpdateParams, StatusUpdateResult,
-};
+use crate::components::char::{ActionPlayMode, CharActionIndex, SpriteRenderDescriptorComponent};
+use crate::components::skills::skills::{SkillDef, SkillTargetType};
+use crate::components::status::status::{StatusUpdateParams, StatusUpdateResult};
 use crate::render::render_command::RenderCommandCollector;
 use crate::render::render_sys::render_single_layer_action;
-use crate::systems::{AssetResources, SystemVariables};
-use rustarok_common::attack::{
-    AreaAttackComponent, DamageDisplayType, HpModificationType, WeaponType,
-};
-use rustarok_common::common::{v2, v2_to_v3, EngineTime, GameTime, Local, Vec2};
+use crate::systems::AssetResources;
+use rustarok_common::common::{v2_to_v3, GameTime, Local, Vec2};
 use rustarok_common::components::char::{
     CharDir, CharOutlook, EntityId, LocalCharStateComp, StaticCharDataComponent,
 };
-use rustarok_common::config::{AssaBladeDashSkillConfig, CommonConfigs};
-use specs::world::WorldExt;
+use rustarok_common::config::AssaBladeDashSkillConfig;
 
 pub struct AssaBladeDashSkill;
 
@@ -31,55 +19,6 @@ impl SkillDef for AssaBladeDashSkill {
         "data\\texture\\À¯ÀúÀÎÅÍÆäÀÌ½º\\item\\mer_incagi.bmp"
     }
 
-    fn finish_cast(
-        &self,
-        params: &FinishCast,
-        ecs_world: &mut specs::world::World,
-    ) -> Option<Box<dyn SkillManifestation>> {
-        if let Some(caster) = ecs_world
-            .write_storage::<LocalCharStateComp<Local>>()
-            .get_mut(params.caster_entity_id.into())
-        {
-            let angle = params.char_to_skill_dir.angle(&Vector2::y());
-            let angle = if params.char_to_skill_dir.x > 0.0 {
-                angle
-            } else {
-                -angle
-            };
-
-            let mut sys_vars = ecs_world.write_resource::<SystemVariables>();
-            let configs = ecs_world
-                .read_resource::<CommonConfigs>()
-                .skills
-                .assa_blade_dash
-                .clone();
-            let now = ecs_world.read_resource::<EngineTime>().now();
-            sys_vars
-                .apply_statuses
-                .push(ApplyStatusComponent::from_status(
-                    params.caster_entity_id,
-                    params.caster_entity_id,
-                    StatusEnum::AssaBladeDashStatus(AssaBladeDashStatus {
-                        caster_entity_id: params.caster_entity_id,
-                        started_at: now,
-                        ends_at: now.add_seconds(configs.duration_seconds),
-                        start_pos: caster.pos(),
-                        center: caster.pos()
-                            + params.char_to_skill_dir * (configs.attributes.casting_range / 2.0),
-                        rot_radian: angle,
-                        vector: params.char_to_skill_dir * configs.attributes.casting_range,
-                        shadow1_pos: Vector2::zeros(),
-                        shadow2_pos: Vector2::zeros(),
-                        forward_damage_done: false,
-                        backward_damage_done: false,
-                        half_duration: (configs.duration_seconds * 1000f32) as u32 / 2,
-                        configs,
-                    }),
-                ));
-        }
-        None
-    }
-
     fn get_skill_target_type(&self) -> SkillTargetType {
         SkillTargetType::Directional
     }
@@ -103,80 +42,7 @@ pub struct AssaBladeDashStatus {
 }
 
 impl AssaBladeDashStatus {
-    pub fn update(&mut self, params: StatusUpdateParams) -> StatusUpdateResult {
-        // TODO2 physics
-        //        if let Some(body) = params
-        //            .physics_world
-        //            .bodies
-        //            .rigid_body_mut(params.target_char.body_handle)
-        //        {
-        //            if self.ends_at.has_already_passed(params.time.now()) {
-        //                params.target_char.set_collidable(params.physics_world);
-        //                StatusUpdateResult::RemoveIt
-        //            } else {
-        //                let duration_percentage = params
-        //                    .time
-        //                    .now()
-        //                    .percentage_between(self.started_at, self.ends_at);
-        //                let pos = if duration_percentage < 0.5 {
-        //                    let forward_perc = duration_percentage * 2.0;
-        //                    self.shadow1_pos = self.start_pos + self.vector * (forward_perc - 0.1).max(0.0);
-        //                    self.shadow2_pos = self.start_pos + self.vector * (forward_perc - 0.2).max(0.0);
-        //                    self.start_pos + self.vector * forward_perc
-        //                } else {
-        //                    let backward_perc = (1.0 - duration_percentage) * 2.0;
-        //                    self.shadow1_pos =
-        //                        self.start_pos + self.vector * (backward_perc + 0.1).min(1.0);
-        //                    self.shadow2_pos =
-        //                        self.start_pos + self.vector * (backward_perc + 0.2).min(1.0);
-        //                    self.start_pos + self.vector * backward_perc
-        //                };
-        //                body.set_position(Isometry2::translation(pos.x, pos.y));
-        //
-        //                if !self.forward_damage_done && duration_percentage > 0.25 {
-        //                    params.area_hp_mod_requests.push(AreaAttackComponent {
-        //                        // TODO2
-        //                        //                            area_shape: Box::new(ncollide2d::shape::Cuboid::new(
-        //                        //                                v2(
-        //                        //                                    self.configs.attributes.width.unwrap_or(1.0),
-        //                        //                                    self.configs.attributes.casting_range,
-        //                        //                                ) / 2.0,
-        //                        //                            )),
-        //                        //                            area_isom: Isometry2::new(self.center, self.rot_radian),
-        //                        source_entity_id: self.caster_entity_id,
-        //                        typ: HpModificationType::BasicDamage(
-        //                            self.configs.first_damage,
-        //                            DamageDisplayType::SingleNumber,
-        //                            WeaponType::Sword,
-        //                        ),
-        //                        except: None,
-        //                    });
-        //                    self.forward_damage_done = true;
-        //                } else if !self.backward_damage_done && duration_percentage > 0.75 {
-        //                    params.area_hp_mod_requests.push(AreaAttackComponent {
-        //                        // TODO2
-        //                        //                            area_shape: Box::new(ncollide2d::shape::Cuboid::new(
-        //                        //                                v2(
-        //                        //                                    self.configs.attributes.width.unwrap_or(1.0),
-        //                        //                                    self.configs.attributes.casting_range,
-        //                        //                                ) / 2.0,
-        //                        //                            )),
-        //                        //                            area_isom: Isometry2::new(self.center, self.rot_radian),
-        //                        source_entity_id: self.caster_entity_id,
-        //                        typ: HpModificationType::BasicDamage(
-        //                            self.configs.second_damage,
-        //                            DamageDisplayType::SingleNumber,
-        //                            WeaponType::Sword,
-        //                        ),
-        //                        except: None,
-        //                    });
-        //                    self.backward_damage_done = true;
-        //                }
-        //                StatusUpdateResult::KeepIt
-        //            }
-        //        } else {
-        //            StatusUpdateResult::RemoveIt
-        //        }
+    pub fn update(&mut self, _params: StatusUpdateParams) -> StatusUpdateResult {
         StatusUpdateResult::RemoveIt
     }
 
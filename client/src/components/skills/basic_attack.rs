@@ -1,6 +1,4 @@
-use crate::components::char::{
-    ActionPlayMode, CharActionIndex, CharacterStateComponent, SpriteRenderDescriptorComponent,
-};
+use crate::components::char::{ActionPlayMode, CharActionIndex, SpriteRenderDescriptorComponent};
 use crate::components::skills::skills::{
     SkillManifestation, SkillManifestationComponent, SkillManifestationUpdateParam,
 };
@@ -9,103 +7,104 @@ use crate::audio::sound_sys::AudioCommandCollectorComponent;
 use crate::components::SoundEffectComponent;
 use crate::render::render_command::RenderCommandCollector;
 use crate::render::render_sys::render_single_layer_action;
-use crate::systems::{AssetResources, SystemVariables};
-use rustarok_common::common::{v2, v3, EngineTime, GameTime, Vec2};
-use rustarok_common::components::char::{CharDir, EntityId};
-use serde::Deserialize;
-use serde::Serialize;
+use crate::systems::AssetResources;
+use rustarok_common::attack::WeaponType;
+use rustarok_common::common::{v3, GameTime, Local, Vec2};
+use rustarok_common::components::char::{CharDir, StaticCharDataComponent};
+use rustarok_common::components::projectile::ProjectileComponent;
 use specs::ReadStorage;
 
-//impl SkillManifestation for BasicRangeAttackBullet {
-//    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
-//        let now = params.time().now();
-//        if params.time().simulation_frame == self.started_tick + 1 {
-//            match self.weapon_type {
-//                WeaponType::Arrow => {
-//                    params.create_entity_with_comp(SoundEffectComponent {
-//                        target_entity_id: self.caster_id,
-//                        sound_id: params.assets().sounds.arrow_attack,
-//                        pos: self.start_pos,
-//                        start_time: now,
-//                    });
-//                }
-//                WeaponType::SilverBullet => {
-//                    params.create_entity_with_comp(SoundEffectComponent {
-//                        target_entity_id: self.caster_id,
-//                        sound_id: params.assets().sounds.gun_attack,
-//                        pos: self.start_pos,
-//                        start_time: now,
-//                    });
-//                }
-//                WeaponType::Sword => {}
-//            }
-//        }
-//
-//        let travel_duration_percentage = params
-//            .time()
-//            .now()
-//            .percentage_between(self.started_at, self.ends_at);
-//        if travel_duration_percentage < 1.0 {
-//            if let Some(target) = params.auth_state_storage.get(self.target_id.into()) {
-//                let dir = target.pos() - self.start_pos;
-//                self.current_pos = self.start_pos + dir * travel_duration_percentage;
-//                self.target_pos = target.pos();
-//            }
-//        } else {
-//            let attack_dmg = params
-//                .char_storage
-//                .get(self.caster_id.into())
-//                .map(|caster| caster.calculated_attribs().attack_damage as u32);
-//            if let Some(attack_dmg) = attack_dmg {
-//                params.add_hp_mod_request(HpModificationRequest {
-//                    src_entity: self.caster_id,
-//                    dst_entity: self.target_id,
-//                    typ: HpModificationType::BasicDamage(
-//                        attack_dmg,
-//                        DamageDisplayType::SingleNumber,
-//                        self.weapon_type,
-//                    ),
-//                });
-//            }
-//            // TODO: return with KeepIt or RemoveMe
-//            params.remove_component::<SkillManifestationComponent>(params.self_entity_id);
-//        }
-//    }
-//
-//    fn render(
-//        &self,
-//        _char_entity_storage: &ReadStorage<CharacterStateComponent>,
-//        now: GameTime<Local>,
-//        _tick: u64,
-//        assets: &AssetResources,
-//        render_commands: &mut RenderCommandCollector,
-//        _audio_command_collector: &mut AudioCommandCollectorComponent,
-//    ) {
-//        let dir = CharDir::determine_dir(&self.target_pos, &self.start_pos);
-//        let anim = SpriteRenderDescriptorComponent {
-//            action_index: CharActionIndex::Idle as usize,
-//            animation_started: GameTime::from(0.0),
-//            animation_ends_at: GameTime::from(0.0),
-//            forced_duration: None,
-//            direction: dir,
-//            fps_multiplier: 1.0,
-//        };
-//        let (spr, scale) = match self.weapon_type {
-//            WeaponType::Arrow => (&assets.sprites.arrow, 1.0),
-//            WeaponType::SilverBullet => (&assets.sprites.ginseng_bullet, 0.25),
-//            WeaponType::Sword => panic!(),
-//        };
-//        render_single_layer_action(
-//            now,
-//            &anim,
-//            spr,
-//            &v3(self.current_pos.x, 2.0, self.current_pos.y),
-//            [0, 0],
-//            false,
-//            scale,
-//            ActionPlayMode::FixFrame(0),
-//            &[255, 255, 255, 255],
-//            render_commands,
-//        );
-//    }
-//}
+/// The copy of a projectile which is simulated by the server, it is only rendered,
+/// the damage arrives from the server
+pub struct BasicRangeAttackBullet {
+    projectile: ProjectileComponent,
+    start_pos: Vec2,
+    target_pos: Vec2,
+    sound_played: bool,
+}
+
+impl BasicRangeAttackBullet {
+    pub fn new(projectile: ProjectileComponent) -> BasicRangeAttackBullet {
+        BasicRangeAttackBullet {
+            start_pos: projectile.pos,
+            target_pos: projectile.pos,
+            projectile,
+            sound_played: false,
+        }
+    }
+}
+
+impl SkillManifestation for BasicRangeAttackBullet {
+    fn update(&mut self, params: SkillManifestationUpdateParam) {
+        let now = params.time().now();
+        if !self.sound_played {
+            self.sound_played = true;
+            let sound_id = match self.projectile.weapon_type {
+                WeaponType::Arrow => Some(params.assets().sounds.arrow_attack),
+                WeaponType::SilverBullet => Some(params.assets().sounds.gun_attack),
+                WeaponType::Sword => None,
+            };
+            if let Some(sound_id) = sound_id {
+                params.create_entity_with_comp(SoundEffectComponent {
+                    target_entity_id: self.projectile.caster_id,
+                    sound_id,
+                    pos: self.start_pos,
+                    start_time: now,
+                });
+            }
+        }
+
+        let target_pos = params
+            .auth_state_storage
+            .get(self.projectile.target_id.into())
+            .filter(|target| target.state().is_alive())
+            .map(|target| target.pos());
+        let finished = match target_pos {
+            Some(target_pos) => {
+                self.target_pos = target_pos;
+                self.projectile.fly_towards(target_pos, now)
+            }
+            None => true,
+        };
+        if finished {
+            params.remove_component::<SkillManifestationComponent>(params.self_entity_id);
+        }
+    }
+
+    fn render(
+        &self,
+        _char_entity_storage: &ReadStorage<StaticCharDataComponent>,
+        now: GameTime<Local>,
+        assets: &AssetResources,
+        render_commands: &mut RenderCommandCollector,
+        _audio_command_collector: &mut AudioCommandCollectorComponent,
+    ) {
+        let dir = CharDir::determine_dir(&self.target_pos, &self.start_pos);
+        let anim = SpriteRenderDescriptorComponent {
+            action_index: CharActionIndex::Idle as usize,
+            animation_started: GameTime::from(0.0),
+            animation_ends_at: GameTime::from(0.0),
+            forced_duration: None,
+            direction: dir,
+            fps_multiplier: 1.0,
+        };
+        let (spr, scale) = match self.projectile.weapon_type {
+            WeaponType::Arrow => (&assets.sprites.arrow, 1.0),
+            WeaponType::SilverBullet => (&assets.sprites.ginseng_bullet, 0.25),
+            WeaponType::Sword => return,
+        };
+        let pos = self.projectile.pos;
+        render_single_layer_action(
+            now,
+            &anim,
+            spr,
+            &v3(pos.x, 2.0, pos.y),
+            [0, 0],
+            false,
+            scale,
+            ActionPlayMode::FixFrame(0),
+            &[255, 255, 255, 255],
+            render_commands,
+        );
+    }
+}
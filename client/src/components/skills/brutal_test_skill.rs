@@ -1,21 +1,19 @@
-use nalgebra::{Isometry2, Vector2};
-use specs::{Entity, LazyUpdate, ReadStorage};
+use nalgebra::Vector2;
+use specs::{LazyUpdate, ReadStorage};
 
 use crate::audio::sound_sys::AudioCommandCollectorComponent;
-use crate::components::char::{ActionPlayMode, CharacterStateComponent};
+use crate::components::char::ActionPlayMode;
 use crate::components::skills::skills::{
-    FinishCast, SkillDef, SkillManifestation, SkillManifestationComponent,
-    SkillManifestationUpdateParam, SkillTargetType, Skills,
+    ClientSkillDefs, FinishCast, SkillDef, SkillManifestation, SkillTargetType, Skills,
 };
 use crate::components::StrEffectComponent;
 use crate::effect::StrEffectType;
 use crate::render::render_command::RenderCommandCollector;
-use crate::systems::{AssetResources, SystemVariables};
+use crate::systems::AssetResources;
 use crate::GameTime;
-use rustarok_common::attack::{AreaAttackComponent, DamageDisplayType, HpModificationType};
 use rustarok_common::common::{rotate_vec2, v2, Local};
 use rustarok_common::common::{EngineTime, Vec2};
-use rustarok_common::components::char::{EntityId, StaticCharDataComponent};
+use rustarok_common::components::char::StaticCharDataComponent;
 use rustarok_common::config::CommonConfigs;
 use specs::world::WorldExt;
 
@@ -42,14 +40,8 @@ impl SkillDef for BrutalTestSkill {
         let entities = &ecs_world.entities();
         let mut updater = ecs_world.write_resource::<LazyUpdate>();
         Some(Box::new(BrutalSkillManifest::new(
-            params.caster_entity_id,
             &params.skill_pos.unwrap(),
             angle_in_rad,
-            ecs_world
-                .read_resource::<CommonConfigs>()
-                .skills
-                .brutal_test_skill
-                .damage,
             ecs_world.read_resource::<EngineTime>().time,
             entities,
             &mut updater,
@@ -82,35 +74,26 @@ impl SkillDef for BrutalTestSkill {
 }
 
 pub struct BrutalSkillManifest {
-    pub caster_entity_id: EntityId<Local>,
-    pub effect_ids: Vec<Entity>,
     pub extents: Vec2,
-    pub half_extents: Vec2,
     pub pos: Vec2,
     pub rot_angle_in_rad: f32,
-    pub created_at: GameTime<Local>,
-    pub die_at: GameTime<Local>,
-    pub next_damage_at: GameTime<Local>,
-    pub damage: u32,
 }
 
 impl BrutalSkillManifest {
     pub fn new(
-        caster_entity_id: EntityId<Local>,
         skill_center: &Vec2,
         rot_angle_in_rad: f32,
-        damage: u32,
         system_time: GameTime<Local>,
         entities: &specs::Entities,
         updater: &mut LazyUpdate,
     ) -> BrutalSkillManifest {
-        let effect_ids = (0..11 * 11)
+        (0..11 * 11)
             .map(|i| {
                 let x = -5.0 + (i % 10) as f32;
                 let y = -5.0 + (i / 10) as f32;
                 skill_center + rotate_vec2(rot_angle_in_rad, &v2(x, y))
             })
-            .map(|effect_coords| {
+            .for_each(|effect_coords| {
                 let effect_comp = StrEffectComponent {
                     effect_id: StrEffectType::FireWall.into(),
                     pos: effect_coords,
@@ -118,49 +101,17 @@ impl BrutalSkillManifest {
                     die_at: Some(system_time.add_seconds(30.0)),
                     play_mode: ActionPlayMode::Repeat,
                 };
-                let effect_entity = entities.create();
-                updater.insert(effect_entity, effect_comp);
-                effect_entity
-            })
-            .collect();
+                updater.insert(entities.create(), effect_comp);
+            });
         BrutalSkillManifest {
-            caster_entity_id,
-            effect_ids,
             rot_angle_in_rad,
             pos: *skill_center,
             extents: v2(10.0, 10.0),
-            half_extents: v2(5.0, 5.0),
-            created_at: system_time.clone(),
-            die_at: system_time.add_seconds(30.0),
-            next_damage_at: system_time,
-            damage,
         }
     }
 }
 
 impl SkillManifestation for BrutalSkillManifest {
-    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
-        if self.die_at.has_already_passed(params.time().now()) {
-            params.remove_component::<SkillManifestationComponent>(params.self_entity_id);
-            for effect_id in &self.effect_ids {
-                params.remove_component::<StrEffectComponent>(*effect_id);
-            }
-        } else {
-            if self.next_damage_at.has_not_passed_yet(params.time().now()) {
-                return;
-            }
-            self.next_damage_at = params.time().now().add_seconds(0.5);
-            params.add_area_hp_mod_request(AreaAttackComponent {
-                // TODO2
-                //                area_shape: Box::new(ncollide2d::shape::Cuboid::new(self.half_extents)),
-                //                area_isom: Isometry2::new(self.pos, self.rot_angle_in_rad),
-                source_entity_id: self.caster_entity_id,
-                typ: HpModificationType::SpellDamage(600, DamageDisplayType::Combo(10)),
-                except: None,
-            });
-        }
-    }
-
     fn render(
         &self,
         _char_entity_storage: &ReadStorage<StaticCharDataComponent>,
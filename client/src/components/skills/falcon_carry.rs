@@ -1,13 +1,15 @@
-use crate::components::char::{CharacterStateComponent, SpriteRenderDescriptorComponent};
-use crate::components::controller::LocalPlayerController;
+use crate::components::char::SpriteRenderDescriptorComponent;
 use crate::components::skills::skills::{
     FinishCast, SkillDef, SkillManifestation, SkillTargetType,
 };
 use crate::components::status::status::{StatusUpdateParams, StatusUpdateResult};
 use crate::render::render_command::RenderCommandCollector;
 use crate::systems::falcon_ai_sys::FalconComponent;
-use crate::systems::{AssetResources, SystemVariables};
+use crate::systems::AssetResources;
 use rustarok_common::common::{EngineTime, GameTime, Local, Vec2};
+use rustarok_common::components::char::{ControllerEntityId, LocalCharStateComp};
+use rustarok_common::components::controller::ControllerComponent;
+use rustarok_common::config::CommonConfigs;
 use specs::prelude::*;
 
 pub struct FalconCarrySkill;
@@ -24,61 +26,56 @@ impl SkillDef for FalconCarrySkill {
         params: &FinishCast,
         ecs_world: &mut World,
     ) -> Option<Box<dyn SkillManifestation>> {
+        let now = ecs_world.read_resource::<EngineTime>().now();
+        let configs = &ecs_world
+            .read_resource::<CommonConfigs>()
+            .skills
+            .falcon_carry;
+        let target_entity = params.target_entity?;
+        let target_pos = ecs_world
+            .read_storage::<LocalCharStateComp<Local>>()
+            .get(target_entity.into())?
+            .pos();
+        let owner_controller_id = (
+            &ecs_world.entities(),
+            &ecs_world.read_storage::<ControllerComponent>(),
+        )
+            .join()
+            .find(|(_entity_id, controller)| {
+                controller.controlled_entity == Some(params.caster_entity_id)
+            })
+            .map(|(entity_id, _controller)| ControllerEntityId::from(entity_id));
+        for (falcon, sprite) in (
+            &mut ecs_world.write_storage::<FalconComponent>(),
+            &mut ecs_world.write_storage::<SpriteRenderDescriptorComponent>(),
+        )
+            .join()
+        {
+            if falcon.owner_entity_id != params.caster_entity_id {
+                continue;
+            }
+            if target_entity == params.caster_entity_id {
+                if let Some(owner_controller_id) = owner_controller_id {
+                    falcon.carry_owner(
+                        owner_controller_id,
+                        &target_pos,
+                        now,
+                        configs.carry_owner_duration,
+                        sprite,
+                    );
+                }
+            } else {
+                falcon.carry_ally(
+                    target_entity,
+                    &target_pos,
+                    now,
+                    configs.carry_ally_duration,
+                    sprite,
+                );
+            }
+            break;
+        }
         None
-        // TODO2
-        //        let now = ecs_world.read_resource::<EngineTime>().now();
-        //        let configs = &ecs_world.read_resource::<CommonConfigs>().skills.falcon_carry;
-        //        let target_entity = params.target_entity.unwrap();
-        //        let target_pos = {
-        //            let char_storage = ecs_world.read_storage::<CharacterStateComponent>();
-        //            if let Some(target) = char_storage.get(target_entity.into()) {
-        //                target.pos()
-        //            } else {
-        //                return None;
-        //            }
-        //        };
-        //        {
-        //            for (falcon, sprite) in (
-        //                &mut ecs_world.write_storage::<FalconComponent>(),
-        //                &mut ecs_world.write_storage::<SpriteRenderDescriptorComponent>(),
-        //            )
-        //                .join()
-        //            {
-        //                if falcon.owner_entity_id != params.caster_entity_id {
-        //                    continue;
-        //                }
-        //                if target_entity == params.caster_entity_id {
-        //                    // falcon.state = FalconState::CarryOwner
-        //                    for (entity_id, controller) in (
-        //                        &ecs_world.entities(),
-        //                        &ecs_world.read_storage::<LocalPlayerControllerComponent>(),
-        //                    )
-        //                        .join()
-        //                    {
-        //                        if controller.controlled_entity == falcon.owner_entity_id {
-        //                            falcon.carry_owner(
-        //                                ControllerEntityId(entity_id),
-        //                                &target_pos,
-        //                                now,
-        //                                configs.carry_owner_duration,
-        //                                sprite,
-        //                            );
-        //                            break;
-        //                        }
-        //                    }
-        //                } else {
-        //                    falcon.carry_ally(
-        //                        target_entity,
-        //                        &target_pos,
-        //                        now,
-        //                        configs.carry_ally_duration,
-        //                        sprite,
-        //                    );
-        //                };
-        //                break;
-        //            }
-        //        }
-        //        None
     }
 
     fn get_skill_target_type(&self) -> SkillTargetType {
@@ -97,8 +94,6 @@ pub struct FalconCarryStatus {
 impl FalconCarryStatus {
     pub fn update(&mut self, params: StatusUpdateParams) -> StatusUpdateResult {
         if self.ends_at.has_already_passed(params.time.now()) {
-            // TODO2 physics
-            //            params.target_char.set_collidable(params.physics_world);
             StatusUpdateResult::RemoveIt
         } else {
             StatusUpdateResult::KeepIt
@@ -1,15 +1,10 @@
-use crate::components::char::{CharacterStateComponent, NpcComponent};
-use crate::components::skills::skills::{
-    FinishCast, SkillDef, SkillManifestation, SkillTargetType, Skills,
-};
+use crate::components::skills::skills::{ClientSkillDefs, SkillDef, SkillTargetType, Skills};
 use crate::render::render_command::RenderCommandCollector;
 
 use nalgebra::Vector2;
 use rustarok_common::common::{v2, Vec2};
-use rustarok_common::components::char::EntityId;
 use rustarok_common::config::CommonConfigs;
 use specs::prelude::*;
-use specs::LazyUpdate;
 
 pub struct GazBarricadeSkill;
 
@@ -21,45 +16,6 @@ impl SkillDef for GazBarricadeSkill {
     }
 
     // TODO if the skill is rejected due to occupied tile, sp should not be lowered
-    fn finish_cast(
-        &self,
-        params: &FinishCast,
-        ecs_world: &mut World,
-    ) -> Option<Box<dyn SkillManifestation>> {
-        None
-        // TODO2
-        //        let entities = &ecs_world.entities();
-        //        let updater = &ecs_world.read_resource::<LazyUpdate>();
-        //        let char_entity_id =  EntityId::from(entities.create());
-        //        updater.insert(char_entity_id.into(), NpcComponent);
-        //        let tile_pos = {
-        //            let pos = params.skill_pos.unwrap();
-        //            Vec2::new((pos.x as i32) as f32, (pos.y as i32) as f32)
-        //        };
-        //
-        //        for char_state in (&mut ecs_world.read_storage::<CharacterStateComponent>()).join() {
-        //            if char_state.job_id != JobId::Barricade {
-        //                continue;
-        //            }
-        //            if char_state.pos().x as i32 == tile_pos.x as i32
-        //                && char_state.pos().y as i32 == tile_pos.y as i32
-        //            {
-        //                // tile is already occupied
-        //                return None;
-        //            }
-        //        }
-        //
-        //        SpawnEntitySystem::create_barricade(
-        //            entities,
-        //            &updater,
-        //            &mut ecs_world.write_resource(),
-        //            &ecs_world.read_resource(),
-        //            params.caster_team,
-        //            tile_pos,
-        //        );
-        //        return None;
-    }
-
     fn get_skill_target_type(&self) -> SkillTargetType {
         SkillTargetType::Area
     }
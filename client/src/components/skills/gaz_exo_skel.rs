@@ -1,19 +1,9 @@
-use crate::components::char::ActionPlayMode;
 use crate::components::char::CharacterStateComponent;
-use crate::components::skills::skills::{
-    FinishCast, SkillDef, SkillManifestation, SkillTargetType,
-};
-use crate::components::status::status::{
-    ApplyStatusComponent, StatusEnum, StatusUpdateParams, StatusUpdateResult,
-};
-use crate::components::StrEffectComponent;
-use crate::effect::StrEffectType;
-use crate::systems::SystemVariables;
+use crate::components::skills::skills::{SkillDef, SkillTargetType};
+use crate::components::status::status::{StatusUpdateParams, StatusUpdateResult};
 use rustarok_common::attack::BasicAttackType;
 use rustarok_common::char_attr::{CharAttributeModifier, CharAttributeModifierCollector};
-use rustarok_common::common::{EngineTime, GameTime, Local, Percentage};
-use rustarok_common::config::CommonConfigs;
-use specs::world::WorldExt;
+use rustarok_common::common::{GameTime, Local, Percentage};
 use specs::{Entities, LazyUpdate};
 
 pub struct ExoSkeletonSkill;
@@ -25,36 +15,6 @@ impl SkillDef for ExoSkeletonSkill {
         "data\\texture\\À¯ÀúÀÎÅÍÆäÀÌ½º\\item\\cr_reflectshield.bmp"
     }
 
-    fn finish_cast(
-        &self,
-        params: &FinishCast,
-        ecs_world: &mut specs::world::World,
-    ) -> Option<Box<dyn SkillManifestation>> {
-        let mut sys_vars = ecs_world.write_resource::<SystemVariables>();
-        let now = ecs_world.read_resource::<EngineTime>().now();
-        let configs = &ecs_world
-            .read_resource::<CommonConfigs>()
-            .skills
-            .exoskeleton;
-        let duration_seconds = configs.duration_seconds;
-        sys_vars
-            .apply_statuses
-            .push(ApplyStatusComponent::from_status(
-                params.caster_entity_id,
-                params.caster_entity_id,
-                StatusEnum::ExoSkeletonStatus(ExoSkeletonStatus::new(
-                    now,
-                    duration_seconds,
-                    configs.armor,
-                    configs.attack_range,
-                    configs.movement_speed,
-                    configs.attack_damage,
-                    configs.attack_speed,
-                )),
-            ));
-        None
-    }
-
     fn get_skill_target_type(&self) -> SkillTargetType {
         SkillTargetType::NoTarget
     }
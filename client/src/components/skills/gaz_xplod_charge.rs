@@ -1,25 +1,18 @@
-use nalgebra::{Isometry2, Vector3};
+use nalgebra::Vector3;
 
 use crate::audio::sound_sys::AudioCommandCollectorComponent;
-use crate::components::char::{
-    ActionPlayMode, CharActionIndex, CharacterStateComponent, SpriteRenderDescriptorComponent,
-};
+use crate::components::char::{ActionPlayMode, CharActionIndex, SpriteRenderDescriptorComponent};
 use crate::components::skills::skills::{
-    FinishCast, SkillDef, SkillManifestation, SkillManifestationComponent,
-    SkillManifestationUpdateParam, SkillTargetType,
+    FinishCast, SkillDef, SkillManifestation, SkillManifestationUpdateParam, SkillTargetType,
 };
-use crate::components::status::status::{ApplyStatusInAreaComponent, StatusEnum};
-use crate::components::status::stun::StunStatus;
 use crate::components::StrEffectComponent;
 use crate::effect::StrEffectType;
 use crate::render::render_command::RenderCommandCollector;
 use crate::render::render_sys::render_single_layer_action;
-use crate::runtime_assets::map::PhysicEngine;
-use crate::systems::{AssetResources, SystemVariables};
-use rustarok_common::attack::{AreaAttackComponent, DamageDisplayType, HpModificationType};
+use crate::systems::AssetResources;
 use rustarok_common::common::{v2_to_v3, v3_to_v2, EngineTime, GameTime, Local};
 use rustarok_common::common::{v3, Vec2};
-use rustarok_common::components::char::{CharDir, EntityId, StaticCharDataComponent, StatusNature};
+use rustarok_common::components::char::{CharDir, StaticCharDataComponent};
 use rustarok_common::config::{CommonConfigs, GazXplodiumChargeSkillConfigInner};
 use specs::world::WorldExt;
 use specs::ReadStorage;
@@ -40,10 +33,8 @@ impl SkillDef for GazXplodiumChargeSkill {
         ecs_world: &mut specs::world::World,
     ) -> Option<Box<dyn SkillManifestation>> {
         Some(Box::new(GazXplodiumChargeSkillManifestation::new(
-            params.caster_entity_id,
             params.caster_pos,
             params.skill_pos.unwrap(),
-            &mut ecs_world.write_resource::<PhysicEngine>(),
             ecs_world.read_resource::<EngineTime>().now(),
             ecs_world
                 .read_resource::<CommonConfigs>()
@@ -63,18 +54,16 @@ struct GazXplodiumChargeSkillManifestation {
     end_pos: Vec2,
     current_pos: Vector3<f32>,
     current_target_pos: Vector3<f32>,
-    caster_id: EntityId<Local>,
     started_at: GameTime<Local>,
     configs: GazXplodiumChargeSkillConfigInner,
     bezier: QuadraticBezier3<f32>,
+    exploded: bool,
 }
 
 impl GazXplodiumChargeSkillManifestation {
     fn new(
-        caster_id: EntityId<Local>,
         start_pos: Vec2,
         end_pos: Vec2,
-        _physics_world: &mut PhysicEngine,
         now: GameTime<Local>,
         configs: GazXplodiumChargeSkillConfigInner,
     ) -> GazXplodiumChargeSkillManifestation {
@@ -83,7 +72,6 @@ impl GazXplodiumChargeSkillManifestation {
             end_pos,
             current_pos: Vector3::new(start_pos.x, 1.0, start_pos.y),
             started_at: now,
-            caster_id,
             current_target_pos: v2_to_v3(&end_pos),
             configs,
             bezier: QuadraticBezier3 {
@@ -91,12 +79,13 @@ impl GazXplodiumChargeSkillManifestation {
                 ctrl: vek::Vec3::new(ctrl.x, ctrl.y, ctrl.z),
                 end: vek::Vec3::new(end_pos.x, 0.0, end_pos.y),
             },
+            exploded: false,
         }
     }
 }
 
 impl SkillManifestation for GazXplodiumChargeSkillManifestation {
-    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
+    fn update(&mut self, params: SkillManifestationUpdateParam) {
         let travel_duration_percentage = params.time().now().percentage_between(
             self.started_at,
             self.started_at
@@ -110,48 +99,15 @@ impl SkillManifestation for GazXplodiumChargeSkillManifestation {
                 .started_at
                 .add_seconds(self.configs.missile_travel_duration_seconds)
                 .add_seconds(self.configs.detonation_duration);
-            if end_time.has_already_passed(params.time().now()) {
-                if let Some(caster_team) = params
-                    .static_char_data_storage
-                    .get(self.caster_id.into())
-                    .map(|caster| caster.team)
-                {
-                    let area_shape =
-                        Box::new(ncollide2d::shape::Ball::new(self.configs.explosion_area));
-                    let area_isom = Isometry2::new(self.end_pos, 0.0);
-                    params.add_area_hp_mod_request(AreaAttackComponent {
-                        // TODO2
-                        //                        area_shape: area_shape.clone(),
-                        //                        area_isom: area_isom.clone(),
-                        source_entity_id: self.caster_id,
-                        typ: HpModificationType::SpellDamage(
-                            self.configs.damage,
-                            DamageDisplayType::SingleNumber,
-                        ),
-                        except: None,
-                    });
-                    params.apply_area_status(ApplyStatusInAreaComponent {
-                        source_entity_id: self.caster_id,
-                        status: StatusEnum::StunStatus(StunStatus::new(
-                            self.caster_id,
-                            params.time().now(),
-                            self.configs.stun_duration_seconds,
-                        )),
-                        area_shape,
-                        area_isom,
-                        except: None,
-                        nature: StatusNature::Harmful,
-                        caster_team,
-                    });
-                    params.create_entity_with_comp(StrEffectComponent {
-                        effect_id: StrEffectType::Explosion.into(),
-                        pos: self.end_pos,
-                        start_time: params.time().now(),
-                        die_at: None,
-                        play_mode: ActionPlayMode::Once,
-                    });
-                }
-                params.remove_component::<SkillManifestationComponent>(params.self_entity_id);
+            if !self.exploded && end_time.has_already_passed(params.time().now()) {
+                self.exploded = true;
+                params.create_entity_with_comp(StrEffectComponent {
+                    effect_id: StrEffectType::Explosion.into(),
+                    pos: self.end_pos,
+                    start_time: params.time().now(),
+                    die_at: None,
+                    play_mode: ActionPlayMode::Once,
+                });
             }
         }
     }
@@ -1,20 +1,16 @@
 use specs::{LazyUpdate, ReadStorage};
 
 use crate::components::skills::skills::{
-    FinishCast, SkillDef, SkillManifestation, SkillManifestationComponent,
-    SkillManifestationUpdateParam, SkillTargetType,
+    FinishCast, SkillDef, SkillManifestation, SkillTargetType,
 };
 
 use crate::audio::sound_sys::AudioCommandCollectorComponent;
-use crate::components::char::CharacterStateComponent;
 use crate::components::SoundEffectComponent;
 use crate::render::opengl_render_sys::Trimesh3dType;
 use crate::render::render_command::RenderCommandCollector;
 use crate::systems::{AssetResources, SystemVariables};
-use rustarok_common::attack::{HpModificationRequest, HpModificationType};
 use rustarok_common::common::{EngineTime, GameTime, Local};
 use rustarok_common::components::char::{EntityId, StaticCharDataComponent};
-use rustarok_common::config::CommonConfigs;
 use specs::world::WorldExt;
 
 pub struct HealSkill;
@@ -31,10 +27,10 @@ impl SkillDef for HealSkill {
         params: &FinishCast,
         ecs_world: &mut specs::world::World,
     ) -> Option<Box<dyn SkillManifestation>> {
-        let target_entity_id = params.target_entity.unwrap();
+        let target_entity_id = params.target_entity?;
         let entities = &ecs_world.entities();
         let updater = ecs_world.read_resource::<LazyUpdate>();
-        let mut sys_vars = ecs_world.write_resource::<SystemVariables>();
+        let sys_vars = ecs_world.read_resource::<SystemVariables>();
         let now = ecs_world.read_resource::<EngineTime>().now();
         let entity = entities.create();
         updater.insert(
@@ -46,16 +42,6 @@ impl SkillDef for HealSkill {
                 start_time: now,
             },
         );
-        // TODO: helper(&mut ecs_world).add_hp_mod()...
-        ecs_world
-            .write_resource::<Vec<HpModificationRequest>>()
-            .push(HpModificationRequest {
-                src_entity: params.caster_entity_id,
-                dst_entity: target_entity_id,
-                typ: HpModificationType::Heal(
-                    ecs_world.read_resource::<CommonConfigs>().skills.heal.heal,
-                ),
-            });
         return Some(Box::new(HealSkillManifest::new(target_entity_id, now)));
     }
 
@@ -86,12 +72,6 @@ impl HealSkillManifest {
 }
 
 impl SkillManifestation for HealSkillManifest {
-    fn update(&mut self, params: SkillManifestationUpdateParam) {
-        if self.die_at.has_already_passed(params.time().now()) {
-            params.remove_component::<SkillManifestationComponent>(params.self_entity_id);
-        }
-    }
-
     fn render(
         &self,
         char_entity_storage: &ReadStorage<StaticCharDataComponent>,
@@ -1,14 +1,9 @@
-use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use nalgebra::Vector2;
-use nphysics2d::object::DefaultColliderHandle;
-use serde::Deserialize;
-use serde::Serialize;
 use specs::prelude::*;
-use strum_macros::EnumIter;
 
-use crate::components::char::{ActionPlayMode, CastingSkillData, CharacterStateComponent};
+use crate::components::char::ActionPlayMode;
 use crate::components::skills::absorb_shield::ABSORB_SHIELD_SKILL;
 use crate::components::skills::brutal_test_skill::BRUTAL_TEST_SKILL;
 use crate::components::skills::cure::CURE_SKILL;
@@ -33,61 +28,43 @@ use crate::components::skills::gaz_turret::{
 };
 use crate::components::skills::gaz_xplod_charge::GAZ_XPLODIUM_CHARGE_SKILL;
 use crate::components::skills::sanctuary::SANCTUARY_SKILL;
-use crate::components::status::status::{ApplyStatusComponent, ApplyStatusInAreaComponent};
 use crate::effect::StrEffectType;
 use crate::render::render_command::RenderCommandCollector;
 use crate::render::render_sys::RenderDesktopClientSystem;
-use crate::systems::{AssetResources, Collision, SystemVariables};
-use crate::{GameTime, PhysicEngine};
-use rustarok_common::attack::{ApplyForceComponent, AreaAttackComponent, HpModificationRequest};
+use crate::systems::{AssetResources, SystemVariables};
+use crate::GameTime;
 use rustarok_common::components::char::{
-    EntityId, LocalCharStateComp, StaticCharDataComponent, Team,
+    CastingSkillData, LocalCharStateComp, StaticCharDataComponent,
 };
-use rustarok_common::config::{CommonConfigs, SkillCastingAttributes};
-
-pub type WorldCollisions = HashMap<(DefaultColliderHandle, DefaultColliderHandle), Collision>;
+pub use rustarok_common::components::skills::skills::{FinishCast, SkillTargetType, Skills};
+use rustarok_common::config::CommonConfigs;
 
 pub struct SkillManifestationUpdateParam<'a, 'longer> {
     pub self_entity_id: Entity,
-    pub all_collisions_in_world: &'longer WorldCollisions,
-    sys_vars: &'longer mut SystemVariables,
-    hp_mod_requests: &'longer mut Vec<HpModificationRequest>,
-    area_hp_mod_requests: &'longer mut Vec<AreaAttackComponent>,
-    pushes: &'longer mut Vec<ApplyForceComponent>,
+    sys_vars: &'longer SystemVariables,
     engine_time: &'longer EngineTime,
     entities: &'a Entities<'a>,
     pub static_char_data_storage: &'longer ReadStorage<'a, StaticCharDataComponent>,
-    pub auth_state_storage: &'longer mut WriteStorage<'a, LocalCharStateComp<Local>>,
-    pub physics_world: &'longer mut PhysicEngine,
-    updater: &'longer mut LazyUpdate,
+    pub auth_state_storage: &'longer ReadStorage<'a, LocalCharStateComp<Local>>,
+    updater: &'longer LazyUpdate,
 }
 
 impl<'a, 'longer> SkillManifestationUpdateParam<'a, 'longer> {
     pub fn new(
         self_entity_id: Entity,
-        all_collisions_in_world: &'longer WorldCollisions,
-        sys_vars: &'longer mut SystemVariables,
-        hp_mod_requests: &'longer mut Vec<HpModificationRequest>,
-        area_hp_mod_requests: &'longer mut Vec<AreaAttackComponent>,
-        pushes: &'longer mut Vec<ApplyForceComponent>,
+        sys_vars: &'longer SystemVariables,
         engine_time: &'longer EngineTime,
         entities: &'a Entities,
         static_char_data_storage: &'longer ReadStorage<'a, StaticCharDataComponent>,
-        auth_state_storage: &'longer mut WriteStorage<'a, LocalCharStateComp<Local>>,
-        physics_world: &'longer mut PhysicEngine,
-        updater: &'longer mut LazyUpdate,
+        auth_state_storage: &'longer ReadStorage<'a, LocalCharStateComp<Local>>,
+        updater: &'longer LazyUpdate,
     ) -> SkillManifestationUpdateParam<'a, 'longer> {
         SkillManifestationUpdateParam {
             self_entity_id,
-            hp_mod_requests,
-            area_hp_mod_requests,
-            pushes,
-            all_collisions_in_world,
             sys_vars,
             engine_time,
             entities,
-            static_char_data_storage: static_char_data_storage,
-            physics_world,
+            static_char_data_storage,
             updater,
             auth_state_storage,
         }
@@ -121,30 +98,12 @@ impl<'a, 'longer> SkillManifestationUpdateParam<'a, 'longer> {
     pub fn assets(&self) -> &AssetResources {
         &self.sys_vars.assets
     }
-
-    pub fn apply_status(&mut self, apply_status_comp: ApplyStatusComponent) {
-        self.sys_vars.apply_statuses.push(apply_status_comp);
-    }
-
-    pub fn apply_area_status(&mut self, apply_status_comp: ApplyStatusInAreaComponent) {
-        self.sys_vars.apply_area_statuses.push(apply_status_comp);
-    }
-
-    pub fn add_hp_mod_request(&mut self, hp_mod_req: HpModificationRequest) {
-        self.hp_mod_requests.push(hp_mod_req);
-    }
-
-    pub fn add_area_hp_mod_request(&mut self, hp_mod_req: AreaAttackComponent) {
-        self.area_hp_mod_requests.push(hp_mod_req);
-    }
-
-    pub fn apply_force(&mut self, force: ApplyForceComponent) {
-        self.pushes.push(force);
-    }
 }
 
-pub trait SkillManifestation {
-    fn update(&mut self, params: SkillManifestationUpdateParam);
+/// The visual half of a replicated skill manifestation. The server simulates it and tells
+/// the client when it appears and ends, `update` can only animate the visuals in between.
+pub trait SkillManifestation: Send + Sync {
+    fn update(&mut self, _params: SkillManifestationUpdateParam) {}
 
     fn render(
         &self,
@@ -156,8 +115,8 @@ pub trait SkillManifestation {
     );
 }
 
-#[storage(HashMapStorage)]
 #[derive(Component)]
+#[storage(HashMapStorage)]
 pub struct SkillManifestationComponent {
     pub self_entity_id: Entity,
     pub skill: Arc<Mutex<Box<dyn SkillManifestation>>>,
@@ -198,33 +157,23 @@ impl SkillManifestationComponent {
     }
 }
 
-unsafe impl Sync for SkillManifestationComponent {}
-
-unsafe impl Send for SkillManifestationComponent {}
-
-pub struct FinishCast {
-    pub skill: Skills,
-    pub caster_entity_id: EntityId<Local>,
-    pub caster_pos: Vec2,
-    pub caster_team: Team,
-    pub skill_pos: Option<Vec2>,
-    pub char_to_skill_dir: Vec2,
-    pub target_entity: Option<EntityId<Local>>,
-}
-
 pub trait SkillDef {
     fn get_icon_path(&self) -> &'static str;
+    /// Called when the server replicates a manifestation of this skill, skills without
+    /// a visible manifestation don't have to override it.
     fn finish_cast(
         &self,
-        params: &FinishCast,
-        ecs_world: &mut World,
-    ) -> Option<Box<dyn SkillManifestation>>;
+        _params: &FinishCast,
+        _ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        None
+    }
 
     fn get_skill_target_type(&self) -> SkillTargetType;
     fn render_casting(
         &self,
         char_pos: &Vec2,
-        casting_state: &CastingSkillData,
+        casting_state: &CastingSkillData<Local>,
         assets: &AssetResources,
         time: &EngineTime,
         dev_configs: &CommonConfigs,
@@ -269,32 +218,6 @@ pub trait SkillDef {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, EnumIter, Serialize, Deserialize)]
-pub enum Skills {
-    AttackMove,
-    FireWall,
-    BrutalTestSkill,
-    Lightning,
-    Heal,
-    Mounting,
-    Poison,
-    Cure,
-    FireBomb,
-    AbsorbShield,
-    WizPyroBlast,
-    AssaBladeDash,
-    AssaPhasePrism,
-    GazXplodiumCharge,
-    GazTurret,
-    GazBarricade,
-    GazDestroyTurret,
-    GazTurretTarget,
-    FalconCarry,
-    FalconAttack,
-    Sanctuary,
-    ExoSkeleton,
-}
-
 pub struct AttackMoveSkill;
 
 pub const ATTACK_MOVE_SKILL: &'static AttackMoveSkill = &AttackMoveSkill;
@@ -304,21 +227,27 @@ impl SkillDef for AttackMoveSkill {
         ""
     }
 
-    fn finish_cast(
-        &self,
-        _params: &FinishCast,
-        _ecs_world: &mut World,
-    ) -> Option<Box<dyn SkillManifestation>> {
-        None
-    }
-
     fn get_skill_target_type(&self) -> SkillTargetType {
         SkillTargetType::Area
     }
 }
 
-impl Skills {
-    pub fn get_definition(&self) -> &'static dyn SkillDef {
+/// The client side (rendering) definitions of the skills, the simulation part lives in
+/// rustarok_common::components::skills
+pub trait ClientSkillDefs {
+    fn get_client_definition(&self) -> &'static dyn SkillDef;
+
+    fn render_casting_box(
+        is_castable: bool,
+        casting_area_size: &Vec2,
+        skill_pos: &Vec2,
+        char_to_skill_dir: &Vec2,
+        render_commands: &mut RenderCommandCollector,
+    );
+}
+
+impl ClientSkillDefs for Skills {
+    fn get_client_definition(&self) -> &'static dyn SkillDef {
         match self {
             Skills::WizPyroBlast => WIZ_PYRO_BLAST_SKILL,
             Skills::FireWall => FIRE_WALL_SKILL,
@@ -345,66 +274,7 @@ impl Skills {
         }
     }
 
-    pub fn get_cast_attributes<'a>(
-        &'a self,
-        configs: &'a CommonConfigs,
-        char_state: &StaticCharDataComponent,
-    ) -> &'a SkillCastingAttributes {
-        match self {
-            Skills::WizPyroBlast => &configs.skills.wiz_pyroblast.attributes,
-            Skills::FireWall => &configs.skills.firewall.attributes,
-            Skills::Heal => &configs.skills.heal.attributes,
-            Skills::BrutalTestSkill => &configs.skills.brutal_test_skill.attributes,
-            Skills::Lightning => &configs.skills.lightning.attributes,
-            Skills::Mounting => {
-                // TODO2 statuses
-                //                if char_state.statuses.is_mounted() {
-                &configs.skills.unmounting
-                //                } else {
-                //                    &configs.skills.mounting
-                //                }
-            }
-            Skills::Poison => &configs.skills.poison.attributes,
-            Skills::Cure => &configs.skills.cure,
-            Skills::FireBomb => &configs.skills.firebomb.attributes,
-            Skills::AbsorbShield => &configs.skills.absorb_shield.attributes,
-            Skills::AssaBladeDash => &configs.skills.assa_blade_dash.attributes,
-            Skills::AssaPhasePrism => &configs.skills.assa_phase_prism.attributes,
-            Skills::GazXplodiumCharge => &configs.skills.gaz_xplodium_charge.attributes,
-            Skills::GazTurret => &configs.skills.gaz_turret.attributes,
-            Skills::GazDestroyTurret => &configs.skills.gaz_destroy_turret,
-            Skills::GazTurretTarget => &configs.skills.gaz_destroy_turret,
-            // TODO
-            //            Skills::GazTurretTarget => &SkillCastingAttributes {
-            //                casting_time: GameTime::from(0.0),
-            //                cast_delay: GameTime::from(0.0),
-            //                casting_range: 999_999_999.0,
-            //                width: None,
-            //            },
-            Skills::FalconCarry => &configs.skills.falcon_carry.attributes,
-            Skills::FalconAttack => &configs.skills.falcon_attack.attributes,
-            Skills::Sanctuary => &configs.skills.sanctuary.attributes,
-            Skills::ExoSkeleton => &configs.skills.exoskeleton.attributes,
-            Skills::AttackMove => &configs.skills.gaz_destroy_turret,
-            // TODO
-            //            Skills::AttackMove => &SkillCastingAttributes {
-            //                casting_time: GameTime::from(0.0),
-            //                cast_delay: GameTime::from(0.0),
-            //                casting_range: 200_000_000.0,
-            //                width: None,
-            //            },
-            Skills::GazBarricade => &configs.skills.gaz_barricade.attributes,
-        }
-    }
-
-    pub fn limit_vector_into_range(char_pos: &Vec2, mouse_pos: &Vec2, range: f32) -> (Vec2, Vec2) {
-        let dir2d = mouse_pos - char_pos;
-        let dir_vector = dir2d.normalize();
-        let pos = char_pos + dir_vector * dir2d.magnitude().min(range);
-        return (pos, dir_vector);
-    }
-
-    pub fn render_casting_box(
+    fn render_casting_box(
         is_castable: bool,
         casting_area_size: &Vec2,
         skill_pos: &Vec2,
@@ -433,44 +303,4 @@ impl Skills {
             .size(casting_area_size.x, casting_area_size.y)
             .add()
     }
-
-    pub fn is_casting_allowed_based_on_target(
-        skill_target_type: SkillTargetType,
-        skill_casting_range: f32,
-        caster_id: EntityId<Local>,
-        target_entity: Option<EntityId<Local>>,
-        target_distance: f32,
-    ) -> bool {
-        match skill_target_type {
-            SkillTargetType::Area => true,
-            SkillTargetType::Directional => true,
-            SkillTargetType::NoTarget => true,
-            SkillTargetType::AnyEntity => {
-                target_entity.is_some() && skill_casting_range >= target_distance
-            }
-            SkillTargetType::OnlyAllyButNoSelf => {
-                target_entity.map(|it| it != caster_id).unwrap_or(false)
-                    && skill_casting_range >= target_distance
-            }
-            SkillTargetType::OnlyAllyAndSelf => {
-                target_entity.is_some() && skill_casting_range >= target_distance
-            }
-            SkillTargetType::OnlyEnemy => {
-                target_entity.is_some() && skill_casting_range >= target_distance
-            }
-        }
-    }
-}
-
-#[derive(Eq, PartialEq)]
-#[allow(dead_code)]
-pub enum SkillTargetType {
-    /// casts immediately
-    NoTarget,
-    Area,
-    Directional,
-    AnyEntity,
-    OnlyAllyButNoSelf,
-    OnlyAllyAndSelf,
-    OnlyEnemy,
 }
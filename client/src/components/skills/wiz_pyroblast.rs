@@ -1,24 +1,19 @@
-use nalgebra::Isometry2;
 use specs::ReadStorage;
 
 use crate::audio::sound_sys::AudioCommandCollectorComponent;
-use crate::components::char::{
-    ActionPlayMode, CastingSkillData, CharacterStateComponent, SpriteRenderDescriptorComponent,
-};
+use crate::components::char::{ActionPlayMode, SpriteRenderDescriptorComponent};
 use crate::components::skills::skills::{
-    FinishCast, SkillDef, SkillManifestation, SkillManifestationComponent,
-    SkillManifestationUpdateParam, SkillTargetType,
+    FinishCast, SkillDef, SkillManifestation, SkillManifestationUpdateParam, SkillTargetType,
 };
-use crate::components::status::status::{ApplyStatusComponent, StatusEnum};
+use crate::components::StrEffectComponent;
 use crate::effect::StrEffectType;
 use crate::render::render_command::RenderCommandCollector;
 use crate::render::render_sys::{render_action, RenderDesktopClientSystem, COLOR_WHITE};
-use crate::runtime_assets::map::PhysicEngine;
-use crate::systems::{AssetResources, SystemVariables};
+use crate::systems::AssetResources;
 use crate::GameTime;
-use rustarok_common::common::{v2, EngineTime, Local, Vec2};
+use rustarok_common::common::{EngineTime, Local, Vec2};
 use rustarok_common::components::char::{
-    CharDir, EntityId, LocalCharStateComp, StaticCharDataComponent,
+    CastingSkillData, CharDir, EntityId, LocalCharStateComp, StaticCharDataComponent,
 };
 use rustarok_common::config::{CommonConfigs, SkillConfigPyroBlastInner};
 use specs::world::WorldExt;
@@ -37,30 +32,16 @@ impl SkillDef for WizPyroBlastSkill {
         params: &FinishCast,
         ecs_world: &mut specs::world::World,
     ) -> Option<Box<dyn SkillManifestation>> {
-        let mut sys_vars = ecs_world.write_resource::<SystemVariables>();
         let configs = ecs_world
             .read_resource::<CommonConfigs>()
             .skills
             .wiz_pyroblast
             .inner
             .clone();
-
-        sys_vars
-            .apply_statuses
-            .push(ApplyStatusComponent::from_status(
-                params.caster_entity_id,
-                params.target_entity.unwrap(),
-                StatusEnum::PyroBlastTargetStatus(PyroBlastTargetStatus {
-                    caster_entity_id: params.caster_entity_id,
-                    splash_radius: configs.splash_radius,
-                }),
-            ));
         Some(Box::new(PyroBlastManifest::new(
-            params.caster_entity_id,
             params.caster_pos,
-            params.target_entity.unwrap(),
+            params.target_entity?,
             ecs_world.read_resource::<EngineTime>().now(),
-            &mut ecs_world.write_resource::<PhysicEngine>(),
             configs,
         )))
     }
@@ -72,7 +53,7 @@ impl SkillDef for WizPyroBlastSkill {
     fn render_casting(
         &self,
         char_pos: &Vec2,
-        casting_state: &CastingSkillData,
+        casting_state: &CastingSkillData<Local>,
         assets: &AssetResources,
         time: &EngineTime,
         dev_configs: &CommonConfigs,
@@ -129,91 +110,55 @@ impl SkillDef for WizPyroBlastSkill {
 }
 
 pub struct PyroBlastManifest {
-    pub caster_entity_id: EntityId<Local>,
     pub pos: Vec2,
-    pub target_last_pos: Vec2,
     pub target_entity_id: EntityId<Local>,
-    pub created_at: GameTime<Local>,
+    pub last_update: GameTime<Local>,
     pub configs: SkillConfigPyroBlastInner,
+    pub exploded: bool,
 }
 
 impl PyroBlastManifest {
     pub fn new(
-        caster_entity_id: EntityId<Local>,
         pos: Vec2,
         target_entity_id: EntityId<Local>,
         created_at: GameTime<Local>,
-        _physics_world: &mut PhysicEngine,
         configs: SkillConfigPyroBlastInner,
     ) -> PyroBlastManifest {
         PyroBlastManifest {
-            caster_entity_id,
             pos,
-            target_last_pos: v2(0.0, 0.0),
             target_entity_id,
-            created_at,
+            last_update: created_at,
             configs,
+            exploded: false,
         }
     }
 }
 
 impl SkillManifestation for PyroBlastManifest {
-    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
-        // TODO2
-        //        let (target_pos, collide) = if let Some(target_char) =
-        //            params.char_storage.get_mut(self.target_entity_id.into())
-        //        {
-        //            let target_pos = target_char.pos();
-        //            let dir_vector = target_pos - self.pos;
-        //            let distance = dir_vector.magnitude();
-        //            if distance > 2.0 {
-        //                let dir_vector = dir_vector.normalize();
-        //                self.pos = self.pos + (dir_vector * params.time().dt() * self.configs.moving_speed);
-        //                (target_pos, false)
-        //            } else {
-        //                target_char.statuses.remove_if(|status| {
-        //                    if let StatusEnum::PyroBlastTargetStatus(status) = status {
-        //                        status.caster_entity_id == self.caster_entity_id
-        //                    } else {
-        //                        false
-        //                    }
-        //                });
-        //                (target_pos, true)
-        //            }
-        //        } else {
-        //            params.remove_component::<SkillManifestationComponent>(params.self_entity_id);
-        //            (v2(0.0, 0.0), false)
-        //        };
-        //        if collide {
-        //            params.remove_component::<SkillManifestationComponent>(params.self_entity_id);
-        //            params.add_hp_mod_request(HpModificationRequest {
-        //                src_entity: self.caster_entity_id,
-        //                dst_entity: self.target_entity_id,
-        //                typ: HpModificationType::SpellDamage(
-        //                    self.configs.damage,
-        //                    DamageDisplayType::SingleNumber,
-        //                ),
-        //            });
-        //            let area_shape = Box::new(ncollide2d::shape::Ball::new(self.configs.splash_radius));
-        //            let area_isom = Isometry2::new(target_pos, 0.0);
-        //            params.add_area_hp_mod_request(AreaAttackComponent {
-        //                area_shape,
-        //                area_isom,
-        //                source_entity_id: self.caster_entity_id,
-        //                typ: HpModificationType::SpellDamage(
-        //                    self.configs.secondary_damage,
-        //                    DamageDisplayType::SingleNumber,
-        //                ),
-        //                except: Some(self.target_entity_id),
-        //            });
-        //            params.create_entity_with_comp(StrEffectComponent {
-        //                effect_id: StrEffectType::Explosion.into(),
-        //                pos: target_pos,
-        //                start_time: params.time().now(),
-        //                die_at: None,
-        //                play_mode: ActionPlayMode::Once,
-        //            });
-        //        }
+    fn update(&mut self, params: SkillManifestationUpdateParam) {
+        let now = params.time().now();
+        let elapsed_seconds = now.elapsed_since(self.last_update).as_seconds_f32();
+        self.last_update = now;
+        if self.exploded {
+            return;
+        }
+        let target_pos = match params.auth_state_storage.get(self.target_entity_id.into()) {
+            Some(target_char) => target_char.pos(),
+            None => return,
+        };
+        let dir_vector = target_pos - self.pos;
+        if dir_vector.magnitude() > 2.0 {
+            self.pos += dir_vector.normalize() * elapsed_seconds * self.configs.moving_speed;
+        } else {
+            self.exploded = true;
+            params.create_entity_with_comp(StrEffectComponent {
+                effect_id: StrEffectType::Explosion.into(),
+                pos: target_pos,
+                start_time: now,
+                die_at: None,
+                play_mode: ActionPlayMode::Once,
+            });
+        }
     }
 
     fn render(
@@ -3,8 +3,6 @@ pub mod status;
 pub mod attack_heal_status;
 pub mod attrib_mod;
 pub mod death_status;
-pub mod heal_area;
 pub mod reflect_damage_status;
 pub mod sacrafice_status;
-pub mod status_applier_area;
 pub mod stun;
@@ -16,13 +16,11 @@ use crate::effect::StrEffectType;
 use crate::grf::SpriteResource;
 use crate::render::render_command::RenderCommandCollector;
 use crate::render::render_sys::RenderDesktopClientSystem;
-use crate::runtime_assets::map::PhysicEngine;
 use crate::systems::{AssetResources, SystemVariables};
 use crate::GameTime;
-use nalgebra::Isometry2;
 use rustarok_common::attack::{
-    ApplyForceComponent, AreaAttackComponent, HpModificationRequest, HpModificationResult,
-    HpModificationType,
+    ApplyForceComponent, AreaAttackComponent, AreaShape, HpModificationRequest,
+    HpModificationResult, HpModificationType,
 };
 use rustarok_common::char_attr::{CharAttributeModifier, CharAttributeModifierCollector};
 use rustarok_common::common::{percentage, EngineTime, Local, Percentage, Vec2};
@@ -43,7 +41,6 @@ pub enum StatusStackingResult {
 pub struct StatusUpdateParams<'a> {
     pub self_char_id: EntityId<Local>,
     pub target_char: &'a mut StaticCharDataComponent,
-    pub physics_world: &'a mut PhysicEngine,
     pub sys_vars: &'a mut SystemVariables,
     pub hp_mod_requests: &'a mut Vec<HpModificationRequest>,
     pub area_hp_mod_requests: &'a mut Vec<AreaAttackComponent>,
@@ -210,16 +207,12 @@ impl StatusEnum {
         updater: &mut LazyUpdate,
         assets: &AssetResources,
         time: &EngineTime,
-        physics_world: &mut PhysicEngine,
     ) {
         // TODO2
         //        match self {
-        //            StatusEnum::AssaBladeDashStatus(_) => {
-        //                // allow to go through anything
-        //                target_char.set_noncollidable(physics_world);
-        //            }
+        //            // the collision of the dashing and carried characters is
+        //            // turned off by the common CollisionSystem based on their statuses
         //            StatusEnum::FalconCarryStatus(_) => {
-        //                target_char.set_noncollidable(physics_world);
         //                target_char.set_state(ClientCharState::StandBy, CharDir::South);
         //            }
         //            StatusEnum::ExoSkeletonStatus(status) => {
@@ -676,7 +669,6 @@ impl Statuses {
         &mut self,
         self_char_id: EntityId<Local>,
         char_state: &mut StaticCharDataComponent,
-        physics_world: &mut PhysicEngine,
         sys_vars: &mut SystemVariables,
         time: &EngineTime,
         entities: &Entities,
@@ -693,7 +685,6 @@ impl Statuses {
             let result = status.as_mut().unwrap().update(StatusUpdateParams {
                 self_char_id,
                 target_char: char_state,
-                physics_world,
                 sys_vars,
                 // TODO2 asd
                 hp_mod_requests: &mut vec![],
@@ -1093,9 +1084,7 @@ pub struct ApplyStatusComponent {
 pub struct ApplyStatusInAreaComponent {
     pub source_entity_id: EntityId<Local>,
     pub status: StatusEnum,
-    // TODO: it should not be a box. Predefine shapes
-    pub area_shape: Box<dyn ncollide2d::shape::Shape<f32>>,
-    pub area_isom: Isometry2<f32>,
+    pub area_shape: AreaShape,
     pub except: Option<EntityId<Local>>,
     pub nature: StatusNature,
     pub caster_team: Team,
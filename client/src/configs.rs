@@ -1,7 +1,8 @@
 use crate::components::controller::CastMode;
 use rustarok_common::char_attr::CharAttributes;
 use rustarok_common::common::Percentage;
-use rustarok_common::config::{CommonConfigs, DevConfigStats, SkillsConfig};
+use rustarok_common::config::{CommonConfigs, SkillsConfig};
+use rustarok_common::packets::Transport;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -13,6 +14,8 @@ pub struct AppConfig {
     pub resolution_h: u32,
     pub grf_paths: Vec<String>,
     pub server_addr: String,
+    pub transport: Transport,
+    pub max_packet_size: usize,
     pub load_models: bool,
     pub load_sprites: bool,
     pub cast_mode: CastMode,
@@ -17,7 +17,7 @@ use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufReader;
 use std::io::{BufRead, Read};
-use std::net::TcpStream;
+use std::net::{TcpStream, UdpSocket};
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
@@ -38,28 +38,39 @@ use rustarok_common::common::{
 };
 use rustarok_common::components::char::EntityTarget;
 use rustarok_common::components::char::{
-    create_common_player_entity, CharDir, CharOutlook, CollisionGroup, ControllerEntityId,
-    EntityId, JobId, LocalCharStateComp, Sex, StaticCharDataComponent, Team,
+    create_common_player_entity, CharDir, CharOutlook, ControllerEntityId, EntityId, JobId,
+    LocalCharStateComp, Sex, StaticCharDataComponent, Team,
 };
 use rustarok_common::components::job_ids::JobSpriteId;
+use rustarok_common::components::projectile::ProjectileComponent;
 use rustarok_common::config::CommonConfigs;
 use rustarok_common::console::CommandArguments;
+use rustarok_common::map::MapWalkingInfo;
 use rustarok_common::packets::from_server::{FromServerPacket, ServerEntityState};
+use rustarok_common::packets::snapshot_delta::SnapshotHistory;
 use rustarok_common::packets::to_server::ToServerPacket;
-use rustarok_common::packets::{NetworkTrafficEvent, PacketHandlerThread, SocketBuffer, SocketId};
+use rustarok_common::packets::{
+    NetworkTrafficEvent, PacketHandlerThread, SocketBuffer, SocketId, Transport, BUILD_ID,
+    PROTOCOL_VERSION,
+};
+use rustarok_common::path_finding::PathFindingBuffers;
+use rustarok_common::state_checksum::{checksum_diff, world_checksum};
 use rustarok_common::systems::char_state_sys::CharacterStateUpdateSystem;
+use rustarok_common::systems::collision_sys::CollisionSystem;
 
 use crate::audio::sound_sys::{AudioCommandCollectorComponent, SoundSystem};
 use crate::client::SimulationTime;
 use crate::components::char::{
-    create_client_entity, create_client_minion_entity, CharActionIndex, CharacterEntityBuilder,
-    CharacterStateComponent, HasServerIdComponent, SpriteRenderDescriptorComponent,
+    create_client_entity, CharActionIndex, CharacterEntityBuilder, CharacterStateComponent,
+    HasServerIdComponent, SpriteRenderDescriptorComponent,
 };
 use crate::components::controller::{
     CameraComponent, HumanInputComponent, LocalPlayerController, SkillKey,
 };
-use crate::components::skills::skills::{SkillManifestationComponent, Skills};
-use crate::components::MinionComponent;
+use crate::components::skills::basic_attack::BasicRangeAttackBullet;
+use crate::components::skills::skills::{
+    ClientSkillDefs, FinishCast, SkillManifestationComponent, Skills,
+};
 use crate::configs::AppConfig;
 use crate::grf::asset_loader::GrfEntryLoader;
 use crate::grf::database::AssetDatabase;
@@ -73,7 +84,7 @@ use crate::runtime_assets::audio::init_audio_and_load_sounds;
 use crate::runtime_assets::ecs::create_ecs_world;
 use crate::runtime_assets::effect::load_str_effects;
 use crate::runtime_assets::graphic::{load_skill_icons, load_status_icons, load_texts};
-use crate::runtime_assets::map::{load_map, MapRenderData, PhysicEngine};
+use crate::runtime_assets::map::{load_map, MapRenderData};
 use crate::systems::atk_calc::{AttackCalculation, AttackSystem};
 use crate::systems::camera_system::CameraSystem;
 use crate::systems::console_system::{
@@ -87,17 +98,12 @@ use crate::systems::input_to_next_action::{
     ClientIntentionToCharTargetSystem, InputToNextActionSystem,
 };
 use crate::systems::intention_sender_sys::{ClientCommandId, IntentionSenderSystem};
-use crate::systems::minion_ai_sys::MinionAiSystem;
 use crate::systems::next_action_applier_sys::{
     SavePreviousCharStateSystem, UpdateCharSpriteBasedOnStateSystem,
 };
-use crate::systems::phys::{FrictionSystem, PhysCollisionCollectorSystem};
 use crate::systems::skill_sys::SkillSystem;
 use crate::systems::snapshot_sys::{ServerAckResult, SnapshotStorage, SnapshotSystem};
-use crate::systems::turret_ai_sys::TurretAiSystem;
-use crate::systems::{
-    CollisionsFromPrevFrame, RenderMatrices, Sprites, SystemFrameDurations, SystemVariables,
-};
+use crate::systems::{RenderMatrices, Sprites, SystemFrameDurations, SystemVariables};
 use crate::video::Video;
 
 #[macro_use]
@@ -127,6 +133,7 @@ pub const MAX_SECONDS_ALLOWED_FOR_SINGLE_SIMULATION_FRAME: f32 =
     SIMULATION_DURATION_MS as f32 / 1000.0;
 pub const MAX_DURATION_ALLOWED_FOR_SINGLE_SIMULATION_FRAME: Duration =
     Duration::from_millis(SIMULATION_DURATION_MS as u64);
+const SESSION_TOKEN_FILE: &str = "session.token";
 
 struct DelayedPacketReceiver {
     packets: Vec<(Instant, FromServerPacket)>,
@@ -177,18 +184,49 @@ fn main() {
     log::info!("<<< GRF loading: {}ms", elapsed.as_millis());
 
     log::info!("starting packet handler thread");
-    let mut packet_handler_thread =
-        PacketHandlerThread::<FromServerPacket, ToServerPacket>::start_thread(1);
-
     log::info!("Connecting to server");
-    let server_socket = packet_handler_thread
-        .handle_socket(TcpStream::connect(config.server_addr.clone()).unwrap());
+    let (mut packet_handler_thread, server_socket) = match config.transport {
+        Transport::Tcp => {
+            let mut packet_handler_thread =
+                PacketHandlerThread::<FromServerPacket, ToServerPacket>::start_thread(
+                    1,
+                    config.max_packet_size,
+                );
+            let server_socket = packet_handler_thread
+                .handle_socket(TcpStream::connect(config.server_addr.clone()).unwrap());
+            (packet_handler_thread, server_socket)
+        }
+        Transport::Udp => {
+            let mut packet_handler_thread =
+                PacketHandlerThread::<FromServerPacket, ToServerPacket>::start_udp_thread(
+                    1,
+                    config.max_packet_size,
+                    UdpSocket::bind("0.0.0.0:0").unwrap(),
+                    false,
+                );
+            let server_socket = packet_handler_thread.connect_udp(
+                config
+                    .server_addr
+                    .parse()
+                    .expect("'server_addr' must be an ip address with port"),
+            );
+            (packet_handler_thread, server_socket)
+        }
+    };
 
+    // the token of the previous session, so a restarted client gets back its character
+    // if the match is still running
+    let last_session_token = std::fs::read_to_string(SESSION_TOKEN_FILE)
+        .ok()
+        .and_then(|it| it.trim().parse::<u64>().ok());
     log::info!("sending welcome msg");
     packet_handler_thread.send(
         server_socket,
         ToServerPacket::Welcome {
+            protocol_version: PROTOCOL_VERSION,
+            build: BUILD_ID.to_owned(),
             name: "sharp".to_owned(),
+            session_token: last_session_token,
         },
     );
 
@@ -202,11 +240,30 @@ fn main() {
             let mut tmp_start_y = 0.0;
             for (socket_id, packet) in tmp_vec.drain(..) {
                 match packet {
+                    NetworkTrafficEvent::Packet(FromServerPacket::Rejected { reason }) => {
+                        log::error!("The server has rejected the connection: {}", reason);
+                        std::process::exit(1);
+                    }
                     NetworkTrafficEvent::Packet(FromServerPacket::Init {
+                        protocol_version,
                         map_name,
                         start_x,
                         start_y,
+                        session_token,
                     }) => {
+                        if protocol_version != PROTOCOL_VERSION {
+                            log::error!(
+                                "Incompatible protocol version, server: {}, client: {}",
+                                protocol_version,
+                                PROTOCOL_VERSION
+                            );
+                            std::process::exit(1);
+                        }
+                        if let Err(e) =
+                            std::fs::write(SESSION_TOKEN_FILE, session_token.to_string())
+                        {
+                            log::warn!("Could not save the session token: {}", e);
+                        }
                         tmp_map_name = map_name;
                         tmp_start_x = start_x;
                         tmp_start_y = start_y;
@@ -233,8 +290,6 @@ fn main() {
     let (mut video, gl, display_modes) =
         Video::init(&sdl_context, config.resolution_w, config.resolution_h);
 
-    let mut physics_world = PhysicEngine::new();
-
     // dummy texture
     GrfEntryLoader::create_texture_from_surface(
         &gl,
@@ -246,7 +301,6 @@ fn main() {
 
     log::info!(">>> Loading map");
     let map_render_data = load_map(
-        &mut physics_world,
         &gl,
         &map_name,
         &asset_loader,
@@ -313,6 +367,7 @@ fn main() {
     ecs_world.insert(Vec::<HpModificationRequest>::with_capacity(128));
     ecs_world.insert(Vec::<AreaAttackComponent>::with_capacity(128));
     ecs_world.insert(Vec::<ApplyForceComponent>::with_capacity(128));
+    ecs_world.insert(Vec::<FinishCast>::with_capacity(128));
 
     ecs_world.insert(sys_vars);
     ecs_world.insert(common_configs);
@@ -322,6 +377,11 @@ fn main() {
         asset_loader.load_sprites(&gl, &mut asset_db);
     }
     ecs_world.insert(gl.clone());
+    ecs_world.insert(MapWalkingInfo::from_gat(
+        &map_render_data.gat,
+        &map_render_data.blocking_rectangles,
+    ));
+    ecs_world.insert(PathFindingBuffers::default());
     ecs_world.insert(map_render_data);
     ecs_world.insert(RenderCommandCollector::new());
     ecs_world.insert(command_buffer);
@@ -332,11 +392,6 @@ fn main() {
     ecs_world.insert(asset_db);
     ecs_world.insert(server_socket);
 
-    ecs_world.insert(CollisionsFromPrevFrame {
-        collisions: HashMap::new(),
-    });
-
-    ecs_world.insert(physics_world);
     ecs_world.insert(SystemFrameDurations(HashMap::new()));
     ecs_world.insert(LocalPlayerController::new());
     ecs_world.insert(ConsoleComponent::new());
@@ -346,12 +401,8 @@ fn main() {
         let mut human_player = HumanInputComponent::new();
         human_player.cast_mode = config.cast_mode;
         human_player.assign_skill(SkillKey::A, Skills::AttackMove);
-
-        human_player.assign_skill(SkillKey::Q, Skills::FireWall);
-        human_player.assign_skill(SkillKey::W, Skills::AbsorbShield);
-        human_player.assign_skill(SkillKey::E, Skills::Heal);
-        human_player.assign_skill(SkillKey::R, Skills::BrutalTestSkill);
         human_player.assign_skill(SkillKey::Y, Skills::Mounting);
+        // the rest is assigned from the job definition when the match starts
 
         ecs_world.insert(RenderCommandCollector::new());
         ecs_world.insert(AudioCommandCollectorComponent::new());
@@ -380,7 +431,6 @@ fn main() {
     let mut next_second: SystemTime = std::time::SystemTime::now()
         .checked_add(Duration::from_secs(1))
         .unwrap();
-    let mut next_minion_spawn = GameTime::<Local>::from(2.0);
     let mut fps_counter: usize = 0;
     let mut fps: usize;
     let mut incoming_packets_per_second: usize = 0;
@@ -411,6 +461,10 @@ fn main() {
 
     let mut server_to_local_ids: HashMap<EntityId<Remote>, EntityId<Local>> =
         HashMap::with_capacity(1024);
+    // skill manifestations are not characters, they are only rendered until the server ends them
+    let mut server_to_local_manifestation_ids: HashMap<EntityId<Remote>, Entity> =
+        HashMap::with_capacity(128);
+    let mut received_snapshots: SnapshotHistory<Remote> = SnapshotHistory::new();
 
     console_print(&mut ecs_world, "Sync");
     {
@@ -448,6 +502,8 @@ fn main() {
         console_print(&mut ecs_world, &format!("avg ping: {}", avg_ping));
 
         packet_handler_thread.send(server_socket, ToServerPacket::ReadyForGame);
+        // the match starts when every player in the lobby is ready
+        packet_handler_thread.send(server_socket, ToServerPacket::ReadyCheck(true));
         // first ACK packet is for initializing our world state
         'outer3: loop {
             packet_handler_thread.receive_exact_into(&mut tmp_vec, 1);
@@ -575,8 +631,13 @@ fn main() {
                         }
                         NetworkTrafficEvent::Disconnected => { // TODO
                         }
+                        NetworkTrafficEvent::Connected(_) => {}
                         NetworkTrafficEvent::Packet(p) => match p {
                             FromServerPacket::Init { .. } => panic!(),
+                            FromServerPacket::Rejected { reason } => {
+                                log::error!("The server has closed the connection: {}", reason);
+                                std::process::exit(1);
+                            }
                             FromServerPacket::Pong { server_time, .. } => {
                                 let ping = ping_sent.elapsed().as_millis() as usize;
                                 avg_ping = (avg_ping + ping) / 2;
@@ -595,21 +656,52 @@ fn main() {
                                 ecs_world
                                     .write_resource::<ConsoleComponent>()
                                     .print("Configs has been updated by the server");
-                                dbg!(configs.stats.player.crusader.attributes.movement_speed);
-                                *ecs_world.write_resource::<CommonConfigs>() = configs.clone();
-                                for (state, static_info) in (
-                                    &mut ecs_world.write_storage::<LocalCharStateComp<Local>>(),
-                                    &ecs_world.read_storage::<StaticCharDataComponent>(),
-                                )
-                                    .join()
-                                {
-                                    state.recalc_attribs_based_on_statuses(
-                                        static_info.job_id,
-                                        &configs,
-                                    );
+                                // the recalculated attributes arrive in the next snapshot,
+                                // only the server knows the modifiers of the statuses
+                                *ecs_world.write_resource::<CommonConfigs>() = configs;
+                            }
+                            FromServerPacket::ConfigsChanged(sections) => {
+                                let names = sections
+                                    .iter()
+                                    .map(|it| it.name())
+                                    .collect::<Vec<_>>()
+                                    .join(", ");
+                                log::info!("Config sections changed by the server: {}", names);
+                                ecs_world
+                                    .write_resource::<ConsoleComponent>()
+                                    .print(&format!(
+                                        "Configs has been updated by the server: {}",
+                                        names
+                                    ));
+                                let mut configs = ecs_world.write_resource::<CommonConfigs>();
+                                for section in sections {
+                                    configs.apply(section);
                                 }
+                                drop(configs);
+                                assign_skills_of_controlled_char(&mut ecs_world);
                             }
-                            FromServerPacket::Ack { cid, mut entries } => {
+                            FromServerPacket::Ack {
+                                cid,
+                                snapshot,
+                                checksum,
+                            } => {
+                                let baseline = snapshot
+                                    .baseline_tick
+                                    .and_then(|tick| received_snapshots.get(tick));
+                                let entries = match snapshot.decode(baseline) {
+                                    Ok(entries) => entries,
+                                    Err(e) => {
+                                        log::error!("Snapshot could not be decoded: {}", e);
+                                        continue;
+                                    }
+                                };
+                                received_snapshots.push(snapshot.tick, entries.clone());
+                                packet_handler_thread.send(
+                                    server_socket,
+                                    ToServerPacket::SnapshotReceived {
+                                        tick: snapshot.tick,
+                                    },
+                                );
                                 let snapshots = &mut ecs_world.write_resource::<SnapshotStorage>();
 
                                 // TODO: replace in place
@@ -625,6 +717,46 @@ fn main() {
                                     })
                                     .collect();
 
+                                {
+                                    let local_to_server_id = |local_id: EntityId<Local>| {
+                                        server_to_local_ids
+                                            .iter()
+                                            .find(|(_server_id, id)| **id == local_id)
+                                            .map(|(server_id, _id)| server_id.as_u64())
+                                            .unwrap_or(0)
+                                    };
+                                    let received: Vec<(u64, &LocalCharStateComp<Local>)> = entries
+                                        .iter()
+                                        .map(|it| (it.id.as_u64(), &it.char_snapshot))
+                                        .collect();
+                                    if world_checksum(received.iter().cloned(), &local_to_server_id)
+                                        != checksum
+                                    {
+                                        log::error!(
+                                            "The snapshot of tick {:?} has been reconstructed incorrectly",
+                                            snapshot.tick
+                                        );
+                                    } else if snapshots.get_unacked_prediction_count() > 0 {
+                                        let predicted: Vec<(u64, &LocalCharStateComp<Local>)> =
+                                            snapshots
+                                                .get_predicted_states_for_next_ack()
+                                                .into_iter()
+                                                .map(|(id, state)| (id.as_u64(), state))
+                                                .collect();
+                                        if world_checksum(
+                                            predicted.iter().cloned(),
+                                            &local_to_server_id,
+                                        ) != checksum
+                                        {
+                                            log::warn!(
+                                                "The predicted world differs from the server at tick {:?}:\n{}",
+                                                snapshot.tick,
+                                                checksum_diff(&received, &predicted, &local_to_server_id)
+                                            );
+                                        }
+                                    }
+                                }
+
                                 ack_result = snapshots.ack_arrived(simulation_frame, cid, entries);
                                 #[cfg(debug_assertions)]
                                 {
@@ -681,19 +813,232 @@ fn main() {
                                     disconnecting_entity_local_id
                                 );
                                 ecs_world.delete_entity(disconnecting_entity_local_id.into());
+                                ecs_world
+                                    .write_resource::<SnapshotStorage>()
+                                    .remove_predicting_entity(disconnecting_entity_id);
+                            }
+                            FromServerPacket::LobbyState { players } => {
+                                for player in &players {
+                                    console_print(
+                                        &mut ecs_world,
+                                        &format!(
+                                            "{} ({}, {}) ready: {}",
+                                            player.name,
+                                            player.team.to_str(),
+                                            player.job_id,
+                                            player.ready
+                                        ),
+                                    );
+                                }
+                            }
+                            FromServerPacket::MatchStarted { controlled_entity } => {
+                                ecs_world
+                                    .write_resource::<LocalPlayerController>()
+                                    .controller
+                                    .controlled_entity =
+                                    Some(server_to_local_ids[&controlled_entity]);
+                                assign_skills_of_controlled_char(&mut ecs_world);
+                            }
+                            FromServerPacket::MatchEnded { winner } => {
+                                for (server_id, local_id) in server_to_local_ids.drain() {
+                                    ecs_world.delete_entity(local_id.into());
+                                    ecs_world
+                                        .write_resource::<SnapshotStorage>()
+                                        .remove_predicting_entity(server_id);
+                                }
+                                for (_server_id, local_id) in
+                                    server_to_local_manifestation_ids.drain()
+                                {
+                                    ecs_world.delete_entity(local_id);
+                                }
+                                ecs_world
+                                    .write_resource::<LocalPlayerController>()
+                                    .controller
+                                    .controlled_entity = None;
+                                let text = match winner {
+                                    Some(team) => format!("The match is won by {}", team.to_str()),
+                                    None => "The match has ended in a draw".to_owned(),
+                                };
+                                console_print(&mut ecs_world, &text);
+                                packet_handler_thread
+                                    .send(server_socket, ToServerPacket::ReadyCheck(true));
+                            }
+                            FromServerPacket::StaticDataChanged { id, data } => {
+                                // unknown entities will arrive with the new data in `NewEntity`
+                                if let Some(local_id) = server_to_local_ids.get(&id) {
+                                    ecs_world
+                                        .write_storage::<StaticCharDataComponent>()
+                                        .insert((*local_id).into(), data)
+                                        .expect("Could not update the static data of an entity");
+                                }
+                                assign_skills_of_controlled_char(&mut ecs_world);
+                            }
+                            FromServerPacket::StructureDestroyed {
+                                id: _id,
+                                team,
+                                job_id,
+                            } => {
+                                console_print(
+                                    &mut ecs_world,
+                                    &format!(
+                                        "The {} of {} has been destroyed",
+                                        job_id,
+                                        team.to_str()
+                                    ),
+                                );
+                            }
+                            FromServerPacket::EntityAppeared { id, state } => {
+                                let local_id = server_to_local_ids[&id];
+                                let state = LocalCharStateComp::server_to_local(
+                                    state,
+                                    now,
+                                    server_to_local_time_diff,
+                                    &server_to_local_ids,
+                                );
+                                ecs_world
+                                    .write_storage::<LocalCharStateComp<Local>>()
+                                    .insert(local_id.into(), state.clone())
+                                    .expect("Could not insert the state of an appeared entity");
+                                ecs_world
+                                    .write_resource::<SnapshotStorage>()
+                                    .add_predicting_entity(id, state);
+                            }
+                            FromServerPacket::EntityDisappeared { id } => {
+                                // the entity is kept with its static data, but without a state
+                                // it is neither simulated nor rendered
+                                let local_id = server_to_local_ids[&id];
+                                ecs_world
+                                    .write_storage::<LocalCharStateComp<Local>>()
+                                    .remove(local_id.into());
+                                ecs_world
+                                    .write_resource::<SnapshotStorage>()
+                                    .remove_predicting_entity(id);
                             }
                             FromServerPacket::Damage {
                                 src_id,
                                 dst_id,
                                 typ,
-                            } => AttackCalculation::add_flying_damage_entity(
-                                server_to_local_ids[&src_id],
-                                server_to_local_ids[&dst_id],
-                                typ,
-                                &ecs_world.entities(),
-                                &ecs_world.read_resource::<LazyUpdate>(),
-                                now,
-                            ),
+                            } => {
+                                // the attacker might be outside of our view radius
+                                if let (Some(src_id), Some(dst_id)) = (
+                                    server_to_local_ids.get(&src_id),
+                                    server_to_local_ids.get(&dst_id),
+                                ) {
+                                    AttackCalculation::add_flying_damage_entity(
+                                        *src_id,
+                                        *dst_id,
+                                        typ,
+                                        &ecs_world.entities(),
+                                        &ecs_world.read_resource::<LazyUpdate>(),
+                                        now,
+                                    )
+                                }
+                            }
+                            FromServerPacket::ProjectileFired {
+                                caster_id,
+                                target_id,
+                                weapon_type,
+                                pos,
+                            } => {
+                                if let (Some(caster_id), Some(target_id)) = (
+                                    server_to_local_ids.get(&caster_id),
+                                    server_to_local_ids.get(&target_id),
+                                ) {
+                                    let bullet = BasicRangeAttackBullet::new(ProjectileComponent {
+                                        caster_id: *caster_id,
+                                        target_id: *target_id,
+                                        weapon_type,
+                                        pos,
+                                        // the damage is sent by the server on arrival
+                                        damage: 0,
+                                        updated_at: now,
+                                    });
+                                    let bullet_entity_id = ecs_world.create_entity().build();
+                                    ecs_world.read_resource::<LazyUpdate>().insert(
+                                        bullet_entity_id,
+                                        SkillManifestationComponent::new(
+                                            bullet_entity_id,
+                                            Box::new(bullet),
+                                        ),
+                                    );
+                                }
+                            }
+                            FromServerPacket::CharDied { id, respawn_at } => {
+                                let local_id = server_to_local_ids.get(&id).copied();
+                                let is_controlled = local_id.is_some()
+                                    && local_id
+                                        == ecs_world
+                                            .read_resource::<LocalPlayerController>()
+                                            .controller
+                                            .controlled_entity;
+                                if is_controlled {
+                                    let text = match respawn_at {
+                                        Some(respawn_at) => {
+                                            let respawn_at = respawn_at
+                                                .to_local_time(now, server_to_local_time_diff);
+                                            format!(
+                                                "You have died, respawning in {:.0} seconds",
+                                                respawn_at.elapsed_since(now).as_seconds_f32()
+                                            )
+                                        }
+                                        None => "You have died".to_owned(),
+                                    };
+                                    console_print(&mut ecs_world, &text);
+                                }
+                            }
+                            FromServerPacket::SkillManifested {
+                                id,
+                                skill,
+                                caster_id,
+                                caster_pos,
+                                skill_pos,
+                                char_to_skill_dir,
+                                target_id,
+                            } => {
+                                let caster =
+                                    server_to_local_ids.get(&caster_id).and_then(|caster_id| {
+                                        ecs_world
+                                            .read_storage::<StaticCharDataComponent>()
+                                            .get((*caster_id).into())
+                                            .map(|caster| (*caster_id, caster.team))
+                                    });
+                                if let Some((caster_entity_id, caster_team)) = caster {
+                                    let finish_cast = FinishCast {
+                                        skill,
+                                        caster_entity_id,
+                                        caster_pos,
+                                        caster_team,
+                                        skill_pos,
+                                        char_to_skill_dir,
+                                        target_entity: target_id
+                                            .and_then(|it| server_to_local_ids.get(&it).copied()),
+                                    };
+                                    let manifestation = skill
+                                        .get_client_definition()
+                                        .finish_cast(&finish_cast, &mut ecs_world);
+                                    if let Some(manifestation) = manifestation {
+                                        let skill_entity_id = ecs_world.create_entity().build();
+                                        ecs_world.read_resource::<LazyUpdate>().insert(
+                                            skill_entity_id,
+                                            SkillManifestationComponent::new(
+                                                skill_entity_id,
+                                                manifestation,
+                                            ),
+                                        );
+                                        server_to_local_manifestation_ids
+                                            .insert(id, skill_entity_id);
+                                    }
+                                }
+                            }
+                            FromServerPacket::SkillManifestationEnded { id } => {
+                                if let Some(local_id) =
+                                    server_to_local_manifestation_ids.remove(&id)
+                                {
+                                    ecs_world.delete_entity(local_id).expect(
+                                        "Could not delete the entity of an ended manifestation",
+                                    );
+                                }
+                            }
                         },
                     }
                 }
@@ -887,15 +1232,6 @@ fn main() {
         }
         fps_counter += 1;
 
-        // TODO2 minions
-        //        let now = ecs_world.read_resource::<EngineTime>().now();
-        //        if next_minion_spawn.has_already_passed(now)
-        //            && ecs_world.read_resource::<CommonConfigs>().minions_enabled
-        //        {
-        //            next_minion_spawn = now.add_seconds(2.0);
-        //            spawn_minions(&mut ecs_world)
-        //        }
-
         let frame_duration = start.elapsed();
         if max_allowed_render_frame_duration > frame_duration {
             std::thread::sleep(max_allowed_render_frame_duration - frame_duration);
@@ -966,6 +1302,26 @@ fn load_all_last_acked_states_into_world(ecs_world: &mut World) {
     );
 }
 
+/// The skill keys are assigned according to the job definition of the controlled character
+fn assign_skills_of_controlled_char(ecs_world: &mut specs::World) {
+    let controlled_entity = ecs_world
+        .read_resource::<LocalPlayerController>()
+        .controller
+        .controlled_entity;
+    let job_id = controlled_entity.and_then(|char_id| {
+        ecs_world
+            .read_storage::<StaticCharDataComponent>()
+            .get(char_id.into())
+            .map(|it| it.job_id)
+    });
+    if let Some(job_id) = job_id {
+        let configs = ecs_world.read_resource::<CommonConfigs>();
+        ecs_world
+            .write_resource::<HumanInputComponent>()
+            .assign_job_skills(configs.job(job_id));
+    }
+}
+
 pub fn console_print(ecs_world: &mut specs::World, text: &str) {
     log::debug!("{}", text);
     ecs_world.write_resource::<ConsoleComponent>().print(text);
@@ -1036,7 +1392,8 @@ impl<'a, 'b> ClientEcs<'a, 'b> {
             );
 
             self.simulation_dispatcher.dispatch(ecs_world);
-            execute_finished_skill_castings(ecs_world);
+            // the predicted casts are not manifested, the server replicates the manifestations
+            ecs_world.write_resource::<Vec<FinishCast>>().clear();
             ecs_world.write_resource::<SnapshotStorage>().tick();
             ecs_world.write_resource::<SimulationTick>().inc();
         }
@@ -1100,10 +1457,6 @@ impl<'a, 'b> ClientEcs<'a, 'b> {
                 "client_intention_to_char_target_system",
                 &[],
             );
-            ecs_dispatcher_builder =
-                ecs_dispatcher_builder.with(FrictionSystem, "friction_sys", &[]);
-            //            .with(MinionAiSystem, "minion_ai_sys", &[])
-            //                .with(TurretAiSystem, "turret_ai_sys", &[])
             //                .with(FalconAiSystem, "falcon_ai_sys", &[])
             if !for_test {
                 ecs_dispatcher_builder.add(
@@ -1129,13 +1482,9 @@ impl<'a, 'b> ClientEcs<'a, 'b> {
                     "char_state_update",
                     &["client_intention_to_char_target_system"],
                 )
-                .with(
-                    PhysCollisionCollectorSystem,
-                    "collision_collector",
-                    &["char_state_update"],
-                )
-                .with(SkillSystem, "skill_sys", &["collision_collector"])
-                .with(AttackSystem::new(), "attack_sys", &["collision_collector"])
+                .with(CollisionSystem, "collision_sys", &["char_state_update"])
+                .with(SkillSystem, "skill_sys", &["collision_sys"])
+                .with(AttackSystem::new(), "attack_sys", &["collision_sys"])
                 .with(SnapshotSystem::new(), "snapshot_sys", &["attack_sys"]);
 
             ecs_dispatcher_builder.build()
@@ -1147,6 +1496,7 @@ impl<'a, 'b> ClientEcs<'a, 'b> {
         return specs::DispatcherBuilder::new()
             .with_thread_local(ClientIntentionToCharTargetSystem)
             .with_thread_local(CharacterStateUpdateSystem)
+            .with_thread_local(CollisionSystem)
             .with_thread_local(SnapshotSystem::new())
             .build();
     }
@@ -1187,38 +1537,6 @@ fn update_desktop_inputs(video: &mut Video, ecs_world: &mut World) -> bool {
     return true;
 }
 
-fn spawn_minions(ecs_world: &mut World) -> () {
-    {
-        let entity_id = create_client_minion_entity(
-            ecs_world,
-            v2(
-                MinionAiSystem::CHECKPOINTS[0][0] as f32,
-                MinionAiSystem::CHECKPOINTS[0][1] as f32,
-            ),
-            Team::Right,
-        );
-
-        let mut storage = ecs_world.write_storage();
-        storage
-            .insert(entity_id.into(), MinionComponent { fountain_up: false })
-            .unwrap();
-    }
-    {
-        let entity_id = create_client_minion_entity(
-            ecs_world,
-            v2(
-                MinionAiSystem::CHECKPOINTS[5][0] as f32,
-                MinionAiSystem::CHECKPOINTS[5][1] as f32,
-            ),
-            Team::Left,
-        );
-        let mut storage = ecs_world.write_storage();
-        storage
-            .insert(entity_id.into(), MinionComponent { fountain_up: false })
-            .unwrap();
-    }
-}
-
 pub struct ConsoleCommandBuffer {
     commands: Vec<String>,
 }
@@ -1272,29 +1590,6 @@ fn execute_console_command(
     }
 }
 
-fn execute_finished_skill_castings(ecs_world: &mut World) {
-    // TODO: avoid allocating new vec
-    let finished_casts = std::mem::replace(
-        &mut ecs_world
-            .write_resource::<SystemVariables>()
-            .just_finished_skill_casts,
-        Vec::with_capacity(128),
-    );
-    for finished_cast in &finished_casts {
-        let manifestation = finished_cast
-            .skill
-            .get_definition()
-            .finish_cast(&finished_cast, ecs_world);
-        if let Some(manifestation) = manifestation {
-            let skill_entity_id = ecs_world.create_entity().build();
-            ecs_world.read_resource::<LazyUpdate>().insert(
-                skill_entity_id,
-                SkillManifestationComponent::new(skill_entity_id, manifestation),
-            );
-        }
-    }
-}
-
 fn get_all_effect_names(asset_loader: &GrfEntryLoader) -> Vec<String> {
     let all_str_names = asset_loader
         .asset_loader
@@ -141,9 +141,8 @@ impl<'a, 'b> OpenGlRenderSystem<'a, 'b> {
                 let two_pi = std::f32::consts::PI * 2.0;
                 let dtheta = two_pi / nsubdivs as f32;
 
-                let mut pts: Vec<Point2<f32>> = Vec::with_capacity(i);
                 let r = 12.0;
-                ncollide2d::procedural::utils::push_xy_arc(r, i as u32, dtheta, &mut pts);
+                let mut pts = arc_points(r, i, dtheta);
                 let rotation_rad = std::f32::consts::FRAC_PI_2;
 
                 pts.iter_mut().for_each(|it| {
@@ -393,9 +392,9 @@ impl<'a, 'b> OpenGlRenderSystem<'a, 'b> {
                 ],
             ),
             circle_vao: {
-                let capsule_mesh = ncollide2d::procedural::circle(&1.0, 32);
-                let coords: Vec<[f32; 9]> = capsule_mesh
-                    .coords()
+                let subdivs = 32;
+                let dtheta = std::f32::consts::PI * 2.0 / subdivs as f32;
+                let coords: Vec<[f32; 9]> = arc_points(0.5, subdivs, dtheta)
                     .iter()
                     .map(|it| [it.x, 0.0, it.y, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0])
                     .collect();
@@ -752,6 +751,16 @@ impl<'a, 'b> OpenGlRenderSystem<'a, 'b> {
     }
 }
 
+/// `count` points of a circle around the origin, starting from the x axis
+fn arc_points(radius: f32, count: usize, dtheta: f32) -> Vec<Point2<f32>> {
+    (0..count)
+        .map(|i| {
+            let theta = i as f32 * dtheta;
+            Point2::new(theta.cos() * radius, theta.sin() * radius)
+        })
+        .collect()
+}
+
 impl<'a> System<'a> for OpenGlRenderSystem<'_, '_> {
     type SystemData = (
         ReadExpect<'a, RenderCommandCollector>,
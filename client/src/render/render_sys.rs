@@ -2,13 +2,15 @@ use crate::audio::sound_sys::AudioCommandCollectorComponent;
 use crate::cam::Camera;
 use crate::client::SimulationTime;
 use crate::components::char::{
-    ActionPlayMode, CharacterStateComponent, ClientCharState, NpcComponent, SpriteBoundingRect,
+    ActionPlayMode, CharacterStateComponent, NpcComponent, SpriteBoundingRect,
     SpriteRenderDescriptorComponent,
 };
 use crate::components::controller::{
     CameraComponent, EntitiesBelowCursor, HumanInputComponent, LocalPlayerController, SkillKey,
 };
-use crate::components::skills::skills::{SkillManifestationComponent, SkillTargetType, Skills};
+use crate::components::skills::skills::{
+    ClientSkillDefs, SkillManifestationComponent, SkillTargetType, Skills,
+};
 use crate::components::{
     FlyingNumberComponent, FlyingNumberType, SoundEffectComponent, StrEffectComponent,
 };
@@ -18,12 +20,12 @@ use crate::effect::StrEffectId;
 use crate::grf::asset_async_loader::SPRITE_UPSCALE_FACTOR;
 use crate::grf::database::AssetDatabase;
 use crate::render::render_command::{RenderCommandCollector, UiLayer2d};
-use crate::runtime_assets::map::{MapRenderData, PhysicEngine};
+use crate::runtime_assets::map::MapRenderData;
 use crate::systems::snapshot_sys::SnapshotStorage;
 use crate::systems::ui::RenderUI;
 use crate::systems::{AssetResources, RenderMatrices, SystemFrameDurations, SystemVariables};
 use crate::{GameTime, Local, SpriteResource};
-use nalgebra::{Isometry2, Vector2, Vector3};
+use nalgebra::{Vector2, Vector3};
 use rustarok_common::common::SimulationTick;
 use rustarok_common::common::{EngineTime, Vec2, Vec3};
 use rustarok_common::components::char::{
@@ -61,7 +63,6 @@ impl RenderDesktopClientSystem {
         input: &HumanInputComponent,
         render_commands: &mut RenderCommandCollector,
         audio_commands: &mut AudioCommandCollectorComponent,
-        physics_world: &ReadExpect<'a, PhysicEngine>,
         assets: &AssetResources,
         time: &EngineTime,
         tick: SimulationTick,
@@ -105,55 +106,6 @@ impl RenderDesktopClientSystem {
             );
         }
 
-        {
-            let _stopwatch = system_benchmark.start_measurement("render.draw_physics_coll");
-            // Draw physics colliders
-            // TODO2 physics
-            //            for physics in (&static_char_data_storage).join() {
-            //                if let Some(collider) = physics_world.colliders.get(physics.collider_handle) {
-            //                    if collider.shape().is_shape::<ncollide2d::shape::Ball<f32>>() {
-            //                        let radius = collider
-            //                            .shape()
-            //                            .bounding_sphere(&Isometry2::new(Vector2::zeros(), 0.0))
-            //                            .radius();
-            //                        let pos = physics_world
-            //                            .bodies
-            //                            .rigid_body(physics.body_handle)
-            //                            .unwrap()
-            //                            .position()
-            //                            .translation
-            //                            .vector;
-            //                        render_commands
-            //                            .circle_3d()
-            //                            .radius(radius)
-            //                            .color(&[255, 0, 255, 255])
-            //                            .pos_2d(&pos)
-            //                            .y(0.05)
-            //                            .add();
-            //                    } else {
-            //                        let extents = collider
-            //                            .shape()
-            //                            .aabb(&Isometry2::new(Vector2::zeros(), 0.0))
-            //                            .extents();
-            //                        let pos = physics_world
-            //                            .bodies
-            //                            .rigid_body(physics.body_handle)
-            //                            .unwrap()
-            //                            .position()
-            //                            .translation
-            //                            .vector;
-            //                        render_commands
-            //                            .rectangle_3d()
-            //                            .pos_2d(&pos)
-            //                            .color(&[255, 0, 255, 255])
-            //                            .y(0.05)
-            //                            .size(extents.x, extents.y)
-            //                            .add();
-            //                    }
-            //                };
-            //            }
-        }
-
         {
             let _stopwatch = system_benchmark.start_measurement("render.models");
             render_models(
@@ -172,9 +124,9 @@ impl RenderDesktopClientSystem {
                         system_benchmark.start_measurement("render.select_skill_target");
                     let char_pos = controlled_auth_char.pos();
                     if let Some((_skill_key, skill)) = local_player.select_skill_target {
-                        let skill_def = skill.get_definition();
+                        let skill_def = skill.get_client_definition();
                         let skill_cast_attr =
-                            skill.get_cast_attributes(&dev_configs, controlled_char);
+                            skill.get_cast_attributes(&dev_configs, controlled_auth_char);
                         let (skill_3d_pos, dir_vector) = Skills::limit_vector_into_range(
                             &char_pos,
                             &input.mouse_world_pos,
@@ -189,11 +141,8 @@ impl RenderDesktopClientSystem {
                                 .color(&[0, 255, 0, 255])
                                 .add();
                             if skill_def.get_skill_target_type() == SkillTargetType::Area {
-                                let is_castable = controlled_auth_char
-                                    .skill_cast_allowed_at
-                                    .get(skill as usize)
-                                    .unwrap_or(&GameTime::from(0.0))
-                                    .has_already_passed(time.now());
+                                let is_castable =
+                                    !controlled_auth_char.is_skill_in_cooldown(skill, time.now());
                                 skill_def.render_target_selection(
                                     is_castable,
                                     &skill_3d_pos,
@@ -335,7 +284,7 @@ impl RenderDesktopClientSystem {
         desktop_target: &Option<&EntityTarget<Local>>,
     ) -> bool {
         return if let Some((_skill_key, skill)) = select_skill_target {
-            match skill.get_definition().get_skill_target_type() {
+            match skill.get_client_definition().get_skill_target_type() {
                 SkillTargetType::AnyEntity => entities_below_cursor
                     .get_enemy_or_friend()
                     .map(|it| it == rendering_entity_id)
@@ -726,7 +675,7 @@ impl RenderDesktopClientSystem {
             // TODO2 casting
             //            if let CharState::CastingSkill(casting_info) = auth_state.state() {
             //                let skill = casting_info.skill;
-            //                skill.get_definition().render_casting(
+            //                skill.get_client_definition().render_casting(
             //                    &char_state.pos(),
             //                    &casting_info,
             //                    assets,
@@ -768,7 +717,6 @@ impl<'a> System<'a> for RenderDesktopClientSystem {
         ReadStorage<'a, SkillManifestationComponent>, // TODO remove me
         ReadStorage<'a, StrEffectComponent>,
         ReadExpect<'a, CameraComponent>,
-        ReadExpect<'a, PhysicEngine>,
         Write<'a, LazyUpdate>,
         ReadStorage<'a, FlyingNumberComponent>,
         ReadStorage<'a, SoundEffectComponent>,
@@ -799,7 +747,6 @@ impl<'a> System<'a> for RenderDesktopClientSystem {
             skill_storage,
             str_effect_storage,
             camera,
-            physics_world,
             updater,
             numbers,
             sound_effects,
@@ -830,7 +777,6 @@ impl<'a> System<'a> for RenderDesktopClientSystem {
                 &input,
                 &mut render_commands,
                 &mut audio_commands,
-                &physics_world,
                 &sys_vars.assets,
                 &time,
                 *sim_time,
@@ -5,13 +5,13 @@ use sdl2::ttf::Sdl2TtfContext;
 use strum::IntoEnumIterator;
 
 use crate::components::controller::SkillKey;
-use crate::components::skills::skills::Skills;
+use crate::components::skills::skills::{ClientSkillDefs, Skills};
 use crate::grf::asset_loader::GrfEntryLoader;
 use crate::grf::database::AssetDatabase;
 use crate::grf::texture::TextureId;
 use crate::my_gl::{Gl, MyGlEnum};
-use crate::systems::console_commands::STATUS_NAMES;
 use crate::video::Video;
+use rustarok_common::console::STATUS_NAMES;
 
 pub struct Texts {
     pub skill_name_texts: HashMap<Skills, TextureId>,
@@ -51,7 +51,7 @@ pub fn load_skill_icons(
 ) -> HashMap<Skills, TextureId> {
     let mut skill_icons = HashMap::new();
     for skill in Skills::iter() {
-        let def = skill.get_definition();
+        let def = skill.get_client_definition();
         if def.get_icon_path().is_empty() {
             continue;
         }
@@ -5,20 +5,9 @@ use crate::grf::rsw::LightData;
 use crate::grf::texture::{TextureId, DUMMY_TEXTURE_ID_FOR_TEST};
 use crate::my_gl::{Gl, MyGlEnum};
 use crate::video::{VertexArray, VertexAttribDefinition};
-use nalgebra::{Rotation3, Vector2, Vector3};
-use ncollide2d::pipeline::CollisionGroups;
-use ncollide2d::shape::ShapeHandle;
-use nphysics2d::force_generator::DefaultForceGeneratorSet;
-use nphysics2d::joint::DefaultJointConstraintSet;
-use nphysics2d::object::{
-    BodyPartHandle, ColliderDesc, DefaultBodyHandle, DefaultBodySet, DefaultColliderHandle,
-    DefaultColliderSet, RigidBodyDesc,
-};
-use nphysics2d::solver::SignoriniModel;
-use nphysics2d::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
-use rustarok_common::common::{measure_time, Mat4};
-use rustarok_common::common::{v2, Vec2};
-use rustarok_common::components::char::CollisionGroup;
+use nalgebra::Vector3;
+use rustarok_common::collision::Aabb;
+use rustarok_common::common::{measure_time, Mat4, Vec2};
 use rustarok_common::grf::gat::{BlockingRectangle, Gat};
 
 pub struct ModelInstance {
@@ -30,6 +19,7 @@ pub struct ModelInstance {
 
 pub struct MapRenderData {
     pub gat: Gat,
+    pub blocking_rectangles: Vec<BlockingRectangle>,
     pub ground_width: u32,
     pub ground_height: u32,
     pub light: LightData,
@@ -86,7 +76,6 @@ struct GroundLoadResult {
 }
 
 pub fn load_map(
-    physics_world: &mut PhysicEngine,
     gl: &Gl,
     map_name: &str,
     asset_loader: &GrfEntryLoader,
@@ -99,16 +88,13 @@ pub fn load_map(
     log::info!("gat loaded: {}ms", elapsed.as_millis());
 
     log::info!("coliders");
-    let colliders: Vec<(Vec2, Vec2)> = rectangles
-        .iter()
-        .map(|cell| create_collider(physics_world, cell))
-        .collect();
+    let colliders: Vec<(Vec2, Vec2)> = rectangles.iter().map(collider_of).collect();
 
     asset_loader.start_loading_ground(
         gl,
         asset_db,
         map_name,
-        rectangles,
+        rectangles.clone(),
         gat.clone(),
         world.water.clone(),
         colliders.clone(),
@@ -197,15 +183,11 @@ pub fn load_map(
         }],
     );
 
-    physics_world
-        .mechanical_world
-        .solver
-        .set_contact_model(Box::new(SignoriniModel::new()));
-
     let minimap_texture = load_minimap_texture(gl, asset_loader, asset_db, &map_name);
 
     MapRenderData {
         gat,
+        blocking_rectangles: rectangles,
         ground_width: ground_data.ground_width,
         ground_height: ground_data.ground_height,
         light: world.light,
@@ -265,101 +247,8 @@ fn load_minimap_texture(
     });
 }
 
-fn create_collider(physics_world: &mut PhysicEngine, cell: &BlockingRectangle) -> (Vec2, Vec2) {
-    let rot = Rotation3::<f32>::new(Vector3::new(180f32.to_radians(), 0.0, 0.0));
-    let half_w = cell.width as f32 / 2.0;
-    let x = cell.start_x as f32 + half_w;
-    let half_h = cell.height as f32 / 2.0;
-    let y = (cell.bottom - cell.height) as f32 + 1.0 + half_h;
-    let half_extents = v2(half_w, half_h);
-
-    let cuboid = ShapeHandle::new(ncollide2d::shape::Cuboid::new(half_extents));
-    let v = rot * Vector3::new(x, 0.0, y);
-    let v2 = v2(v.x, v.z);
-    let parent_rigid_body = RigidBodyDesc::new()
-        .translation(v2)
-        .gravity_enabled(false)
-        .status(nphysics2d::object::BodyStatus::Static)
-        .build();
-    let parent_handle = physics_world.bodies.insert(parent_rigid_body);
-    let cuboid = ColliderDesc::new(cuboid)
-        .density(10.0)
-        .collision_groups(
-            CollisionGroups::new()
-                .with_membership(&[CollisionGroup::StaticModel as usize])
-                .with_blacklist(&[
-                    CollisionGroup::StaticModel as usize,
-                    CollisionGroup::NonCollidablePlayer as usize,
-                ]),
-        )
-        .build(BodyPartHandle(parent_handle, 0));
-    let cuboid_pos = cuboid.position_wrt_body().translation.vector;
-    physics_world.colliders.insert(cuboid);
-    (half_extents, cuboid_pos)
-}
-
-pub struct PhysicEngine {
-    pub mechanical_world: DefaultMechanicalWorld<f32>,
-    pub geometrical_world: DefaultGeometricalWorld<f32>,
-
-    pub bodies: DefaultBodySet<f32>,
-    pub colliders: DefaultColliderSet<f32>,
-    pub joint_constraints: DefaultJointConstraintSet<f32>,
-    pub force_generators: DefaultForceGeneratorSet<f32>,
-}
-
-impl PhysicEngine {
-    pub fn new() -> PhysicEngine {
-        PhysicEngine {
-            mechanical_world: DefaultMechanicalWorld::new(Vector2::zeros()),
-            geometrical_world: DefaultGeometricalWorld::new(),
-
-            bodies: DefaultBodySet::new(),
-            colliders: DefaultColliderSet::new(),
-            joint_constraints: DefaultJointConstraintSet::new(),
-            force_generators: DefaultForceGeneratorSet::new(),
-        }
-    }
-
-    pub fn step(&mut self, dt: f32) {
-        self.mechanical_world.set_timestep(dt);
-        self.mechanical_world.step(
-            &mut self.geometrical_world,
-            &mut self.bodies,
-            &mut self.colliders,
-            &mut self.joint_constraints,
-            &mut self.force_generators,
-        );
-    }
-
-    pub fn add_cuboid_skill_area(
-        &mut self,
-        pos: Vec2,
-        rot_angle_in_rad: f32,
-        extent: Vec2,
-    ) -> (DefaultColliderHandle, DefaultBodyHandle) {
-        let cuboid = ShapeHandle::new(ncollide2d::shape::Cuboid::new(extent / 2.0));
-        let body_handle = self.bodies.insert(
-            RigidBodyDesc::new()
-                .status(nphysics2d::object::BodyStatus::Static)
-                .gravity_enabled(false)
-                .build(),
-        );
-        let coll_handle = self.colliders.insert(
-            ColliderDesc::new(cuboid)
-                .translation(pos)
-                .rotation(rot_angle_in_rad.to_degrees())
-                .collision_groups(
-                    CollisionGroups::new()
-                        .with_membership(&[CollisionGroup::SkillArea as usize])
-                        .with_blacklist(&[
-                            CollisionGroup::StaticModel as usize,
-                            CollisionGroup::SkillArea as usize,
-                        ]),
-                )
-                .sensor(true)
-                .build(BodyPartHandle(body_handle, 0)),
-        );
-        return (coll_handle, body_handle);
-    }
+/// (half extents, center) of the rectangle, the walls are handled by `MapWalkingInfo`
+fn collider_of(cell: &BlockingRectangle) -> (Vec2, Vec2) {
+    let aabb = Aabb::from_blocking_rectangle(cell);
+    ((aabb.max - aabb.min) / 2.0, (aabb.min + aabb.max) / 2.0)
 }
@@ -1,5 +1,3 @@
-use nalgebra::Isometry2;
-use ncollide2d::query::Proximity;
 use specs::prelude::*;
 use specs::LazyUpdate;
 
@@ -11,11 +9,12 @@ use crate::components::status::status::{
 use crate::components::{FlyingNumberComponent, FlyingNumberType, SoundEffectComponent};
 use crate::runtime_assets::audio::Sounds;
 use crate::systems::{SystemEvent, SystemFrameDurations, SystemVariables};
-use crate::{GameTime, Local, PhysicEngine};
+use crate::{GameTime, Local};
 use rustarok_common::attack::{
     ApplyForceComponent, AreaAttackComponent, DamageDisplayType, HpModificationRequest,
     HpModificationResult, HpModificationResultType, HpModificationType, WeaponType,
 };
+use rustarok_common::collision::CHAR_COLLISION_RADIUS;
 use rustarok_common::common::SimulationTick;
 use rustarok_common::common::{percentage, EngineTime, Percentage, Vec2};
 use rustarok_common::components::char::{
@@ -38,7 +37,6 @@ impl<'a> System<'a> for AttackSystem {
         WriteStorage<'a, LocalCharStateComp<Local>>,
         WriteStorage<'a, StaticCharDataComponent>,
         WriteExpect<'a, SystemVariables>,
-        WriteExpect<'a, PhysicEngine>,
         WriteExpect<'a, SystemFrameDurations>,
         ReadExpect<'a, EngineTime>,
         ReadExpect<'a, SimulationTick>,
@@ -58,7 +56,6 @@ impl<'a> System<'a> for AttackSystem {
             mut auth_char_state_storage,
             mut static_char_data_storage,
             mut sys_vars,
-            mut physics_world,
             mut system_benchmark,
             time,
             tick,
@@ -91,28 +88,20 @@ impl<'a> System<'a> for AttackSystem {
         sys_vars.apply_statuses.append(&mut new_status_applies);
         sys_vars.apply_area_statuses.clear();
 
-        for apply_force in pushes.iter() {
-            //            TODO2 push
-            //            if let Some(char_body) = physics_world.bodies.rigid_body_mut(apply_force.body_handle) {
-            //                let char_state = char_state_storage
-            //                    .get_mut(apply_force.dst_entity.into())
-            //                    .unwrap();
-            //                log::trace!("Try to apply push {:?}", apply_force);
-            //                if char_state.statuses.allow_push(apply_force) {
-            //                    log::trace!("Push was allowed");
-            //                    char_body.set_linear_velocity(apply_force.force);
-            //                    let char_state = char_state_storage
-            //                        .get_mut(apply_force.dst_entity.into())
-            //                        .unwrap();
-            //                    char_state
-            //                        .cannot_control_until
-            //                        .run_at_least_until_seconds(time.now(), apply_force.duration);
-            //                } else {
-            //                    log::trace!("Push was denied");
-            //                }
-            //            }
+        for apply_force in pushes.drain(..) {
+            let allowed = char_state_storage
+                .get_mut(apply_force.dst_entity.into())
+                .map(|char_state| char_state.statuses.allow_push(&apply_force))
+                .unwrap_or(true);
+            if !allowed {
+                log::trace!("Push was denied {:?}", apply_force);
+                continue;
+            }
+            if let Some(auth_state) = auth_char_state_storage.get_mut(apply_force.dst_entity.into())
+            {
+                auth_state.apply_force(&apply_force, time.now());
+            }
         }
-        pushes.clear();
 
         // TODO: use a preallocated backbuffer
         let status_changes =
@@ -125,7 +114,6 @@ impl<'a> System<'a> for AttackSystem {
             &time,
             &entities,
             &mut updater,
-            &mut physics_world,
             &dev_configs,
         );
 
@@ -174,16 +162,10 @@ impl AttackCalculation {
             {
                 continue;
             }
-            // for optimized, shape-specific queries
-            // ncollide2d::query::distance_internal::
-            let coll_result = ncollide2d::query::proximity(
-                &area_status.area_isom,
-                &*area_status.area_shape,
-                &Isometry2::new(target_auth_char.pos(), 0.0),
-                &ncollide2d::shape::Ball::new(1.0),
-                0.0,
-            );
-            if coll_result == Proximity::Intersecting {
+            if area_status
+                .area_shape
+                .overlaps_circle(&target_auth_char.pos(), CHAR_COLLISION_RADIUS)
+            {
                 result_statuses.push(ApplyStatusComponent {
                     source_entity_id: area_status.source_entity_id,
                     target_entity_id,
@@ -320,7 +302,6 @@ impl AttackSystem {
         time: &EngineTime,
         entities: &Entities,
         updater: &mut LazyUpdate,
-        physics_world: &mut PhysicEngine,
         dev_configs: &CommonConfigs,
     ) {
         for mut status_change in status_changes.into_iter() {
@@ -344,17 +325,10 @@ impl AttackSystem {
                     updater,
                     &sys_vars.assets,
                     time,
-                    physics_world,
                 );
                 // TODO2 statuses
                 // target_char.statuses.add(status_change.status);
-                target_char.recalc_attribs_based_on_statuses(
-                    char_static_state_storage
-                        .get(status_change.target_entity_id.into())
-                        .unwrap()
-                        .job_id,
-                    dev_configs,
-                );
+                // the server recalculates the attributes, they arrive in the snapshots
                 log::trace!(
                     "Status added. Attributes({:?}): current: {:?}",
                     target_entity_id,
@@ -368,10 +342,7 @@ impl AttackSystem {
         target_char: &StaticCharDataComponent,
         configs: &CommonConfigs,
     ) -> Percentage {
-        return match target_char.job_id {
-            JobId::CRUSADER => configs.stats.player.crusader.mounted_speedup,
-            _ => percentage(30),
-        };
+        configs.job(target_char.job_id).mounted_speedup
     }
 
     fn remove_statuses(
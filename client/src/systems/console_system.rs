@@ -3,12 +3,10 @@ use crate::components::controller::HumanInputComponent;
 use crate::render::opengl_render_sys::{NORMAL_FONT_H, NORMAL_FONT_W};
 use crate::render::render_command::{Font, RenderCommandCollector, UiLayer2d};
 use crate::systems::console_commands::{
-    cmd_add_falcon, cmd_add_status, cmd_bind_key, cmd_clear, cmd_clone_char, cmd_control_char,
-    cmd_disable_collision, cmd_enable_collision, cmd_follow_char, cmd_get_pos, cmd_goto, cmd_heal,
-    cmd_inspect, cmd_kill_all, cmd_list_entities, cmd_list_players, cmd_list_statuses,
-    cmd_reload_configs, cmd_remove_falcon, cmd_resurrect, cmd_set_config, cmd_set_damping,
-    cmd_set_fullscreen, cmd_set_job, cmd_set_mass, cmd_set_outlook, cmd_set_pos,
-    cmd_set_resolution, cmd_set_team, cmd_spawn_area, cmd_spawn_entity, cmd_toggle_console,
+    cmd_add_falcon, cmd_bind_key, cmd_clear, cmd_control_char, cmd_executed_by_server,
+    cmd_follow_char, cmd_get_pos, cmd_inspect, cmd_list_entities, cmd_list_players,
+    cmd_list_statuses, cmd_remove_falcon, cmd_set_config, cmd_set_fullscreen, cmd_set_resolution,
+    cmd_toggle_console,
 };
 use crate::systems::SystemVariables;
 use crate::video::Video;
@@ -16,7 +14,10 @@ use crate::GameTime;
 use rustarok_common::common::{EngineTime, Local};
 use rustarok_common::components::char::{CharType, EntityId, StaticCharDataComponent};
 use rustarok_common::config::CommonConfigs;
-use rustarok_common::console::{CommandArguments, CommandElement};
+pub use rustarok_common::console::CommandParamType;
+use rustarok_common::console::{
+    validate_arguments, CommandArguments, CommandElement, SERVER_COMMANDS,
+};
 use rustarok_common::packets::to_server::ToServerPacket::ConsoleCommand;
 use sdl2::keyboard::Scancode;
 use specs::prelude::*;
@@ -450,51 +451,9 @@ impl ConsoleComponent {
         self.history_pos = 0;
         // validate input
         if let Some(command_def) = command_def {
-            let mandatory_arg_count = command_def.arguments.iter().take_while(|it| it.2).count();
-            let actual_arg_count = args.args.len() - 1;
-            if actual_arg_count < mandatory_arg_count
-                || actual_arg_count > command_def.arguments.len()
-            {
-                self.error(&format!(
-                    "Illegal number of parameters (expected at least {}, at most {}, provided {})",
-                    mandatory_arg_count,
-                    command_def.arguments.len(),
-                    actual_arg_count
-                ));
-                return;
-            }
-
-            let ok = command_def.arguments.iter().enumerate().all(
-                |(i, (param_name, arg_type, mandatory))| {
-                    let ok = match arg_type {
-                        CommandParamType::Float => args
-                            .as_str(i)
-                            .map(|it| it.parse::<f32>().is_ok())
-                            .unwrap_or(!*mandatory),
-                        CommandParamType::Int => args
-                            .as_str(i)
-                            .map(|it| it.parse::<i32>().is_ok())
-                            .unwrap_or(!*mandatory),
-                        CommandParamType::String => true,
-                    };
-                    if !ok {
-                        self.error(&format!(
-                            "{}, the {}. parameter ('{}') must be {}",
-                            param_name,
-                            i,
-                            args.as_str(i).unwrap_or(""),
-                            match *arg_type {
-                                CommandParamType::Float => "float",
-                                CommandParamType::Int => "int",
-                                CommandParamType::String => "string",
-                            }
-                        ));
-                    }
-                    ok
-                },
-            );
-            if ok {
-                self.command_to_execute = Some(args);
+            match validate_arguments(&command_def.arguments, &args) {
+                Ok(()) => self.command_to_execute = Some(args),
+                Err(e) => self.error(&e),
             }
         } else {
             self.error("Unknown command")
@@ -630,39 +589,25 @@ impl ConsoleSystem {
         resolutions: Vec<String>,
     ) -> HashMap<String, CommandDefinition> {
         let mut command_defs: HashMap<String, CommandDefinition> = HashMap::new();
-        ConsoleSystem::add_command(&mut command_defs, cmd_set_pos());
         ConsoleSystem::add_command(&mut command_defs, cmd_get_pos());
-        ConsoleSystem::add_command(&mut command_defs, cmd_add_status());
         ConsoleSystem::add_command(&mut command_defs, cmd_list_statuses());
         ConsoleSystem::add_command(&mut command_defs, cmd_list_players());
         ConsoleSystem::add_command(&mut command_defs, cmd_set_resolution(resolutions));
         ConsoleSystem::add_command(&mut command_defs, cmd_set_fullscreen());
         ConsoleSystem::add_command(&mut command_defs, cmd_list_entities());
         //        ConsoleSystem::add_command(&mut command_defs, cmd_spawn_effect(effect_names));
-        ConsoleSystem::add_command(&mut command_defs, cmd_spawn_area());
-        ConsoleSystem::add_command(&mut command_defs, cmd_spawn_entity());
-        ConsoleSystem::add_command(&mut command_defs, cmd_reload_configs());
-        ConsoleSystem::add_command(&mut command_defs, cmd_heal());
-        ConsoleSystem::add_command(&mut command_defs, cmd_kill_all());
-        ConsoleSystem::add_command(&mut command_defs, cmd_goto());
         ConsoleSystem::add_command(&mut command_defs, cmd_follow_char());
         ConsoleSystem::add_command(&mut command_defs, cmd_control_char());
-        ConsoleSystem::add_command(&mut command_defs, cmd_set_outlook());
-        ConsoleSystem::add_command(&mut command_defs, cmd_resurrect());
-        ConsoleSystem::add_command(&mut command_defs, cmd_set_team());
-        ConsoleSystem::add_command(&mut command_defs, cmd_set_damping());
-        ConsoleSystem::add_command(&mut command_defs, cmd_set_mass());
         ConsoleSystem::add_command(&mut command_defs, cmd_clear());
         ConsoleSystem::add_command(&mut command_defs, cmd_add_falcon());
         ConsoleSystem::add_command(&mut command_defs, cmd_remove_falcon());
-        ConsoleSystem::add_command(&mut command_defs, cmd_set_job());
-        ConsoleSystem::add_command(&mut command_defs, cmd_enable_collision());
-        ConsoleSystem::add_command(&mut command_defs, cmd_disable_collision());
-        ConsoleSystem::add_command(&mut command_defs, cmd_clone_char());
         ConsoleSystem::add_command(&mut command_defs, cmd_bind_key());
         ConsoleSystem::add_command(&mut command_defs, cmd_toggle_console());
         ConsoleSystem::add_command(&mut command_defs, cmd_inspect());
         ConsoleSystem::add_command(&mut command_defs, cmd_set_config());
+        for command_def in SERVER_COMMANDS {
+            ConsoleSystem::add_command(&mut command_defs, cmd_executed_by_server(command_def));
+        }
 
         return command_defs;
     }
@@ -676,13 +621,6 @@ trait AutocompletionGenerator {
     fn create_autocompletion_list(&self) -> Vec<String>;
 }
 
-#[derive(Copy, Clone)]
-pub enum CommandParamType {
-    String,
-    Int,
-    Float,
-}
-
 pub trait AutocompletionProvider {
     fn get_autocompletion_list(
         &self,
@@ -769,6 +707,25 @@ impl AutocompletionProvider for AutocompletionProviderWithUsernameCompletion {
     }
 }
 
+/// Completes the parameters based on the shared metadata of the server commands
+pub struct SharedAutocompletionProvider(pub &'static rustarok_common::console::CommandDefinition);
+
+impl AutocompletionProvider for SharedAutocompletionProvider {
+    fn get_autocompletion_list(
+        &self,
+        param_index: usize,
+        input: &ReadStorage<StaticCharDataComponent>,
+    ) -> Option<Vec<String>> {
+        self.0.completion_list(param_index, || {
+            input
+                .join()
+                .filter(|it| it.typ == CharType::Player)
+                .map(|it| it.name.clone())
+                .collect()
+        })
+    }
+}
+
 pub struct CommandDefinition {
     pub name: String,
     pub arguments: Vec<(&'static str, CommandParamType, bool)>, // name, type, mandatory
@@ -1,12 +1,7 @@
-use crate::components::char::{
-    CharActionIndex, CharacterStateComponent, SpriteRenderDescriptorComponent,
-};
+use crate::components::char::{CharActionIndex, SpriteRenderDescriptorComponent};
 use crate::components::controller::LocalPlayerController;
-use crate::components::skills::falcon_carry::FalconCarryStatus;
-use crate::components::status::status::{ApplyStatusComponent, StatusEnum};
-use crate::runtime_assets::map::PhysicEngine;
-use crate::systems::{SystemFrameDurations, SystemVariables};
-use nalgebra::{Isometry2, Vector2, Vector3};
+use crate::systems::SystemFrameDurations;
+use nalgebra::{Vector2, Vector3};
 use rustarok_common::common::{v2, v2_to_v3, v3, v3_to_v2, EngineTime, GameTime, Local, Vec2};
 use rustarok_common::components::char::{
     CharDir, ControllerEntityId, EntityId, LocalCharStateComp,
@@ -151,8 +146,6 @@ impl<'a> System<'a> for FalconAiSystem {
         ReadStorage<'a, ControllerComponent>,
         ReadExpect<'a, LocalPlayerController>,
         WriteExpect<'a, SystemFrameDurations>,
-        WriteExpect<'a, PhysicEngine>,
-        WriteExpect<'a, SystemVariables>,
         ReadExpect<'a, EngineTime>,
     );
 
@@ -166,8 +159,6 @@ impl<'a> System<'a> for FalconAiSystem {
             controller_storage,
             local_player,
             mut system_benchmark,
-            mut physics_world,
-            mut sys_vars,
             time,
         ): Self::SystemData,
     ) {
@@ -233,18 +224,6 @@ impl<'a> System<'a> for FalconAiSystem {
                     } else if duration_percentage < 1.0 {
                         if !target_is_caught {
                             sprite.action_index = CharActionIndex::Idle as usize;
-                            sys_vars
-                                .apply_statuses
-                                .push(ApplyStatusComponent::from_status(
-                                    falcon.owner_entity_id,
-                                    falcon.owner_entity_id,
-                                    StatusEnum::FalconCarryStatus(FalconCarryStatus {
-                                        started_at,
-                                        ends_at,
-                                        carry_owner: true,
-                                        end_pos: Vector2::zeros(),
-                                    }),
-                                ));
                             falcon.state = FalconState::CarryOwner {
                                 owner_controller_id,
                                 started_at,
@@ -303,21 +282,7 @@ impl<'a> System<'a> for FalconAiSystem {
                                 falcon.pos += dir * falcon.acceleration;
                             }
                         }
-
-                        if let Some(target) = auth_char_storage.get(falcon.owner_entity_id.into()) {
-                            // TODO4 falcon
-                            //                            let body = physics_world
-                            //                                .bodies
-                            //                                .rigid_body_mut(target.body_handle)
-                            //                                .unwrap();
-                            //                            body.set_position(Isometry2::translation(falcon.pos.x, falcon.pos.z));
-                            //                            target.set_y(falcon.pos.y - 2.5);
-                        }
                     } else {
-                        if let Some(target) = auth_char_storage.get(falcon.owner_entity_id.into()) {
-                            // TODO4 falcon
-                            //                            target.set_y(0.0);
-                        }
                         falcon.state = FalconState::Follow;
                         sprite.action_index = CharActionIndex::Idle as usize;
                     }
@@ -363,18 +328,6 @@ impl<'a> System<'a> for FalconAiSystem {
                                         end_pos.y,
                                     ),
                                 };
-                                sys_vars
-                                    .apply_statuses
-                                    .push(ApplyStatusComponent::from_status(
-                                        falcon.owner_entity_id,
-                                        target_id,
-                                        StatusEnum::FalconCarryStatus(FalconCarryStatus {
-                                            started_at,
-                                            ends_at,
-                                            carry_owner: false,
-                                            end_pos,
-                                        }),
-                                    ));
                                 falcon.state = FalconState::CarryAlly {
                                     target_id,
                                     start_pos,
@@ -391,21 +344,8 @@ impl<'a> System<'a> for FalconAiSystem {
                         let duration_percentage = (duration_percentage - 0.3) / 0.7;
                         let pos = falcon.bezier.evaluate(duration_percentage);
                         falcon.pos = v3(pos.x, pos.y, pos.z);
-                        if let Some(target) = auth_char_storage.get(target_id.into()) {
-                            // TODO4 falcon
-                            //                            let body = physics_world
-                            //                                .bodies
-                            //                                .rigid_body_mut(target.body_handle)
-                            //                                .unwrap();
-                            //                            body.set_position(Isometry2::translation(falcon.pos.x, falcon.pos.z));
-                            //                            target.set_y(falcon.pos.y - 2.5);
-                        }
                         sprite.direction = CharDir::determine_dir(&end_pos, &v3_to_v2(&falcon.pos));
                     } else {
-                        // TODO4 falcon
-                        if let Some(target) = auth_char_storage.get(target_id.into()) {
-                            //                            target.set_y(0.0);
-                        }
                         falcon.state = FalconState::Follow;
                         sprite.action_index = CharActionIndex::Idle as usize;
                     }
@@ -1,5 +1,5 @@
 use crate::components::controller::{CameraComponent, CameraMode, HumanInputComponent, SkillKey};
-use crate::components::skills::skills::{SkillTargetType, Skills};
+use crate::components::skills::skills::{ClientSkillDefs, SkillTargetType, Skills};
 use crate::systems::RenderMatrices;
 use crate::systems::SystemVariables;
 use crate::ConsoleCommandBuffer;
@@ -243,11 +243,9 @@ impl InputConsumerSystem {
         mouse_pos: Vec2,
     ) -> Option<PlayerIntention<Local>> {
         // NoTarget skills have to be casted immediately without selecting target
-        if skill.get_definition().get_skill_target_type() == SkillTargetType::NoTarget {
+        if skill.get_client_definition().get_skill_target_type() == SkillTargetType::NoTarget {
             log::debug!("Skill '{:?}' is no target, so cast it", skill);
-            // TODO2
-            //Some(PlayerIntention::Casting(skill, false, mouse_pos))
-            None
+            Some(PlayerIntention::Casting(skill, false, mouse_pos, None))
         } else {
             None
         }
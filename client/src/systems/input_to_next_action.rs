@@ -3,18 +3,19 @@ use crate::components::char::CharacterStateComponent;
 use crate::components::controller::{
     CastMode, HumanInputComponent, LocalPlayerController, SkillKey,
 };
-use crate::components::skills::skills::{SkillTargetType, Skills};
+use crate::components::skills::skills::{ClientSkillDefs, SkillTargetType, Skills};
 use crate::cursor::{CursorFrame, CURSOR_CLICK, CURSOR_NORMAL, CURSOR_STOP, CURSOR_TARGET};
 use crate::runtime_assets::map::MapRenderData;
 use crate::systems::input_sys::InputConsumerSystem;
 use crate::systems::{SystemFrameDurations, SystemVariables};
 use crate::GameTime;
 use rustarok_common::common::SimulationTick;
-use rustarok_common::common::{EngineTime, Local};
+use rustarok_common::common::{EngineTime, Local, Vec2};
 use rustarok_common::components::char::{
     EntityId, LocalCharStateComp, StaticCharDataComponent, Team,
 };
 use rustarok_common::components::controller::PlayerIntention;
+use rustarok_common::config::CommonConfigs;
 use rustarok_common::systems::intention_applier::ControllerIntentionToCharTarget;
 use sdl2::keyboard::Scancode;
 use specs::prelude::*;
@@ -145,16 +146,17 @@ impl InputToNextActionSystem {
 
         // in console mode, only moving around is allowed
         if input.is_console_open {
+            let controller = &mut local_player.controller;
             if let Some(next_action) = &controller.intention {
                 match next_action {
                     PlayerIntention::MoveTo(_) => {}
                     PlayerIntention::MoveTowardsMouse(_) => {}
                     PlayerIntention::Attack(_) => {}
-                    PlayerIntention::AttackTowards(_) => {} // TODO2
-                                                            //                        PlayerIntention::Casting(_, _, _) => {
-                                                            //                            log::debug!("...but the console is open");
-                                                            //                            controller.next_action = None;
-                                                            //                        }
+                    PlayerIntention::AttackTowards(_) => {}
+                    PlayerIntention::Casting(..) => {
+                        log::debug!("...but the console is open");
+                        controller.intention = None;
+                    }
                 }
             }
         }
@@ -196,13 +198,29 @@ pub struct ClientIntentionToCharTargetSystem;
 impl<'a> System<'a> for ClientIntentionToCharTargetSystem {
     type SystemData = (
         WriteStorage<'a, LocalCharStateComp<Local>>,
+        ReadStorage<'a, StaticCharDataComponent>,
         ReadExpect<'a, LocalPlayerController>,
+        ReadExpect<'a, EngineTime>,
+        ReadExpect<'a, CommonConfigs>,
     );
 
-    fn run(&mut self, (mut auth_char_state_storage, local_player): Self::SystemData) {
+    fn run(
+        &mut self,
+        (
+            mut auth_char_state_storage,
+            static_char_data_storage,
+            local_player,
+            time,
+            configs,
+        ): Self::SystemData,
+    ) {
         ControllerIntentionToCharTarget::controller_intention_to_char_target(
             &local_player.controller,
             &mut auth_char_state_storage,
+            &static_char_data_storage,
+            time.now(),
+            &configs,
+            None,
         )
     }
 }
@@ -216,15 +234,15 @@ impl InputToNextActionSystem {
         static_char_data_storage: &ReadStorage<StaticCharDataComponent>,
         self_team: Team,
     ) -> (CursorFrame, [u8; 3]) {
-        return if let Some((skill_key, skill)) = local_player.select_skill_target {
-            let is_castable = auth_char_state_storage
+        return if let Some((_skill_key, skill)) = local_player.select_skill_target {
+            let is_castable = !auth_char_state_storage
                 .get(controlled_entity.into())
                 .unwrap()
-                .skill_cast_allowed_at[skill_key as usize]
-                .has_already_passed(now);
+                .is_skill_in_cooldown(skill, now);
             if !is_castable {
                 (CURSOR_STOP, [255, 255, 255])
-            } else if skill.get_definition().get_skill_target_type() != SkillTargetType::Area {
+            } else if skill.get_client_definition().get_skill_target_type() != SkillTargetType::Area
+            {
                 (CURSOR_TARGET, [255, 255, 255])
             } else {
                 (CURSOR_CLICK, [255, 255, 255])
@@ -257,6 +275,28 @@ impl InputToNextActionSystem {
 }
 
 impl InputToNextActionSystem {
+    fn casting_intention(
+        skill: Skills,
+        is_self_cast: bool,
+        local_player: &LocalPlayerController,
+        mouse_world_pos: Vec2,
+    ) -> PlayerIntention<Local> {
+        let entities_below_cursor = &local_player.entities_below_cursor;
+        let target_entity = match skill.get_skill_target_type() {
+            SkillTargetType::AnyEntity => entities_below_cursor.get_enemy_or_friend(),
+            SkillTargetType::NoTarget | SkillTargetType::Area | SkillTargetType::Directional => {
+                None
+            }
+            SkillTargetType::OnlyAllyButNoSelf => local_player
+                .controller
+                .controlled_entity
+                .and_then(|self_id| entities_below_cursor.get_friend_except(self_id)),
+            SkillTargetType::OnlyAllyAndSelf => entities_below_cursor.get_friend(),
+            SkillTargetType::OnlyEnemy => entities_below_cursor.get_enemy(),
+        };
+        PlayerIntention::Casting(skill, is_self_cast, mouse_world_pos, target_entity)
+    }
+
     fn determine_intention(
         auth_char_state_storage: &ReadStorage<LocalCharStateComp<Local>>,
         input: &HumanInputComponent,
@@ -282,13 +322,15 @@ impl InputToNextActionSystem {
                     CastMode::Normal => {
                         if input.left_mouse_released {
                             log::debug!("Player wants to cast {:?}", skill);
-                            // TODO2
-                            //                                Some(PlayerIntention::Casting(
-                            //                                    skill,
-                            //                                    false,
-                            //                                    input.mouse_world_pos,
-                            //                                ))
-                            (None, None)
+                            (
+                                Some(InputToNextActionSystem::casting_intention(
+                                    skill,
+                                    false,
+                                    local_player,
+                                    input.mouse_world_pos,
+                                )),
+                                None,
+                            )
                         } else if input.right_mouse_pressed
                             || input.is_key_just_pressed(Scancode::Escape)
                         {
@@ -322,16 +364,17 @@ impl InputToNextActionSystem {
                     CastMode::OnKeyRelease => {
                         if input.is_key_just_released(casting_skill_key.scancode()) {
                             log::debug!("Player wants to cast {:?}", skill);
-                            // TODO2
-                            //                                Some(
-                            //                                    PlayerIntention::Casting(
-                            //                                        input.get_skill_for_key(casting_skill_key)
-                            //                                            .expect("'is_casting_selection' must be Some only if the casting skill is valid! "),
-                            //                                        false,
-                            //                                        input.mouse_world_pos,
-                            //                                    )
-                            //                                )
-                            (None, None)
+                            (
+                                Some(InputToNextActionSystem::casting_intention(
+                                    input.get_skill_for_key(casting_skill_key).expect(
+                                        "'is_casting_selection' must be Some only if the casting skill is valid! ",
+                                    ),
+                                    false,
+                                    local_player,
+                                    input.mouse_world_pos,
+                                )),
+                                None,
+                            )
                         } else if input.right_mouse_pressed
                             || input.is_key_just_pressed(Scancode::Escape)
                         {
@@ -378,13 +421,15 @@ impl InputToNextActionSystem {
                 }
                 CastMode::OnKeyPress => {
                     log::debug!("Player wants to cast {:?}, alt={:?}", skill, alt_down);
-                    // TODO2
-                    //                        Some(PlayerIntention::Casting(
-                    //                            skill,
-                    //                            alt_down,
-                    //                            input.mouse_world_pos,
-                    //                        ))
-                    (None, None)
+                    (
+                        Some(InputToNextActionSystem::casting_intention(
+                            skill,
+                            alt_down,
+                            local_player,
+                            input.mouse_world_pos,
+                        )),
+                        None,
+                    )
                 }
             }
         } else if let Some((_skill_key, skill)) = just_released_skill_key.and_then(|skill_key| {
@@ -395,9 +440,15 @@ impl InputToNextActionSystem {
             // can get here only when alt was down and OnKeyRelease
             if alt_down {
                 log::debug!("Player wants to cast {:?}, SELF", skill);
-                // TODO2
-                //                    Some(PlayerIntention::Casting(skill, true, input.mouse_world_pos))
-                (None, local_player.select_skill_target)
+                (
+                    Some(InputToNextActionSystem::casting_intention(
+                        skill,
+                        true,
+                        local_player,
+                        input.mouse_world_pos,
+                    )),
+                    local_player.select_skill_target,
+                )
             } else {
                 (None, local_player.select_skill_target)
             }
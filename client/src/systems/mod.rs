@@ -1,4 +1,4 @@
-use crate::components::skills::skills::{FinishCast, Skills};
+use crate::components::skills::skills::Skills;
 use crate::components::status::status::{
     ApplyStatusComponent, ApplyStatusInAreaComponent, RemoveStatusComponent,
 };
@@ -10,7 +10,6 @@ use crate::runtime_assets::graphic::Texts;
 use crate::strum::IntoEnumIterator;
 use crate::video::ortho;
 use crate::SpriteResource;
-use nphysics2d::object::DefaultColliderHandle;
 use rustarok_common::common::SimulationTick;
 use rustarok_common::common::{Local, Mat4};
 use rustarok_common::components::char::{CharState, EntityId, JobId, MonsterId};
@@ -29,12 +28,9 @@ pub mod input_sys;
 pub mod input_sys_scancodes;
 pub mod input_to_next_action;
 pub mod intention_sender_sys;
-pub mod minion_ai_sys;
 pub mod next_action_applier_sys;
-pub mod phys;
 pub mod skill_sys;
 pub mod snapshot_sys;
-pub mod turret_ai_sys;
 pub mod ui;
 
 pub struct EffectSprites {
@@ -174,7 +170,6 @@ pub struct SystemVariables {
     pub assets: AssetResources,
     pub matrices: RenderMatrices,
     pub apply_statuses: Vec<ApplyStatusComponent>,
-    pub just_finished_skill_casts: Vec<FinishCast>,
     pub apply_area_statuses: Vec<ApplyStatusInAreaComponent>,
     pub remove_statuses: Vec<RemoveStatusComponent>,
 }
@@ -203,32 +198,12 @@ impl SystemVariables {
             },
             matrices: render_matrices,
             apply_statuses: Vec::with_capacity(128),
-            just_finished_skill_casts: Vec::with_capacity(128),
             apply_area_statuses: Vec::with_capacity(128),
             remove_statuses: Vec::with_capacity(128),
         }
     }
 }
 
-#[derive(Debug)]
-pub struct Collision {
-    pub character_coll_handle: DefaultColliderHandle,
-    pub other_coll_handle: DefaultColliderHandle,
-}
-
-#[derive(Debug)]
-pub struct CollisionsFromPrevFrame {
-    pub collisions: HashMap<(DefaultColliderHandle, DefaultColliderHandle), Collision>,
-}
-
-impl CollisionsFromPrevFrame {
-    pub fn remove_collider_handle(&mut self, collider_handle: DefaultColliderHandle) {
-        self.collisions.retain(|(coll_1, coll_2), _collision| {
-            *coll_1 != collider_handle && *coll_2 != collider_handle
-        });
-    }
-}
-
 #[derive(Clone)]
 pub struct SystemFrameDurationsFrame {
     pub min: u32,
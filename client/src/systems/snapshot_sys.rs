@@ -136,6 +136,11 @@ impl SnapshotStorage {
         });
     }
 
+    pub fn remove_predicting_entity(&mut self, server_id: EntityId<Remote>) {
+        self.snapshots_for_each_char
+            .retain(|it| it.server_id != server_id);
+    }
+
     pub fn set_client_last_command_id(&mut self, cid: u32) {
         self.client_last_command_id = cid;
     }
@@ -163,6 +168,21 @@ impl SnapshotStorage {
         return &char_snapshots.get_snapshot(self.last_acknowledged_index);
     }
 
+    /// The predictions which the next acknowledged server state is compared to
+    pub fn get_predicted_states_for_next_ack(
+        &self,
+    ) -> Vec<(EntityId<Remote>, &LocalCharStateComp<Local>)> {
+        self.snapshots_for_each_char
+            .iter()
+            .map(|it| {
+                (
+                    it.server_id,
+                    it.get_snapshot(self.last_acknowledged_index + 1),
+                )
+            })
+            .collect()
+    }
+
     pub fn get_last_rollback_at(&self) -> SimulationTick {
         self.last_rollback_at
     }
@@ -473,6 +493,7 @@ impl SnapshotStorage {
                     CharState::Dead => false,
                     CharState::Attacking { .. } => false,
                     CharState::StandBy => false,
+                    CharState::CastingSkill(..) => false,
                     CharState::Walking(..) => {
                         // if the pos are the same but the target pos differs, don't repredict
                         // TODO but set the new target pos for remote entities
@@ -16,11 +16,9 @@ use crate::render::render_command::RenderCommandCollector;
 use crate::runtime_assets::audio::Sounds;
 use crate::runtime_assets::ecs::create_ecs_world;
 use crate::runtime_assets::graphic::Texts;
-use crate::runtime_assets::map::PhysicEngine;
 use crate::systems::spawn_entity_system::SpawnEntitySystem;
 use crate::systems::{
-    CollisionsFromPrevFrame, RenderMatrices, Sex, Sprites, SystemEvent, SystemFrameDurations,
-    SystemVariables,
+    RenderMatrices, Sex, Sprites, SystemEvent, SystemFrameDurations, SystemVariables,
 };
 use crate::{register_server_systems, run_main_frame};
 use assert_approx_eq::assert_approx_eq;
@@ -84,11 +82,6 @@ fn setup_ecs_world<'a, 'b>() -> TestUtil<'a, 'b> {
     ecs_world.add_resource(sys_vars);
     ecs_world.add_resource(DevConfig::new().unwrap());
     ecs_world.add_resource(RenderCommandCollector::new());
-    ecs_world.add_resource(CollisionsFromPrevFrame {
-        collisions: HashMap::new(),
-    });
-
-    ecs_world.add_resource(PhysicEngine::new());
     ecs_world.add_resource(SystemFrameDurations(HashMap::new()));
     ecs_world.add_resource(Vec::<SystemEvent>::with_capacity(1024));
     return TestUtil {
@@ -458,17 +451,11 @@ impl<'a, 'b> TestUtil<'a, 'b> {
         let char_id = EntityId::from(self.ecs_world.create_entity().build());
         {
             let updater = &self.ecs_world.read_resource::<LazyUpdate>();
-            let physics_world = &mut self.ecs_world.write_resource::<PhysicEngine>();
             let dev_configs = &self.ecs_world.read_resource::<CommonConfigs>();
             CharacterEntityBuilder::new(char_id, "test_char")
                 .insert_sprite_render_descr_component(updater)
-                .physics(pos, physics_world, |builder| {
-                    builder
-                        .collision_group(team.get_collision_group())
-                        .circle(1.0)
-                })
                 .char_state(updater, dev_configs, |ch| {
-                    ch.outlook_player(Sex::Male, JobSpriteId::from_job_id(JobId::CRUSADER), 0)
+                    ch.outlook_player(Sex::Male, JobSpriteId::CRUSADER, 0)
                         .job_id(JobId::CRUSADER)
                         .team(team)
                 });
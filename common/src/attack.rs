@@ -1,6 +1,8 @@
 use crate::char_attr::CharAttributes;
-use crate::common::{v2, EngineTime, GameTime, Local, Percentage, Vec2};
+use crate::collision::{circles_overlap, rotated_rect_overlaps_circle};
+use crate::common::{rotate_vec2, EngineTime, Local, Percentage, Vec2};
 use crate::components::char::EntityId;
+use crate::components::projectile::ProjectileComponent;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -11,14 +13,27 @@ pub enum WeaponType {
     SilverBullet,
 }
 
+impl WeaponType {
+    /// Units per second, `None` for the melee weapons
+    pub fn projectile_speed(&self) -> Option<f32> {
+        match self {
+            WeaponType::Sword => None,
+            WeaponType::Arrow => Some(25.0),
+            WeaponType::SilverBullet => Some(40.0),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 #[allow(variant_size_differences)]
 pub enum BasicAttackType {
     MeleeSimple,
-    #[allow(dead_code)]
+    /// Consecutive hits on the same target strike one more time each, up to `combo_count`
     MeleeCombo {
         combo_count: u8,
         base_dmg_percentage_for_each_combo: Percentage,
+        /// The combo starts over if the next hit does not land within this
+        combo_window_seconds: f32,
     },
     Ranged {
         bullet_type: WeaponType,
@@ -32,16 +47,18 @@ pub enum DamageDisplayType {
 }
 
 impl BasicAttackType {
+    /// The ranged attacks damage their target only when the returned projectile arrives.
+    /// `combo_hits` is the number of strikes of a `MeleeCombo`, it is ignored otherwise.
     pub fn finish_attack(
         &self,
         calculated_attribs: &CharAttributes,
         caster_entity_id: EntityId<Local>,
         caster_pos: Vec2,
-        target_pos: Vec2,
         target_entity_id: EntityId<Local>,
+        combo_hits: u8,
         hp_mod_requests: &mut Vec<HpModificationRequest>,
         time: &EngineTime,
-    ) -> Option<Box<u32>> {
+    ) -> Option<ProjectileComponent> {
         match self {
             BasicAttackType::MeleeSimple => {
                 hp_mod_requests.push(HpModificationRequest {
@@ -56,36 +73,35 @@ impl BasicAttackType {
                 None
             }
             BasicAttackType::MeleeCombo {
-                combo_count,
                 base_dmg_percentage_for_each_combo,
+                ..
             } => {
                 let p = base_dmg_percentage_for_each_combo;
                 let dmg =
-                    (p.of(calculated_attribs.attack_damage as i32) * *combo_count as i32) as u32;
+                    (p.of(calculated_attribs.attack_damage as i32) * combo_hits as i32) as u32;
                 hp_mod_requests.push(HpModificationRequest {
                     src_entity: caster_entity_id,
                     dst_entity: target_entity_id,
                     typ: HpModificationType::BasicDamage(
                         dmg,
-                        DamageDisplayType::Combo(*combo_count),
+                        if combo_hits > 1 {
+                            DamageDisplayType::Combo(combo_hits)
+                        } else {
+                            DamageDisplayType::SingleNumber
+                        },
                         WeaponType::Sword,
                     ),
                 });
                 None
             }
-            // TODO2 ranged
-            BasicAttackType::Ranged { bullet_type } => {
-                //                Some(Box::new(BasicRangeAttackBullet::new(
-                //                    caster_pos,
-                //                    caster_entity_id,
-                //                    target_entity_id,
-                //                    target_pos,
-                //                    time.now(),
-                //                    *bullet_type,
-                //                    time.simulation_frame,
-                //                )))
-                None
-            }
+            BasicAttackType::Ranged { bullet_type } => Some(ProjectileComponent {
+                caster_id: caster_entity_id,
+                target_id: target_entity_id,
+                weapon_type: *bullet_type,
+                pos: caster_pos,
+                damage: calculated_attribs.attack_damage as u32,
+                updated_at: time.now(),
+            }),
         }
     }
 }
@@ -156,11 +172,59 @@ pub enum HpModificationResultType {
     Absorbed,
 }
 
-// TODO: be static types for Cuboid area attack components, Circle, etc
+#[derive(Clone, Debug)]
+pub enum AreaShape {
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+    Rectangle {
+        center: Vec2,
+        half_extents: Vec2,
+        rot_angle_in_rad: f32,
+    },
+}
+
+impl AreaShape {
+    pub fn contains(&self, pos: &Vec2) -> bool {
+        match self {
+            AreaShape::Circle { center, radius } => (pos - center).magnitude() <= *radius,
+            AreaShape::Rectangle {
+                center,
+                half_extents,
+                rot_angle_in_rad,
+            } => {
+                // rotate the point into the rectangle's space
+                let local_pos = rotate_vec2(-*rot_angle_in_rad, &(pos - center));
+                local_pos.x.abs() <= half_extents.x && local_pos.y.abs() <= half_extents.y
+            }
+        }
+    }
+
+    /// Whether a body, e.g. a character, is inside the area, the skill areas are sensors
+    pub fn overlaps_circle(&self, pos: &Vec2, radius: f32) -> bool {
+        match self {
+            AreaShape::Circle {
+                center,
+                radius: area_radius,
+            } => circles_overlap(*center, *area_radius, *pos, radius),
+            AreaShape::Rectangle {
+                center,
+                half_extents,
+                rot_angle_in_rad,
+            } => rotated_rect_overlaps_circle(
+                *center,
+                *half_extents,
+                *rot_angle_in_rad,
+                *pos,
+                radius,
+            ),
+        }
+    }
+}
+
 pub struct AreaAttackComponent {
-    // TODO2
-    //    pub area_shape: Box<dyn ncollide2d::shape::Shape<f32>>,
-    //    pub area_isom: Isometry2<f32>,
+    pub area_shape: AreaShape,
     pub source_entity_id: EntityId<Local>,
     pub typ: HpModificationType,
     pub except: Option<EntityId<Local>>,
@@ -173,39 +237,3 @@ pub struct ApplyForceComponent {
     pub force: Vec2,
     pub duration: f32,
 }
-
-struct BasicRangeAttackBullet {
-    start_pos: Vec2,
-    target_pos: Vec2,
-    current_pos: Vec2,
-    caster_id: EntityId<Local>,
-    target_id: EntityId<Local>,
-    started_at: GameTime<Local>,
-    ends_at: GameTime<Local>,
-    weapon_type: WeaponType,
-    started_tick: u64,
-}
-
-impl BasicRangeAttackBullet {
-    fn new(
-        start_pos: Vec2,
-        caster_id: EntityId<Local>,
-        target_id: EntityId<Local>,
-        target_pos: Vec2,
-        now: GameTime<Local>,
-        bullet_type: WeaponType,
-        now_tick: u64,
-    ) -> BasicRangeAttackBullet {
-        BasicRangeAttackBullet {
-            start_pos,
-            current_pos: v2(0.0, 0.0),
-            target_pos: v2(0.0, 0.0),
-            caster_id,
-            target_id,
-            started_at: now,
-            ends_at: now.add_seconds(((target_pos - start_pos).magnitude() * 0.05).min(0.3)),
-            weapon_type: bullet_type,
-            started_tick: now_tick,
-        }
-    }
-}
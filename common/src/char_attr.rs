@@ -1,10 +1,10 @@
 use crate::common::{percentage, GameTime, Local, Percentage};
 use crate::components::char::JobId;
-use crate::config::{CommonConfigs, DevConfigStats};
+use crate::config::CommonConfigs;
 use serde::Deserialize;
 use serde::Serialize;
 
-#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub struct CharAttributes {
     pub max_hp: i32,
     pub attack_damage: u16,
@@ -24,67 +24,8 @@ pub struct CharAttributesBonuses {
 }
 
 impl CharAttributes {
-    pub const TARGET_DUMMY_ATTRIBUTES: CharAttributes = CharAttributes {
-        movement_speed: percentage(0),
-        attack_range: percentage(0),
-        attack_speed: percentage(0),
-        attack_damage: 0,
-        armor: percentage(0),
-        healing: percentage(100),
-        hp_regen: percentage(0),
-        max_hp: 1_000_000,
-        mana_regen: percentage(0),
-    };
-
-    pub const HEALING_DUMMY_ATTRIBUTES: CharAttributes = CharAttributes {
-        movement_speed: percentage(0),
-        attack_range: percentage(0),
-        attack_speed: percentage(0),
-        attack_damage: 0,
-        armor: percentage(0),
-        healing: percentage(100),
-        hp_regen: percentage(0),
-        max_hp: 1_000_000,
-        mana_regen: percentage(0),
-    };
-
-    pub const BARRICADE_ATTRIBUTES: CharAttributes = CharAttributes {
-        movement_speed: percentage(0),
-        attack_range: percentage(0),
-        attack_speed: percentage(0),
-        attack_damage: 0,
-        armor: percentage(0),
-        healing: percentage(100),
-        hp_regen: percentage(0),
-        max_hp: 1_000_000,
-        mana_regen: percentage(0),
-    };
-
-    pub const OTHER_ATTRIBUTES: CharAttributes = CharAttributes {
-        movement_speed: percentage(100),
-        attack_range: percentage(100),
-        attack_speed: percentage(100),
-        attack_damage: 76,
-        armor: percentage(10),
-        healing: percentage(100),
-        hp_regen: percentage(100),
-        max_hp: 2000,
-        mana_regen: percentage(100),
-    };
-
     pub fn get_base_attributes(job_id: JobId, configs: &CommonConfigs) -> &CharAttributes {
-        return match job_id {
-            JobId::CRUSADER => &configs.stats.player.crusader.attributes,
-            JobId::GUNSLINGER => &configs.stats.player.gunslinger.attributes,
-            JobId::RANGER => &configs.stats.player.hunter.attributes,
-            JobId::RangedMinion => &configs.stats.minion.ranged,
-            JobId::HealingDummy => &CharAttributes::HEALING_DUMMY_ATTRIBUTES,
-            JobId::TargetDummy => &CharAttributes::TARGET_DUMMY_ATTRIBUTES,
-            JobId::MeleeMinion => &configs.stats.minion.melee,
-            JobId::Turret => &configs.skills.gaz_turret.turret,
-            JobId::Barricade => &configs.skills.gaz_barricade.char_attrs,
-            _ => &CharAttributes::OTHER_ATTRIBUTES,
-        };
+        &configs.job(job_id).attributes
     }
 
     pub fn zero() -> CharAttributes {
@@ -213,25 +154,25 @@ pub struct BonusDurations {
 impl BonusDurations {
     pub fn with_invalid_times() -> BonusDurations {
         BonusDurations {
-            max_hp_bonus_ends_at: GameTime::from(std::f32::MAX),
-            walking_speed_bonus_ends_at: GameTime::from(std::f32::MAX),
-            attack_range_bonus_ends_at: GameTime::from(std::f32::MAX),
-            attack_speed_bonus_ends_at: GameTime::from(std::f32::MAX),
-            attack_damage_bonus_ends_at: GameTime::from(std::f32::MAX),
-            armor_bonus_ends_at: GameTime::from(std::f32::MAX),
-            healing_bonus_ends_at: GameTime::from(std::f32::MAX),
-            hp_regen_bonus_ends_at: GameTime::from(std::f32::MAX),
-            mana_regen_bonus_ends_at: GameTime::from(std::f32::MAX),
+            max_hp_bonus_ends_at: GameTime::from(f32::MAX),
+            walking_speed_bonus_ends_at: GameTime::from(f32::MAX),
+            attack_range_bonus_ends_at: GameTime::from(f32::MAX),
+            attack_speed_bonus_ends_at: GameTime::from(f32::MAX),
+            attack_damage_bonus_ends_at: GameTime::from(f32::MAX),
+            armor_bonus_ends_at: GameTime::from(f32::MAX),
+            healing_bonus_ends_at: GameTime::from(f32::MAX),
+            hp_regen_bonus_ends_at: GameTime::from(f32::MAX),
+            mana_regen_bonus_ends_at: GameTime::from(f32::MAX),
 
-            max_hp_bonus_started_at: GameTime::from(std::f32::MAX),
-            walking_speed_bonus_started_at: GameTime::from(std::f32::MAX),
-            attack_range_bonus_started_at: GameTime::from(std::f32::MAX),
-            attack_speed_bonus_started_at: GameTime::from(std::f32::MAX),
-            attack_damage_bonus_started_at: GameTime::from(std::f32::MAX),
-            armor_bonus_started_at: GameTime::from(std::f32::MAX),
-            healing_bonus_started_at: GameTime::from(std::f32::MAX),
-            hp_regen_bonus_started_at: GameTime::from(std::f32::MAX),
-            mana_regen_bonus_started_at: GameTime::from(std::f32::MAX),
+            max_hp_bonus_started_at: GameTime::from(f32::MAX),
+            walking_speed_bonus_started_at: GameTime::from(f32::MAX),
+            attack_range_bonus_started_at: GameTime::from(f32::MAX),
+            attack_speed_bonus_started_at: GameTime::from(f32::MAX),
+            attack_damage_bonus_started_at: GameTime::from(f32::MAX),
+            armor_bonus_started_at: GameTime::from(f32::MAX),
+            healing_bonus_started_at: GameTime::from(f32::MAX),
+            hp_regen_bonus_started_at: GameTime::from(f32::MAX),
+            mana_regen_bonus_started_at: GameTime::from(f32::MAX),
         }
     }
 }
@@ -0,0 +1,278 @@
+use crate::common::{rotate_vec2, v2, Vec2};
+use crate::grf::gat::BlockingRectangle;
+use std::collections::HashMap;
+
+/// Every character has the same collision circle, it fits into a single cell,
+/// so the characters can walk through the paths found over the cells
+pub const CHAR_COLLISION_RADIUS: f32 = 0.4;
+/// Big enough so that a character overlaps at most 4 cells
+pub const BROADPHASE_CELL_SIZE: f32 = 4.0;
+
+/// Axis aligned rectangle in world coordinates
+#[derive(Clone, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Aabb {
+    pub fn new(min: Vec2, max: Vec2) -> Aabb {
+        Aabb { min, max }
+    }
+
+    pub fn of_circle(center: Vec2, radius: f32) -> Aabb {
+        Aabb {
+            min: v2(center.x - radius, center.y - radius),
+            max: v2(center.x + radius, center.y + radius),
+        }
+    }
+
+    /// The rectangle covers the cells from `start_x` to `start_x + width - 1`
+    /// and from `bottom - height + 1` to `bottom` on the GAT grid
+    pub fn from_blocking_rectangle(rect: &BlockingRectangle) -> Aabb {
+        let top_cell_y = rect.bottom - rect.height + 1;
+        Aabb {
+            min: v2(rect.start_x as f32, -(rect.bottom + 1) as f32),
+            max: v2((rect.start_x + rect.width) as f32, -top_cell_y as f32),
+        }
+    }
+
+    /// Touching counts as overlapping
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    /// Touching counts as overlapping, like for `AreaShape::contains`
+    pub fn overlaps_circle(&self, center: Vec2, radius: f32) -> bool {
+        (center - self.closest_point(center)).magnitude_squared() <= radius * radius
+    }
+
+    fn closest_point(&self, pos: Vec2) -> Vec2 {
+        v2(
+            pos.x.max(self.min.x).min(self.max.x),
+            pos.y.max(self.min.y).min(self.max.y),
+        )
+    }
+
+    /// The translation which moves the circle out of the rectangle, None if they do not overlap
+    pub fn circle_penetration(&self, center: Vec2, radius: f32) -> Option<Vec2> {
+        let diff = center - self.closest_point(center);
+        let distance_sq = diff.magnitude_squared();
+        if distance_sq >= radius * radius {
+            None
+        } else if distance_sq > 0.0 {
+            let distance = distance_sq.sqrt();
+            Some(diff * ((radius - distance) / distance))
+        } else {
+            // the center is inside, it is pushed out through the closest edge
+            let to_left = center.x - self.min.x;
+            let to_right = self.max.x - center.x;
+            let to_bottom = center.y - self.min.y;
+            let to_top = self.max.y - center.y;
+            let min = to_left.min(to_right).min(to_bottom).min(to_top);
+            Some(if min == to_left {
+                v2(-(to_left + radius), 0.0)
+            } else if min == to_right {
+                v2(to_right + radius, 0.0)
+            } else if min == to_bottom {
+                v2(0.0, -(to_bottom + radius))
+            } else {
+                v2(0.0, to_top + radius)
+            })
+        }
+    }
+}
+
+/// The translation which moves the circle `a` out of the circle `b`, None if they do not overlap
+pub fn circle_circle_penetration(a: Vec2, radius_a: f32, b: Vec2, radius_b: f32) -> Option<Vec2> {
+    let diff = a - b;
+    let radii = radius_a + radius_b;
+    let distance_sq = diff.magnitude_squared();
+    if distance_sq >= radii * radii {
+        None
+    } else if distance_sq > 0.0 {
+        let distance = distance_sq.sqrt();
+        Some(diff * ((radii - distance) / distance))
+    } else {
+        // exactly on top of each other, any direction would do but it must be the same everywhere
+        Some(v2(radii, 0.0))
+    }
+}
+
+/// Touching counts as overlapping, like for `AreaShape::contains`
+pub fn circles_overlap(a: Vec2, radius_a: f32, b: Vec2, radius_b: f32) -> bool {
+    let radii = radius_a + radius_b;
+    (a - b).magnitude_squared() <= radii * radii
+}
+
+/// The rectangle is rotated around its center, the same way as `AreaShape::Rectangle`
+pub fn rotated_rect_overlaps_circle(
+    rect_center: Vec2,
+    half_extents: Vec2,
+    rot_angle_in_rad: f32,
+    circle_center: Vec2,
+    radius: f32,
+) -> bool {
+    // rotate the circle into the rectangle's space
+    let local_center = rotate_vec2(-rot_angle_in_rad, &(circle_center - rect_center));
+    Aabb::new(-half_extents, half_extents).overlaps_circle(local_center, radius)
+}
+
+/// Uniform grid, only the bodies sharing a cell have to be tested against each other
+pub struct BroadphaseGrid {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl BroadphaseGrid {
+    pub fn new(cell_size: f32) -> BroadphaseGrid {
+        BroadphaseGrid {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    fn cell_of(&self, pos: Vec2) -> (i32, i32) {
+        (
+            (pos.x / self.cell_size).floor() as i32,
+            (pos.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    pub fn insert(&mut self, index: usize, aabb: &Aabb) {
+        let (min_x, min_y) = self.cell_of(aabb.min);
+        let (max_x, max_y) = self.cell_of(aabb.max);
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                self.cells.entry((x, y)).or_default().push(index);
+            }
+        }
+    }
+
+    /// Every pair only once and in ascending order, so the order of the resolution
+    /// does not depend on the hashing
+    pub fn potential_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for indices in self.cells.values() {
+            for (i, a) in indices.iter().enumerate() {
+                for b in &indices[i + 1..] {
+                    pairs.push((*a.min(b), *a.max(b)));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_vec_eq(a: Vec2, b: Vec2) {
+        assert!((a - b).magnitude() < 0.0001, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn blocking_rectangle_covers_its_cells() {
+        let aabb = Aabb::from_blocking_rectangle(&BlockingRectangle {
+            area: 6,
+            start_x: 2,
+            bottom: 3,
+            width: 2,
+            height: 3,
+        });
+        assert_eq!(aabb, Aabb::new(v2(2.0, -4.0), v2(4.0, -1.0)));
+    }
+
+    #[test]
+    fn circle_is_pushed_out_of_the_rectangle() {
+        let aabb = Aabb::new(v2(0.0, 0.0), v2(2.0, 2.0));
+        assert_eq!(aabb.circle_penetration(v2(3.0, 1.0), 0.5), None);
+        assert_vec_eq(
+            aabb.circle_penetration(v2(2.25, 1.0), 0.5).unwrap(),
+            v2(0.25, 0.0),
+        );
+        // inside, closest to the top edge
+        assert_vec_eq(
+            aabb.circle_penetration(v2(1.0, 1.75), 0.5).unwrap(),
+            v2(0.0, 0.75),
+        );
+    }
+
+    #[test]
+    fn overlapping_circles_are_pushed_apart() {
+        assert_eq!(
+            circle_circle_penetration(v2(0.0, 0.0), 0.4, v2(1.0, 0.0), 0.4),
+            None
+        );
+        assert_vec_eq(
+            circle_circle_penetration(v2(0.0, 0.0), 0.4, v2(0.0, 0.5), 0.4).unwrap(),
+            v2(0.0, -0.3),
+        );
+        assert_vec_eq(
+            circle_circle_penetration(v2(1.0, 1.0), 0.4, v2(1.0, 1.0), 0.4).unwrap(),
+            v2(0.8, 0.0),
+        );
+    }
+
+    #[test]
+    fn rectangles_overlap() {
+        let aabb = Aabb::new(v2(0.0, 0.0), v2(2.0, 2.0));
+        assert!(aabb.overlaps(&Aabb::new(v2(1.0, 1.0), v2(3.0, 3.0))));
+        assert!(aabb.overlaps(&Aabb::new(v2(2.0, 0.0), v2(3.0, 1.0))));
+        assert!(!aabb.overlaps(&Aabb::new(v2(2.5, 0.0), v2(3.0, 1.0))));
+    }
+
+    #[test]
+    fn circle_overlaps_the_rotated_rectangle() {
+        // a 4x1 rectangle, rotated to be vertical
+        let center = v2(0.0, 0.0);
+        let half_extents = v2(2.0, 0.5);
+        let rot = 90f32.to_radians();
+        assert!(rotated_rect_overlaps_circle(
+            center,
+            half_extents,
+            rot,
+            v2(0.0, 2.2),
+            0.4
+        ));
+        assert!(!rotated_rect_overlaps_circle(
+            center,
+            half_extents,
+            rot,
+            v2(1.5, 0.0),
+            0.4
+        ));
+        // without the rotation it would overlap
+        assert!(rotated_rect_overlaps_circle(
+            center,
+            half_extents,
+            0.0,
+            v2(1.5, 0.0),
+            0.4
+        ));
+    }
+
+    #[test]
+    fn circles_touching_each_other_overlap() {
+        assert!(circles_overlap(v2(0.0, 0.0), 0.5, v2(1.0, 0.0), 0.5));
+        assert!(!circles_overlap(v2(0.0, 0.0), 0.5, v2(1.1, 0.0), 0.5));
+    }
+
+    #[test]
+    fn broadphase_pairs_only_the_neighbours_once() {
+        let mut grid = BroadphaseGrid::new(4.0);
+        // on the border of 4 cells
+        grid.insert(2, &Aabb::of_circle(v2(4.0, 4.0), 0.4));
+        grid.insert(0, &Aabb::of_circle(v2(4.2, 4.2), 0.4));
+        grid.insert(1, &Aabb::of_circle(v2(20.0, 20.0), 0.4));
+        grid.insert(3, &Aabb::of_circle(v2(3.5, 4.5), 0.4));
+
+        assert_eq!(grid.potential_pairs(), vec![(0, 2), (0, 3), (2, 3)]);
+    }
+}
@@ -3,8 +3,8 @@ use nalgebra::{Matrix3, Matrix4, Point2, Point3, Rotation3, Vector2, Vector3};
 use serde::Deserialize;
 use serde::Serialize;
 
-use serde::export::fmt::Error;
-use serde::export::{Formatter, PhantomData};
+use std::fmt::Formatter;
+use std::marker::PhantomData;
 use std::time::{Duration, Instant};
 
 pub type Mat3 = Matrix3<f32>;
@@ -50,7 +50,7 @@ pub struct Local;
 impl NetworkedObj for Local {}
 
 impl std::fmt::Display for Local {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
         std::fmt::Result::Ok(())
     }
 }
@@ -60,7 +60,7 @@ pub struct Remote;
 
 impl NetworkedObj for Remote {}
 impl std::fmt::Display for Remote {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
         std::fmt::Result::Ok(())
     }
 }
@@ -186,11 +186,11 @@ impl GameTime<Remote> {
         let local_time = (self.0 as i64 + server_to_local_time_diff).max(0);
         #[cfg(debug_assertions)]
         {
-            if local_time > std::u32::MAX as i64 {
-                panic!(format!(
+            if local_time > u32::MAX as i64 {
+                panic!(
                     "time from server: {:?}, server_to_local_time_diff: {:?}, local_now: {:?}",
                     self, server_to_local_time_diff, now
-                ));
+                );
             }
         }
         return GameTime::from(local_time as u32);
@@ -202,10 +202,30 @@ pub trait NetworkedObj:
 {
 }
 
-#[derive(Copy, Clone, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd)]
-#[serde(from = "f32")]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct GameTime<T: NetworkedObj>(u32, PhantomData<T>);
 
+// config files contain the times in seconds, while network packets carry the exact millis
+impl<T: NetworkedObj> Serialize for GameTime<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_f32(self.0 as f32 / 1000f32)
+        } else {
+            serializer.serialize_u32(self.0)
+        }
+    }
+}
+
+impl<'de, T: NetworkedObj> Deserialize<'de> for GameTime<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            f32::deserialize(deserializer).map(GameTime::from)
+        } else {
+            u32::deserialize(deserializer).map(GameTime::from)
+        }
+    }
+}
+
 impl<T: NetworkedObj> std::fmt::Debug for GameTime<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "Time({})", self.0)
@@ -273,9 +293,9 @@ impl GameTime<Local> {
         self.0 <= system_time.0
     }
 
-    - csak a local kliensnél van rollback, ha más lát, nála nincs
-    - ha mást látok támadnim, az animáció nem fut le végig
-    - localban viszont lassabb mint kellene sztem
+    // csak a local kliensnél van rollback, ha más lát, nála nincs
+    // ha mást látok támadnim, az animáció nem fut le végig
+    // localban viszont lassabb mint kellene sztem
 
     pub fn has_not_passed_yet(&self, other: GameTime<Local>) -> bool {
         self.0 > other.0
@@ -313,9 +333,9 @@ impl From<i32> for Percentage {
     }
 }
 
-impl Into<i32> for Percentage {
-    fn into(self) -> i32 {
-        self.value / Percentage::PERCENTAGE_FACTOR
+impl From<Percentage> for i32 {
+    fn from(val: Percentage) -> Self {
+        val.value / Percentage::PERCENTAGE_FACTOR
     }
 }
 
@@ -1,18 +1,19 @@
-use crate::attack::{BasicAttackType, WeaponType};
+use crate::attack::{ApplyForceComponent, BasicAttackType};
 use crate::char_attr::CharAttributes;
 use crate::common::{float_cmp, v2, GameTime, Local, NetworkedObj, Remote, Vec2};
-use crate::components::controller::PlayerIntention;
 use crate::components::job_ids::JobSpriteId;
-use crate::config::CommonConfigs;
-use crate::packets::SocketBuffer;
-use serde::export::fmt::{Debug, Display, Error};
-use serde::export::{Formatter, PhantomData};
+use crate::components::skills::skills::Skills;
+use crate::components::status::status::{ReplicatedStatus, StatusEnumDiscriminants, Statuses};
+use crate::config::{CommonConfigs, JobDefinition};
+use crate::map::MapWalkingInfo;
+use crate::path_finding::{PathFindingBuffers, WalkingPath};
 use serde::{Deserialize, Serialize};
 use specs::prelude::*;
 use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
 use std::hash::Hash;
+use std::marker::PhantomData;
 use strum_macros::Display;
-use strum_macros::EnumCount;
 use strum_macros::EnumIter;
 use strum_macros::EnumString;
 
@@ -36,9 +37,9 @@ impl<T> Display for EntityId<T> {
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct ControllerEntityId(Entity);
 
-impl Into<specs::Entity> for ControllerEntityId {
-    fn into(self) -> specs::Entity {
-        self.0
+impl From<ControllerEntityId> for specs::Entity {
+    fn from(val: ControllerEntityId) -> Self {
+        val.0
     }
 }
 
@@ -56,13 +57,24 @@ impl ControllerEntityId {
 
 impl EntityId<Local> {
     pub fn new(id: specs::Entity) -> EntityId<Local> {
-        EntityId(unsafe { std::mem::transmute(id) }, PhantomData)
+        EntityId(
+            unsafe { std::mem::transmute::<specs::Entity, u64>(id) },
+            PhantomData,
+        )
     }
 }
 
-impl Into<specs::Entity> for EntityId<Local> {
-    fn into(self) -> specs::Entity {
-        unsafe { std::mem::transmute(self.0) }
+/// Only the server is allowed to send its ids to the other side, see `prepare_entity_id_for_sending`
+#[cfg(test)]
+impl EntityId<Local> {
+    pub fn as_remote(self) -> EntityId<Remote> {
+        EntityId(self.0, PhantomData)
+    }
+}
+
+impl From<EntityId<Local>> for specs::Entity {
+    fn from(val: EntityId<Local>) -> Self {
+        unsafe { std::mem::transmute(val.0) }
     }
 }
 
@@ -78,6 +90,17 @@ pub enum Sex {
     Female,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CastingSkillData<T: NetworkedObj> {
+    pub target_area_pos: Option<Vec2>,
+    pub char_to_skill_dir_when_casted: Vec2,
+    pub target_entity: Option<EntityId<T>>,
+    pub cast_started: GameTime<T>,
+    pub cast_ends: GameTime<T>,
+    pub can_move: bool,
+    pub skill: Skills,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum CharState<T: NetworkedObj> {
     Idle,
@@ -91,7 +114,7 @@ pub enum CharState<T: NetworkedObj> {
     },
     ReceivingDamage,
     Dead,
-    //    CastingSkill(CastingSkillData),
+    CastingSkill(CastingSkillData<T>),
 }
 
 impl<T: NetworkedObj> Display for CharState<T> {
@@ -111,6 +134,11 @@ impl<T: NetworkedObj> Display for CharState<T> {
                 "Attacking(dst: {}, time: {:?})",
                 target.0, damage_occurs_at
             ),
+            CharState::CastingSkill(casting_info) => write!(
+                f,
+                "CastingSkill({:?}, ends: {:?})",
+                casting_info.skill, casting_info.cast_ends
+            ),
         }
     }
 }
@@ -121,31 +149,23 @@ impl<T: NetworkedObj> CharState<T> {
     }
 
     pub fn is_walking(&self) -> bool {
-        match self {
-            CharState::Walking(..) => true,
-            _ => false,
-        }
+        matches!(self, CharState::Walking(..))
     }
 
     pub fn is_attacking(&self) -> bool {
-        match self {
-            CharState::Attacking { .. } => true,
-            _ => false,
-        }
+        matches!(self, CharState::Attacking { .. })
+    }
+
+    pub fn is_casting(&self) -> bool {
+        matches!(self, CharState::CastingSkill(..))
     }
 
     pub fn is_alive(&self) -> bool {
-        match self {
-            CharState::Dead => false,
-            _ => true,
-        }
+        !matches!(self, CharState::Dead)
     }
 
     pub fn is_dead(&self) -> bool {
-        match self {
-            CharState::Dead => true,
-            _ => false,
-        }
+        matches!(self, CharState::Dead)
     }
 
     pub fn name(&self) -> &'static str {
@@ -156,10 +176,27 @@ impl<T: NetworkedObj> CharState<T> {
             CharState::Attacking { .. } => "Attacking",
             CharState::ReceivingDamage => "ReceivingDamage",
             CharState::Dead => "Dead",
+            CharState::CastingSkill(..) => "CastingSkill",
         }
     }
 }
 
+/// Collected by `CharacterStateUpdateSystem` in the tick the hp of a character has dropped to zero,
+/// the server decides what happens to the corpse
+#[derive(Debug)]
+pub struct CharDied {
+    pub char_id: EntityId<Local>,
+}
+
+/// Collected by the server's `AttackSystem` when a character has been damaged by another one,
+/// the nearby minions and guardians of the attacked team might turn against the attacker.
+/// They are kept for one frame, so every AI system can react to them
+#[derive(Debug)]
+pub struct CharAttacked {
+    pub attacker_id: EntityId<Local>,
+    pub attacked_id: EntityId<Local>,
+}
+
 // Sprites are loaded based on the enum names, so non-camelcase names must be allowed
 #[allow(non_camel_case_types)]
 #[derive(
@@ -202,26 +239,8 @@ pub enum JobId {
     RangedMinion,
     Turret,
     Guard,
-}
-
-impl JobId {
-    pub fn get_basic_attack_type(&self) -> BasicAttackType {
-        match self {
-            JobId::GUNSLINGER => BasicAttackType::Ranged {
-                bullet_type: WeaponType::SilverBullet,
-            },
-            JobId::RangedMinion => BasicAttackType::Ranged {
-                bullet_type: WeaponType::Arrow,
-            },
-            JobId::RANGER => BasicAttackType::Ranged {
-                bullet_type: WeaponType::Arrow,
-            },
-            JobId::Turret => BasicAttackType::Ranged {
-                bullet_type: WeaponType::SilverBullet,
-            },
-            _ => BasicAttackType::MeleeSimple,
-        }
-    }
+    Tower,
+    Core,
 }
 
 /// It determines the behaviour of some skill etc, e.g. if skills cannot be casted on Guards
@@ -233,6 +252,8 @@ pub enum CharType {
     Mercenary,
     Boss,
     Guard,
+    /// Towers and cores, they never move, the match ends when a core is destroyed
+    Structure,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -247,7 +268,7 @@ pub enum CharOutlook {
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum EntityTarget<T> {
     OtherEntity(EntityId<T>),
     Pos(Vec2),
@@ -285,36 +306,84 @@ pub struct StaticCharDataComponent {
 }
 
 impl StaticCharDataComponent {
+    pub fn collision_group(&self) -> CollisionGroup {
+        match self.job_id {
+            JobId::Turret => return CollisionGroup::Turret,
+            JobId::Barricade => {
+                return match self.team {
+                    Team::Left | Team::Right => self.team.get_barricade_collision_group(),
+                    _ => CollisionGroup::Structure,
+                };
+            }
+            _ => {}
+        }
+        match self.typ {
+            CharType::Minion => CollisionGroup::Minion,
+            CharType::Guard => CollisionGroup::Guard,
+            CharType::Structure => CollisionGroup::Structure,
+            CharType::Player | CharType::Mercenary | CharType::Boss => {
+                self.team.get_collision_group()
+            }
+        }
+    }
+
     pub fn new(
         name: String,
         team: Team,
         typ: CharType,
-        job_id: JobId,
+        job: &JobDefinition,
         outlook: CharOutlook,
     ) -> StaticCharDataComponent {
         StaticCharDataComponent {
             name,
             team,
-            basic_attack_type: job_id.get_basic_attack_type(),
+            basic_attack_type: job.basic_attack_type(),
             typ,
             outlook,
-            job_id,
+            job_id: job.job_id,
         }
     }
 }
 
+/// The progress of a `BasicAttackType::MeleeCombo` against `target`
+#[derive(Clone, Debug)]
+pub struct BasicAttackCombo<T: 'static + NetworkedObj> {
+    pub target: EntityId<T>,
+    pub hits: u8,
+    pub expires_at: GameTime<T>,
+}
+
+/// The deceleration of a pushed character, units/second^2
+const KNOCKBACK_DECELERATION: f32 = 60.0;
+
+/// The movement caused by an `ApplyForceComponent`, the character slows down until it stops
+#[derive(Clone, Debug)]
+pub struct Knockback<T: 'static + NetworkedObj> {
+    pub velocity: Vec2,
+    pub updated_at: GameTime<T>,
+}
+
 #[derive(Component, Clone, Debug, Serialize, Deserialize)]
 pub struct LocalCharStateComp<T: 'static + NetworkedObj> {
-    pos: Vec2,
-    dir: CharDir,
-    state: CharState<T>,
+    pub(crate) pos: Vec2,
+    pub(crate) dir: CharDir,
+    pub(crate) state: CharState<T>,
     pub target: Option<EntityTarget<T>>,
-    calculated_attribs: CharAttributes,
+    pub(crate) calculated_attribs: CharAttributes,
     pub attack_delay_ends_at: GameTime<T>,
-    // TODO [SkillKey::Count]
-    pub skill_cast_allowed_at: [GameTime<T>; 6],
+    pub skill_cast_allowed_at: HashMap<Skills, GameTime<T>>,
     pub cannot_control_until: GameTime<T>,
     pub hp: i32,
+    pub(crate) statuses: Vec<ReplicatedStatus<T>>,
+    /// Not replicated, only the server calculates the damage
+    #[serde(skip, default = "Option::default")]
+    pub(crate) combo: Option<BasicAttackCombo<T>>,
+    /// Not replicated, both sides find the same path on the same map
+    #[serde(skip, default = "Option::default")]
+    pub(crate) path: Option<WalkingPath>,
+    /// Not replicated, only its result, the position is
+    #[serde(skip, default = "Option::default")]
+    pub(crate) knockback: Option<Knockback<T>>,
 }
 
 impl PartialEq for LocalCharStateComp<Local> {
@@ -336,7 +405,7 @@ impl PartialEq for LocalCharStateComp<Local> {
     }
 }
 
-impl Default for LocalCharStateComp<Local> {
+impl<T: NetworkedObj> Default for LocalCharStateComp<T> {
     fn default() -> Self {
         LocalCharStateComp {
             pos: v2(0.0, 0.0),
@@ -345,9 +414,13 @@ impl Default for LocalCharStateComp<Local> {
             target: None,
             calculated_attribs: Default::default(),
             attack_delay_ends_at: GameTime::from(0.0),
-            skill_cast_allowed_at: [GameTime::from(0.0); 6],
+            skill_cast_allowed_at: HashMap::new(),
             cannot_control_until: GameTime::from(0.0),
             hp: 0,
+            statuses: Vec::new(),
+            combo: None,
+            path: None,
+            knockback: None,
         }
     }
 }
@@ -379,6 +452,21 @@ impl LocalCharStateComp<Remote> {
                 },
                 CharState::ReceivingDamage => CharState::ReceivingDamage,
                 CharState::Dead => CharState::Dead,
+                CharState::CastingSkill(casting_info) => {
+                    CharState::CastingSkill(CastingSkillData {
+                        target_area_pos: casting_info.target_area_pos,
+                        char_to_skill_dir_when_casted: casting_info.char_to_skill_dir_when_casted,
+                        target_entity: casting_info.target_entity.map(|it| map[&it]),
+                        cast_started: casting_info
+                            .cast_started
+                            .to_local_time(now, server_to_local_time_diff),
+                        cast_ends: casting_info
+                            .cast_ends
+                            .to_local_time(now, server_to_local_time_diff),
+                        can_move: casting_info.can_move,
+                        skill: casting_info.skill,
+                    })
+                }
             },
             target: match self.target {
                 None => None,
@@ -394,18 +482,33 @@ impl LocalCharStateComp<Remote> {
             attack_delay_ends_at: self
                 .attack_delay_ends_at
                 .to_local_time(now, server_to_local_time_diff),
-            skill_cast_allowed_at: [
-                self.skill_cast_allowed_at[0].to_local_time(now, server_to_local_time_diff),
-                self.skill_cast_allowed_at[1].to_local_time(now, server_to_local_time_diff),
-                self.skill_cast_allowed_at[2].to_local_time(now, server_to_local_time_diff),
-                self.skill_cast_allowed_at[3].to_local_time(now, server_to_local_time_diff),
-                self.skill_cast_allowed_at[4].to_local_time(now, server_to_local_time_diff),
-                self.skill_cast_allowed_at[5].to_local_time(now, server_to_local_time_diff),
-            ],
+            skill_cast_allowed_at: self
+                .skill_cast_allowed_at
+                .into_iter()
+                .map(|(skill, allowed_at)| {
+                    (
+                        skill,
+                        allowed_at.to_local_time(now, server_to_local_time_diff),
+                    )
+                })
+                .collect(),
             cannot_control_until: self
                 .cannot_control_until
                 .to_local_time(now, server_to_local_time_diff),
             hp: self.hp,
+            statuses: self
+                .statuses
+                .into_iter()
+                .map(|status| ReplicatedStatus {
+                    typ: status.typ,
+                    until: status
+                        .until
+                        .map(|until| until.to_local_time(now, server_to_local_time_diff)),
+                })
+                .collect(),
+            combo: None,
+            path: None,
+            knockback: None,
         }
     }
 }
@@ -426,15 +529,83 @@ impl LocalCharStateComp<Local> {
             hp: base_attributes.max_hp,
             calculated_attribs: base_attributes,
             attack_delay_ends_at: GameTime::from(0.0),
-            skill_cast_allowed_at: [GameTime::from(0.0); 6],
+            skill_cast_allowed_at: HashMap::new(),
             cannot_control_until: GameTime::from(0.0),
+            statuses: Vec::new(),
+            combo: None,
+            path: None,
+            knockback: None,
         }
     }
 
+    /// Returns how many times the basic attack which has just landed on `target` strikes
+    pub fn register_basic_attack_hit(
+        &mut self,
+        basic_attack: BasicAttackType,
+        target: EntityId<Local>,
+        now: GameTime<Local>,
+    ) -> u8 {
+        let (combo_count, combo_window_seconds) = match basic_attack {
+            BasicAttackType::MeleeCombo {
+                combo_count,
+                combo_window_seconds,
+                ..
+            } => (combo_count, combo_window_seconds),
+            _ => return 1,
+        };
+        let hits = match &self.combo {
+            Some(combo) if combo.target == target && combo.expires_at.has_not_passed_yet(now) => {
+                combo.hits % combo_count.max(1) + 1
+            }
+            _ => 1,
+        };
+        self.combo = Some(BasicAttackCombo {
+            target,
+            hits,
+            expires_at: now.add_seconds(combo_window_seconds),
+        });
+        hits
+    }
+
+    /// The character can not be controlled until the force expires
+    pub fn apply_force(&mut self, push: &ApplyForceComponent, now: GameTime<Local>) {
+        if self.state.is_dead() {
+            return;
+        }
+        self.knockback = Some(Knockback {
+            velocity: push.force,
+            updated_at: now,
+        });
+        self.cannot_control_until
+            .run_at_least_until(now, (push.duration * 1000.0) as u32);
+    }
+
+    /// Moves the pushed character
+    pub fn update_knockback(&mut self, now: GameTime<Local>) {
+        let (displacement, new_knockback) = match &self.knockback {
+            Some(knockback) => {
+                let elapsed_seconds = now.elapsed_since(knockback.updated_at).as_seconds_f32();
+                let speed = knockback.velocity.magnitude();
+                let new_speed = speed - KNOCKBACK_DECELERATION * elapsed_seconds;
+                let new_knockback = if new_speed > 0.0 {
+                    Some(Knockback {
+                        velocity: knockback.velocity * (new_speed / speed),
+                        updated_at: now,
+                    })
+                } else {
+                    None
+                };
+                (knockback.velocity * elapsed_seconds, new_knockback)
+            }
+            None => return,
+        };
+        self.pos += displacement;
+        self.knockback = new_knockback;
+    }
+
     pub fn can_cast(&self, sys_time: GameTime<Local>) -> bool {
         let can_cast_by_state = match &self.state {
-            // TODO2
-            //        CharState::CastingSkill(_) => false,
+            CharState::CastingSkill(_) => false,
             CharState::Idle => true,
             CharState::Walking(_pos) => true,
             CharState::StandBy => true,
@@ -442,15 +613,16 @@ impl LocalCharStateComp<Local> {
             CharState::ReceivingDamage => false,
             CharState::Dead => false,
         };
-        can_cast_by_state && self.cannot_control_until.has_already_passed(sys_time)
-        // TODO2
-        //        && char_state.statuses.can_cast()
+        can_cast_by_state
+            && self.cannot_control_until.has_already_passed(sys_time)
+            && self
+                .active_statuses(sys_time)
+                .all(|status| status.typ.can_target_cast())
     }
 
     pub fn can_move(&self, sys_time: GameTime<Local>) -> bool {
         let can_move_by_state = match &self.state {
-            // TODO2
-            //        CharState::CastingSkill(casting_info) => casting_info.can_move,
+            CharState::CastingSkill(casting_info) => casting_info.can_move,
             CharState::Idle => true,
             CharState::Walking(_pos) => true,
             CharState::StandBy => true,
@@ -458,29 +630,122 @@ impl LocalCharStateComp<Local> {
             CharState::ReceivingDamage => true,
             CharState::Dead => false,
         };
-        can_move_by_state && self.cannot_control_until.has_already_passed(sys_time)
-        // TODO2
-        //        && char_state.statuses.can_move()
+        can_move_by_state
+            && self.cannot_control_until.has_already_passed(sys_time)
+            && self
+                .active_statuses(sys_time)
+                .all(|status| status.typ.can_target_move())
+    }
+
+    /// E.g. while it is dashing, the intentions of its player are ignored
+    pub fn can_be_controlled(&self, sys_time: GameTime<Local>) -> bool {
+        self.state.is_alive()
+            && self
+                .active_statuses(sys_time)
+                .all(|status| status.typ.can_target_be_controlled())
+    }
+
+    /// The client might not have received the removal of an expired status yet
+    pub fn active_statuses(
+        &self,
+        now: GameTime<Local>,
+    ) -> impl Iterator<Item = &ReplicatedStatus<Local>> {
+        self.statuses
+            .iter()
+            .filter(move |status| status.is_active(now))
+    }
+
+    /// Known by the clients as well, from the replicated statuses
+    pub fn is_mounted(&self) -> bool {
+        self.statuses
+            .iter()
+            .any(|status| status.typ == StatusEnumDiscriminants::MountedStatus)
     }
 
-    pub fn recalc_attribs_based_on_statuses(&mut self, job_id: JobId, dev_configs: &CommonConfigs) {
-        // TODO2
+    /// E.g. while it is dashing or carried by a falcon, nobody stops it
+    pub fn is_noncollidable(&self) -> bool {
+        self.statuses
+            .iter()
+            .any(|status| status.typ.is_noncollidable())
+    }
+
+    /// Some statuses change the basic attack of their owner, e.g. the exoskeleton
+    pub fn basic_attack_type(&self, static_data: &StaticCharDataComponent) -> BasicAttackType {
+        self.statuses
+            .iter()
+            .find_map(|status| status.typ.basic_attack_type())
+            .unwrap_or(static_data.basic_attack_type)
+    }
+
+    pub fn is_skill_in_cooldown(&self, skill: Skills, now: GameTime<Local>) -> bool {
+        self.skill_cast_allowed_at
+            .get(&skill)
+            .map(|allowed_at| allowed_at.has_not_passed_yet(now))
+            .unwrap_or(false)
+    }
+
+    /// Only the server has the full statuses, the clients get the result in the snapshots
+    pub fn recalc_attribs_based_on_statuses(
+        &mut self,
+        job_id: JobId,
+        statuses: &mut Statuses,
+        dev_configs: &CommonConfigs,
+    ) {
         let base_attributes = CharAttributes::get_base_attributes(job_id, dev_configs);
-        //        let modifier_collector = self.statuses.calc_attributes();
-        //        self.calculated_attribs = base_attributes.apply(modifier_collector);
-        self.calculated_attribs = base_attributes.clone();
-        //
-        //        self.attrib_bonuses = self
-        //            .calculated_attribs
-        //            .differences(&base_attributes, modifier_collector);
+        self.calculated_attribs = base_attributes.apply(statuses.calc_attributes());
+        self.statuses = statuses.replicated();
+    }
+
+    /// The statuses are not affected, they have been removed when the character died
+    pub fn resurrect(&mut self) {
+        self.set_state_dbg(CharState::Idle, "resurrected");
+        self.target = None;
+        self.hp = self.calculated_attribs.max_hp;
     }
 
     pub fn calculated_attribs(&self) -> &CharAttributes {
         &self.calculated_attribs
     }
 
+    /// The targets inside this distance can be attacked without moving
+    pub fn attack_distance(&self) -> f32 {
+        self.calculated_attribs.attack_range.as_f32() * 2.0
+    }
+
     pub fn set_pos(&mut self, new_pos: Vec2) {
         self.pos = new_pos;
+        // e.g. teleported, the path might not be valid from the new position
+        self.path = None;
+    }
+
+    /// The point where the character should head to get closer to `destination`,
+    /// None if it can not get any closer
+    pub fn next_waypoint(
+        &mut self,
+        map: &MapWalkingInfo,
+        path_finding_buffers: &mut PathFindingBuffers,
+        destination: Vec2,
+    ) -> Option<Vec2> {
+        if map.is_empty() {
+            return Some(destination);
+        }
+        let is_new_walk = !matches!(self.state, CharState::Walking(_));
+        let outdated = match &self.path {
+            Some(path) => is_new_walk || !path.leads_to(destination),
+            None => true,
+        };
+        if outdated {
+            self.path = Some(WalkingPath::new(
+                map,
+                path_finding_buffers,
+                self.pos,
+                destination,
+            ));
+        }
+        let pos = self.pos;
+        self.path
+            .as_mut()
+            .and_then(|path| path.next_waypoint(pos, destination))
     }
 
     pub fn set_dir(&mut self, new_dir: CharDir) {
@@ -521,9 +786,8 @@ impl LocalCharStateComp<Local> {
 
     pub fn set_receiving_damage(&mut self) {
         match &self.state {
-            // TODO2
-            //            | CharState::CastingSkill(_)
-            CharState::Idle
+            CharState::CastingSkill(_)
+            | CharState::Idle
             | CharState::Walking(_)
             | CharState::StandBy
             | CharState::ReceivingDamage => {
@@ -578,11 +842,11 @@ impl CharDir {
             dd
         };
         let dir_index = (dd / 45.0 + 0.5) as usize % 8;
-        return unsafe { std::mem::transmute(DIRECTION_TABLE[dir_index] as u8) };
+        return unsafe { std::mem::transmute::<u8, CharDir>(DIRECTION_TABLE[dir_index] as u8) };
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CollisionGroup {
     StaticModel,
     LeftPlayer,
@@ -594,9 +858,112 @@ pub enum CollisionGroup {
     Minion,
     Turret,
     Guard,
+    Structure,
     SkillArea,
 }
 
+impl CollisionGroup {
+    /// The groups which can pass through the members of this group
+    pub fn blacklist(self) -> &'static [CollisionGroup] {
+        match self {
+            CollisionGroup::Guard => &[
+                CollisionGroup::Minion,
+                CollisionGroup::NonCollidablePlayer,
+                CollisionGroup::StaticModel,
+                CollisionGroup::LeftPlayer,
+                CollisionGroup::RightPlayer,
+                CollisionGroup::Guard,
+                CollisionGroup::SkillArea,
+                CollisionGroup::Turret,
+                CollisionGroup::NeutralPlayerPlayer,
+                CollisionGroup::LeftBarricade,
+                CollisionGroup::RightBarricade,
+            ],
+            CollisionGroup::StaticModel => &[
+                CollisionGroup::StaticModel,
+                CollisionGroup::NonCollidablePlayer,
+            ],
+            CollisionGroup::LeftPlayer | CollisionGroup::RightPlayer => &[
+                CollisionGroup::Minion,
+                CollisionGroup::NonCollidablePlayer,
+                CollisionGroup::Guard,
+                CollisionGroup::Turret,
+            ],
+            CollisionGroup::NonCollidablePlayer => &[
+                CollisionGroup::Minion,
+                CollisionGroup::NonCollidablePlayer,
+                CollisionGroup::StaticModel,
+                CollisionGroup::LeftPlayer,
+                CollisionGroup::RightPlayer,
+                CollisionGroup::Guard,
+                CollisionGroup::Turret,
+                CollisionGroup::NeutralPlayerPlayer,
+            ],
+            CollisionGroup::Minion => &[
+                CollisionGroup::LeftPlayer,
+                CollisionGroup::RightPlayer,
+                CollisionGroup::StaticModel,
+                CollisionGroup::NonCollidablePlayer,
+                CollisionGroup::Turret,
+                CollisionGroup::NeutralPlayerPlayer,
+            ],
+            CollisionGroup::SkillArea => &[CollisionGroup::StaticModel, CollisionGroup::SkillArea],
+            // they never move, so they do not have to be pushed out of each other or the walls
+            CollisionGroup::Structure => &[
+                CollisionGroup::StaticModel,
+                CollisionGroup::Structure,
+                CollisionGroup::NonCollidablePlayer,
+            ],
+            CollisionGroup::Turret => &[
+                CollisionGroup::Minion,
+                CollisionGroup::NonCollidablePlayer,
+                CollisionGroup::StaticModel,
+                CollisionGroup::LeftPlayer,
+                CollisionGroup::RightPlayer,
+                CollisionGroup::Guard,
+                CollisionGroup::Turret,
+                CollisionGroup::NeutralPlayerPlayer,
+            ],
+            CollisionGroup::NeutralPlayerPlayer => &[
+                CollisionGroup::Minion,
+                CollisionGroup::NonCollidablePlayer,
+                CollisionGroup::Guard,
+                CollisionGroup::Turret,
+            ],
+            CollisionGroup::LeftBarricade => &[
+                CollisionGroup::LeftPlayer,
+                CollisionGroup::Minion,
+                CollisionGroup::NonCollidablePlayer,
+                CollisionGroup::Guard,
+                CollisionGroup::Turret,
+            ],
+            CollisionGroup::RightBarricade => &[
+                CollisionGroup::RightPlayer,
+                CollisionGroup::Minion,
+                CollisionGroup::NonCollidablePlayer,
+                CollisionGroup::Guard,
+                CollisionGroup::Turret,
+            ],
+        }
+    }
+
+    /// The members of these groups are never pushed by the collisions
+    pub fn is_immovable(self) -> bool {
+        matches!(
+            self,
+            CollisionGroup::Structure
+                | CollisionGroup::LeftBarricade
+                | CollisionGroup::RightBarricade
+                | CollisionGroup::Turret
+        )
+    }
+
+    /// Neither of them is allowed to pass through the other
+    pub fn collides_with(self, other: CollisionGroup) -> bool {
+        !self.blacklist().contains(&other) && !other.blacklist().contains(&self)
+    }
+}
+
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
 pub enum StatusNature {
     Supportive,
@@ -733,14 +1100,18 @@ pub fn create_common_player_entity(
     pos: Vec2,
     team: Team,
     outlook: CharOutlook,
-) -> EntityBuilder {
-    let base_attributes =
-        CharAttributes::get_base_attributes(job_id, &world.read_resource::<CommonConfigs>())
-            .clone();
+) -> EntityBuilder<'_> {
+    let (base_attributes, static_data) = {
+        let configs = world.read_resource::<CommonConfigs>();
+        let job = configs.job(job_id);
+        (
+            job.attributes.clone(),
+            StaticCharDataComponent::new(name, team, typ, job, outlook),
+        )
+    };
     return world
         .create_entity()
         .with(LocalCharStateComp::new(pos, base_attributes))
-        .with(StaticCharDataComponent::new(
-            name, team, typ, job_id, outlook,
-        ));
+        .with(Statuses::new())
+        .with(static_data);
 }
@@ -1,5 +1,6 @@
 use crate::common::{Local, Vec2};
 use crate::components::char::EntityId;
+use crate::components::skills::skills::Skills;
 use serde::Deserialize;
 use serde::Serialize;
 use specs::prelude::*;
@@ -13,8 +14,9 @@ pub enum PlayerIntention<T> {
     Attack(EntityId<T>),
     /// Move to the coordination, attack any enemy on the way.
     AttackTowards(Vec2),
-    //    /// bool = is self cast
-    //    Casting(Skills, bool, Vec2),
+    /// bool = is self cast, Vec2 = mouse world position,
+    /// the entity is the one below the cursor (if any) the skill is targeted at
+    Casting(Skills, bool, Vec2, Option<EntityId<T>>),
 }
 
 // It can be a player, an AI, script etc
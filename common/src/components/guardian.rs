@@ -0,0 +1,32 @@
+use crate::common::Vec2;
+use crate::components::minion::Provocation;
+use serde::Deserialize;
+use serde::Serialize;
+use specs::prelude::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GuardianConfig {
+    /// An enemy player who has damaged an allied player inside the guarded area
+    /// is the preferred target for this long
+    pub provoked_seconds: f32,
+}
+
+/// The AI of a tower or a guard, it is attached to the controller of the guardian's character.
+/// It attacks the enemies inside the guarded area and returns to its place when there is none.
+#[derive(Component)]
+pub struct GuardianComponent {
+    pub home: Vec2,
+    /// None if the guardian attacks only what it can reach from its place, e.g. the towers
+    pub guarded_radius: Option<f32>,
+    pub provoked_by: Option<Provocation>,
+}
+
+impl GuardianComponent {
+    pub fn new(home: Vec2, guarded_radius: Option<f32>) -> GuardianComponent {
+        GuardianComponent {
+            home,
+            guarded_radius,
+            provoked_by: None,
+        }
+    }
+}
@@ -1,38 +1,9 @@
-use crate::components::char::JobId;
 use serde::Deserialize;
 use serde::Serialize;
 use strum_macros::Display;
-use strum_macros::EnumCount;
 use strum_macros::EnumIter;
 use strum_macros::EnumString;
 
-impl JobSpriteId {
-    pub fn from_job_id(job_id: JobId) -> JobSpriteId {
-        match job_id {
-            JobId::WIZARD => JobSpriteId::WIZARD,
-            JobId::CRUSADER => JobSpriteId::CRUSADER,
-            JobId::SWORDMAN => JobSpriteId::SWORDMAN,
-            JobId::ARCHER => JobSpriteId::ARCHER,
-            JobId::ASSASSIN => JobSpriteId::ASSASSIN,
-            JobId::KNIGHT => JobSpriteId::KNIGHT,
-            JobId::SAGE => JobSpriteId::SAGE,
-            JobId::ALCHEMIST => JobSpriteId::ALCHEMIST,
-            JobId::BLACKSMITH => JobSpriteId::BLACKSMITH,
-            JobId::PRIEST => JobSpriteId::PRIEST,
-            JobId::MONK => JobSpriteId::MONK,
-            JobId::GUNSLINGER => JobSpriteId::GUNSLINGER,
-            JobId::ROGUE => JobSpriteId::ROGUE,
-            JobId::RANGER => JobSpriteId::RANGER,
-            JobId::TargetDummy => panic!(),
-            JobId::HealingDummy => panic!(),
-            JobId::MeleeMinion => panic!(),
-            JobId::RangedMinion => panic!(),
-            JobId::Turret => panic!(),
-            JobId::Guard => panic!(),
-            JobId::Barricade => panic!(),
-        }
-    }
-}
 //½ÅÆÄÄÚÅ©·Ç¼¼ÀÌ´Õ_H_¿©
 // male: ¿©
 // female:
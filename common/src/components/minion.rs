@@ -0,0 +1,137 @@
+use crate::common::{v2, GameTime, Local, Vec2};
+use crate::components::char::{EntityId, Team};
+use serde::Deserialize;
+use serde::Serialize;
+use specs::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LaneWaypoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// The route of the minions of a team on a map, from their base to the enemy base.
+/// The waves of the team are spawned at the first waypoint.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MinionLane {
+    pub map_name: String,
+    pub team: Team,
+    pub waypoints: Vec<LaneWaypoint>,
+}
+
+impl MinionLane {
+    pub fn waypoint(&self, index: usize) -> Vec2 {
+        let waypoint = &self.waypoints[index];
+        v2(waypoint.x, waypoint.y)
+    }
+}
+
+/// The lanes of the current map, `MinionComponent::lane` indexes into it
+pub struct MinionLanes {
+    pub lanes: Vec<MinionLane>,
+}
+
+impl MinionLanes {
+    pub fn of_map(lanes: &[MinionLane], map_name: &str) -> MinionLanes {
+        MinionLanes {
+            lanes: lanes
+                .iter()
+                .filter(|lane| lane.map_name == map_name && !lane.waypoints.is_empty())
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&MinionLane> {
+        self.lanes.get(index)
+    }
+
+    /// The lane of the team and the index of its waypoint which are the closest to `pos`,
+    /// e.g. for the minions spawned by a console command
+    pub fn closest_waypoint(&self, team: Team, pos: Vec2) -> Option<(usize, usize)> {
+        let mut closest = None;
+        let mut closest_distance = f32::INFINITY;
+        for (lane_index, lane) in self.lanes.iter().enumerate() {
+            if lane.team != team {
+                continue;
+            }
+            for waypoint_index in 0..lane.waypoints.len() {
+                let distance = (lane.waypoint(waypoint_index) - pos).magnitude();
+                if distance < closest_distance {
+                    closest_distance = distance;
+                    closest = Some((lane_index, waypoint_index));
+                }
+            }
+        }
+        closest
+    }
+}
+
+/// How the minions behave and how often their waves come
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MinionConfig {
+    /// The minions attack the enemies inside this radius
+    pub aggro_radius: f32,
+    /// They give up chasing their target when it gets farther than this
+    pub chase_radius: f32,
+    /// A player who has damaged a minion or an ally next to it is its preferred target for this long
+    pub provoked_seconds: f32,
+    /// The minion heads towards the next waypoint when it gets this close to the current one
+    pub waypoint_reached_radius: f32,
+    pub first_wave_seconds: f32,
+    pub wave_interval_seconds: f32,
+    pub melee_minions_per_wave: usize,
+    pub ranged_minions_per_wave: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Provocation {
+    pub attacker_id: EntityId<Local>,
+    pub until: GameTime<Local>,
+}
+
+/// The AI of a minion, it is attached to the controller of the minion's character
+#[derive(Component)]
+pub struct MinionComponent {
+    /// Index into `MinionLanes`, the minions without a lane only defend their position
+    pub lane: Option<usize>,
+    pub next_waypoint: usize,
+    pub provoked_by: Option<Provocation>,
+}
+
+impl MinionComponent {
+    pub fn new(lane: Option<usize>, next_waypoint: usize) -> MinionComponent {
+        MinionComponent {
+            lane,
+            next_waypoint,
+            provoked_by: None,
+        }
+    }
+
+    /// The waypoint the minion walks towards, the ones it has already passed are skipped,
+    /// e.g. after it has chased an enemy along the lane
+    pub fn next_waypoint(
+        &mut self,
+        pos: Vec2,
+        lane: &MinionLane,
+        reached_radius: f32,
+    ) -> Option<Vec2> {
+        if lane.waypoints.is_empty() {
+            return None;
+        }
+        let last = lane.waypoints.len() - 1;
+        self.next_waypoint = self.next_waypoint.min(last);
+        while self.next_waypoint < last {
+            let current = lane.waypoint(self.next_waypoint);
+            let following = lane.waypoint(self.next_waypoint + 1);
+            let reached = (current - pos).magnitude() <= reached_radius;
+            let passed = (following - pos).magnitude() < (following - current).magnitude();
+            if reached || passed {
+                self.next_waypoint += 1;
+            } else {
+                break;
+            }
+        }
+        Some(lane.waypoint(self.next_waypoint))
+    }
+}
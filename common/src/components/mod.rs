@@ -1,3 +1,9 @@
 pub mod char;
 pub mod controller;
+pub mod guardian;
 pub mod job_ids;
+pub mod minion;
+pub mod projectile;
+pub mod skills;
+pub mod status;
+pub mod turret;
@@ -0,0 +1,53 @@
+use crate::attack::{DamageDisplayType, HpModificationRequest, HpModificationType, WeaponType};
+use crate::common::{GameTime, Local, Vec2};
+use crate::components::char::EntityId;
+use specs::prelude::*;
+
+/// The bullet of a ranged basic attack, it follows its target and damages it on arrival.
+/// Only the server simulates it, the clients are notified by `FromServerPacket::ProjectileFired`
+/// and render their own copy.
+#[derive(Component, Clone, Debug)]
+pub struct ProjectileComponent {
+    pub caster_id: EntityId<Local>,
+    pub target_id: EntityId<Local>,
+    pub weapon_type: WeaponType,
+    pub pos: Vec2,
+    /// Calculated when the projectile is fired, the caster might die before it arrives
+    pub damage: u32,
+    pub updated_at: GameTime<Local>,
+}
+
+impl ProjectileComponent {
+    /// Moves the projectile towards the current position of its target,
+    /// returns true if it has arrived
+    pub fn fly_towards(&mut self, target_pos: Vec2, now: GameTime<Local>) -> bool {
+        let elapsed_seconds = now.elapsed_since(self.updated_at).as_seconds_f32();
+        self.updated_at = now;
+        // melee weapons have no projectiles, it is checked by `CommonConfigs::validate`
+        let max_distance = match self.weapon_type.projectile_speed() {
+            Some(speed) => speed * elapsed_seconds,
+            None => f32::INFINITY,
+        };
+        let to_target = target_pos - self.pos;
+        let distance = to_target.magnitude();
+        if distance <= max_distance {
+            self.pos = target_pos;
+            true
+        } else {
+            self.pos += to_target * (max_distance / distance);
+            false
+        }
+    }
+
+    pub fn hp_mod_request(&self) -> HpModificationRequest {
+        HpModificationRequest {
+            src_entity: self.caster_id,
+            dst_entity: self.target_id,
+            typ: HpModificationType::BasicDamage(
+                self.damage,
+                DamageDisplayType::SingleNumber,
+                self.weapon_type,
+            ),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::attack::{
+    HpModificationRequest, HpModificationResult, HpModificationResultType, HpModificationType,
+};
+use crate::common::{EngineTime, GameTime, Local};
+use crate::components::char::EntityId;
+use crate::components::skills::skills::{FinishCast, SkillDef, SkillManifestation};
+use crate::components::status::status::{
+    ApplyStatusComponent, StatusEnum, StatusStackingResult, StatusUpdateResult,
+};
+use crate::config::CommonConfigs;
+use specs::prelude::*;
+
+pub struct AbsorbShieldSkill;
+
+pub const ABSORB_SHIELD_SKILL: &AbsorbShieldSkill = &AbsorbShieldSkill;
+
+impl SkillDef for AbsorbShieldSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let now = ecs_world.read_resource::<EngineTime>().now();
+        let duration_seconds = ecs_world
+            .read_resource::<CommonConfigs>()
+            .skills
+            .absorb_shield
+            .duration_seconds;
+        ecs_world
+            .write_resource::<Vec<ApplyStatusComponent>>()
+            .push(ApplyStatusComponent::from_status(
+                params.caster_entity_id,
+                params.target_entity?,
+                StatusEnum::AbsorbStatus(AbsorbStatus::new(
+                    params.caster_entity_id,
+                    now,
+                    duration_seconds,
+                )),
+            ));
+        None
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AbsorbStatus {
+    pub caster_entity_id: EntityId<Local>,
+    pub started: GameTime<Local>,
+    pub until: GameTime<Local>,
+    pub absorbed_damage: u32,
+}
+
+impl AbsorbStatus {
+    pub fn new(
+        caster_entity_id: EntityId<Local>,
+        now: GameTime<Local>,
+        duration: f32,
+    ) -> AbsorbStatus {
+        AbsorbStatus {
+            caster_entity_id,
+            started: now,
+            until: now.add_seconds(duration),
+            absorbed_damage: 0,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        now: GameTime<Local>,
+        self_char_id: EntityId<Local>,
+        hp_mod_requests: &mut Vec<HpModificationRequest>,
+    ) -> StatusUpdateResult {
+        if self.until.has_already_passed(now) {
+            if self.absorbed_damage > 0 {
+                hp_mod_requests.push(HpModificationRequest {
+                    src_entity: self.caster_entity_id,
+                    dst_entity: self_char_id,
+                    typ: HpModificationType::Heal(self.absorbed_damage),
+                });
+            }
+            StatusUpdateResult::RemoveIt
+        } else {
+            StatusUpdateResult::KeepIt
+        }
+    }
+
+    pub fn hp_mod_is_calculated_but_not_applied_yet(
+        &mut self,
+        outcome: HpModificationResult,
+    ) -> HpModificationResult {
+        return match outcome.typ {
+            HpModificationResultType::Ok(hp_mod_req) => match hp_mod_req {
+                HpModificationType::BasicDamage(value, _, _)
+                | HpModificationType::SpellDamage(value, _)
+                | HpModificationType::Poison(value) => {
+                    self.absorbed_damage += value;
+                    outcome.absorbed()
+                }
+                HpModificationType::Heal(_) => outcome,
+            },
+            HpModificationResultType::Blocked | HpModificationResultType::Absorbed => outcome,
+        };
+    }
+
+    pub fn stack(&self, _other: &StatusEnum) -> StatusStackingResult {
+        // I think it should be overwritten only when the caster_entity_id is the same
+        // otherwise other players should get the healed credits for their armors
+        StatusStackingResult::AddTheNewStatus
+    }
+}
@@ -0,0 +1,124 @@
+use crate::attack::{
+    AreaAttackComponent, AreaShape, DamageDisplayType, HpModificationType, WeaponType,
+};
+use crate::common::{v2, EngineTime, GameTime, Local, Vec2};
+use crate::components::char::EntityId;
+use crate::components::skills::skills::{FinishCast, SkillDef, SkillManifestation};
+use crate::components::status::status::{
+    ApplyStatusComponent, StatusEnum, StatusUpdateParams, StatusUpdateResult,
+};
+use crate::config::{AssaBladeDashSkillConfig, CommonConfigs};
+use nalgebra::Vector2;
+use specs::prelude::*;
+
+pub struct AssaBladeDashSkill;
+
+pub const ASSA_BLADE_DASH_SKILL: &AssaBladeDashSkill = &AssaBladeDashSkill;
+
+impl SkillDef for AssaBladeDashSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let angle = params.char_to_skill_dir.angle(&Vector2::y());
+        let angle = if params.char_to_skill_dir.x > 0.0 {
+            angle
+        } else {
+            -angle
+        };
+        let configs = ecs_world
+            .read_resource::<CommonConfigs>()
+            .skills
+            .assa_blade_dash
+            .clone();
+        let now = ecs_world.read_resource::<EngineTime>().now();
+        ecs_world
+            .write_resource::<Vec<ApplyStatusComponent>>()
+            .push(ApplyStatusComponent::from_status(
+                params.caster_entity_id,
+                params.caster_entity_id,
+                StatusEnum::AssaBladeDashStatus(AssaBladeDashStatus {
+                    caster_entity_id: params.caster_entity_id,
+                    started_at: now,
+                    ends_at: now.add_seconds(configs.duration_seconds),
+                    start_pos: params.caster_pos,
+                    center: params.caster_pos
+                        + params.char_to_skill_dir * (configs.attributes.casting_range / 2.0),
+                    rot_radian: angle,
+                    vector: params.char_to_skill_dir * configs.attributes.casting_range,
+                    forward_damage_done: false,
+                    backward_damage_done: false,
+                    half_duration: (configs.duration_seconds * 1000f32) as u32 / 2,
+                    configs,
+                }),
+            ));
+        None
+    }
+}
+
+/// The assassin dashes forward then back to its starting position,
+/// damaging everyone along the way in both directions
+#[derive(Clone, Debug)]
+pub struct AssaBladeDashStatus {
+    pub caster_entity_id: EntityId<Local>,
+    pub started_at: GameTime<Local>,
+    pub ends_at: GameTime<Local>,
+    pub start_pos: Vec2,
+    pub center: Vec2,
+    pub rot_radian: f32,
+    pub half_duration: u32,
+    pub vector: Vec2,
+    pub forward_damage_done: bool,
+    pub backward_damage_done: bool,
+    pub configs: AssaBladeDashSkillConfig,
+}
+
+impl AssaBladeDashStatus {
+    pub fn update(&mut self, params: StatusUpdateParams) -> StatusUpdateResult {
+        let now = params.time.now();
+        if self.ends_at.has_already_passed(now) {
+            params.target_char.set_pos(self.start_pos);
+            return StatusUpdateResult::RemoveIt;
+        }
+        let duration_percentage = now.percentage_between(self.started_at, self.ends_at);
+        let travelled_percentage = if duration_percentage < 0.5 {
+            duration_percentage * 2.0
+        } else {
+            (1.0 - duration_percentage) * 2.0
+        };
+        params
+            .target_char
+            .set_pos(self.start_pos + self.vector * travelled_percentage);
+
+        let damage = if !self.forward_damage_done && duration_percentage > 0.25 {
+            self.forward_damage_done = true;
+            Some(self.configs.first_damage)
+        } else if !self.backward_damage_done && duration_percentage > 0.75 {
+            self.backward_damage_done = true;
+            Some(self.configs.second_damage)
+        } else {
+            None
+        };
+        if let Some(damage) = damage {
+            params.area_hp_mod_requests.push(AreaAttackComponent {
+                area_shape: AreaShape::Rectangle {
+                    center: self.center,
+                    half_extents: v2(
+                        self.configs.attributes.width.unwrap_or(1.0),
+                        self.configs.attributes.casting_range,
+                    ) / 2.0,
+                    rot_angle_in_rad: self.rot_radian,
+                },
+                source_entity_id: self.caster_entity_id,
+                typ: HpModificationType::BasicDamage(
+                    damage,
+                    DamageDisplayType::SingleNumber,
+                    WeaponType::Sword,
+                ),
+                except: None,
+            });
+        }
+        StatusUpdateResult::KeepIt
+    }
+}
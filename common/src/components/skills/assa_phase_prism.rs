@@ -0,0 +1,158 @@
+use crate::attack::AreaShape;
+use crate::common::{v2, EngineTime, GameTime, Local, Vec2};
+use crate::components::char::{CharType, EntityId};
+use crate::components::skills::skills::{
+    FinishCast, SkillDef, SkillManifestation, SkillManifestationUpdateParam,
+};
+use crate::components::status::status::{
+    ApplyStatusComponent, RemoveStatusComponent, RemoveStatusComponentPayload, StatusEnum,
+    StatusEnumDiscriminants, StatusUpdateParams, StatusUpdateResult,
+};
+use crate::config::CommonConfigs;
+use specs::prelude::*;
+
+/// The swap takes at least this many seconds, even if the two characters are next to each other
+const MIN_SWAP_DURATION: f32 = 0.5;
+
+pub struct AssaPhasePrismSkill;
+
+pub const ASSA_PHASE_PRISM_SKILL: &AssaPhasePrismSkill = &AssaPhasePrismSkill;
+
+impl SkillDef for AssaPhasePrismSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let configs = &ecs_world
+            .read_resource::<CommonConfigs>()
+            .skills
+            .assa_phase_prism;
+        let now = ecs_world.read_resource::<EngineTime>().now();
+        Some(Box::new(AssaPhasePrismSkillManifestation {
+            start_pos: params.caster_pos,
+            pos: params.caster_pos,
+            caster_id: params.caster_entity_id,
+            dir: params.char_to_skill_dir,
+            started_at: now,
+            ends_at: now.add_seconds(configs.duration_seconds),
+            casting_range: configs.attributes.casting_range,
+            swap_duration_unit_per_second: configs.swap_duration_unit_per_second,
+        }))
+    }
+}
+
+/// A projectile which swaps the position of the caster and the first character it hits
+struct AssaPhasePrismSkillManifestation {
+    start_pos: Vec2,
+    pos: Vec2,
+    caster_id: EntityId<Local>,
+    dir: Vec2,
+    started_at: GameTime<Local>,
+    ends_at: GameTime<Local>,
+    casting_range: f32,
+    swap_duration_unit_per_second: f32,
+}
+
+impl AssaPhasePrismSkillManifestation {
+    fn swap(
+        &self,
+        params: &mut SkillManifestationUpdateParam,
+        target_id: EntityId<Local>,
+        target_pos: Vec2,
+    ) {
+        let now = params.time().now();
+        let caster_pos = match params.auth_state_storage.get(self.caster_id.into()) {
+            Some(caster) => caster.pos(),
+            None => return,
+        };
+        let distance = (target_pos - caster_pos).magnitude();
+        let ends_at =
+            now.add_seconds((distance * self.swap_duration_unit_per_second).max(MIN_SWAP_DURATION));
+        params.apply_status(ApplyStatusComponent::from_status(
+            self.caster_id,
+            self.caster_id,
+            StatusEnum::AssaPhasePrismStatus(AssaPhasePrismStatus {
+                caster_entity_id: self.caster_id,
+                started_at: now,
+                ends_at,
+                start_pos: caster_pos,
+                vector: target_pos - caster_pos,
+            }),
+        ));
+        params.apply_status(ApplyStatusComponent::from_status(
+            self.caster_id,
+            target_id,
+            StatusEnum::AssaPhasePrismStatus(AssaPhasePrismStatus {
+                caster_entity_id: self.caster_id,
+                started_at: now,
+                ends_at,
+                start_pos: target_pos,
+                vector: caster_pos - target_pos,
+            }),
+        ));
+        params.remove_status(RemoveStatusComponent {
+            source_entity_id: self.caster_id,
+            target_entity_id: self.caster_id,
+            status: RemoveStatusComponentPayload::RemovingStatusDiscr(
+                StatusEnumDiscriminants::AssaBladeDashStatus,
+            ),
+        });
+    }
+}
+
+impl SkillManifestation for AssaPhasePrismSkillManifestation {
+    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
+        let now = params.time().now();
+        if self.ends_at.has_already_passed(now) {
+            params.remove_manifestation();
+            return;
+        }
+        let duration_percentage = now.percentage_between(self.started_at, self.ends_at);
+        self.pos = self.start_pos + self.dir * (self.casting_range * duration_percentage);
+        let area_shape = AreaShape::Rectangle {
+            center: self.pos,
+            half_extents: v2(0.5, 0.5),
+            rot_angle_in_rad: 0.0,
+        };
+        let target = params.collect_chars_in_area(&area_shape).into_iter().find(
+            |(target_id, _target_pos, _target_team)| {
+                *target_id != self.caster_id
+                    && params
+                        .static_char_data_storage
+                        .get((*target_id).into())
+                        .map(|it| it.typ != CharType::Structure)
+                        .unwrap_or(false)
+            },
+        );
+        if let Some((target_id, target_pos, _target_team)) = target {
+            self.swap(&mut params, target_id, target_pos);
+            params.remove_manifestation();
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AssaPhasePrismStatus {
+    pub caster_entity_id: EntityId<Local>,
+    pub started_at: GameTime<Local>,
+    pub ends_at: GameTime<Local>,
+    pub start_pos: Vec2,
+    pub vector: Vec2,
+}
+
+impl AssaPhasePrismStatus {
+    pub fn update(&mut self, params: StatusUpdateParams) -> StatusUpdateResult {
+        let now = params.time.now();
+        if self.ends_at.has_already_passed(now) {
+            params.target_char.set_pos(self.start_pos + self.vector);
+            StatusUpdateResult::RemoveIt
+        } else {
+            let duration_percentage = now.percentage_between(self.started_at, self.ends_at);
+            params
+                .target_char
+                .set_pos(self.start_pos + self.vector * duration_percentage);
+            StatusUpdateResult::KeepIt
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::attack::{AreaAttackComponent, AreaShape, DamageDisplayType, HpModificationType};
+use crate::common::{v2, EngineTime, GameTime, Local, Vec2};
+use crate::components::char::EntityId;
+use crate::components::skills::skills::{
+    FinishCast, SkillDef, SkillManifestation, SkillManifestationUpdateParam,
+};
+use crate::config::CommonConfigs;
+use nalgebra::Vector2;
+use specs::prelude::*;
+
+pub struct BrutalTestSkill;
+
+pub const BRUTAL_TEST_SKILL: &BrutalTestSkill = &BrutalTestSkill;
+
+impl SkillDef for BrutalTestSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let angle_in_rad = params.char_to_skill_dir.angle(&Vector2::y());
+        let angle_in_rad = if params.char_to_skill_dir.x > 0.0 {
+            angle_in_rad
+        } else {
+            -angle_in_rad
+        };
+        Some(Box::new(BrutalSkillManifest::new(
+            params.caster_entity_id,
+            &params.skill_pos?,
+            angle_in_rad,
+            ecs_world
+                .read_resource::<CommonConfigs>()
+                .skills
+                .brutal_test_skill
+                .damage,
+            ecs_world.read_resource::<EngineTime>().now(),
+        )))
+    }
+}
+
+pub struct BrutalSkillManifest {
+    pub caster_entity_id: EntityId<Local>,
+    pub half_extents: Vec2,
+    pub pos: Vec2,
+    pub rot_angle_in_rad: f32,
+    pub die_at: GameTime<Local>,
+    pub next_damage_at: GameTime<Local>,
+    pub damage: u32,
+}
+
+impl BrutalSkillManifest {
+    pub fn new(
+        caster_entity_id: EntityId<Local>,
+        skill_center: &Vec2,
+        rot_angle_in_rad: f32,
+        damage: u32,
+        system_time: GameTime<Local>,
+    ) -> BrutalSkillManifest {
+        BrutalSkillManifest {
+            caster_entity_id,
+            rot_angle_in_rad,
+            pos: *skill_center,
+            half_extents: v2(5.0, 5.0),
+            die_at: system_time.add_seconds(30.0),
+            next_damage_at: system_time,
+            damage,
+        }
+    }
+}
+
+impl SkillManifestation for BrutalSkillManifest {
+    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
+        let now = params.time().now();
+        if self.die_at.has_already_passed(now) {
+            params.remove_manifestation();
+        } else if self.next_damage_at.has_already_passed(now) {
+            self.next_damage_at = now.add_seconds(0.5);
+            params.add_area_hp_mod_request(AreaAttackComponent {
+                area_shape: AreaShape::Rectangle {
+                    center: self.pos,
+                    half_extents: self.half_extents,
+                    rot_angle_in_rad: self.rot_angle_in_rad,
+                },
+                source_entity_id: self.caster_entity_id,
+                typ: HpModificationType::SpellDamage(self.damage, DamageDisplayType::Combo(10)),
+                except: None,
+            });
+        }
+    }
+}
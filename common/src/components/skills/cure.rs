@@ -0,0 +1,25 @@
+use crate::components::char::StatusNature;
+use crate::components::skills::skills::{FinishCast, SkillDef, SkillManifestation};
+use crate::components::status::status::RemoveStatusComponent;
+use specs::prelude::*;
+
+pub struct CureSkill;
+
+pub const CURE_SKILL: &CureSkill = &CureSkill;
+
+impl SkillDef for CureSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        ecs_world
+            .write_resource::<Vec<RemoveStatusComponent>>()
+            .push(RemoveStatusComponent::by_status_nature(
+                params.caster_entity_id,
+                params.target_entity?,
+                StatusNature::Harmful,
+            ));
+        None
+    }
+}
@@ -0,0 +1,101 @@
+use crate::attack::{AreaShape, DamageDisplayType, HpModificationRequest, HpModificationType};
+use crate::common::{v2, EngineTime, GameTime, Local, Percentage, Vec2};
+use crate::components::char::{EntityId, Team};
+use crate::components::skills::skills::{
+    FinishCast, SkillDef, SkillManifestation, SkillManifestationUpdateParam,
+};
+use crate::components::status::attrib_mod::WalkingSpeedModifierStatus;
+use crate::components::status::status::{ApplyStatusComponent, StatusEnum};
+use crate::config::CommonConfigs;
+use nalgebra::Vector2;
+use specs::prelude::*;
+use std::collections::HashSet;
+
+pub struct FalconAttackSkill;
+
+pub const FALCON_ATTACK_SKILL: &FalconAttackSkill = &FalconAttackSkill;
+
+impl SkillDef for FalconAttackSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let configs = &ecs_world
+            .read_resource::<CommonConfigs>()
+            .skills
+            .falcon_attack;
+        let angle_in_rad = params.char_to_skill_dir.angle(&Vector2::y());
+        let angle_in_rad = if params.char_to_skill_dir.x > 0.0 {
+            angle_in_rad
+        } else {
+            -angle_in_rad
+        };
+        let now = ecs_world.read_resource::<EngineTime>().now();
+        Some(Box::new(FalconAttackSkillManifestation {
+            damaged_entities: HashSet::with_capacity(8),
+            half_extents: v2(configs.attributes.width.unwrap_or(1.0), 2.5) / 2.0,
+            start_pos: params.caster_pos,
+            path: params.char_to_skill_dir * configs.attributes.casting_range,
+            rot_angle_in_rad: angle_in_rad,
+            created_at: now,
+            die_at: now.add_seconds(configs.duration_in_seconds),
+            falcon_owner_id: params.caster_entity_id,
+            team: params.caster_team,
+            damage: configs.damage,
+            slow: configs.slow,
+            slow_duration: configs.slow_duration,
+        }))
+    }
+}
+
+/// The falcon flies through the enemies, damaging and slowing each of them once
+struct FalconAttackSkillManifestation {
+    damaged_entities: HashSet<EntityId<Local>>,
+    half_extents: Vec2,
+    start_pos: Vec2,
+    path: Vec2,
+    rot_angle_in_rad: f32,
+    created_at: GameTime<Local>,
+    die_at: GameTime<Local>,
+    falcon_owner_id: EntityId<Local>,
+    team: Team,
+    damage: u32,
+    slow: Percentage,
+    slow_duration: f32,
+}
+
+impl SkillManifestation for FalconAttackSkillManifestation {
+    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
+        let now = params.time().now();
+        if self.die_at.has_already_passed(now) {
+            params.remove_manifestation();
+            return;
+        }
+        let duration_percentage = now.percentage_between(self.created_at, self.die_at);
+        let area_shape = AreaShape::Rectangle {
+            center: self.start_pos + self.path * duration_percentage,
+            half_extents: self.half_extents,
+            rot_angle_in_rad: self.rot_angle_in_rad,
+        };
+        for (target_id, _target_pos, target_team) in params.collect_chars_in_area(&area_shape) {
+            if !self.team.can_attack(target_team) || !self.damaged_entities.insert(target_id) {
+                continue;
+            }
+            params.add_hp_mod_request(HpModificationRequest {
+                src_entity: self.falcon_owner_id,
+                dst_entity: target_id,
+                typ: HpModificationType::SpellDamage(self.damage, DamageDisplayType::Combo(2)),
+            });
+            params.apply_status(ApplyStatusComponent::from_status(
+                self.falcon_owner_id,
+                target_id,
+                StatusEnum::WalkingSpeedModifierStatus(WalkingSpeedModifierStatus::new(
+                    now,
+                    self.slow,
+                    self.slow_duration,
+                )),
+            ));
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use crate::common::{v2, EngineTime, GameTime, Local, Vec2};
+use crate::components::char::EntityId;
+use crate::components::controller::PlayerIntention;
+use crate::components::skills::skills::{
+    FinishCast, SkillDef, SkillManifestation, SkillManifestationUpdateParam,
+};
+use crate::components::status::status::{
+    ApplyStatusComponent, StatusEnum, StatusUpdateParams, StatusUpdateResult,
+};
+use crate::config::CommonConfigs;
+use specs::prelude::*;
+
+/// The part of the duration while the falcon flies to the ally
+const CARRY_ALLY_PICK_PERCENTAGE: f32 = 0.3;
+const CARRY_OWNER_PICK_SECONDS: f32 = 0.3;
+/// Units per second while the falcon carries its owner
+const CARRY_OWNER_SPEED: f32 = 8.57;
+
+pub struct FalconCarrySkill;
+
+pub const FALCON_CARRY_SKILL: &FalconCarrySkill = &FalconCarrySkill;
+
+impl SkillDef for FalconCarrySkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let now = ecs_world.read_resource::<EngineTime>().now();
+        let target_id = params.target_entity?;
+        let configs = &ecs_world
+            .read_resource::<CommonConfigs>()
+            .skills
+            .falcon_carry;
+        let carry_owner = target_id == params.caster_entity_id;
+        let (picked_at, ends_at) = if carry_owner {
+            (
+                now.add_seconds(CARRY_OWNER_PICK_SECONDS),
+                now.add_seconds(configs.carry_owner_duration),
+            )
+        } else {
+            (
+                now.add_seconds(configs.carry_ally_duration * CARRY_ALLY_PICK_PERCENTAGE),
+                now.add_seconds(configs.carry_ally_duration),
+            )
+        };
+        Some(Box::new(FalconCarryManifest {
+            owner_id: params.caster_entity_id,
+            target_id,
+            started_at: now,
+            picked_at,
+            ends_at,
+            last_update: now,
+            caught: false,
+            path: None,
+        }))
+    }
+}
+
+/// The way of the ally from where it has been caught to the owner of the falcon
+struct CarryAllyPath {
+    start: Vec2,
+    ctrl: Vec2,
+    end: Vec2,
+}
+
+impl CarryAllyPath {
+    fn evaluate(&self, t: f32) -> Vec2 {
+        let t = t.clamp(0.0, 1.0);
+        self.start * ((1.0 - t) * (1.0 - t))
+            + self.ctrl * (2.0 * (1.0 - t) * t)
+            + self.end * (t * t)
+    }
+}
+
+/// The falcon catches the target, then carries its owner to where it wants to move,
+/// or carries the ally to its owner
+struct FalconCarryManifest {
+    owner_id: EntityId<Local>,
+    target_id: EntityId<Local>,
+    started_at: GameTime<Local>,
+    picked_at: GameTime<Local>,
+    ends_at: GameTime<Local>,
+    last_update: GameTime<Local>,
+    caught: bool,
+    /// Always None when the owner is carried
+    path: Option<CarryAllyPath>,
+}
+
+impl FalconCarryManifest {
+    fn carry_owner(&self) -> bool {
+        self.owner_id == self.target_id
+    }
+
+    fn catch_target(&mut self, params: &mut SkillManifestationUpdateParam, target_pos: Vec2) {
+        let end_pos = if self.carry_owner() {
+            v2(0.0, 0.0)
+        } else {
+            let owner_pos = match params.auth_state_storage.get(self.owner_id.into()) {
+                Some(owner) => owner.pos(),
+                None => target_pos,
+            };
+            let line = target_pos - owner_pos;
+            self.path = Some(CarryAllyPath {
+                start: target_pos,
+                ctrl: target_pos + line * 0.2 + v2(5.0, 0.0),
+                end: owner_pos,
+            });
+            owner_pos
+        };
+        params.apply_status(ApplyStatusComponent::from_status(
+            self.owner_id,
+            self.target_id,
+            StatusEnum::FalconCarryStatus(FalconCarryStatus {
+                started_at: self.started_at,
+                ends_at: self.ends_at,
+                carry_owner: self.carry_owner(),
+                end_pos,
+            }),
+        ));
+    }
+
+    fn owner_destination(&self, params: &SkillManifestationUpdateParam) -> Option<Vec2> {
+        params
+            .controller_storage
+            .join()
+            .find(|controller| controller.controlled_entity == Some(self.owner_id))
+            .and_then(|controller| match controller.intention {
+                Some(PlayerIntention::MoveTo(pos))
+                | Some(PlayerIntention::MoveTowardsMouse(pos)) => Some(pos),
+                _ => None,
+            })
+    }
+}
+
+impl SkillManifestation for FalconCarryManifest {
+    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
+        let now = params.time().now();
+        let elapsed_seconds = now.elapsed_since(self.last_update).as_seconds_f32();
+        self.last_update = now;
+        if self.ends_at.has_already_passed(now) {
+            params.remove_manifestation();
+            return;
+        }
+        let target_pos = match params
+            .auth_state_storage
+            .get(self.target_id.into())
+            .filter(|target| target.state().is_alive())
+        {
+            Some(target) => target.pos(),
+            None => {
+                params.remove_manifestation();
+                return;
+            }
+        };
+        if self.picked_at.has_not_passed_yet(now) {
+            return;
+        }
+        if !self.caught {
+            self.caught = true;
+            self.catch_target(&mut params, target_pos);
+        }
+        let new_pos = match &self.path {
+            Some(path) => path.evaluate(now.percentage_between(self.picked_at, self.ends_at)),
+            None => match self.owner_destination(&params) {
+                Some(destination) if (destination - target_pos).magnitude() > 0.1 => {
+                    let dir = (destination - target_pos).normalize();
+                    let step = (CARRY_OWNER_SPEED * elapsed_seconds)
+                        .min((destination - target_pos).magnitude());
+                    target_pos + dir * step
+                }
+                _ => target_pos,
+            },
+        };
+        if let Some(target) = params.auth_state_storage.get_mut(self.target_id.into()) {
+            target.set_pos(new_pos);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FalconCarryStatus {
+    pub started_at: GameTime<Local>,
+    pub ends_at: GameTime<Local>,
+    pub carry_owner: bool,
+    pub end_pos: Vec2,
+}
+
+impl FalconCarryStatus {
+    pub fn update(&mut self, params: StatusUpdateParams) -> StatusUpdateResult {
+        if self.ends_at.has_already_passed(params.time.now()) {
+            StatusUpdateResult::RemoveIt
+        } else {
+            StatusUpdateResult::KeepIt
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::attack::{AreaAttackComponent, AreaShape, DamageDisplayType, HpModificationType};
+use crate::common::{EngineTime, GameTime, Local};
+use crate::components::char::{EntityId, StaticCharDataComponent, StatusNature, Team};
+use crate::components::skills::skills::{FinishCast, SkillDef, SkillManifestation};
+use crate::components::status::status::{
+    ApplyStatusComponent, ApplyStatusInAreaComponent, StatusEnum, StatusUpdateParams,
+    StatusUpdateResult,
+};
+use crate::config::CommonConfigs;
+use specs::prelude::*;
+
+const FIRE_BOMB_EXPLOSION_RADIUS: f32 = 2.0;
+
+/// The bomb explodes after this many seconds
+const FIRE_BOMB_DURATION: f32 = 2.0;
+
+/// The explosion spreads the bomb to the nearby enemies only this many times
+const FIRE_BOMB_MAX_SPREAD_COUNT: u8 = 1;
+
+pub struct FireBombSkill;
+
+pub const FIRE_BOMB_SKILL: &FireBombSkill = &FireBombSkill;
+
+impl SkillDef for FireBombSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let now = ecs_world.read_resource::<EngineTime>().now();
+        let damage = ecs_world
+            .read_resource::<CommonConfigs>()
+            .skills
+            .firebomb
+            .damage;
+        let caster_team = ecs_world
+            .read_storage::<StaticCharDataComponent>()
+            .get(params.caster_entity_id.into())?
+            .team;
+        ecs_world
+            .write_resource::<Vec<ApplyStatusComponent>>()
+            .push(ApplyStatusComponent::from_status(
+                params.caster_entity_id,
+                params.target_entity?,
+                StatusEnum::FireBombStatus(FireBombStatus {
+                    caster_entity_id: params.caster_entity_id,
+                    caster_team,
+                    damage,
+                    started: now,
+                    until: now.add_seconds(FIRE_BOMB_DURATION),
+                    spread_count: 0,
+                }),
+            ));
+        None
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FireBombStatus {
+    pub caster_entity_id: EntityId<Local>,
+    pub caster_team: Team,
+    pub damage: u32,
+    pub started: GameTime<Local>,
+    pub until: GameTime<Local>,
+    pub spread_count: u8,
+}
+
+impl FireBombStatus {
+    pub fn update(&mut self, params: StatusUpdateParams) -> StatusUpdateResult {
+        let now = params.time.now();
+        if self.until.has_not_passed_yet(now) {
+            return StatusUpdateResult::KeepIt;
+        }
+        let area_shape = AreaShape::Circle {
+            center: params.target_char.pos(),
+            radius: FIRE_BOMB_EXPLOSION_RADIUS,
+        };
+        params.area_hp_mod_requests.push(AreaAttackComponent {
+            area_shape: area_shape.clone(),
+            source_entity_id: self.caster_entity_id,
+            typ: HpModificationType::SpellDamage(self.damage, DamageDisplayType::Combo(10)),
+            except: None,
+        });
+        if self.spread_count < FIRE_BOMB_MAX_SPREAD_COUNT {
+            params.apply_area_statuses.push(ApplyStatusInAreaComponent {
+                source_entity_id: self.caster_entity_id,
+                status: StatusEnum::FireBombStatus(FireBombStatus {
+                    caster_entity_id: self.caster_entity_id,
+                    caster_team: self.caster_team,
+                    damage: self.damage,
+                    started: now,
+                    until: now.add_seconds(FIRE_BOMB_DURATION),
+                    spread_count: self.spread_count + 1,
+                }),
+                area_shape,
+                except: Some(params.self_char_id),
+                nature: StatusNature::Harmful,
+                caster_team: self.caster_team,
+            });
+        }
+        StatusUpdateResult::RemoveIt
+    }
+}
@@ -0,0 +1,140 @@
+use crate::attack::{
+    ApplyForceComponent, AreaShape, DamageDisplayType, HpModificationRequest, HpModificationType,
+};
+use crate::common::{v2, EngineTime, GameTime, Local, Vec2};
+use crate::components::char::{EntityId, StaticCharDataComponent, Team};
+use crate::components::skills::skills::{
+    FinishCast, SkillDef, SkillManifestation, SkillManifestationUpdateParam,
+};
+use crate::config::CommonConfigs;
+use nalgebra::Vector2;
+use specs::prelude::*;
+use std::collections::HashMap;
+
+pub struct FireWallSkill;
+
+pub const FIRE_WALL_SKILL: &FireWallSkill = &FireWallSkill;
+
+impl SkillDef for FireWallSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let team = ecs_world
+            .read_storage::<StaticCharDataComponent>()
+            .get(params.caster_entity_id.into())?
+            .team;
+        let angle_in_rad = params.char_to_skill_dir.angle(&Vector2::y());
+        let angle_in_rad = if params.char_to_skill_dir.x > 0.0 {
+            angle_in_rad
+        } else {
+            -angle_in_rad
+        };
+        let now = ecs_world.read_resource::<EngineTime>().now();
+        let configs = &ecs_world.read_resource::<CommonConfigs>().skills.firewall;
+        Some(Box::new(PushBackWallSkill::new(
+            params.caster_entity_id,
+            team,
+            configs.damage,
+            configs.pushback_force,
+            configs.force_duration_seconds,
+            &params.skill_pos?,
+            angle_in_rad,
+            now,
+            configs.duration_seconds,
+            configs.width,
+        )))
+    }
+}
+
+pub struct PushBackWallSkill {
+    pub caster_entity_id: EntityId<Local>,
+    pub area_shape: AreaShape,
+    pub pos: Vec2,
+    pub rot_angle_in_rad: f32,
+    pub width: u16,
+    pub die_at: GameTime<Local>,
+    cannot_damage_until: HashMap<EntityId<Local>, GameTime<Local>>,
+    team: Team,
+    damage: u32,
+    pushback_force: f32,
+    force_duration_seconds: f32,
+}
+
+impl PushBackWallSkill {
+    pub fn new(
+        caster_entity_id: EntityId<Local>,
+        team: Team,
+        damage: u32,
+        pushback_force: f32,
+        force_duration_seconds: f32,
+        skill_center: &Vec2,
+        rot_angle_in_rad: f32,
+        system_time: GameTime<Local>,
+        duration_seconds: f32,
+        width: u16,
+    ) -> PushBackWallSkill {
+        PushBackWallSkill {
+            caster_entity_id,
+            area_shape: AreaShape::Rectangle {
+                center: *skill_center,
+                half_extents: v2(width as f32 / 2.0, 0.5),
+                rot_angle_in_rad,
+            },
+            rot_angle_in_rad,
+            pos: *skill_center,
+            width,
+            die_at: system_time.add_seconds(duration_seconds),
+            cannot_damage_until: HashMap::new(),
+            team,
+            damage,
+            pushback_force,
+            force_duration_seconds,
+        }
+    }
+}
+
+impl SkillManifestation for PushBackWallSkill {
+    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
+        let now = params.time().now();
+        if self.die_at.has_already_passed(now) {
+            params.remove_manifestation();
+            return;
+        }
+        for (target_char_entity_id, target_pos, target_team) in
+            params.collect_chars_in_area(&self.area_shape)
+        {
+            if !self.team.can_attack(target_team)
+                || !self
+                    .cannot_damage_until
+                    .get(&target_char_entity_id)
+                    .unwrap_or(&now)
+                    .has_already_passed(now)
+            {
+                continue;
+            }
+            let push_dir = self.pos - target_pos;
+            let push_dir = if push_dir.x == 0.0 && push_dir.y == 0.0 {
+                v2(1.0, 0.0) // "random"
+            } else {
+                -push_dir.normalize()
+            };
+            params.add_hp_mod_request(HpModificationRequest {
+                src_entity: self.caster_entity_id,
+                dst_entity: target_char_entity_id,
+                typ: HpModificationType::SpellDamage(self.damage, DamageDisplayType::SingleNumber),
+            });
+            params.apply_force(ApplyForceComponent {
+                src_entity: self.caster_entity_id,
+                dst_entity: target_char_entity_id,
+                force: push_dir * self.pushback_force,
+                duration: self.force_duration_seconds,
+            });
+            self.cannot_damage_until.insert(
+                target_char_entity_id,
+                now.add_seconds(self.force_duration_seconds),
+            );
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::common::{v2, Local};
+use crate::components::char::{
+    create_common_player_entity, CharOutlook, CharType, JobId, LocalCharStateComp, MonsterId,
+    StaticCharDataComponent,
+};
+use crate::components::skills::skills::{FinishCast, SkillDef, SkillManifestation};
+use specs::prelude::*;
+
+pub struct GazBarricadeSkill;
+
+pub const GAZ_BARRICADE_SKILL: &GazBarricadeSkill = &GazBarricadeSkill;
+
+impl SkillDef for GazBarricadeSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let skill_pos = params.skill_pos?;
+        let tile_pos = (skill_pos.x as i32, skill_pos.y as i32);
+        let tile_is_occupied = (
+            &ecs_world.read_storage::<StaticCharDataComponent>(),
+            &ecs_world.read_storage::<LocalCharStateComp<Local>>(),
+        )
+            .join()
+            .any(|(static_data, char_state)| {
+                static_data.job_id == JobId::Barricade
+                    && char_state.state().is_alive()
+                    && (char_state.pos().x as i32, char_state.pos().y as i32) == tile_pos
+            });
+        if tile_is_occupied {
+            return None;
+        }
+        create_common_player_entity(
+            "Barricade".to_owned(),
+            ecs_world,
+            CharType::Mercenary,
+            JobId::Barricade,
+            v2(tile_pos.0 as f32, tile_pos.1 as f32),
+            params.caster_team,
+            CharOutlook::Monster(MonsterId::Barricade),
+        )
+        .build();
+        None
+    }
+}
@@ -0,0 +1,115 @@
+use crate::char_attr::{CharAttributeModifier, CharAttributeModifierCollector};
+use crate::common::{EngineTime, GameTime, Local, Percentage};
+use crate::components::skills::skills::{FinishCast, SkillDef, SkillManifestation};
+use crate::components::status::status::{
+    ApplyStatusComponent, StatusEnum, StatusUpdateParams, StatusUpdateResult,
+};
+use crate::config::CommonConfigs;
+use specs::prelude::*;
+
+pub struct ExoSkeletonSkill;
+
+pub const EXO_SKELETON_SKILL: &ExoSkeletonSkill = &ExoSkeletonSkill;
+
+impl SkillDef for ExoSkeletonSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let now = ecs_world.read_resource::<EngineTime>().now();
+        let status = {
+            let configs = &ecs_world
+                .read_resource::<CommonConfigs>()
+                .skills
+                .exoskeleton;
+            ExoSkeletonStatus::new(
+                now,
+                configs.duration_seconds,
+                configs.armor,
+                configs.attack_range,
+                configs.movement_speed,
+                configs.attack_damage,
+                configs.attack_speed,
+            )
+        };
+        ecs_world
+            .write_resource::<Vec<ApplyStatusComponent>>()
+            .push(ApplyStatusComponent::from_status(
+                params.caster_entity_id,
+                params.caster_entity_id,
+                StatusEnum::ExoSkeletonStatus(status),
+            ));
+        None
+    }
+}
+
+/// While it is active, the basic attacks of its owner are ranged
+#[derive(Clone, Debug)]
+pub struct ExoSkeletonStatus {
+    started: GameTime<Local>,
+    pub until: GameTime<Local>,
+    armor: Percentage,
+    attack_range: Percentage,
+    movement_speed: Percentage,
+    attack_damage: Percentage,
+    attack_speed: Percentage,
+}
+
+impl ExoSkeletonStatus {
+    fn new(
+        now: GameTime<Local>,
+        duration: f32,
+        armor: Percentage,
+        attack_range: Percentage,
+        movement_speed: Percentage,
+        attack_damage: Percentage,
+        attack_speed: Percentage,
+    ) -> ExoSkeletonStatus {
+        ExoSkeletonStatus {
+            started: now,
+            until: now.add_seconds(duration),
+            armor,
+            attack_range,
+            movement_speed,
+            attack_damage,
+            attack_speed,
+        }
+    }
+
+    pub fn calc_attribs(&self, modifiers: &mut CharAttributeModifierCollector) {
+        modifiers.change_armor(
+            CharAttributeModifier::AddPercentage(self.armor),
+            self.started,
+            self.until,
+        );
+        modifiers.change_walking_speed(
+            CharAttributeModifier::AddPercentage(self.movement_speed),
+            self.started,
+            self.until,
+        );
+        modifiers.change_attack_range(
+            CharAttributeModifier::AddPercentage(self.attack_range),
+            self.started,
+            self.until,
+        );
+        modifiers.change_attack_damage(
+            CharAttributeModifier::IncreaseByPercentage(self.attack_damage),
+            self.started,
+            self.until,
+        );
+        modifiers.change_attack_speed(
+            CharAttributeModifier::AddPercentage(self.attack_speed),
+            self.started,
+            self.until,
+        );
+    }
+
+    pub fn update(&mut self, params: StatusUpdateParams) -> StatusUpdateResult {
+        if self.until.has_already_passed(params.time.now()) {
+            StatusUpdateResult::RemoveIt
+        } else {
+            StatusUpdateResult::KeepIt
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::common::Local;
+use crate::components::char::{
+    create_common_player_entity, CharOutlook, CharType, EntityId, JobId, LocalCharStateComp,
+    MonsterId,
+};
+use crate::components::controller::ControllerComponent;
+use crate::components::skills::skills::{FinishCast, SkillDef, SkillManifestation};
+use crate::components::turret::{TurretComponent, TurretControllerComponent};
+use specs::prelude::*;
+
+pub struct GazTurretSkill;
+
+pub const GAZ_TURRET_SKILL: &GazTurretSkill = &GazTurretSkill;
+
+impl SkillDef for GazTurretSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let turret_id = EntityId::from(
+            create_common_player_entity(
+                "Turret".to_owned(),
+                ecs_world,
+                CharType::Minion,
+                JobId::Turret,
+                params.skill_pos?,
+                params.caster_team,
+                CharOutlook::Monster(MonsterId::Dimik),
+            )
+            .with(TurretComponent {
+                owner_entity_id: params.caster_entity_id,
+                preferred_target: None,
+            })
+            .build(),
+        );
+        ecs_world
+            .create_entity()
+            .with(ControllerComponent::new(turret_id))
+            .with(TurretControllerComponent)
+            .build();
+        None
+    }
+}
+
+pub struct GazDestroyTurretSkill;
+
+pub const GAZ_DESTROY_TURRET_SKILL: &GazDestroyTurretSkill = &GazDestroyTurretSkill;
+
+impl SkillDef for GazDestroyTurretSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let target_entity_id = params.target_entity?;
+        let is_own_turret = ecs_world
+            .read_storage::<TurretComponent>()
+            .get(target_entity_id.into())
+            .map(|turret| turret.owner_entity_id == params.caster_entity_id)
+            .unwrap_or(false);
+        if is_own_turret {
+            // it is an ally, it cannot be damaged, the death is handled by the char state system
+            if let Some(turret) = ecs_world
+                .write_storage::<LocalCharStateComp<Local>>()
+                .get_mut(target_entity_id.into())
+            {
+                turret.hp = 0;
+            }
+        }
+        None
+    }
+}
+
+pub struct GazTurretTargetSkill;
+
+pub const GAZ_TURRET_TARGET_SKILL: &GazTurretTargetSkill = &GazTurretTargetSkill;
+
+impl SkillDef for GazTurretTargetSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        for turret in (&mut ecs_world.write_storage::<TurretComponent>()).join() {
+            if turret.owner_entity_id == params.caster_entity_id {
+                turret.preferred_target = params.target_entity;
+            }
+        }
+        None
+    }
+}
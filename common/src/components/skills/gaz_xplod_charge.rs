@@ -0,0 +1,84 @@
+use crate::attack::{AreaAttackComponent, AreaShape, DamageDisplayType, HpModificationType};
+use crate::common::{EngineTime, GameTime, Local, Vec2};
+use crate::components::char::{EntityId, StatusNature, Team};
+use crate::components::skills::skills::{
+    FinishCast, SkillDef, SkillManifestation, SkillManifestationUpdateParam,
+};
+use crate::components::status::status::{ApplyStatusInAreaComponent, StatusEnum};
+use crate::components::status::stun::StunStatus;
+use crate::config::{CommonConfigs, GazXplodiumChargeSkillConfigInner};
+use specs::prelude::*;
+
+pub struct GazXplodiumChargeSkill;
+
+pub const GAZ_XPLODIUM_CHARGE_SKILL: &GazXplodiumChargeSkill = &GazXplodiumChargeSkill;
+
+impl SkillDef for GazXplodiumChargeSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        Some(Box::new(GazXplodiumChargeSkillManifestation {
+            end_pos: params.skill_pos?,
+            caster_id: params.caster_entity_id,
+            caster_team: params.caster_team,
+            started_at: ecs_world.read_resource::<EngineTime>().now(),
+            configs: ecs_world
+                .read_resource::<CommonConfigs>()
+                .skills
+                .gaz_xplodium_charge
+                .inner
+                .clone(),
+        }))
+    }
+}
+
+/// The missile flies to the target area, then it explodes after the detonation time,
+/// damaging and stunning the enemies around
+struct GazXplodiumChargeSkillManifestation {
+    end_pos: Vec2,
+    caster_id: EntityId<Local>,
+    caster_team: Team,
+    started_at: GameTime<Local>,
+    configs: GazXplodiumChargeSkillConfigInner,
+}
+
+impl SkillManifestation for GazXplodiumChargeSkillManifestation {
+    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
+        let now = params.time().now();
+        let explodes_at = self
+            .started_at
+            .add_seconds(self.configs.missile_travel_duration_seconds)
+            .add_seconds(self.configs.detonation_duration);
+        if explodes_at.has_not_passed_yet(now) {
+            return;
+        }
+        let area_shape = AreaShape::Circle {
+            center: self.end_pos,
+            radius: self.configs.explosion_area,
+        };
+        params.add_area_hp_mod_request(AreaAttackComponent {
+            area_shape: area_shape.clone(),
+            source_entity_id: self.caster_id,
+            typ: HpModificationType::SpellDamage(
+                self.configs.damage,
+                DamageDisplayType::SingleNumber,
+            ),
+            except: None,
+        });
+        params.apply_area_status(ApplyStatusInAreaComponent {
+            source_entity_id: self.caster_id,
+            status: StatusEnum::StunStatus(StunStatus::new(
+                self.caster_id,
+                now,
+                self.configs.stun_duration_seconds,
+            )),
+            area_shape,
+            except: None,
+            nature: StatusNature::Harmful,
+            caster_team: self.caster_team,
+        });
+        params.remove_manifestation();
+    }
+}
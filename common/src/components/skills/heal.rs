@@ -0,0 +1,62 @@
+use crate::attack::{HpModificationRequest, HpModificationType};
+use crate::common::{EngineTime, GameTime, Local};
+use crate::components::char::EntityId;
+use crate::components::skills::skills::{
+    FinishCast, SkillDef, SkillManifestation, SkillManifestationUpdateParam,
+};
+use crate::config::CommonConfigs;
+use specs::prelude::*;
+
+pub struct HealSkill;
+
+pub const HEAL_SKILL: &HealSkill = &HealSkill;
+
+impl SkillDef for HealSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let target_entity_id = params.target_entity?;
+        let now = ecs_world.read_resource::<EngineTime>().now();
+        ecs_world
+            .write_resource::<Vec<HpModificationRequest>>()
+            .push(HpModificationRequest {
+                src_entity: params.caster_entity_id,
+                dst_entity: target_entity_id,
+                typ: HpModificationType::Heal(
+                    ecs_world.read_resource::<CommonConfigs>().skills.heal.heal,
+                ),
+            });
+        return Some(Box::new(HealSkillManifest::new(target_entity_id, now)));
+    }
+}
+
+pub struct HealSkillManifest {
+    pub target_entity_id: EntityId<Local>,
+    pub created_at: GameTime<Local>,
+    pub middle: GameTime<Local>,
+    pub die_at: GameTime<Local>,
+}
+
+impl HealSkillManifest {
+    pub fn new(
+        target_entity_id: EntityId<Local>,
+        system_time: GameTime<Local>,
+    ) -> HealSkillManifest {
+        HealSkillManifest {
+            target_entity_id,
+            created_at: system_time,
+            middle: system_time.add_seconds(1.0),
+            die_at: system_time.add_seconds(2.0),
+        }
+    }
+}
+
+impl SkillManifestation for HealSkillManifest {
+    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
+        if self.die_at.has_already_passed(params.time().now()) {
+            params.remove_manifestation();
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::attack::{AreaAttackComponent, AreaShape, DamageDisplayType, HpModificationType};
+use crate::common::{EngineTime, GameTime, Local, Vec2};
+use crate::components::char::EntityId;
+use crate::components::skills::skills::{
+    FinishCast, SkillDef, SkillManifestation, SkillManifestationUpdateParam,
+};
+use specs::prelude::*;
+
+pub struct LightningSkill;
+
+pub const LIGHTNING_SKILL: &LightningSkill = &LightningSkill;
+
+impl SkillDef for LightningSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        Some(Box::new(LightningManifest::new(
+            params.caster_entity_id,
+            &params.skill_pos?,
+            &params.char_to_skill_dir,
+            ecs_world.read_resource::<EngineTime>().now(),
+        )))
+    }
+}
+
+pub struct LightningManifest {
+    pub caster_entity_id: EntityId<Local>,
+    pub pos: Vec2,
+    pub dir_vector: Vec2,
+    pub created_at: GameTime<Local>,
+    pub next_action_at: GameTime<Local>,
+    pub next_damage_at: GameTime<Local>,
+    pub last_skill_pos: Vec2,
+    pub action_count: u8,
+}
+
+impl LightningManifest {
+    pub fn new(
+        caster_entity_id: EntityId<Local>,
+        skill_center: &Vec2,
+        dir_vector: &Vec2,
+        now: GameTime<Local>,
+    ) -> LightningManifest {
+        LightningManifest {
+            caster_entity_id,
+            pos: *skill_center,
+            created_at: now,
+            next_action_at: now,
+            next_damage_at: now,
+            last_skill_pos: *skill_center,
+            action_count: 0,
+            dir_vector: *dir_vector,
+        }
+    }
+
+    // the strikes go forward and then come back
+    fn strike_pos(&self, action_count: u8) -> Option<Vec2> {
+        let distance = match action_count {
+            0 | 5 => 0.0,
+            1 | 4 => 1.0,
+            2 | 3 => 2.0,
+            _ => {
+                return None;
+            }
+        };
+        Some(self.pos + self.dir_vector * distance * 2.2)
+    }
+}
+
+impl SkillManifestation for LightningManifest {
+    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
+        let now = params.time().now();
+        if self.created_at.add_seconds(12.0).has_already_passed(now) {
+            params.remove_manifestation();
+        } else {
+            if self.next_action_at.has_already_passed(now) {
+                if let Some(pos) = self.strike_pos(self.action_count) {
+                    self.last_skill_pos = pos;
+                    self.action_count += 1;
+                    self.next_action_at = now.add_seconds(1.5);
+                    self.next_damage_at = now.add_seconds(1.0);
+                } else {
+                    return;
+                }
+            }
+            if self.next_damage_at.has_already_passed(now) {
+                params.add_area_hp_mod_request(AreaAttackComponent {
+                    area_shape: AreaShape::Circle {
+                        center: self.last_skill_pos,
+                        radius: 1.0,
+                    },
+                    source_entity_id: self.caster_entity_id,
+                    typ: HpModificationType::SpellDamage(120, DamageDisplayType::SingleNumber),
+                    except: None,
+                });
+                self.next_damage_at = self.next_damage_at.add_seconds(0.6);
+            }
+        }
+    }
+}
@@ -0,0 +1,21 @@
+#[macro_use]
+pub mod skills;
+pub mod absorb_shield;
+pub mod assa_blade_dash;
+pub mod assa_phase_prism;
+pub mod brutal_test_skill;
+pub mod cure;
+pub mod falcon_attack;
+pub mod falcon_carry;
+pub mod fire_bomb;
+pub mod firewall;
+pub mod gaz_barricade;
+pub mod gaz_exo_skel;
+pub mod gaz_turret;
+pub mod gaz_xplod_charge;
+pub mod heal;
+pub mod lightning;
+pub mod mounting;
+pub mod poison;
+pub mod sanctuary;
+pub mod wiz_pyroblast;
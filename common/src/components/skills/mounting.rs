@@ -0,0 +1,61 @@
+use crate::common::Percentage;
+use crate::components::char::StaticCharDataComponent;
+use crate::components::skills::skills::{FinishCast, SkillDef, SkillManifestation};
+use crate::components::status::status::{
+    ApplyStatusComponent, RemoveStatusComponent, RemoveStatusComponentPayload, StatusEnum,
+    StatusEnumDiscriminants, Statuses,
+};
+use crate::config::CommonConfigs;
+use specs::prelude::*;
+
+pub struct MountingSkill;
+
+pub const MOUNTING_SKILL: &MountingSkill = &MountingSkill;
+
+impl SkillDef for MountingSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let is_mounted = ecs_world
+            .read_storage::<Statuses>()
+            .get(params.caster_entity_id.into())?
+            .is_mounted();
+        if is_mounted {
+            ecs_world
+                .write_resource::<Vec<RemoveStatusComponent>>()
+                .push(RemoveStatusComponent {
+                    source_entity_id: params.caster_entity_id,
+                    target_entity_id: params.caster_entity_id,
+                    status: RemoveStatusComponentPayload::RemovingStatusDiscr(
+                        StatusEnumDiscriminants::MountedStatus,
+                    ),
+                })
+        } else {
+            let mounted_speedup = calc_mounted_speedup(
+                ecs_world
+                    .read_storage::<StaticCharDataComponent>()
+                    .get(params.caster_entity_id.into())?,
+                &ecs_world.read_resource::<CommonConfigs>(),
+            );
+            ecs_world
+                .write_resource::<Vec<ApplyStatusComponent>>()
+                .push(ApplyStatusComponent::from_status(
+                    params.caster_entity_id,
+                    params.caster_entity_id,
+                    StatusEnum::MountedStatus {
+                        speedup: mounted_speedup,
+                    },
+                ));
+        }
+        None
+    }
+}
+
+pub fn calc_mounted_speedup(
+    target_char: &StaticCharDataComponent,
+    configs: &CommonConfigs,
+) -> Percentage {
+    configs.job(target_char.job_id).mounted_speedup
+}
@@ -0,0 +1,34 @@
+use crate::common::EngineTime;
+use crate::components::skills::skills::{FinishCast, SkillDef, SkillManifestation};
+use crate::components::status::status::{ApplyStatusComponent, PoisonStatus, StatusEnum};
+use crate::config::CommonConfigs;
+use specs::prelude::*;
+
+pub struct PosionSkill;
+
+pub const POISON_SKILL: &PosionSkill = &PosionSkill;
+
+impl SkillDef for PosionSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let now = ecs_world.read_resource::<EngineTime>().now();
+        let configs = &ecs_world.read_resource::<CommonConfigs>().skills.poison;
+        ecs_world
+            .write_resource::<Vec<ApplyStatusComponent>>()
+            .push(ApplyStatusComponent::from_status(
+                params.caster_entity_id,
+                params.target_entity?,
+                StatusEnum::PoisonStatus(PoisonStatus {
+                    poison_caster_entity_id: params.caster_entity_id,
+                    started: now,
+                    until: now.add_seconds(configs.duration_seconds),
+                    next_damage_at: now,
+                    damage: configs.damage,
+                }),
+            ));
+        None
+    }
+}
@@ -0,0 +1,80 @@
+use crate::attack::{AreaAttackComponent, AreaShape, HpModificationType};
+use crate::common::{v2, EngineTime, GameTime, Local, Vec2};
+use crate::components::char::EntityId;
+use crate::components::skills::skills::{
+    FinishCast, SkillDef, SkillManifestation, SkillManifestationUpdateParam,
+};
+use crate::config::CommonConfigs;
+use specs::prelude::*;
+
+pub struct SanctuarySkill;
+
+pub const SANCTUARY_SKILL: &SanctuarySkill = &SanctuarySkill;
+
+impl SkillDef for SanctuarySkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let configs = &ecs_world.read_resource::<CommonConfigs>().skills.sanctuary;
+        Some(Box::new(SanctuarySkillManifest::new(
+            params.caster_entity_id,
+            &params.skill_pos?,
+            configs.heal,
+            configs.heal_freq_seconds,
+            ecs_world.read_resource::<EngineTime>().now(),
+            configs.duration,
+        )))
+    }
+}
+
+pub struct SanctuarySkillManifest {
+    pub caster_entity_id: EntityId<Local>,
+    pub pos: Vec2,
+    pub die_at: GameTime<Local>,
+    pub next_heal_at: GameTime<Local>,
+    pub heal_freq: f32,
+    pub heal: u32,
+}
+
+impl SanctuarySkillManifest {
+    pub fn new(
+        caster_entity_id: EntityId<Local>,
+        skill_center: &Vec2,
+        heal: u32,
+        heal_freq: f32,
+        system_time: GameTime<Local>,
+        duration: f32,
+    ) -> SanctuarySkillManifest {
+        SanctuarySkillManifest {
+            caster_entity_id,
+            pos: *skill_center,
+            die_at: system_time.add_seconds(duration),
+            next_heal_at: system_time,
+            heal,
+            heal_freq,
+        }
+    }
+}
+
+impl SkillManifestation for SanctuarySkillManifest {
+    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
+        let now = params.time().now();
+        if self.die_at.has_already_passed(now) {
+            params.remove_manifestation();
+        } else if self.next_heal_at.has_already_passed(now) {
+            self.next_heal_at = now.add_seconds(self.heal_freq);
+            params.add_area_hp_mod_request(AreaAttackComponent {
+                area_shape: AreaShape::Rectangle {
+                    center: self.pos,
+                    half_extents: v2(2.5, 2.5),
+                    rot_angle_in_rad: 0.0,
+                },
+                source_entity_id: self.caster_entity_id,
+                typ: HpModificationType::Heal(self.heal),
+                except: None,
+            });
+        }
+    }
+}
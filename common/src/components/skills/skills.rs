@@ -0,0 +1,462 @@
+use crate::attack::{ApplyForceComponent, AreaAttackComponent, AreaShape, HpModificationRequest};
+use crate::collision::CHAR_COLLISION_RADIUS;
+use crate::common::{EngineTime, Local, Vec2};
+use crate::components::char::{EntityId, LocalCharStateComp, StaticCharDataComponent, Team};
+use crate::components::controller::ControllerComponent;
+use crate::components::skills::absorb_shield::ABSORB_SHIELD_SKILL;
+use crate::components::skills::assa_blade_dash::ASSA_BLADE_DASH_SKILL;
+use crate::components::skills::assa_phase_prism::ASSA_PHASE_PRISM_SKILL;
+use crate::components::skills::brutal_test_skill::BRUTAL_TEST_SKILL;
+use crate::components::skills::cure::CURE_SKILL;
+use crate::components::skills::falcon_attack::FALCON_ATTACK_SKILL;
+use crate::components::skills::falcon_carry::FALCON_CARRY_SKILL;
+use crate::components::skills::fire_bomb::FIRE_BOMB_SKILL;
+use crate::components::skills::firewall::FIRE_WALL_SKILL;
+use crate::components::skills::gaz_barricade::GAZ_BARRICADE_SKILL;
+use crate::components::skills::gaz_exo_skel::EXO_SKELETON_SKILL;
+use crate::components::skills::gaz_turret::{
+    GAZ_DESTROY_TURRET_SKILL, GAZ_TURRET_SKILL, GAZ_TURRET_TARGET_SKILL,
+};
+use crate::components::skills::gaz_xplod_charge::GAZ_XPLODIUM_CHARGE_SKILL;
+use crate::components::skills::heal::HEAL_SKILL;
+use crate::components::skills::lightning::LIGHTNING_SKILL;
+use crate::components::skills::mounting::MOUNTING_SKILL;
+use crate::components::skills::poison::POISON_SKILL;
+use crate::components::skills::sanctuary::SANCTUARY_SKILL;
+use crate::components::skills::wiz_pyroblast::WIZ_PYRO_BLAST_SKILL;
+use crate::components::status::status::{
+    ApplyStatusComponent, ApplyStatusInAreaComponent, RemoveStatusComponent,
+};
+use crate::config::{CommonConfigs, SkillCastingAttributes};
+use serde::Deserialize;
+use serde::Serialize;
+use specs::prelude::*;
+use std::sync::{Arc, Mutex};
+use strum_macros::EnumIter;
+
+/// Everything a skill manifestation needs to simulate itself, independently from rendering
+pub struct SkillManifestationUpdateParam<'a, 'longer> {
+    pub self_entity_id: Entity,
+    hp_mod_requests: &'longer mut Vec<HpModificationRequest>,
+    area_hp_mod_requests: &'longer mut Vec<AreaAttackComponent>,
+    pushes: &'longer mut Vec<ApplyForceComponent>,
+    apply_statuses: &'longer mut Vec<ApplyStatusComponent>,
+    apply_area_statuses: &'longer mut Vec<ApplyStatusInAreaComponent>,
+    remove_statuses: &'longer mut Vec<RemoveStatusComponent>,
+    ended_manifestations: &'longer mut Vec<SkillManifestationEnded>,
+    engine_time: &'longer EngineTime,
+    entities: &'a Entities<'a>,
+    pub static_char_data_storage: &'longer ReadStorage<'a, StaticCharDataComponent>,
+    pub auth_state_storage: &'longer mut WriteStorage<'a, LocalCharStateComp<Local>>,
+    pub controller_storage: &'longer ReadStorage<'a, ControllerComponent>,
+    updater: &'longer LazyUpdate,
+}
+
+impl<'a, 'longer> SkillManifestationUpdateParam<'a, 'longer> {
+    pub fn new(
+        self_entity_id: Entity,
+        hp_mod_requests: &'longer mut Vec<HpModificationRequest>,
+        area_hp_mod_requests: &'longer mut Vec<AreaAttackComponent>,
+        pushes: &'longer mut Vec<ApplyForceComponent>,
+        apply_statuses: &'longer mut Vec<ApplyStatusComponent>,
+        apply_area_statuses: &'longer mut Vec<ApplyStatusInAreaComponent>,
+        remove_statuses: &'longer mut Vec<RemoveStatusComponent>,
+        ended_manifestations: &'longer mut Vec<SkillManifestationEnded>,
+        engine_time: &'longer EngineTime,
+        entities: &'a Entities,
+        static_char_data_storage: &'longer ReadStorage<'a, StaticCharDataComponent>,
+        auth_state_storage: &'longer mut WriteStorage<'a, LocalCharStateComp<Local>>,
+        controller_storage: &'longer ReadStorage<'a, ControllerComponent>,
+        updater: &'longer LazyUpdate,
+    ) -> SkillManifestationUpdateParam<'a, 'longer> {
+        SkillManifestationUpdateParam {
+            self_entity_id,
+            hp_mod_requests,
+            area_hp_mod_requests,
+            pushes,
+            apply_statuses,
+            apply_area_statuses,
+            remove_statuses,
+            ended_manifestations,
+            engine_time,
+            entities,
+            static_char_data_storage,
+            auth_state_storage,
+            controller_storage,
+            updater,
+        }
+    }
+
+    /// Removes the manifestation and notifies the clients which render it
+    pub fn remove_manifestation(&mut self) {
+        if let Err(e) = self.entities.delete(self.self_entity_id) {
+            log::error!("Could not remove skill manifestation: {}", e);
+        }
+        self.ended_manifestations.push(SkillManifestationEnded {
+            manifestation_id: EntityId::new(self.self_entity_id),
+        });
+    }
+
+    pub fn remove_component<C>(&self, entitiy_id: Entity)
+    where
+        C: Component + Send + Sync,
+    {
+        self.updater.remove::<C>(entitiy_id);
+    }
+
+    pub fn insert_comp<C>(&self, entitiy_id: Entity, comp: C)
+    where
+        C: Component + Send + Sync,
+    {
+        self.updater.insert(entitiy_id, comp);
+    }
+
+    pub fn create_entity_with_comp<C>(&self, comp: C)
+    where
+        C: Component + Send + Sync,
+    {
+        self.insert_comp(self.entities.create(), comp);
+    }
+
+    pub fn time(&self) -> &EngineTime {
+        self.engine_time
+    }
+
+    pub fn apply_status(&mut self, apply_status_comp: ApplyStatusComponent) {
+        self.apply_statuses.push(apply_status_comp);
+    }
+
+    pub fn apply_area_status(&mut self, apply_status_comp: ApplyStatusInAreaComponent) {
+        self.apply_area_statuses.push(apply_status_comp);
+    }
+
+    pub fn remove_status(&mut self, remove_status_comp: RemoveStatusComponent) {
+        self.remove_statuses.push(remove_status_comp);
+    }
+
+    pub fn add_hp_mod_request(&mut self, hp_mod_req: HpModificationRequest) {
+        self.hp_mod_requests.push(hp_mod_req);
+    }
+
+    pub fn add_area_hp_mod_request(&mut self, hp_mod_req: AreaAttackComponent) {
+        self.area_hp_mod_requests.push(hp_mod_req);
+    }
+
+    pub fn apply_force(&mut self, force: ApplyForceComponent) {
+        self.pushes.push(force);
+    }
+
+    pub fn collect_chars_in_area(&self, area: &AreaShape) -> Vec<(EntityId<Local>, Vec2, Team)> {
+        (
+            self.entities,
+            &*self.auth_state_storage,
+            self.static_char_data_storage,
+        )
+            .join()
+            .filter(|(_entity_id, char_state, _static_data)| {
+                char_state.state().is_alive()
+                    && area.overlaps_circle(&char_state.pos(), CHAR_COLLISION_RADIUS)
+            })
+            .map(|(entity_id, char_state, static_data)| {
+                (EntityId::new(entity_id), char_state.pos(), static_data.team)
+            })
+            .collect()
+    }
+}
+
+/// The simulation half of a skill, it runs only on the server, the clients render
+/// the manifestations based on the replicated `SkillManifested` events.
+/// The manifestations are updated by the `SkillSystem`, so they must be `Send` and `Sync`.
+pub trait SkillManifestation: Send + Sync {
+    fn update(&mut self, params: SkillManifestationUpdateParam);
+}
+
+#[derive(Component)]
+#[storage(HashMapStorage)]
+pub struct SkillManifestationComponent {
+    pub self_entity_id: Entity,
+    pub skill: Arc<Mutex<Box<dyn SkillManifestation>>>,
+}
+
+impl SkillManifestationComponent {
+    #[allow(clippy::arc_with_non_send_sync)]
+    pub fn new(
+        self_entity_id: Entity,
+        skill: Box<dyn SkillManifestation>,
+    ) -> SkillManifestationComponent {
+        SkillManifestationComponent {
+            self_entity_id,
+            skill: Arc::new(Mutex::new(skill)),
+        }
+    }
+
+    pub fn update(&mut self, params: SkillManifestationUpdateParam) {
+        let mut skill = self.skill.lock().unwrap();
+        skill.update(params);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FinishCast {
+    pub skill: Skills,
+    pub caster_entity_id: EntityId<Local>,
+    pub caster_pos: Vec2,
+    pub caster_team: Team,
+    pub skill_pos: Option<Vec2>,
+    pub char_to_skill_dir: Vec2,
+    pub target_entity: Option<EntityId<Local>>,
+}
+
+/// Collected when a skill has been manifested, the server replicates it to the clients
+#[derive(Clone, Debug)]
+pub struct SkillManifested {
+    pub manifestation_id: EntityId<Local>,
+    pub cast: FinishCast,
+}
+
+/// Collected when a manifestation has been removed, the server replicates it to the clients
+#[derive(Clone, Debug)]
+pub struct SkillManifestationEnded {
+    pub manifestation_id: EntityId<Local>,
+}
+
+pub trait SkillDef {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>>;
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, EnumIter, Serialize, Deserialize)]
+pub enum Skills {
+    AttackMove,
+    FireWall,
+    BrutalTestSkill,
+    Lightning,
+    Heal,
+    Mounting,
+    Poison,
+    Cure,
+    FireBomb,
+    AbsorbShield,
+    WizPyroBlast,
+    AssaBladeDash,
+    AssaPhasePrism,
+    GazXplodiumCharge,
+    GazTurret,
+    GazBarricade,
+    GazDestroyTurret,
+    GazTurretTarget,
+    FalconCarry,
+    FalconAttack,
+    Sanctuary,
+    ExoSkeleton,
+}
+
+/// The attack move is handled by the controller, it has nothing to manifest
+struct AttackMoveSkill;
+
+const ATTACK_MOVE_SKILL: &AttackMoveSkill = &AttackMoveSkill;
+
+impl SkillDef for AttackMoveSkill {
+    fn finish_cast(
+        &self,
+        _params: &FinishCast,
+        _ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        None
+    }
+}
+
+impl Skills {
+    pub fn get_definition(&self) -> &'static dyn SkillDef {
+        match self {
+            Skills::FireWall => FIRE_WALL_SKILL,
+            Skills::Heal => HEAL_SKILL,
+            Skills::Lightning => LIGHTNING_SKILL,
+            Skills::Mounting => MOUNTING_SKILL,
+            Skills::Poison => POISON_SKILL,
+            Skills::Cure => CURE_SKILL,
+            Skills::AbsorbShield => ABSORB_SHIELD_SKILL,
+            Skills::BrutalTestSkill => BRUTAL_TEST_SKILL,
+            Skills::FireBomb => FIRE_BOMB_SKILL,
+            Skills::WizPyroBlast => WIZ_PYRO_BLAST_SKILL,
+            Skills::AssaBladeDash => ASSA_BLADE_DASH_SKILL,
+            Skills::AssaPhasePrism => ASSA_PHASE_PRISM_SKILL,
+            Skills::GazXplodiumCharge => GAZ_XPLODIUM_CHARGE_SKILL,
+            Skills::GazTurret => GAZ_TURRET_SKILL,
+            Skills::GazBarricade => GAZ_BARRICADE_SKILL,
+            Skills::GazDestroyTurret => GAZ_DESTROY_TURRET_SKILL,
+            Skills::GazTurretTarget => GAZ_TURRET_TARGET_SKILL,
+            Skills::FalconCarry => FALCON_CARRY_SKILL,
+            Skills::FalconAttack => FALCON_ATTACK_SKILL,
+            Skills::Sanctuary => SANCTUARY_SKILL,
+            Skills::ExoSkeleton => EXO_SKELETON_SKILL,
+            Skills::AttackMove => ATTACK_MOVE_SKILL,
+        }
+    }
+
+    pub fn get_skill_target_type(&self) -> SkillTargetType {
+        match self {
+            Skills::AttackMove => SkillTargetType::Area,
+            Skills::FireWall => SkillTargetType::Area,
+            Skills::BrutalTestSkill => SkillTargetType::Area,
+            Skills::Lightning => SkillTargetType::Area,
+            Skills::Heal => SkillTargetType::OnlyAllyAndSelf,
+            Skills::Mounting => SkillTargetType::NoTarget,
+            Skills::Poison => SkillTargetType::OnlyEnemy,
+            Skills::Cure => SkillTargetType::OnlyAllyAndSelf,
+            Skills::FireBomb => SkillTargetType::OnlyEnemy,
+            Skills::AbsorbShield => SkillTargetType::OnlyAllyAndSelf,
+            Skills::WizPyroBlast => SkillTargetType::OnlyEnemy,
+            Skills::AssaBladeDash => SkillTargetType::Directional,
+            Skills::AssaPhasePrism => SkillTargetType::Directional,
+            Skills::GazXplodiumCharge => SkillTargetType::Area,
+            Skills::GazTurret => SkillTargetType::Area,
+            Skills::GazBarricade => SkillTargetType::Area,
+            Skills::GazDestroyTurret => SkillTargetType::OnlyAllyButNoSelf,
+            Skills::GazTurretTarget => SkillTargetType::OnlyEnemy,
+            Skills::FalconCarry => SkillTargetType::OnlyAllyAndSelf,
+            Skills::FalconAttack => SkillTargetType::Directional,
+            Skills::Sanctuary => SkillTargetType::Area,
+            Skills::ExoSkeleton => SkillTargetType::NoTarget,
+        }
+    }
+
+    pub fn get_cast_attributes<'a>(
+        &'a self,
+        configs: &'a CommonConfigs,
+        char_state: &LocalCharStateComp<Local>,
+    ) -> &'a SkillCastingAttributes {
+        match self {
+            Skills::WizPyroBlast => &configs.skills.wiz_pyroblast.attributes,
+            Skills::FireWall => &configs.skills.firewall.attributes,
+            Skills::Heal => &configs.skills.heal.attributes,
+            Skills::BrutalTestSkill => &configs.skills.brutal_test_skill.attributes,
+            Skills::Lightning => &configs.skills.lightning.attributes,
+            Skills::Mounting => {
+                if char_state.is_mounted() {
+                    &configs.skills.unmounting
+                } else {
+                    &configs.skills.mounting
+                }
+            }
+            Skills::Poison => &configs.skills.poison.attributes,
+            Skills::Cure => &configs.skills.cure,
+            Skills::FireBomb => &configs.skills.firebomb.attributes,
+            Skills::AbsorbShield => &configs.skills.absorb_shield.attributes,
+            Skills::AssaBladeDash => &configs.skills.assa_blade_dash.attributes,
+            Skills::AssaPhasePrism => &configs.skills.assa_phase_prism.attributes,
+            Skills::GazXplodiumCharge => &configs.skills.gaz_xplodium_charge.attributes,
+            Skills::GazTurret => &configs.skills.gaz_turret.attributes,
+            Skills::GazDestroyTurret => &configs.skills.gaz_destroy_turret,
+            Skills::GazTurretTarget => &configs.skills.gaz_destroy_turret,
+            Skills::FalconCarry => &configs.skills.falcon_carry.attributes,
+            Skills::FalconAttack => &configs.skills.falcon_attack.attributes,
+            Skills::Sanctuary => &configs.skills.sanctuary.attributes,
+            Skills::ExoSkeleton => &configs.skills.exoskeleton.attributes,
+            Skills::AttackMove => &configs.skills.gaz_destroy_turret,
+            Skills::GazBarricade => &configs.skills.gaz_barricade.attributes,
+        }
+    }
+
+    pub fn limit_vector_into_range(char_pos: &Vec2, mouse_pos: &Vec2, range: f32) -> (Vec2, Vec2) {
+        let dir2d = mouse_pos - char_pos;
+        // e.g. self casts, normalizing a zero vector would result in NaN
+        if dir2d.magnitude() < f32::EPSILON {
+            return (*char_pos, Vec2::new(0.0, 1.0));
+        }
+        let dir_vector = dir2d.normalize();
+        let pos = char_pos + dir_vector * dir2d.magnitude().min(range);
+        return (pos, dir_vector);
+    }
+
+    pub fn is_casting_allowed_based_on_target(
+        skill_target_type: SkillTargetType,
+        skill_casting_range: f32,
+        caster_id: EntityId<Local>,
+        target_entity: Option<EntityId<Local>>,
+        target_distance: f32,
+    ) -> bool {
+        match skill_target_type {
+            SkillTargetType::Area => true,
+            SkillTargetType::Directional => true,
+            SkillTargetType::NoTarget => true,
+            SkillTargetType::AnyEntity => {
+                target_entity.is_some() && skill_casting_range >= target_distance
+            }
+            SkillTargetType::OnlyAllyButNoSelf => {
+                target_entity.map(|it| it != caster_id).unwrap_or(false)
+                    && skill_casting_range >= target_distance
+            }
+            SkillTargetType::OnlyAllyAndSelf => {
+                target_entity.is_some() && skill_casting_range >= target_distance
+            }
+            SkillTargetType::OnlyEnemy => {
+                target_entity.is_some() && skill_casting_range >= target_distance
+            }
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[allow(dead_code)]
+pub enum SkillTargetType {
+    /// casts immediately
+    NoTarget,
+    Area,
+    Directional,
+    AnyEntity,
+    OnlyAllyButNoSelf,
+    OnlyAllyAndSelf,
+    OnlyEnemy,
+}
+
+pub fn execute_finished_skill_castings(ecs_world: &mut World) {
+    // TODO: avoid allocating new vec
+    let finished_casts = std::mem::replace(
+        &mut *ecs_world.write_resource::<Vec<FinishCast>>(),
+        Vec::with_capacity(128),
+    );
+    for finished_cast in &finished_casts {
+        let manifestation = finished_cast
+            .skill
+            .get_definition()
+            .finish_cast(finished_cast, ecs_world);
+        if let Some(manifestation) = manifestation {
+            let skill_entity_id = ecs_world.create_entity().build();
+            ecs_world.read_resource::<LazyUpdate>().insert(
+                skill_entity_id,
+                SkillManifestationComponent::new(skill_entity_id, manifestation),
+            );
+            ecs_world
+                .write_resource::<Vec<SkillManifested>>()
+                .push(SkillManifested {
+                    manifestation_id: EntityId::new(skill_entity_id),
+                    cast: finished_cast.clone(),
+                });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::v2;
+
+    #[test]
+    fn limited_vector_is_never_longer_than_the_range() {
+        let (pos, dir) = Skills::limit_vector_into_range(&v2(1.0, 1.0), &v2(11.0, 1.0), 4.0);
+        assert_eq!(v2(5.0, 1.0), pos);
+        assert_eq!(v2(1.0, 0.0), dir);
+    }
+
+    #[test]
+    fn self_cast_does_not_produce_nan() {
+        let (pos, dir) = Skills::limit_vector_into_range(&v2(3.0, 4.0), &v2(3.0, 4.0), 10.0);
+        assert_eq!(v2(3.0, 4.0), pos);
+        assert!(dir.x.is_finite() && dir.y.is_finite());
+        assert!((dir.magnitude() - 1.0).abs() < f32::EPSILON);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::attack::{
+    AreaAttackComponent, AreaShape, DamageDisplayType, HpModificationRequest, HpModificationType,
+};
+use crate::common::{EngineTime, GameTime, Local, Vec2};
+use crate::components::char::EntityId;
+use crate::components::skills::skills::{
+    FinishCast, SkillDef, SkillManifestation, SkillManifestationUpdateParam,
+};
+use crate::components::status::status::{
+    ApplyStatusComponent, RemoveStatusComponent, RemoveStatusComponentPayload, StatusEnum,
+    StatusEnumDiscriminants,
+};
+use crate::config::{CommonConfigs, SkillConfigPyroBlastInner};
+use specs::prelude::*;
+
+/// The ball explodes when it gets this close to its target
+const EXPLOSION_DISTANCE: f32 = 2.0;
+
+pub struct WizPyroBlastSkill;
+
+pub const WIZ_PYRO_BLAST_SKILL: &WizPyroBlastSkill = &WizPyroBlastSkill;
+
+impl SkillDef for WizPyroBlastSkill {
+    fn finish_cast(
+        &self,
+        params: &FinishCast,
+        ecs_world: &mut World,
+    ) -> Option<Box<dyn SkillManifestation>> {
+        let target_entity_id = params.target_entity?;
+        let configs = ecs_world
+            .read_resource::<CommonConfigs>()
+            .skills
+            .wiz_pyroblast
+            .inner
+            .clone();
+        ecs_world
+            .write_resource::<Vec<ApplyStatusComponent>>()
+            .push(ApplyStatusComponent::from_status(
+                params.caster_entity_id,
+                target_entity_id,
+                StatusEnum::PyroBlastTargetStatus(PyroBlastTargetStatus {
+                    caster_entity_id: params.caster_entity_id,
+                    splash_radius: configs.splash_radius,
+                }),
+            ));
+        Some(Box::new(PyroBlastManifest::new(
+            params.caster_entity_id,
+            params.caster_pos,
+            target_entity_id,
+            ecs_world.read_resource::<EngineTime>().now(),
+            configs,
+        )))
+    }
+}
+
+pub struct PyroBlastManifest {
+    pub caster_entity_id: EntityId<Local>,
+    pub pos: Vec2,
+    pub target_entity_id: EntityId<Local>,
+    pub last_update: GameTime<Local>,
+    pub configs: SkillConfigPyroBlastInner,
+}
+
+impl PyroBlastManifest {
+    pub fn new(
+        caster_entity_id: EntityId<Local>,
+        pos: Vec2,
+        target_entity_id: EntityId<Local>,
+        created_at: GameTime<Local>,
+        configs: SkillConfigPyroBlastInner,
+    ) -> PyroBlastManifest {
+        PyroBlastManifest {
+            caster_entity_id,
+            pos,
+            target_entity_id,
+            last_update: created_at,
+            configs,
+        }
+    }
+
+    fn remove_target_status(&self, params: &mut SkillManifestationUpdateParam) {
+        params.remove_status(RemoveStatusComponent {
+            source_entity_id: self.caster_entity_id,
+            target_entity_id: self.target_entity_id,
+            status: RemoveStatusComponentPayload::RemovingStatusDiscr(
+                StatusEnumDiscriminants::PyroBlastTargetStatus,
+            ),
+        });
+    }
+}
+
+impl SkillManifestation for PyroBlastManifest {
+    fn update(&mut self, mut params: SkillManifestationUpdateParam) {
+        let now = params.time().now();
+        let elapsed_seconds = now.elapsed_since(self.last_update).as_seconds_f32();
+        self.last_update = now;
+        let target_pos = match params
+            .auth_state_storage
+            .get(self.target_entity_id.into())
+            .filter(|target_char| target_char.state().is_alive())
+        {
+            Some(target_char) => target_char.pos(),
+            None => {
+                self.remove_target_status(&mut params);
+                params.remove_manifestation();
+                return;
+            }
+        };
+        let dir_vector = target_pos - self.pos;
+        if dir_vector.magnitude() > EXPLOSION_DISTANCE {
+            self.pos += dir_vector.normalize() * elapsed_seconds * self.configs.moving_speed;
+            return;
+        }
+        self.remove_target_status(&mut params);
+        params.add_hp_mod_request(HpModificationRequest {
+            src_entity: self.caster_entity_id,
+            dst_entity: self.target_entity_id,
+            typ: HpModificationType::SpellDamage(
+                self.configs.damage,
+                DamageDisplayType::SingleNumber,
+            ),
+        });
+        params.add_area_hp_mod_request(AreaAttackComponent {
+            area_shape: AreaShape::Circle {
+                center: target_pos,
+                radius: self.configs.splash_radius,
+            },
+            source_entity_id: self.caster_entity_id,
+            typ: HpModificationType::SpellDamage(
+                self.configs.secondary_damage,
+                DamageDisplayType::SingleNumber,
+            ),
+            except: Some(self.target_entity_id),
+        });
+        params.remove_manifestation();
+    }
+}
+
+/// Marks the target of the pyroblast, it is removed when the ball explodes
+#[derive(Clone, Debug)]
+pub struct PyroBlastTargetStatus {
+    pub caster_entity_id: EntityId<Local>,
+    pub splash_radius: f32,
+}
@@ -0,0 +1,53 @@
+use crate::attack::{
+    HpModificationRequest, HpModificationResult, HpModificationResultType, HpModificationType,
+};
+use crate::common::{GameTime, Local, Percentage};
+use crate::components::char::EntityId;
+use crate::components::status::status::{StatusUpdateParams, StatusUpdateResult};
+
+/// A part of the dealt basic attack damages heals the attacker
+#[derive(Clone, Debug)]
+pub struct AttackHealStatus {
+    pub started: GameTime<Local>,
+    pub until: GameTime<Local>,
+    pub healed_amount: u32,
+    pub heal: Percentage,
+}
+
+impl AttackHealStatus {
+    pub fn new(heal: Percentage, now: GameTime<Local>, duration: f32) -> AttackHealStatus {
+        AttackHealStatus {
+            started: now,
+            until: now.add_seconds(duration),
+            healed_amount: 0,
+            heal,
+        }
+    }
+
+    pub fn update(&mut self, params: StatusUpdateParams) -> StatusUpdateResult {
+        if self.until.has_already_passed(params.time.now()) {
+            StatusUpdateResult::RemoveIt
+        } else {
+            StatusUpdateResult::KeepIt
+        }
+    }
+
+    pub fn hp_mod_has_been_applied_on_enemy(
+        &mut self,
+        self_id: EntityId<Local>,
+        outcome: &HpModificationResult,
+        hp_mod_reqs: &mut Vec<HpModificationRequest>,
+    ) {
+        if let HpModificationResultType::Ok(HpModificationType::BasicDamage(value, _, _)) =
+            outcome.typ
+        {
+            let healed_amount = self.heal.of(value as i32).max(0) as u32;
+            self.healed_amount += healed_amount;
+            hp_mod_reqs.push(HpModificationRequest {
+                src_entity: self_id,
+                dst_entity: self_id,
+                typ: HpModificationType::Heal(healed_amount),
+            });
+        }
+    }
+}
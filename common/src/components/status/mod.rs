@@ -0,0 +1,5 @@
+pub mod attack_heal_status;
+pub mod attrib_mod;
+pub mod reflect_damage_status;
+pub mod status;
+pub mod stun;
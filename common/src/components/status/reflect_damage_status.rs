@@ -0,0 +1,69 @@
+use crate::attack::{
+    DamageDisplayType, HpModificationRequest, HpModificationResult, HpModificationResultType,
+    HpModificationType,
+};
+use crate::common::{GameTime, Local, Percentage};
+use crate::components::char::EntityId;
+use crate::components::status::status::{StatusUpdateParams, StatusUpdateResult};
+
+/// A part of the received basic attack damages is dealt back to the attacker
+#[derive(Clone, Debug)]
+pub struct ReflectDamageStatus {
+    pub started: GameTime<Local>,
+    pub until: GameTime<Local>,
+    pub reflected_damage: u32,
+    pub reflected_amount: Percentage,
+}
+
+impl ReflectDamageStatus {
+    pub fn new(
+        reflected_amount: Percentage,
+        now: GameTime<Local>,
+        duration: f32,
+    ) -> ReflectDamageStatus {
+        ReflectDamageStatus {
+            started: now,
+            until: now.add_seconds(duration),
+            reflected_damage: 0,
+            reflected_amount,
+        }
+    }
+
+    pub fn update(&mut self, params: StatusUpdateParams) -> StatusUpdateResult {
+        if self.until.has_already_passed(params.time.now()) {
+            StatusUpdateResult::RemoveIt
+        } else {
+            StatusUpdateResult::KeepIt
+        }
+    }
+
+    pub fn hp_mod_has_been_applied_on_me(
+        &mut self,
+        self_id: EntityId<Local>,
+        outcome: &HpModificationResult,
+        hp_mod_reqs: &mut Vec<HpModificationRequest>,
+    ) {
+        if let HpModificationResultType::Ok(HpModificationType::BasicDamage(
+            value,
+            _,
+            weapon_type,
+        )) = outcome.typ
+        {
+            let reflected_value = self.reflected_amount.of(value as i32).max(0) as u32;
+            // two reflecting characters would hit each other forever
+            if reflected_value == 0 {
+                return;
+            }
+            self.reflected_damage += reflected_value;
+            hp_mod_reqs.push(HpModificationRequest {
+                src_entity: self_id,
+                dst_entity: outcome.src_entity,
+                typ: HpModificationType::BasicDamage(
+                    reflected_value,
+                    DamageDisplayType::SingleNumber,
+                    weapon_type,
+                ),
+            });
+        }
+    }
+}
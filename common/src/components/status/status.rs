@@ -0,0 +1,981 @@
+use crate::attack::{
+    ApplyForceComponent, AreaAttackComponent, AreaShape, BasicAttackType, HpModificationRequest,
+    HpModificationResult, HpModificationType, WeaponType,
+};
+use crate::char_attr::{CharAttributeModifier, CharAttributeModifierCollector};
+use crate::common::{EngineTime, GameTime, Local, NetworkedObj, Percentage};
+use crate::components::char::{EntityId, LocalCharStateComp, StatusNature, Team};
+use crate::components::skills::absorb_shield::AbsorbStatus;
+use crate::components::skills::assa_blade_dash::AssaBladeDashStatus;
+use crate::components::skills::assa_phase_prism::AssaPhasePrismStatus;
+use crate::components::skills::falcon_carry::FalconCarryStatus;
+use crate::components::skills::fire_bomb::FireBombStatus;
+use crate::components::skills::gaz_exo_skel::ExoSkeletonStatus;
+use crate::components::skills::wiz_pyroblast::PyroBlastTargetStatus;
+use crate::components::status::attack_heal_status::AttackHealStatus;
+use crate::components::status::attrib_mod::{ArmorModifierStatus, WalkingSpeedModifierStatus};
+use crate::components::status::reflect_damage_status::ReflectDamageStatus;
+use crate::components::status::stun::StunStatus;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use strum_macros::EnumCount;
+use strum_macros::EnumDiscriminants;
+
+#[derive(Debug)]
+pub enum StatusStackingResult {
+    DontAddTheNewStatus,
+    AddTheNewStatus,
+    Replace,
+}
+
+pub enum StatusUpdateResult {
+    RemoveIt,
+    KeepIt,
+}
+
+pub struct StatusUpdateParams<'a> {
+    pub self_char_id: EntityId<Local>,
+    /// The statuses which move their target (e.g. dashes) set its position directly
+    pub target_char: &'a mut LocalCharStateComp<Local>,
+    pub hp_mod_requests: &'a mut Vec<HpModificationRequest>,
+    pub area_hp_mod_requests: &'a mut Vec<AreaAttackComponent>,
+    pub apply_area_statuses: &'a mut Vec<ApplyStatusInAreaComponent>,
+    pub time: &'a EngineTime,
+}
+
+const NONSTACKABLE_STATUS_COUNT: usize = 5;
+
+/// The simulated part of the statuses, the client renders them based on these values
+#[allow(variant_size_differences)]
+#[derive(Clone, Debug, EnumCount, EnumDiscriminants)]
+#[strum_discriminants(derive(Hash, Serialize, Deserialize))]
+pub enum StatusEnum {
+    // the nonstackable statuses must be the first ones, their discriminant is their slot index
+    MountedStatus { speedup: Percentage },
+    AssaBladeDashStatus(AssaBladeDashStatus),
+    AssaPhasePrismStatus(AssaPhasePrismStatus),
+    FalconCarryStatus(FalconCarryStatus),
+    ExoSkeletonStatus(ExoSkeletonStatus),
+
+    // stackable statuses
+    AbsorbStatus(AbsorbStatus),
+    ArmorModifierStatus(ArmorModifierStatus),
+    WalkingSpeedModifierStatus(WalkingSpeedModifierStatus),
+    PoisonStatus(PoisonStatus),
+    StunStatus(StunStatus),
+    FireBombStatus(FireBombStatus),
+    PyroBlastTargetStatus(PyroBlastTargetStatus),
+    ReflectDamageStatus(ReflectDamageStatus),
+    AttackHealStatus(AttackHealStatus),
+}
+
+impl StatusEnumDiscriminants {
+    // the clients know only the types of the statuses, so these must not depend on their content
+    pub fn can_target_move(self) -> bool {
+        match self {
+            StatusEnumDiscriminants::AbsorbStatus
+            | StatusEnumDiscriminants::ArmorModifierStatus
+            | StatusEnumDiscriminants::WalkingSpeedModifierStatus
+            | StatusEnumDiscriminants::PoisonStatus
+            | StatusEnumDiscriminants::ExoSkeletonStatus
+            | StatusEnumDiscriminants::FireBombStatus
+            | StatusEnumDiscriminants::PyroBlastTargetStatus
+            | StatusEnumDiscriminants::ReflectDamageStatus
+            | StatusEnumDiscriminants::AttackHealStatus
+            | StatusEnumDiscriminants::MountedStatus => true,
+            StatusEnumDiscriminants::StunStatus
+            | StatusEnumDiscriminants::AssaBladeDashStatus
+            | StatusEnumDiscriminants::AssaPhasePrismStatus
+            | StatusEnumDiscriminants::FalconCarryStatus => false,
+        }
+    }
+
+    pub fn can_target_cast(self) -> bool {
+        match self {
+            StatusEnumDiscriminants::AbsorbStatus
+            | StatusEnumDiscriminants::ArmorModifierStatus
+            | StatusEnumDiscriminants::WalkingSpeedModifierStatus
+            | StatusEnumDiscriminants::PoisonStatus
+            | StatusEnumDiscriminants::ExoSkeletonStatus
+            | StatusEnumDiscriminants::FireBombStatus
+            | StatusEnumDiscriminants::PyroBlastTargetStatus
+            | StatusEnumDiscriminants::ReflectDamageStatus
+            | StatusEnumDiscriminants::AttackHealStatus
+            | StatusEnumDiscriminants::AssaBladeDashStatus
+            | StatusEnumDiscriminants::MountedStatus => true,
+            StatusEnumDiscriminants::StunStatus
+            | StatusEnumDiscriminants::AssaPhasePrismStatus
+            | StatusEnumDiscriminants::FalconCarryStatus => false,
+        }
+    }
+
+    /// The moved characters pass through everything while the status lasts
+    pub fn is_noncollidable(self) -> bool {
+        match self {
+            StatusEnumDiscriminants::AssaBladeDashStatus
+            | StatusEnumDiscriminants::AssaPhasePrismStatus
+            | StatusEnumDiscriminants::FalconCarryStatus => true,
+            StatusEnumDiscriminants::AbsorbStatus
+            | StatusEnumDiscriminants::ArmorModifierStatus
+            | StatusEnumDiscriminants::WalkingSpeedModifierStatus
+            | StatusEnumDiscriminants::PoisonStatus
+            | StatusEnumDiscriminants::ExoSkeletonStatus
+            | StatusEnumDiscriminants::FireBombStatus
+            | StatusEnumDiscriminants::PyroBlastTargetStatus
+            | StatusEnumDiscriminants::StunStatus
+            | StatusEnumDiscriminants::ReflectDamageStatus
+            | StatusEnumDiscriminants::AttackHealStatus
+            | StatusEnumDiscriminants::MountedStatus => false,
+        }
+    }
+
+    /// The basic attack of the character is replaced while the status lasts
+    pub fn basic_attack_type(self) -> Option<BasicAttackType> {
+        match self {
+            StatusEnumDiscriminants::ExoSkeletonStatus => Some(BasicAttackType::Ranged {
+                bullet_type: WeaponType::SilverBullet,
+            }),
+            StatusEnumDiscriminants::AbsorbStatus
+            | StatusEnumDiscriminants::ArmorModifierStatus
+            | StatusEnumDiscriminants::WalkingSpeedModifierStatus
+            | StatusEnumDiscriminants::PoisonStatus
+            | StatusEnumDiscriminants::FireBombStatus
+            | StatusEnumDiscriminants::PyroBlastTargetStatus
+            | StatusEnumDiscriminants::StunStatus
+            | StatusEnumDiscriminants::AssaBladeDashStatus
+            | StatusEnumDiscriminants::AssaPhasePrismStatus
+            | StatusEnumDiscriminants::FalconCarryStatus
+            | StatusEnumDiscriminants::ReflectDamageStatus
+            | StatusEnumDiscriminants::AttackHealStatus
+            | StatusEnumDiscriminants::MountedStatus => None,
+        }
+    }
+
+    /// The intentions of the players are ignored while the status lasts
+    pub fn can_target_be_controlled(self) -> bool {
+        match self {
+            StatusEnumDiscriminants::AbsorbStatus
+            | StatusEnumDiscriminants::ArmorModifierStatus
+            | StatusEnumDiscriminants::WalkingSpeedModifierStatus
+            | StatusEnumDiscriminants::PoisonStatus
+            | StatusEnumDiscriminants::ExoSkeletonStatus
+            | StatusEnumDiscriminants::FireBombStatus
+            | StatusEnumDiscriminants::PyroBlastTargetStatus
+            | StatusEnumDiscriminants::StunStatus
+            | StatusEnumDiscriminants::ReflectDamageStatus
+            | StatusEnumDiscriminants::AttackHealStatus
+            | StatusEnumDiscriminants::MountedStatus => true,
+            StatusEnumDiscriminants::AssaBladeDashStatus
+            | StatusEnumDiscriminants::AssaPhasePrismStatus
+            | StatusEnumDiscriminants::FalconCarryStatus => false,
+        }
+    }
+}
+
+impl StatusEnum {
+    fn can_target_move(&self) -> bool {
+        StatusEnumDiscriminants::from(self).can_target_move()
+    }
+
+    fn can_target_cast(&self) -> bool {
+        StatusEnumDiscriminants::from(self).can_target_cast()
+    }
+
+    /// None if the status remains until it is removed explicitly
+    pub fn until(&self) -> Option<GameTime<Local>> {
+        match self {
+            StatusEnum::MountedStatus { .. } | StatusEnum::PyroBlastTargetStatus(_) => None,
+            StatusEnum::AssaBladeDashStatus(status) => Some(status.ends_at),
+            StatusEnum::AssaPhasePrismStatus(status) => Some(status.ends_at),
+            StatusEnum::FalconCarryStatus(status) => Some(status.ends_at),
+            StatusEnum::ExoSkeletonStatus(status) => Some(status.until),
+            StatusEnum::FireBombStatus(status) => Some(status.until),
+            StatusEnum::AbsorbStatus(status) => Some(status.until),
+            StatusEnum::ArmorModifierStatus(status) => Some(status.until),
+            StatusEnum::WalkingSpeedModifierStatus(status) => Some(status.until),
+            StatusEnum::PoisonStatus(status) => Some(status.until),
+            StatusEnum::StunStatus(status) => Some(status.until),
+            StatusEnum::ReflectDamageStatus(status) => Some(status.until),
+            StatusEnum::AttackHealStatus(status) => Some(status.until),
+        }
+    }
+
+    fn typ(&self) -> StatusNature {
+        match self {
+            StatusEnum::AbsorbStatus(_)
+            | StatusEnum::MountedStatus { .. }
+            | StatusEnum::AssaBladeDashStatus(_)
+            | StatusEnum::AssaPhasePrismStatus(_)
+            | StatusEnum::FalconCarryStatus(_)
+            | StatusEnum::ReflectDamageStatus(_)
+            | StatusEnum::AttackHealStatus(_)
+            | StatusEnum::ExoSkeletonStatus(_) => StatusNature::Supportive,
+            StatusEnum::ArmorModifierStatus(status) => {
+                if status.modifier.as_f32() < 0.0 {
+                    StatusNature::Harmful
+                } else {
+                    StatusNature::Supportive
+                }
+            }
+            StatusEnum::WalkingSpeedModifierStatus(status) => {
+                if status.modifier.as_f32() < 0.0 {
+                    StatusNature::Harmful
+                } else {
+                    StatusNature::Supportive
+                }
+            }
+            StatusEnum::PoisonStatus(_)
+            | StatusEnum::StunStatus(_)
+            | StatusEnum::FireBombStatus(_)
+            | StatusEnum::PyroBlastTargetStatus(_) => StatusNature::Harmful,
+        }
+    }
+
+    pub fn calc_attribs(&self, modifiers: &mut CharAttributeModifierCollector) {
+        match self {
+            StatusEnum::MountedStatus { speedup } => {
+                // it is applied directly on the base moving speed, since it is called first
+                modifiers.change_walking_speed(
+                    CharAttributeModifier::IncreaseByPercentage(*speedup),
+                    GameTime::from(0.0),
+                    GameTime::from(0.0),
+                );
+            }
+            StatusEnum::ArmorModifierStatus(status) => {
+                status.calc_attribs(modifiers);
+            }
+            StatusEnum::WalkingSpeedModifierStatus(status) => {
+                status.calc_attribs(modifiers);
+            }
+            StatusEnum::ExoSkeletonStatus(status) => {
+                status.calc_attribs(modifiers);
+            }
+            StatusEnum::AbsorbStatus(_)
+            | StatusEnum::PoisonStatus(_)
+            | StatusEnum::StunStatus(_)
+            | StatusEnum::AssaBladeDashStatus(_)
+            | StatusEnum::AssaPhasePrismStatus(_)
+            | StatusEnum::FalconCarryStatus(_)
+            | StatusEnum::FireBombStatus(_)
+            | StatusEnum::PyroBlastTargetStatus(_)
+            | StatusEnum::ReflectDamageStatus(_)
+            | StatusEnum::AttackHealStatus(_) => {}
+        }
+    }
+
+    pub fn update(&mut self, params: StatusUpdateParams) -> StatusUpdateResult {
+        match self {
+            StatusEnum::AbsorbStatus(status) => status.update(
+                params.time.now(),
+                params.self_char_id,
+                params.hp_mod_requests,
+            ),
+            StatusEnum::ArmorModifierStatus(status) => status.update(params),
+            StatusEnum::WalkingSpeedModifierStatus(status) => status.update(params),
+            StatusEnum::PoisonStatus(status) => status.update(params),
+            StatusEnum::StunStatus(status) => status.update(params),
+            StatusEnum::AssaBladeDashStatus(status) => status.update(params),
+            StatusEnum::AssaPhasePrismStatus(status) => status.update(params),
+            StatusEnum::FalconCarryStatus(status) => status.update(params),
+            StatusEnum::ExoSkeletonStatus(status) => status.update(params),
+            StatusEnum::FireBombStatus(status) => status.update(params),
+            StatusEnum::ReflectDamageStatus(status) => status.update(params),
+            StatusEnum::AttackHealStatus(status) => status.update(params),
+            // the pyroblast removes it when it arrives
+            StatusEnum::MountedStatus { .. } | StatusEnum::PyroBlastTargetStatus(_) => {
+                StatusUpdateResult::KeepIt
+            }
+        }
+    }
+
+    pub fn hp_mod_is_calculated_but_not_applied_yet(
+        &mut self,
+        outcome: HpModificationResult,
+    ) -> HpModificationResult {
+        match self {
+            StatusEnum::AbsorbStatus(status) => {
+                status.hp_mod_is_calculated_but_not_applied_yet(outcome)
+            }
+            StatusEnum::ArmorModifierStatus(_)
+            | StatusEnum::WalkingSpeedModifierStatus(_)
+            | StatusEnum::PoisonStatus(_)
+            | StatusEnum::StunStatus(_)
+            | StatusEnum::AssaBladeDashStatus(_)
+            | StatusEnum::AssaPhasePrismStatus(_)
+            | StatusEnum::FalconCarryStatus(_)
+            | StatusEnum::ExoSkeletonStatus(_)
+            | StatusEnum::FireBombStatus(_)
+            | StatusEnum::PyroBlastTargetStatus(_)
+            | StatusEnum::ReflectDamageStatus(_)
+            | StatusEnum::AttackHealStatus(_)
+            | StatusEnum::MountedStatus { .. } => outcome,
+        }
+    }
+
+    pub fn hp_mod_has_been_applied_on_me(
+        &mut self,
+        self_id: EntityId<Local>,
+        outcome: &HpModificationResult,
+        hp_mod_reqs: &mut Vec<HpModificationRequest>,
+    ) {
+        match self {
+            StatusEnum::ReflectDamageStatus(status) => {
+                status.hp_mod_has_been_applied_on_me(self_id, outcome, hp_mod_reqs)
+            }
+            StatusEnum::AbsorbStatus(_)
+            | StatusEnum::ArmorModifierStatus(_)
+            | StatusEnum::WalkingSpeedModifierStatus(_)
+            | StatusEnum::PoisonStatus(_)
+            | StatusEnum::StunStatus(_)
+            | StatusEnum::AssaBladeDashStatus(_)
+            | StatusEnum::AssaPhasePrismStatus(_)
+            | StatusEnum::FalconCarryStatus(_)
+            | StatusEnum::ExoSkeletonStatus(_)
+            | StatusEnum::FireBombStatus(_)
+            | StatusEnum::PyroBlastTargetStatus(_)
+            | StatusEnum::AttackHealStatus(_)
+            | StatusEnum::MountedStatus { .. } => {}
+        }
+    }
+
+    pub fn hp_mod_has_been_applied_on_enemy(
+        &mut self,
+        self_id: EntityId<Local>,
+        outcome: &HpModificationResult,
+        hp_mod_reqs: &mut Vec<HpModificationRequest>,
+    ) {
+        match self {
+            StatusEnum::AttackHealStatus(status) => {
+                status.hp_mod_has_been_applied_on_enemy(self_id, outcome, hp_mod_reqs)
+            }
+            StatusEnum::AbsorbStatus(_)
+            | StatusEnum::ArmorModifierStatus(_)
+            | StatusEnum::WalkingSpeedModifierStatus(_)
+            | StatusEnum::PoisonStatus(_)
+            | StatusEnum::StunStatus(_)
+            | StatusEnum::AssaBladeDashStatus(_)
+            | StatusEnum::AssaPhasePrismStatus(_)
+            | StatusEnum::FalconCarryStatus(_)
+            | StatusEnum::ExoSkeletonStatus(_)
+            | StatusEnum::FireBombStatus(_)
+            | StatusEnum::PyroBlastTargetStatus(_)
+            | StatusEnum::ReflectDamageStatus(_)
+            | StatusEnum::MountedStatus { .. } => {}
+        }
+    }
+
+    pub fn allow_push(&self, _push: &ApplyForceComponent) -> bool {
+        match self {
+            StatusEnum::ArmorModifierStatus(_)
+            | StatusEnum::WalkingSpeedModifierStatus(_)
+            | StatusEnum::PoisonStatus(_)
+            | StatusEnum::StunStatus(_)
+            | StatusEnum::ExoSkeletonStatus(_)
+            | StatusEnum::FireBombStatus(_)
+            | StatusEnum::PyroBlastTargetStatus(_)
+            | StatusEnum::ReflectDamageStatus(_)
+            | StatusEnum::AttackHealStatus(_)
+            | StatusEnum::MountedStatus { .. } => true,
+            StatusEnum::AbsorbStatus(_)
+            | StatusEnum::AssaBladeDashStatus(_)
+            | StatusEnum::AssaPhasePrismStatus(_)
+            | StatusEnum::FalconCarryStatus(_) => false,
+        }
+    }
+
+    pub fn stack(&mut self, other: &StatusEnum) -> StatusStackingResult {
+        match self {
+            StatusEnum::ArmorModifierStatus(_)
+            | StatusEnum::WalkingSpeedModifierStatus(_)
+            | StatusEnum::PoisonStatus(_)
+            | StatusEnum::StunStatus(_)
+            | StatusEnum::FireBombStatus(_)
+            | StatusEnum::PyroBlastTargetStatus(_)
+            | StatusEnum::ReflectDamageStatus(_)
+            | StatusEnum::AttackHealStatus(_) => StatusStackingResult::AddTheNewStatus,
+            StatusEnum::AbsorbStatus(status) => status.stack(other),
+            StatusEnum::MountedStatus { .. }
+            | StatusEnum::AssaBladeDashStatus(_)
+            | StatusEnum::FalconCarryStatus(_)
+            | StatusEnum::ExoSkeletonStatus(_) => StatusStackingResult::DontAddTheNewStatus,
+            StatusEnum::AssaPhasePrismStatus(_) => StatusStackingResult::Replace,
+        }
+    }
+}
+
+/// The part of a status which is sent to the clients, the simulation of the statuses
+/// runs only on the server
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplicatedStatus<T: 'static + NetworkedObj> {
+    pub typ: StatusEnumDiscriminants,
+    pub until: Option<GameTime<T>>,
+}
+
+impl ReplicatedStatus<Local> {
+    pub fn is_active(&self, now: GameTime<Local>) -> bool {
+        self.until
+            .map(|until| until.has_not_passed_yet(now))
+            .unwrap_or(true)
+    }
+}
+
+// if you change the size, the update function has to be changed as well
+const STATUS_ARRAY_SIZE: usize = 32;
+#[derive(Component)]
+pub struct Statuses {
+    statuses: [Option<StatusEnum>; STATUS_ARRAY_SIZE],
+    first_free_index: usize,
+    cached_modifier_collector: CharAttributeModifierCollector,
+}
+
+impl Statuses {
+    pub fn new() -> Statuses {
+        Statuses {
+            statuses: Default::default(),
+            first_free_index: NONSTACKABLE_STATUS_COUNT,
+            cached_modifier_collector: CharAttributeModifierCollector::new(),
+        }
+    }
+
+    pub fn get_statuses(&self) -> &[Option<StatusEnum>; STATUS_ARRAY_SIZE] {
+        &self.statuses
+    }
+
+    pub fn can_move(&self) -> bool {
+        self.statuses
+            .iter()
+            .take(self.first_free_index)
+            .all(|it| it.as_ref().map(|it| it.can_target_move()).unwrap_or(true))
+    }
+
+    pub fn can_cast(&self) -> bool {
+        self.statuses
+            .iter()
+            .take(self.first_free_index)
+            .all(|it| it.as_ref().map(|it| it.can_target_cast()).unwrap_or(true))
+    }
+
+    pub fn allow_push(&self, push: &ApplyForceComponent) -> bool {
+        self.statuses
+            .iter()
+            .take(self.first_free_index)
+            .all(|it| it.as_ref().map(|it| it.allow_push(push)).unwrap_or(true))
+    }
+
+    pub fn hp_mod_is_calculated_but_not_applied_yet(
+        &mut self,
+        mut outcome: HpModificationResult,
+    ) -> HpModificationResult {
+        for status in self
+            .statuses
+            .iter_mut()
+            .take(self.first_free_index)
+            .filter(|it| it.is_some())
+        {
+            outcome = status
+                .as_mut()
+                .unwrap()
+                .hp_mod_is_calculated_but_not_applied_yet(outcome);
+        }
+        return outcome;
+    }
+
+    pub fn hp_mod_has_been_applied_on_me(
+        &mut self,
+        self_id: EntityId<Local>,
+        outcome: &HpModificationResult,
+        hp_mod_reqs: &mut Vec<HpModificationRequest>,
+    ) {
+        for status in self
+            .statuses
+            .iter_mut()
+            .take(self.first_free_index)
+            .filter_map(|it| it.as_mut())
+        {
+            status.hp_mod_has_been_applied_on_me(self_id, outcome, hp_mod_reqs);
+        }
+    }
+
+    pub fn hp_mod_has_been_applied_on_enemy(
+        &mut self,
+        self_id: EntityId<Local>,
+        outcome: &HpModificationResult,
+        hp_mod_reqs: &mut Vec<HpModificationRequest>,
+    ) {
+        for status in self
+            .statuses
+            .iter_mut()
+            .take(self.first_free_index)
+            .filter_map(|it| it.as_mut())
+        {
+            status.hp_mod_has_been_applied_on_enemy(self_id, outcome, hp_mod_reqs);
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        self_char_id: EntityId<Local>,
+        target_char: &mut LocalCharStateComp<Local>,
+        time: &EngineTime,
+        hp_mod_requests: &mut Vec<HpModificationRequest>,
+        area_hp_mod_requests: &mut Vec<AreaAttackComponent>,
+        apply_area_statuses: &mut Vec<ApplyStatusInAreaComponent>,
+    ) -> u32 {
+        let mut changed: u32 = 0;
+        for (i, status) in self
+            .statuses
+            .iter_mut()
+            .enumerate()
+            .take(self.first_free_index)
+            .filter(|(_i, it)| it.is_some())
+        {
+            let result = status.as_mut().unwrap().update(StatusUpdateParams {
+                self_char_id,
+                target_char,
+                hp_mod_requests,
+                area_hp_mod_requests,
+                apply_area_statuses,
+                time,
+            });
+            match result {
+                StatusUpdateResult::RemoveIt => {
+                    changed |= 1 << i;
+                }
+                StatusUpdateResult::KeepIt => {}
+            }
+        }
+
+        return changed;
+    }
+
+    pub fn remove_statuses(&mut self, bit_indices: u32) {
+        for i in 0..STATUS_ARRAY_SIZE {
+            if ((1 << i) & bit_indices) > 0 {
+                self.statuses[i] = None;
+            }
+        }
+        self.move_free_index();
+    }
+
+    pub fn calc_attributes(&mut self) -> &CharAttributeModifierCollector {
+        self.cached_modifier_collector.clear();
+        for status in &mut self
+            .statuses
+            .iter()
+            .take(self.first_free_index)
+            .filter(|it| it.is_some())
+        {
+            status
+                .as_ref()
+                .unwrap()
+                .calc_attribs(&mut self.cached_modifier_collector);
+        }
+        return &self.cached_modifier_collector;
+    }
+
+    pub fn replicated(&self) -> Vec<ReplicatedStatus<Local>> {
+        self.statuses
+            .iter()
+            .take(self.first_free_index)
+            .filter_map(|it| it.as_ref())
+            .map(|status| ReplicatedStatus {
+                typ: StatusEnumDiscriminants::from(status),
+                until: status.until(),
+            })
+            .collect()
+    }
+
+    pub fn is_mounted(&self) -> bool {
+        self.statuses[StatusEnumDiscriminants::MountedStatus as usize].is_some()
+    }
+
+    pub fn add(&mut self, new_status: StatusEnum) {
+        log::debug!("Try to add status: {:?}", new_status);
+        if self.first_free_index >= STATUS_ARRAY_SIZE {
+            log::error!("There is no more space for new Status!");
+            return;
+        }
+
+        let mut current_index = self.first_free_index;
+        let mut stack_type = StatusStackingResult::AddTheNewStatus;
+        let adding_status_type = StatusEnumDiscriminants::from(&new_status) as usize;
+        for i in 0..self.first_free_index {
+            if i < NONSTACKABLE_STATUS_COUNT {
+                let target_slot_type = i;
+                if target_slot_type == adding_status_type {
+                    stack_type = self.statuses[i]
+                        .as_mut()
+                        .map(|it| it.stack(&new_status))
+                        .unwrap_or(StatusStackingResult::Replace);
+                    current_index = i;
+                    break;
+                }
+            } else {
+                if let Some(status) = self.statuses[i].as_mut() {
+                    let target_slot_type = {
+                        let s: &StatusEnum = status;
+                        StatusEnumDiscriminants::from(s) as usize
+                    };
+                    if target_slot_type == adding_status_type {
+                        stack_type = status.stack(&new_status);
+                        current_index = i;
+                        break;
+                    }
+                }
+            }
+        }
+
+        log::trace!("stack_type: {:?}, index: {}", stack_type, current_index);
+        match stack_type {
+            StatusStackingResult::Replace => {
+                self.statuses[current_index] = Some(new_status);
+            }
+            StatusStackingResult::AddTheNewStatus => {
+                self.statuses[self.first_free_index] = Some(new_status);
+                self.first_free_index += 1;
+            }
+            StatusStackingResult::DontAddTheNewStatus => {
+                return;
+            }
+        }
+    }
+
+    pub fn remove_all(&mut self) {
+        log::debug!("Remove all status");
+        for status in self.statuses.iter_mut().take(self.first_free_index) {
+            *status = None;
+        }
+        self.first_free_index = NONSTACKABLE_STATUS_COUNT;
+    }
+
+    pub fn remove_by_nature(&mut self, status_type: StatusNature) {
+        log::debug!("remove_by_nature: {:?}", status_type);
+        self.remove_if(|it| it.typ() == status_type);
+    }
+
+    pub fn remove(&mut self, discr: StatusEnumDiscriminants) {
+        log::debug!("remove: {:?}", discr);
+        self.remove_if(|it| StatusEnumDiscriminants::from(it) == discr);
+    }
+
+    fn move_free_index(&mut self) {
+        while self.first_free_index > NONSTACKABLE_STATUS_COUNT
+            && self.statuses[self.first_free_index - 1].is_none()
+        {
+            self.first_free_index -= 1;
+        }
+    }
+
+    pub fn remove_if<F>(&mut self, predicate: F)
+    where
+        F: Fn(&StatusEnum) -> bool,
+    {
+        for status in self.statuses.iter_mut().take(self.first_free_index) {
+            let should_remove = status.as_ref().map(&predicate).unwrap_or(false);
+            if should_remove {
+                *status = None;
+            }
+        }
+        self.move_free_index();
+    }
+
+    pub fn get_status(&self, requested_type_id: StatusEnumDiscriminants) -> Option<&StatusEnum> {
+        for status in self.statuses.iter().filter(|it| it.is_some()) {
+            let status = status.as_ref().unwrap();
+            let type_id: StatusEnumDiscriminants = status.into();
+            if requested_type_id == type_id {
+                return Some(status);
+            }
+        }
+        return None;
+    }
+
+    pub fn count(&self) -> usize {
+        let secondary_status_count = self
+            .statuses
+            .iter()
+            .skip(NONSTACKABLE_STATUS_COUNT)
+            .take(self.first_free_index - NONSTACKABLE_STATUS_COUNT)
+            .filter(|it| it.is_some())
+            .count();
+        let main_status_count = self
+            .statuses
+            .iter()
+            .take(NONSTACKABLE_STATUS_COUNT)
+            .filter(|it| it.is_some())
+            .count();
+        return main_status_count + secondary_status_count;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PoisonStatus {
+    pub poison_caster_entity_id: EntityId<Local>,
+    pub started: GameTime<Local>,
+    pub until: GameTime<Local>,
+    pub next_damage_at: GameTime<Local>,
+    pub damage: u32,
+}
+
+impl PoisonStatus {
+    pub fn update(&mut self, params: StatusUpdateParams) -> StatusUpdateResult {
+        if self.until.has_already_passed(params.time.now()) {
+            StatusUpdateResult::RemoveIt
+        } else {
+            if self.next_damage_at.has_already_passed(params.time.now()) {
+                params.hp_mod_requests.push(HpModificationRequest {
+                    src_entity: self.poison_caster_entity_id,
+                    dst_entity: params.self_char_id,
+                    typ: HpModificationType::Poison(self.damage),
+                });
+                self.next_damage_at = params.time.now().add_seconds(1.0);
+            }
+            StatusUpdateResult::KeepIt
+        }
+    }
+}
+
+pub struct ApplyStatusComponent {
+    pub source_entity_id: EntityId<Local>,
+    pub target_entity_id: EntityId<Local>,
+    pub status: StatusEnum,
+}
+
+impl ApplyStatusComponent {
+    pub fn from_status(
+        source_entity_id: EntityId<Local>,
+        target_entity_id: EntityId<Local>,
+        m: StatusEnum,
+    ) -> ApplyStatusComponent {
+        ApplyStatusComponent {
+            source_entity_id,
+            target_entity_id,
+            status: m,
+        }
+    }
+}
+
+/// The status is applied on every character inside the area whose team is compatible
+/// with the nature of the status
+pub struct ApplyStatusInAreaComponent {
+    pub source_entity_id: EntityId<Local>,
+    pub status: StatusEnum,
+    pub area_shape: AreaShape,
+    pub except: Option<EntityId<Local>>,
+    pub nature: StatusNature,
+    pub caster_team: Team,
+}
+
+pub enum RemoveStatusComponentPayload {
+    RemovingStatusType(StatusNature),
+    RemovingStatusDiscr(StatusEnumDiscriminants),
+}
+
+pub struct RemoveStatusComponent {
+    pub source_entity_id: EntityId<Local>,
+    pub target_entity_id: EntityId<Local>,
+    pub status: RemoveStatusComponentPayload,
+}
+
+impl RemoveStatusComponent {
+    pub fn by_status_nature(
+        source_entity_id: EntityId<Local>,
+        target_entity_id: EntityId<Local>,
+        status_type: StatusNature,
+    ) -> RemoveStatusComponent {
+        RemoveStatusComponent {
+            source_entity_id,
+            target_entity_id,
+            status: RemoveStatusComponentPayload::RemovingStatusType(status_type),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::{DamageDisplayType, HpModificationResultType};
+    use crate::common::{percentage, v2};
+    use std::time::Duration;
+
+    fn create_char_id() -> EntityId<Local> {
+        let mut world = World::new();
+        EntityId::new(world.create_entity().build())
+    }
+
+    #[test]
+    fn non_stackable_statuses() {
+        let mut statuses = Statuses::new();
+
+        assert_eq!(statuses.first_free_index, NONSTACKABLE_STATUS_COUNT);
+        assert!(statuses.statuses[0].is_none());
+        statuses.add(StatusEnum::MountedStatus {
+            speedup: percentage(0),
+        });
+        assert_eq!(statuses.first_free_index, NONSTACKABLE_STATUS_COUNT);
+        assert!(statuses.is_mounted());
+        statuses.add(StatusEnum::MountedStatus {
+            speedup: percentage(0),
+        });
+        assert_eq!(statuses.count(), 1);
+
+        statuses.remove(StatusEnumDiscriminants::MountedStatus);
+        assert_eq!(statuses.first_free_index, NONSTACKABLE_STATUS_COUNT);
+        assert!(!statuses.is_mounted());
+    }
+
+    #[test]
+    fn stackable_statuses() {
+        let mut statuses = Statuses::new();
+
+        let status = StatusEnum::WalkingSpeedModifierStatus(WalkingSpeedModifierStatus {
+            started: GameTime::from(0.0),
+            until: GameTime::from(0.0),
+            modifier: percentage(0),
+        });
+
+        statuses.add(status.clone());
+        statuses.add(status.clone());
+        assert_eq!(statuses.first_free_index, NONSTACKABLE_STATUS_COUNT + 2);
+        assert_eq!(statuses.count(), 2);
+
+        statuses.remove(StatusEnumDiscriminants::WalkingSpeedModifierStatus);
+        assert_eq!(statuses.first_free_index, NONSTACKABLE_STATUS_COUNT);
+        assert_eq!(statuses.count(), 0);
+    }
+
+    #[test]
+    fn poison_damages_until_it_expires() {
+        let mut time = EngineTime::new_for_tests(Duration::from_millis(500));
+        let char_id = create_char_id();
+        let mut statuses = Statuses::new();
+        statuses.add(StatusEnum::PoisonStatus(PoisonStatus {
+            poison_caster_entity_id: char_id,
+            started: time.now(),
+            until: time.now().add_seconds(2.0),
+            next_damage_at: time.now(),
+            damage: 10,
+        }));
+        assert!(statuses.can_move() && statuses.can_cast());
+
+        let mut char_state = LocalCharStateComp::new(v2(0.0, 0.0), Default::default());
+        let mut hp_mod_requests = vec![];
+        for _ in 0..5 {
+            let removed = statuses.update(
+                char_id,
+                &mut char_state,
+                &time,
+                &mut hp_mod_requests,
+                &mut vec![],
+                &mut vec![],
+            );
+            statuses.remove_statuses(removed);
+            time.tick(Duration::from_millis(500));
+        }
+        assert_eq!(hp_mod_requests.len(), 2);
+        assert_eq!(statuses.count(), 0);
+    }
+
+    #[test]
+    fn stun_prevents_moving_and_casting() {
+        let time = EngineTime::new_for_tests(Duration::from_millis(500));
+        let char_id = create_char_id();
+        let mut statuses = Statuses::new();
+        statuses.add(StatusEnum::StunStatus(StunStatus::new(
+            char_id,
+            time.now(),
+            1.0,
+        )));
+        assert!(!statuses.can_move());
+        assert!(!statuses.can_cast());
+
+        statuses.remove_by_nature(StatusNature::Harmful);
+        assert!(statuses.can_move());
+    }
+
+    #[test]
+    fn replicated_stun_prevents_moving_and_casting_until_it_expires() {
+        let mut time = EngineTime::new_for_tests(Duration::from_millis(500));
+        let char_id = create_char_id();
+        let mut statuses = Statuses::new();
+        statuses.add(StatusEnum::MountedStatus {
+            speedup: percentage(30),
+        });
+        statuses.add(StatusEnum::StunStatus(StunStatus::new(
+            char_id,
+            time.now(),
+            1.0,
+        )));
+        let mut char_state = LocalCharStateComp::new(v2(0.0, 0.0), Default::default());
+        char_state.statuses = statuses.replicated();
+        assert_eq!(char_state.statuses.len(), 2);
+        assert!(!char_state.can_move(time.now()));
+        assert!(!char_state.can_cast(time.now()));
+
+        // the client still has the stun, but it has already expired
+        for _ in 0..3 {
+            time.tick(Duration::from_millis(500));
+        }
+        assert!(char_state.can_move(time.now()));
+        assert!(char_state.can_cast(time.now()));
+        assert_eq!(char_state.active_statuses(time.now()).count(), 1);
+    }
+
+    #[test]
+    fn dashing_char_can_not_be_controlled() {
+        let now = GameTime::from(0.0);
+        let mut char_state = LocalCharStateComp::new(v2(0.0, 0.0), Default::default());
+        assert!(char_state.can_be_controlled(now));
+
+        char_state.statuses = vec![ReplicatedStatus {
+            typ: StatusEnumDiscriminants::AssaBladeDashStatus,
+            until: Some(GameTime::from(1.0)),
+        }];
+        assert!(!char_state.can_be_controlled(now));
+        assert!(char_state.can_be_controlled(GameTime::from(1.5)));
+    }
+
+    #[test]
+    fn reflect_and_attack_heal_react_to_basic_attacks() {
+        let now = GameTime::from(0.0);
+        let attacker = create_char_id();
+        let attacked = create_char_id();
+        let mut attacker_statuses = Statuses::new();
+        attacker_statuses.add(StatusEnum::AttackHealStatus(AttackHealStatus::new(
+            percentage(50),
+            now,
+            1.0,
+        )));
+        let mut attacked_statuses = Statuses::new();
+        attacked_statuses.add(StatusEnum::ReflectDamageStatus(ReflectDamageStatus::new(
+            percentage(10),
+            now,
+            1.0,
+        )));
+        let outcome = HpModificationResult {
+            src_entity: attacker,
+            dst_entity: attacked,
+            typ: HpModificationResultType::Ok(HpModificationType::BasicDamage(
+                100,
+                DamageDisplayType::SingleNumber,
+                WeaponType::Sword,
+            )),
+        };
+
+        let mut hp_mod_reqs = Vec::new();
+        attacked_statuses.hp_mod_has_been_applied_on_me(attacked, &outcome, &mut hp_mod_reqs);
+        attacker_statuses.hp_mod_has_been_applied_on_enemy(attacker, &outcome, &mut hp_mod_reqs);
+        // the attacker would reflect its own damage as well, but it does not have the status
+        attacker_statuses.hp_mod_has_been_applied_on_me(attacker, &outcome, &mut hp_mod_reqs);
+
+        assert_eq!(hp_mod_reqs.len(), 2);
+        assert_eq!(hp_mod_reqs[0].src_entity, attacked);
+        assert_eq!(hp_mod_reqs[0].dst_entity, attacker);
+        assert!(matches!(
+            hp_mod_reqs[0].typ,
+            HpModificationType::BasicDamage(10, _, WeaponType::Sword)
+        ));
+        assert_eq!(hp_mod_reqs[1].dst_entity, attacker);
+        assert!(matches!(hp_mod_reqs[1].typ, HpModificationType::Heal(50)));
+    }
+}
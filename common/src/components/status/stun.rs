@@ -0,0 +1,32 @@
+use crate::common::{GameTime, Local};
+use crate::components::char::EntityId;
+use crate::components::status::status::{StatusUpdateParams, StatusUpdateResult};
+
+#[derive(Clone, Debug)]
+pub struct StunStatus {
+    pub caster_entity_id: EntityId<Local>,
+    pub started: GameTime<Local>,
+    pub until: GameTime<Local>,
+}
+
+impl StunStatus {
+    pub fn new(
+        caster_entity_id: EntityId<Local>,
+        now: GameTime<Local>,
+        duration: f32,
+    ) -> StunStatus {
+        StunStatus {
+            caster_entity_id,
+            started: now,
+            until: now.add_seconds(duration),
+        }
+    }
+
+    pub fn update(&mut self, params: StatusUpdateParams) -> StatusUpdateResult {
+        if self.until.has_already_passed(params.time.now()) {
+            StatusUpdateResult::RemoveIt
+        } else {
+            StatusUpdateResult::KeepIt
+        }
+    }
+}
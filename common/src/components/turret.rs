@@ -0,0 +1,16 @@
+use crate::common::Local;
+use crate::components::char::EntityId;
+use specs::prelude::*;
+
+/// The turrets of the gazes, they are attached to the characters of the turrets
+#[derive(Component)]
+pub struct TurretComponent {
+    pub owner_entity_id: EntityId<Local>,
+    /// Set by the owner, the turret attacks it while it is in range
+    pub preferred_target: Option<EntityId<Local>>,
+}
+
+/// The AI of a turret, it is attached to the controller of the turret's character
+#[derive(Component, Default)]
+#[storage(NullStorage)]
+pub struct TurretControllerComponent;
@@ -1,40 +1,127 @@
+use crate::attack::{BasicAttackType, WeaponType};
 use crate::char_attr::CharAttributes;
-use crate::common::{GameTime, Local, Percentage};
+use crate::common::{percentage, GameTime, Local, Percentage};
+use crate::components::char::JobId;
+use crate::components::job_ids::JobSpriteId;
+use crate::components::skills::skills::Skills;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Display;
+use strum::IntoEnumIterator;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct CommonConfigs {
-    pub stats: DevConfigStats,
+    pub jobs: JobRegistry,
     pub skills: SkillsConfig,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct DevConfigStats {
-    pub minion: DevConfigStatsMinion,
-    pub player: DevConfigStatsPlayer,
+impl CommonConfigs {
+    /// Every job has a definition, it is checked by `validate`
+    pub fn job(&self, job_id: JobId) -> &JobDefinition {
+        self.jobs.get(job_id).unwrap_or_else(|| {
+            panic!("{} has no definition in the configs", job_id);
+        })
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct DevConfigStatsMinion {
-    pub melee: CharAttributes,
-    pub ranged: CharAttributes,
+/// A skill bound to a skill key on the client, e.g. "Q"
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct JobSkill {
+    pub key: String,
+    pub skill: Skills,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct DevConfigStatsPlayerJob {
+fn default_mounted_speedup() -> Percentage {
+    percentage(30)
+}
+
+/// See `BasicAttackType::MeleeCombo`
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct MeleeComboDefinition {
+    pub combo_count: u8,
+    pub base_dmg_percentage_for_each_combo: Percentage,
+    pub combo_window_seconds: f32,
+}
+
+/// Everything which differs between the jobs, so adding a job requires only
+/// a new `JobId` and its `[[jobs]]` entry in config-runtime.toml
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct JobDefinition {
+    pub job_id: JobId,
     pub attributes: CharAttributes,
+    /// `None` means melee basic attacks
+    #[serde(default)]
+    pub ranged_weapon: Option<WeaponType>,
+    /// Melee basic attacks which build up a combo, `ranged_weapon` must be missing
+    #[serde(default)]
+    pub melee_combo: Option<MeleeComboDefinition>,
+    /// `None` for the jobs which are not drawn as humans, e.g. dummies
+    #[serde(default)]
+    pub sprite: Option<JobSpriteId>,
+    #[serde(default)]
+    pub skills: Vec<JobSkill>,
+    #[serde(default = "default_mounted_speedup")]
     pub mounted_speedup: Percentage,
+    /// Only these jobs can be chosen in the lobby
+    #[serde(default)]
+    pub playable: bool,
+}
+
+impl JobDefinition {
+    pub fn basic_attack_type(&self) -> BasicAttackType {
+        match (self.ranged_weapon, &self.melee_combo) {
+            (Some(bullet_type), _) => BasicAttackType::Ranged { bullet_type },
+            (None, Some(combo)) => BasicAttackType::MeleeCombo {
+                combo_count: combo.combo_count,
+                base_dmg_percentage_for_each_combo: combo.base_dmg_percentage_for_each_combo,
+                combo_window_seconds: combo.combo_window_seconds,
+            },
+            (None, None) => BasicAttackType::MeleeSimple,
+        }
+    }
+
+    /// Mounting is bound to every character on the client, the rest comes from `skills`
+    pub fn has_skill(&self, skill: Skills) -> bool {
+        skill == Skills::Mounting || self.skills.iter().any(|it| it.skill == skill)
+    }
+}
+
+/// The config files contain the definitions as a list, since the table keys are lowercased
+/// by the config loader
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(from = "Vec<JobDefinition>", into = "Vec<JobDefinition>")]
+pub struct JobRegistry {
+    definitions: HashMap<JobId, JobDefinition>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct DevConfigStatsPlayer {
-    pub crusader: DevConfigStatsPlayerJob,
-    pub gunslinger: DevConfigStatsPlayerJob,
-    pub hunter: DevConfigStatsPlayerJob,
+impl JobRegistry {
+    pub fn get(&self, job_id: JobId) -> Option<&JobDefinition> {
+        self.definitions.get(&job_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &JobDefinition> {
+        self.definitions.values()
+    }
+}
+
+impl From<Vec<JobDefinition>> for JobRegistry {
+    fn from(definitions: Vec<JobDefinition>) -> Self {
+        JobRegistry {
+            definitions: definitions.into_iter().map(|it| (it.job_id, it)).collect(),
+        }
+    }
+}
+
+impl From<JobRegistry> for Vec<JobDefinition> {
+    fn from(val: JobRegistry) -> Self {
+        let mut definitions: Vec<JobDefinition> = val.definitions.into_values().collect();
+        definitions.sort_by_key(|it| it.job_id.to_string());
+        definitions
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct SkillCastingAttributes {
     pub casting_time: GameTime<Local>,
     pub cast_delay: GameTime<Local>,
@@ -43,7 +130,7 @@ pub struct SkillCastingAttributes {
     pub width: Option<f32>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct SkillConfigFireWall {
     pub pushback_force: f32,
     pub damage: u32,
@@ -53,7 +140,7 @@ pub struct SkillConfigFireWall {
     pub attributes: SkillCastingAttributes,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct SkillConfigPyroBlastInner {
     pub moving_speed: f32,
     pub damage: u32,
@@ -62,19 +149,19 @@ pub struct SkillConfigPyroBlastInner {
     pub splash_radius: f32,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct SkillConfigPyroBlast {
     pub inner: SkillConfigPyroBlastInner,
     pub attributes: SkillCastingAttributes,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct SkillConfigHeal {
     pub heal: u32,
     pub attributes: SkillCastingAttributes,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct SkillConfigBrutalTestSkill {
     pub damage: u32,
     pub width: f32,
@@ -82,7 +169,7 @@ pub struct SkillConfigBrutalTestSkill {
     pub attributes: SkillCastingAttributes,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct SkillConfigSanctuarySkill {
     pub heal: u32,
     pub heal_freq_seconds: f32,
@@ -90,31 +177,31 @@ pub struct SkillConfigSanctuarySkill {
     pub attributes: SkillCastingAttributes,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct LightningSkillConfig {
     pub attributes: SkillCastingAttributes,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct PoisonSkillConfig {
     pub attributes: SkillCastingAttributes,
     pub damage: u32,
     pub duration_seconds: f32,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct FireBombSkillConfig {
     pub attributes: SkillCastingAttributes,
     pub damage: u32,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct AbsorbShieldSkillConfig {
     pub attributes: SkillCastingAttributes,
     pub duration_seconds: f32,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct ExoSkeletonSkillConfig {
     pub attributes: SkillCastingAttributes,
     pub duration_seconds: f32,
@@ -125,7 +212,7 @@ pub struct ExoSkeletonSkillConfig {
     pub attack_speed: Percentage,
 }
 
-#[derive(Debug, Deserialize, Clone, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct AssaBladeDashSkillConfig {
     pub attributes: SkillCastingAttributes,
     pub duration_seconds: f32,
@@ -133,7 +220,7 @@ pub struct AssaBladeDashSkillConfig {
     pub second_damage: u32,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct AssaPhasePrismSkillConfig {
     pub attributes: SkillCastingAttributes,
     pub duration_seconds: f32,
@@ -141,14 +228,14 @@ pub struct AssaPhasePrismSkillConfig {
     pub damage: u32,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct FalconCarry {
     pub attributes: SkillCastingAttributes,
     pub carry_ally_duration: f32,
     pub carry_owner_duration: f32,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct FalconAttack {
     pub attributes: SkillCastingAttributes,
     pub damage: u32,
@@ -157,7 +244,7 @@ pub struct FalconAttack {
     pub slow_duration: f32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct GazXplodiumChargeSkillConfigInner {
     pub missile_travel_duration_seconds: f32,
     pub detonation_duration: f32,
@@ -166,25 +253,25 @@ pub struct GazXplodiumChargeSkillConfigInner {
     pub explosion_area: f32,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct GazXplodiumChargeSkillConfig {
     pub attributes: SkillCastingAttributes,
     pub inner: GazXplodiumChargeSkillConfigInner,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+/// The attributes of the turret are in the definition of `JobId::Turret`
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct GazTurretSkillConfig {
     pub attributes: SkillCastingAttributes,
-    pub turret: CharAttributes,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+/// The attributes of the barricade are in the definition of `JobId::Barricade`
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct GazBarricadeSkillConfig {
     pub attributes: SkillCastingAttributes,
-    pub char_attrs: CharAttributes,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct SkillsConfig {
     pub firewall: SkillConfigFireWall,
     pub wiz_pyroblast: SkillConfigPyroBlast,
@@ -208,3 +295,315 @@ pub struct SkillsConfig {
     pub gaz_destroy_turret: SkillCastingAttributes,
     pub sanctuary: SkillConfigSanctuarySkill,
 }
+
+/// Nothing on the map is farther than this, bigger values are surely typos
+pub const MAX_CASTING_RANGE: f32 = 50.0;
+
+macro_rules! config_sections {
+    ($($section:ident($typ:ty) => $($field:ident).+;)*) => {
+        /// The parts of the configs which can be replaced independently,
+        /// after a reload only the modified ones are sent to the clients
+        #[derive(Clone, Debug, Deserialize, Serialize)]
+        pub enum ConfigSection {
+            $($section($typ),)*
+        }
+
+        impl ConfigSection {
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(ConfigSection::$section(_) => stringify!($section),)*
+                }
+            }
+        }
+
+        impl CommonConfigs {
+            pub fn changed_sections(&self, new_configs: &CommonConfigs) -> Vec<ConfigSection> {
+                let mut sections = Vec::new();
+                $(
+                    if self.$($field).+ != new_configs.$($field).+ {
+                        sections.push(ConfigSection::$section(new_configs.$($field).+.clone()));
+                    }
+                )*
+                sections
+            }
+
+            pub fn apply(&mut self, section: ConfigSection) {
+                match section {
+                    $(ConfigSection::$section(value) => self.$($field).+ = value,)*
+                }
+            }
+        }
+    };
+}
+
+config_sections! {
+    Jobs(JobRegistry) => jobs;
+    Firewall(SkillConfigFireWall) => skills.firewall;
+    WizPyroblast(SkillConfigPyroBlast) => skills.wiz_pyroblast;
+    Heal(SkillConfigHeal) => skills.heal;
+    BrutalTestSkill(SkillConfigBrutalTestSkill) => skills.brutal_test_skill;
+    Lightning(LightningSkillConfig) => skills.lightning;
+    Mounting(SkillCastingAttributes) => skills.mounting;
+    Unmounting(SkillCastingAttributes) => skills.unmounting;
+    Cure(SkillCastingAttributes) => skills.cure;
+    Poison(PoisonSkillConfig) => skills.poison;
+    Firebomb(FireBombSkillConfig) => skills.firebomb;
+    AbsorbShield(AbsorbShieldSkillConfig) => skills.absorb_shield;
+    Exoskeleton(ExoSkeletonSkillConfig) => skills.exoskeleton;
+    AssaBladeDash(AssaBladeDashSkillConfig) => skills.assa_blade_dash;
+    AssaPhasePrism(AssaPhasePrismSkillConfig) => skills.assa_phase_prism;
+    FalconCarry(FalconCarry) => skills.falcon_carry;
+    FalconAttack(FalconAttack) => skills.falcon_attack;
+    GazXplodiumCharge(GazXplodiumChargeSkillConfig) => skills.gaz_xplodium_charge;
+    GazTurret(GazTurretSkillConfig) => skills.gaz_turret;
+    GazBarricade(GazBarricadeSkillConfig) => skills.gaz_barricade;
+    GazDestroyTurret(SkillCastingAttributes) => skills.gaz_destroy_turret;
+    Sanctuary(SkillConfigSanctuarySkill) => skills.sanctuary;
+}
+
+struct ConfigValidator {
+    errors: Vec<String>,
+}
+
+impl ConfigValidator {
+    fn check(&mut self, valid: bool, path: &str, problem: impl Display) {
+        if !valid {
+            self.errors.push(format!("{}: {}", path, problem));
+        }
+    }
+
+    fn non_negative(&mut self, path: &str, value: f32) {
+        self.check(
+            value.is_finite() && value >= 0.0,
+            path,
+            format!("must not be negative, got {}", value),
+        );
+    }
+
+    fn positive(&mut self, path: &str, value: f32) {
+        self.check(
+            value.is_finite() && value > 0.0,
+            path,
+            format!("must be positive, got {}", value),
+        );
+    }
+
+    fn non_negative_percentage(&mut self, path: &str, value: Percentage) {
+        let value: i32 = value.into();
+        self.check(
+            value >= 0,
+            path,
+            format!("must not be negative, got {}%", value),
+        );
+    }
+
+    fn attributes(&mut self, path: &str, attrs: &CharAttributes) {
+        self.check(
+            attrs.max_hp > 0,
+            &format!("{}.max_hp", path),
+            format!("must be positive, got {}", attrs.max_hp),
+        );
+        for (name, value) in &[
+            ("movement_speed", attrs.movement_speed),
+            ("attack_range", attrs.attack_range),
+            ("attack_speed", attrs.attack_speed),
+            ("armor", attrs.armor),
+            ("healing", attrs.healing),
+            ("hp_regen", attrs.hp_regen),
+            ("mana_regen", attrs.mana_regen),
+        ] {
+            self.non_negative_percentage(&format!("{}.{}", path, name), *value);
+        }
+    }
+
+    fn casting(&mut self, path: &str, attrs: &SkillCastingAttributes) {
+        let range = attrs.casting_range;
+        self.check(
+            range.is_finite() && (0.0..=MAX_CASTING_RANGE).contains(&range),
+            &format!("{}.casting_range", path),
+            format!("must be between 0 and {}, got {}", MAX_CASTING_RANGE, range),
+        );
+        if let Some(width) = attrs.width {
+            self.positive(&format!("{}.width", path), width);
+        }
+    }
+}
+
+impl CommonConfigs {
+    /// Lists every invalid value, so a designer can fix all of them at once
+    pub fn validate(&self) -> Result<(), String> {
+        let mut v = ConfigValidator { errors: Vec::new() };
+        for job_id in JobId::iter() {
+            match self.jobs.get(job_id) {
+                Some(job) => {
+                    let path = format!("jobs.{}", job_id);
+                    v.attributes(&format!("{}.attributes", path), &job.attributes);
+                    v.non_negative_percentage(
+                        &format!("{}.mounted_speedup", path),
+                        job.mounted_speedup,
+                    );
+                    if let Some(weapon) = job.ranged_weapon {
+                        v.check(
+                            weapon.projectile_speed().is_some(),
+                            &format!("{}.ranged_weapon", path),
+                            format!("{:?} is not a ranged weapon", weapon),
+                        );
+                    }
+                    if let Some(combo) = &job.melee_combo {
+                        let path = format!("{}.melee_combo", path);
+                        v.check(
+                            job.ranged_weapon.is_none(),
+                            &path,
+                            "ranged weapons can not have combos",
+                        );
+                        v.check(
+                            combo.combo_count > 0,
+                            &format!("{}.combo_count", path),
+                            "must be positive",
+                        );
+                        v.non_negative_percentage(
+                            &format!("{}.base_dmg_percentage_for_each_combo", path),
+                            combo.base_dmg_percentage_for_each_combo,
+                        );
+                        v.non_negative(
+                            &format!("{}.combo_window_seconds", path),
+                            combo.combo_window_seconds,
+                        );
+                    }
+                    for (i, job_skill) in job.skills.iter().enumerate() {
+                        v.check(
+                            !job.skills[..i].iter().any(|it| it.key == job_skill.key),
+                            &format!("{}.skills", path),
+                            format!("'{}' key is assigned more than once", job_skill.key),
+                        );
+                    }
+                }
+                None => v.check(false, "jobs", format!("{} has no definition", job_id)),
+            }
+        }
+
+        let skills = &self.skills;
+        for (name, attrs) in &[
+            ("firewall", &skills.firewall.attributes),
+            ("wiz_pyroblast", &skills.wiz_pyroblast.attributes),
+            ("heal", &skills.heal.attributes),
+            ("brutal_test_skill", &skills.brutal_test_skill.attributes),
+            ("lightning", &skills.lightning.attributes),
+            ("poison", &skills.poison.attributes),
+            ("firebomb", &skills.firebomb.attributes),
+            ("absorb_shield", &skills.absorb_shield.attributes),
+            ("exoskeleton", &skills.exoskeleton.attributes),
+            ("assa_blade_dash", &skills.assa_blade_dash.attributes),
+            ("assa_phase_prism", &skills.assa_phase_prism.attributes),
+            ("falcon_carry", &skills.falcon_carry.attributes),
+            ("falcon_attack", &skills.falcon_attack.attributes),
+            (
+                "gaz_xplodium_charge",
+                &skills.gaz_xplodium_charge.attributes,
+            ),
+            ("gaz_turret", &skills.gaz_turret.attributes),
+            ("gaz_barricade", &skills.gaz_barricade.attributes),
+            ("sanctuary", &skills.sanctuary.attributes),
+        ] {
+            v.casting(&format!("skills.{}.attributes", name), attrs);
+        }
+        v.casting("skills.mounting", &skills.mounting);
+        v.casting("skills.unmounting", &skills.unmounting);
+        v.casting("skills.cure", &skills.cure);
+        v.casting("skills.gaz_destroy_turret", &skills.gaz_destroy_turret);
+
+        let firewall = &skills.firewall;
+        v.non_negative("skills.firewall.pushback_force", firewall.pushback_force);
+        v.non_negative(
+            "skills.firewall.duration_seconds",
+            firewall.duration_seconds,
+        );
+        v.non_negative(
+            "skills.firewall.force_duration_seconds",
+            firewall.force_duration_seconds,
+        );
+        let pyroblast = &skills.wiz_pyroblast.inner;
+        v.positive(
+            "skills.wiz_pyroblast.inner.moving_speed",
+            pyroblast.moving_speed,
+        );
+        v.positive("skills.wiz_pyroblast.inner.ball_size", pyroblast.ball_size);
+        v.non_negative(
+            "skills.wiz_pyroblast.inner.splash_radius",
+            pyroblast.splash_radius,
+        );
+        let brutal = &skills.brutal_test_skill;
+        v.positive("skills.brutal_test_skill.width", brutal.width);
+        v.positive("skills.brutal_test_skill.height", brutal.height);
+        let sanctuary = &skills.sanctuary;
+        v.positive(
+            "skills.sanctuary.heal_freq_seconds",
+            sanctuary.heal_freq_seconds,
+        );
+        v.non_negative("skills.sanctuary.duration", sanctuary.duration);
+        v.non_negative(
+            "skills.poison.duration_seconds",
+            skills.poison.duration_seconds,
+        );
+        v.non_negative(
+            "skills.absorb_shield.duration_seconds",
+            skills.absorb_shield.duration_seconds,
+        );
+        v.non_negative(
+            "skills.exoskeleton.duration_seconds",
+            skills.exoskeleton.duration_seconds,
+        );
+        v.non_negative(
+            "skills.assa_blade_dash.duration_seconds",
+            skills.assa_blade_dash.duration_seconds,
+        );
+        let prism = &skills.assa_phase_prism;
+        v.non_negative(
+            "skills.assa_phase_prism.duration_seconds",
+            prism.duration_seconds,
+        );
+        v.non_negative(
+            "skills.assa_phase_prism.swap_duration_unit_per_second",
+            prism.swap_duration_unit_per_second,
+        );
+        let carry = &skills.falcon_carry;
+        v.non_negative(
+            "skills.falcon_carry.carry_ally_duration",
+            carry.carry_ally_duration,
+        );
+        v.non_negative(
+            "skills.falcon_carry.carry_owner_duration",
+            carry.carry_owner_duration,
+        );
+        let falcon_attack = &skills.falcon_attack;
+        v.non_negative(
+            "skills.falcon_attack.duration_in_seconds",
+            falcon_attack.duration_in_seconds,
+        );
+        v.non_negative(
+            "skills.falcon_attack.slow_duration",
+            falcon_attack.slow_duration,
+        );
+        let xplodium = &skills.gaz_xplodium_charge.inner;
+        for (name, value) in &[
+            (
+                "missile_travel_duration_seconds",
+                xplodium.missile_travel_duration_seconds,
+            ),
+            ("detonation_duration", xplodium.detonation_duration),
+            ("stun_duration_seconds", xplodium.stun_duration_seconds),
+            ("explosion_area", xplodium.explosion_area),
+        ] {
+            v.non_negative(
+                &format!("skills.gaz_xplodium_charge.inner.{}", name),
+                *value,
+            );
+        }
+
+        if v.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(v.errors.join("\n"))
+        }
+    }
+}
@@ -1,8 +1,11 @@
+use crate::components::char::{JobId, MonsterId};
+use crate::components::job_ids::JobSpriteId;
 use serde::Deserialize;
 use serde::Serialize;
 use std::fmt::Debug;
 use std::fmt::Error;
 use std::fmt::Formatter;
+use strum::IntoEnumIterator;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct CommandArguments {
@@ -102,7 +105,7 @@ impl CommandArguments {
     }
 
     pub fn get_command_name(&self) -> Option<&str> {
-        self.args.get(0).map(|it| it.text.as_str())
+        self.args.first().map(|it| it.text.as_str())
     }
     // first argument is the command name!
     pub fn as_int(&self, index: usize) -> Option<i32> {
@@ -128,7 +131,7 @@ impl CommandArguments {
     pub fn as_str(&self, index: usize) -> Option<&str> {
         self.args.get(index + 1).map(|it| {
             if it.qouted {
-                if it.text.as_bytes()[it.text.len() - 1] == '\"' as u8 {
+                if it.text.as_bytes()[it.text.len() - 1] == b'\"' {
                     &it.text[1..it.text.len() - 1]
                 } else {
                     &it.text[1..it.text.len()]
@@ -140,6 +143,348 @@ impl CommandArguments {
     }
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CommandParamType {
+    String,
+    Int,
+    Float,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum CommandPermission {
+    Player,
+    Admin,
+}
+
+#[derive(Copy, Clone)]
+pub enum ArgCompletion {
+    None,
+    Values(&'static [&'static str]),
+    Generated(fn() -> Vec<String>),
+    /// The names of the player characters, the client collects them from its own world
+    CharNames,
+}
+
+pub struct CommandParam {
+    pub name: &'static str,
+    pub typ: CommandParamType,
+    pub mandatory: bool,
+    pub completion: ArgCompletion,
+}
+
+/// The metadata of a command which is executed by the server, the clients use it
+/// for validation and autocompletion before forwarding the command
+pub struct CommandDefinition {
+    pub name: &'static str,
+    pub params: &'static [CommandParam],
+    pub permission: CommandPermission,
+}
+
+impl CommandDefinition {
+    pub fn validate(&self, args: &CommandArguments) -> Result<(), String> {
+        let params: Vec<(&str, CommandParamType, bool)> = self
+            .params
+            .iter()
+            .map(|it| (it.name, it.typ, it.mandatory))
+            .collect();
+        validate_arguments(&params, args)
+    }
+
+    pub fn completion_list(
+        &self,
+        param_index: usize,
+        char_names: impl FnOnce() -> Vec<String>,
+    ) -> Option<Vec<String>> {
+        match self.params.get(param_index)?.completion {
+            ArgCompletion::None => None,
+            ArgCompletion::Values(values) => {
+                Some(values.iter().map(|it| (*it).to_owned()).collect())
+            }
+            ArgCompletion::Generated(generator) => Some(generator()),
+            ArgCompletion::CharNames => Some(char_names()),
+        }
+    }
+}
+
+/// `params` contains the name, the type and the mandatoriness of the parameters,
+/// the optional ones must be at the end
+pub fn validate_arguments(
+    params: &[(&str, CommandParamType, bool)],
+    args: &CommandArguments,
+) -> Result<(), String> {
+    let mandatory_arg_count = params.iter().take_while(|it| it.2).count();
+    let actual_arg_count = args.args.len().saturating_sub(1);
+    if actual_arg_count < mandatory_arg_count || actual_arg_count > params.len() {
+        return Err(format!(
+            "Illegal number of parameters (expected at least {}, at most {}, provided {})",
+            mandatory_arg_count,
+            params.len(),
+            actual_arg_count
+        ));
+    }
+    for (i, (param_name, arg_type, mandatory)) in params.iter().enumerate() {
+        let ok = match arg_type {
+            CommandParamType::Float => args
+                .as_str(i)
+                .map(|it| it.parse::<f32>().is_ok())
+                .unwrap_or(!*mandatory),
+            CommandParamType::Int => args
+                .as_str(i)
+                .map(|it| it.parse::<i32>().is_ok())
+                .unwrap_or(!*mandatory),
+            CommandParamType::String => true,
+        };
+        if !ok {
+            return Err(format!(
+                "{}, the {}. parameter ('{}') must be {}",
+                param_name,
+                i,
+                args.as_str(i).unwrap_or(""),
+                match *arg_type {
+                    CommandParamType::Float => "float",
+                    CommandParamType::Int => "int",
+                    CommandParamType::String => "string",
+                }
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub const STATUS_NAMES: &[&str] = &["absorb", "poison", "armor", "reflect", "attack_heal"];
+pub const TEAM_NAMES: &[&str] = &["left", "right"];
+
+pub const SPAWNABLE_TYPES: &[&str] = &[
+    "minion_melee",
+    "minion_ranged",
+    "dummy_enemy",
+    "dummy_ally",
+    "guard",
+    "tower",
+];
+
+fn job_names() -> Vec<String> {
+    JobId::iter().map(|it| it.to_string()).collect()
+}
+
+fn outlook_names() -> Vec<String> {
+    JobSpriteId::iter()
+        .map(|it| it.to_string())
+        .chain(MonsterId::iter().map(|it| it.to_string()))
+        .collect()
+}
+
+fn kill_all_types() -> Vec<String> {
+    ["all", "left_team", "right_team"]
+        .iter()
+        .map(|it| (*it).to_owned())
+        .chain(job_names())
+        .collect()
+}
+
+const fn param(
+    name: &'static str,
+    typ: CommandParamType,
+    mandatory: bool,
+    completion: ArgCompletion,
+) -> CommandParam {
+    CommandParam {
+        name,
+        typ,
+        mandatory,
+        completion,
+    }
+}
+
+/// The commands which modify the simulation, so only the server can execute them.
+/// The optional character name parameter defaults to the character of the caller.
+/// The rest of the console commands stay on the client, they only affect its own view
+/// (e.g. `inspect`, `follow_char`, `set_config`, the cosmetic falcons).
+pub const SERVER_COMMANDS: &[CommandDefinition] = &[
+    CommandDefinition {
+        name: "kill_all",
+        params: &[param(
+            "[type=all]",
+            CommandParamType::String,
+            false,
+            ArgCompletion::Generated(kill_all_types),
+        )],
+        permission: CommandPermission::Admin,
+    },
+    CommandDefinition {
+        name: "reload_configs",
+        params: &[],
+        permission: CommandPermission::Admin,
+    },
+    CommandDefinition {
+        name: "spawn_entity",
+        params: &[
+            param(
+                "type",
+                CommandParamType::String,
+                true,
+                ArgCompletion::Values(SPAWNABLE_TYPES),
+            ),
+            param(
+                "team",
+                CommandParamType::String,
+                true,
+                ArgCompletion::Values(TEAM_NAMES),
+            ),
+            param(
+                "[count:1]",
+                CommandParamType::Int,
+                false,
+                ArgCompletion::None,
+            ),
+            param("[x]", CommandParamType::Int, false, ArgCompletion::None),
+            param("[z]", CommandParamType::Int, false, ArgCompletion::None),
+            param(
+                "[outlook]",
+                CommandParamType::String,
+                false,
+                ArgCompletion::Generated(outlook_names),
+            ),
+        ],
+        permission: CommandPermission::Admin,
+    },
+    CommandDefinition {
+        name: "set_job",
+        params: &[
+            param(
+                "class_name",
+                CommandParamType::String,
+                true,
+                ArgCompletion::Generated(job_names),
+            ),
+            param(
+                "[username]",
+                CommandParamType::String,
+                false,
+                ArgCompletion::CharNames,
+            ),
+        ],
+        permission: CommandPermission::Admin,
+    },
+    CommandDefinition {
+        name: "set_outlook",
+        params: &[
+            param(
+                "class_name",
+                CommandParamType::String,
+                true,
+                ArgCompletion::Generated(outlook_names),
+            ),
+            param(
+                "[username]",
+                CommandParamType::String,
+                false,
+                ArgCompletion::CharNames,
+            ),
+        ],
+        permission: CommandPermission::Admin,
+    },
+    CommandDefinition {
+        name: "set_team",
+        params: &[
+            param(
+                "team",
+                CommandParamType::String,
+                true,
+                ArgCompletion::Values(TEAM_NAMES),
+            ),
+            param(
+                "[charname]",
+                CommandParamType::String,
+                false,
+                ArgCompletion::CharNames,
+            ),
+        ],
+        permission: CommandPermission::Admin,
+    },
+    CommandDefinition {
+        name: "heal",
+        params: &[
+            param("value", CommandParamType::Int, true, ArgCompletion::None),
+            param(
+                "[username]",
+                CommandParamType::String,
+                false,
+                ArgCompletion::CharNames,
+            ),
+        ],
+        permission: CommandPermission::Admin,
+    },
+    CommandDefinition {
+        name: "add_status",
+        params: &[
+            param(
+                "status_name",
+                CommandParamType::String,
+                true,
+                ArgCompletion::Values(STATUS_NAMES),
+            ),
+            param("time(ms)", CommandParamType::Int, true, ArgCompletion::None),
+            param("[value]", CommandParamType::Int, false, ArgCompletion::None),
+            param(
+                "[username]",
+                CommandParamType::String,
+                false,
+                ArgCompletion::CharNames,
+            ),
+        ],
+        permission: CommandPermission::Admin,
+    },
+    CommandDefinition {
+        name: "resurrect",
+        params: &[param(
+            "charname",
+            CommandParamType::String,
+            true,
+            ArgCompletion::CharNames,
+        )],
+        permission: CommandPermission::Admin,
+    },
+    CommandDefinition {
+        name: "set_pos",
+        params: &[
+            param("x", CommandParamType::Int, true, ArgCompletion::None),
+            param("z", CommandParamType::Int, true, ArgCompletion::None),
+            param(
+                "[username]",
+                CommandParamType::String,
+                false,
+                ArgCompletion::CharNames,
+            ),
+        ],
+        permission: CommandPermission::Admin,
+    },
+    CommandDefinition {
+        name: "goto",
+        params: &[param(
+            "username",
+            CommandParamType::String,
+            true,
+            ArgCompletion::CharNames,
+        )],
+        permission: CommandPermission::Admin,
+    },
+    CommandDefinition {
+        name: "clone",
+        params: &[param(
+            "[charname]",
+            CommandParamType::String,
+            false,
+            ArgCompletion::CharNames,
+        )],
+        permission: CommandPermission::Admin,
+    },
+];
+
+pub fn find_server_command(name: &str) -> Option<&'static CommandDefinition> {
+    SERVER_COMMANDS.iter().find(|it| it.name == name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,4 +522,40 @@ mod tests {
             CommandArguments::new("skip 1 2   3").as_str(2).unwrap()
         );
     }
+
+    #[test]
+    fn server_commands_are_validated_by_their_definitions() {
+        let set_pos = find_server_command("set_pos").unwrap();
+        assert!(set_pos
+            .validate(&CommandArguments::new("set_pos 1 2"))
+            .is_ok());
+        assert!(set_pos
+            .validate(&CommandArguments::new("set_pos 1 2 sharp"))
+            .is_ok());
+        assert!(set_pos
+            .validate(&CommandArguments::new("set_pos 1"))
+            .is_err());
+        assert!(set_pos
+            .validate(&CommandArguments::new("set_pos 1 a"))
+            .is_err());
+        assert!(set_pos
+            .validate(&CommandArguments::new("set_pos 1 2 sharp 4"))
+            .is_err());
+        assert!(find_server_command("toggle_console").is_none());
+    }
+
+    #[test]
+    fn optional_params_follow_the_mandatory_ones() {
+        for def in SERVER_COMMANDS {
+            let mandatory_count = def.params.iter().filter(|it| it.mandatory).count();
+            assert!(
+                def.params
+                    .iter()
+                    .take(mandatory_count)
+                    .all(|it| it.mandatory),
+                "{}",
+                def.name
+            );
+        }
+    }
 }
@@ -31,7 +31,7 @@ pub struct CommonAssetLoader {
     paths: Vec<String>,
 }
 
-impl<'a> CommonAssetLoader {
+impl CommonAssetLoader {
     pub fn new<P: AsRef<Path> + Clone>(paths: &[P]) -> Result<CommonAssetLoader, std::io::Error> {
         let path_str: Vec<String> = paths
             .iter()
@@ -64,8 +64,7 @@ impl<'a> CommonAssetLoader {
         } else {
             let readers: Result<Vec<BinaryReader>, std::io::Error> = paths
                 .iter()
-                .enumerate()
-                .map(|(_i, path)| BinaryReader::new(path.clone()))
+                .map(|path| BinaryReader::new(path.clone()))
                 .collect();
             match readers {
                 Err(e) => return Err(e),
@@ -73,10 +72,9 @@ impl<'a> CommonAssetLoader {
                     let entries: HashMap<String, (usize, GrfEntry)> = readers
                         .into_iter()
                         .enumerate()
-                        .map(|(file_index, buf)| {
+                        .flat_map(|(file_index, buf)| {
                             CommonAssetLoader::read_grf_entries(paths, file_index, buf)
                         })
-                        .flatten()
                         .collect();
 
                     match File::create("grf.cache") {
@@ -89,7 +87,7 @@ impl<'a> CommonAssetLoader {
                                 cache_file
                                     .write_u16::<LittleEndian>(filename.len() as u16)
                                     .unwrap();
-                                cache_file.write(filename.as_bytes()).unwrap();
+                                cache_file.write_all(filename.as_bytes()).unwrap();
                                 cache_file.write_u8(*grf_index as u8).unwrap();
                                 cache_file
                                     .write_u32::<LittleEndian>(grf_entry.pack_size)
@@ -194,7 +192,7 @@ impl<'a> CommonAssetLoader {
     }
 
     pub fn exists(&self, file_name: &str) -> bool {
-        self.entries.get(file_name).is_some()
+        self.entries.contains_key(file_name)
     }
 
     pub fn get_content(&self, file_name: &str) -> Result<Vec<u8>, String> {
@@ -217,14 +215,12 @@ impl<'a> CommonAssetLoader {
         f.seek(SeekFrom::Start(
             entry.offset as u64 + GRF_HEADER_SIZE as u64,
         ))
-        .expect(&format!("Could not get {}", file_name));
+        .unwrap_or_else(|_| panic!("Could not get {}", file_name));
         f.take(entry.length_aligned as u64)
             .read_to_end(&mut buf)
-            .expect(&format!("Could not get {}", file_name));
+            .unwrap_or_else(|_| panic!("Could not get {}", file_name));
 
-        if entry.typ & GRF_FILELIST_TYPE_ENCRYPT_MIXED != 0 {
-            panic!("'{}' is encrypted!", file_name);
-        } else if entry.typ & GRF_FILELIST_TYPE_ENCRYPT_HEADER != 0 {
+        if entry.typ & (GRF_FILELIST_TYPE_ENCRYPT_MIXED | GRF_FILELIST_TYPE_ENCRYPT_HEADER) != 0 {
             panic!("'{}' is encrypted!", file_name);
         }
         let mut decoder = libflate::zlib::Decoder::new(buf.as_slice()).unwrap();
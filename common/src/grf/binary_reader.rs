@@ -40,7 +40,7 @@ impl BinaryReader {
 
     #[inline]
     pub fn as_u16(buf: &[u8], index: usize) -> u16 {
-        unsafe { *(buf.as_ptr().offset(index as isize) as *const u16) }
+        unsafe { *(buf.as_ptr().add(index) as *const u16) }
     }
 
     pub fn get_u16(&self, index: usize) -> u16 {
@@ -56,31 +56,35 @@ impl BinaryReader {
         self.buf.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
     pub fn next_u8(&mut self) -> u8 {
         self.index += 1;
         self.buf[self.index - 1]
     }
 
     pub fn next_f32(&mut self) -> f32 {
-        let result = unsafe { *(self.buf.as_ptr().offset(self.index as isize) as *const f32) };
+        let result = unsafe { *(self.buf.as_ptr().add(self.index) as *const f32) };
         self.index += 4;
         return result;
     }
 
     pub fn next_i32(&mut self) -> i32 {
-        let result = unsafe { *(self.buf.as_ptr().offset(self.index as isize) as *const i32) };
+        let result = unsafe { *(self.buf.as_ptr().add(self.index) as *const i32) };
         self.index += 4;
         return result;
     }
 
     pub fn next_u32(&mut self) -> u32 {
-        let result = unsafe { *(self.buf.as_ptr().offset(self.index as isize) as *const u32) };
+        let result = unsafe { *(self.buf.as_ptr().add(self.index) as *const u32) };
         self.index += 4;
         return result;
     }
 
     pub fn next_u16(&mut self) -> u16 {
-        let result = unsafe { *(self.buf.as_ptr().offset(self.index as isize) as *const u16) };
+        let result = unsafe { *(self.buf.as_ptr().add(self.index) as *const u16) };
         self.index += 2;
         return result;
     }
@@ -94,12 +98,12 @@ impl BinaryReader {
             .skip(i)
             .take(max_len as usize)
             .take_while(|b| **b != 0)
-            .map(|b| *b)
+            .copied()
             .collect();
-        let decoded = encoding::all::WINDOWS_1252
+
+        encoding::all::WINDOWS_1252
             .decode(&bytes, encoding::DecoderTrap::Strict)
-            .unwrap();
-        decoded
+            .unwrap()
     }
 
     pub fn skip(&mut self, size: u32) {
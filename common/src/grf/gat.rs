@@ -150,11 +150,9 @@ impl Gat {
             };
             // remove the max rectangle
             let start_y = largest_rect.bottom - (largest_rect.height - 1);
-            for x in largest_rect.start_x..=largest_rect.start_x + largest_rect.width {
+            for x in largest_rect.start_x..largest_rect.start_x + largest_rect.width {
                 for y in start_y..=largest_rect.bottom {
                     let i = (y as usize * width) + x as usize;
-                    // TODO alde cas 04
-                    // thread 'main' panicked at 'index out of bounds: the len is 57600 but the index is 57600',
                     non_walkable_cells[i] = false;
                 }
             }
@@ -206,7 +204,7 @@ impl Gat {
                 }
             }
 
-            if (x + 1) % width == 0 && x > 1 {
+            if (x + 1).is_multiple_of(width) && x > 1 {
                 // row is ready
                 let row = &heights[y * width..(y * width) + width];
                 let (area, start_x, width, height) =
@@ -0,0 +1,139 @@
+use crate::common::{GameTime, Local};
+use crate::components::char::EntityId;
+use std::collections::HashMap;
+
+pub struct LagCompensatedTarget {
+    pub target: EntityId<Local>,
+    /// The distance between the attacker and the target at the tick the client has seen
+    pub distance: f32,
+    pub valid_until: GameTime<Local>,
+}
+
+/// The range checks of attacks and targeted skills accept the distances the clients saw
+/// when they sent their intentions, so players with high ping hit what they saw on their screen.
+/// An entry belongs to a single intention, it is removed when the next intention of the attacker
+/// arrives or when the attack or cast it was created for has started.
+/// Only the server fills it, the client predicts with the current distances.
+pub struct LagCompensatedTargets {
+    entries: HashMap<EntityId<Local>, LagCompensatedTarget>,
+}
+
+impl LagCompensatedTargets {
+    pub fn new() -> LagCompensatedTargets {
+        LagCompensatedTargets {
+            entries: HashMap::with_capacity(64),
+        }
+    }
+
+    pub fn insert(&mut self, attacker: EntityId<Local>, entry: LagCompensatedTarget) {
+        self.entries.insert(attacker, entry);
+    }
+
+    /// The intention of the entry has been carried out or replaced by a new one
+    pub fn remove(&mut self, attacker: EntityId<Local>) {
+        self.entries.remove(&attacker);
+    }
+
+    pub fn remove_expired(&mut self, now: GameTime<Local>) {
+        self.entries
+            .retain(|_attacker, entry| !entry.valid_until.has_already_passed(now));
+    }
+
+    /// The smaller of the current and the compensated distance
+    pub fn distance(
+        &self,
+        attacker: EntityId<Local>,
+        target: EntityId<Local>,
+        current_distance: f32,
+        now: GameTime<Local>,
+    ) -> f32 {
+        match self.entries.get(&attacker) {
+            Some(entry) if entry.target == target && !entry.valid_until.has_already_passed(now) => {
+                current_distance.min(entry.distance)
+            }
+            _ => current_distance,
+        }
+    }
+}
+
+pub fn compensated_distance(
+    lag_compensation: Option<&LagCompensatedTargets>,
+    attacker: EntityId<Local>,
+    target: EntityId<Local>,
+    current_distance: f32,
+    now: GameTime<Local>,
+) -> f32 {
+    match lag_compensation {
+        Some(lag_compensation) => {
+            lag_compensation.distance(attacker, target, current_distance, now)
+        }
+        None => current_distance,
+    }
+}
+
+pub fn consume_compensation(
+    lag_compensation: Option<&mut LagCompensatedTargets>,
+    attacker: EntityId<Local>,
+) {
+    if let Some(lag_compensation) = lag_compensation {
+        lag_compensation.remove(attacker);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::prelude::*;
+
+    #[test]
+    fn compensated_distance_is_used_only_for_the_target_until_it_expires() {
+        let mut world = World::new();
+        let attacker = EntityId::new(world.create_entity().build());
+        let target = EntityId::new(world.create_entity().build());
+        let other = EntityId::new(world.create_entity().build());
+        let mut lag_compensation = LagCompensatedTargets::new();
+        lag_compensation.insert(
+            attacker,
+            LagCompensatedTarget {
+                target,
+                distance: 2.0,
+                valid_until: GameTime::from(1.0),
+            },
+        );
+
+        let now = GameTime::from(0.5);
+        assert_eq!(lag_compensation.distance(attacker, target, 5.0, now), 2.0);
+        assert_eq!(lag_compensation.distance(attacker, target, 1.0, now), 1.0);
+        assert_eq!(lag_compensation.distance(attacker, other, 5.0, now), 5.0);
+        assert_eq!(lag_compensation.distance(target, attacker, 5.0, now), 5.0);
+
+        let later = GameTime::from(1.5);
+        assert_eq!(lag_compensation.distance(attacker, target, 5.0, later), 5.0);
+        lag_compensation.remove_expired(later);
+        assert_eq!(
+            compensated_distance(Some(&lag_compensation), attacker, target, 5.0, now),
+            5.0
+        );
+    }
+
+    #[test]
+    fn compensated_distance_is_used_only_once() {
+        let mut world = World::new();
+        let attacker = EntityId::new(world.create_entity().build());
+        let target = EntityId::new(world.create_entity().build());
+        let mut lag_compensation = LagCompensatedTargets::new();
+        lag_compensation.insert(
+            attacker,
+            LagCompensatedTarget {
+                target,
+                distance: 2.0,
+                valid_until: GameTime::from(1.0),
+            },
+        );
+
+        let now = GameTime::from(0.5);
+        assert_eq!(lag_compensation.distance(attacker, target, 5.0, now), 2.0);
+        consume_compensation(Some(&mut lag_compensation), attacker);
+        assert_eq!(lag_compensation.distance(attacker, target, 5.0, now), 5.0);
+    }
+}
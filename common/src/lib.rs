@@ -9,20 +9,31 @@
 //    unused_qualifications,
 //    clippy::all
 //)]
+// the explicit returns, the skills::skills like modules and the long system helper signatures are the style of the code base
+#![allow(
+    clippy::needless_return,
+    clippy::module_inception,
+    clippy::too_many_arguments,
+    clippy::new_without_default
+)]
 
 #[macro_use]
 extern crate specs_derive;
 extern crate hexplay;
 
-use specs;
-
 pub mod attack;
 pub mod char_attr;
+pub mod collision;
 pub mod common;
 pub mod components;
 pub mod config;
 pub mod console;
 pub mod grf;
+pub mod lag_compensation;
 pub mod map;
 pub mod packets;
+pub mod path_finding;
+pub mod state_checksum;
 pub mod systems;
+#[cfg(any(test, feature = "test_utils"))]
+pub mod test_utils;
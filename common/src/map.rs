@@ -1,5 +1,9 @@
 // remove grf::gat from common
 
+use crate::collision::Aabb;
+use crate::common::{v2, Vec2};
+use crate::grf::gat::{BlockingRectangle, Gat};
+
 pub enum CellType {
     None = 1 << 0,
     Walkable = 1 << 1,
@@ -7,18 +11,153 @@ pub enum CellType {
     Snipable = 1 << 3,
 }
 
+/// The navigation grid of the map.
+/// The cell (x, y) covers the [x, x+1] x [-(y+1), -y] area of the world.
 pub struct MapWalkingInfo {
     pub width: u32,
     pub height: u32,
-    pub cells: Vec<CellType>,
+    /// The `CellType` flags of the cells, row by row
+    pub cells: Vec<u8>,
+    /// The non-walkable cells merged into rectangles
+    pub walls: Vec<Aabb>,
+    /// The index of the wall which covers the cell, `NO_WALL` for the walkable cells
+    wall_of_cells: Vec<u32>,
 }
 
+const NO_WALL: u32 = u32::MAX;
+/// Pushing out of a wall might push into another one, but a few rounds settle it
+const MAX_WALL_RESOLUTION_ROUNDS: usize = 4;
+
 impl MapWalkingInfo {
+    /// Everything is walkable on an empty map
     pub fn new() -> MapWalkingInfo {
         MapWalkingInfo {
             width: 0,
             height: 0,
             cells: vec![],
+            walls: vec![],
+            wall_of_cells: vec![],
+        }
+    }
+
+    /// `rectangles` are the ones returned by `Gat::load`
+    pub fn from_gat(gat: &Gat, rectangles: &[BlockingRectangle]) -> MapWalkingInfo {
+        MapWalkingInfo::from_cells(
+            gat.width,
+            gat.height,
+            gat.cells.iter().map(|cell| cell.cell_type).collect(),
+            rectangles,
+        )
+    }
+
+    pub fn from_cells(
+        width: u32,
+        height: u32,
+        cells: Vec<u8>,
+        rectangles: &[BlockingRectangle],
+    ) -> MapWalkingInfo {
+        let mut map = MapWalkingInfo {
+            width,
+            height,
+            cells,
+            walls: Vec::with_capacity(rectangles.len()),
+            wall_of_cells: vec![NO_WALL; width as usize * height as usize],
+        };
+        for rect in rectangles {
+            let wall_index = map.walls.len() as u32;
+            map.walls.push(Aabb::from_blocking_rectangle(rect));
+            for y in rect.bottom - rect.height + 1..=rect.bottom {
+                for x in rect.start_x..rect.start_x + rect.width {
+                    if let Some(index) = map.index_of(x, y) {
+                        map.wall_of_cells[index] = wall_index;
+                    }
+                }
+            }
         }
+        // e.g. an outdated .cel cache file, every non-walkable cell must block
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let index = y as usize * width as usize + x as usize;
+                if !map.is_walkable(x, y) && map.wall_of_cells[index] == NO_WALL {
+                    map.wall_of_cells[index] = map.walls.len() as u32;
+                    let min = MapWalkingInfo::cell_center(x, y) - v2(0.5, 0.5);
+                    map.walls.push(Aabb::new(min, min + v2(1.0, 1.0)));
+                }
+            }
+        }
+        map
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    fn index_of(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            None
+        } else {
+            Some(y as usize * self.width as usize + x as usize)
+        }
+    }
+
+    /// The cells outside of the map are not walkable
+    pub fn is_walkable(&self, x: i32, y: i32) -> bool {
+        self.index_of(x, y)
+            .map(|index| self.cells[index] & CellType::Walkable as u8 != 0)
+            .unwrap_or(false)
+    }
+
+    /// Everywhere is walkable on an empty map
+    pub fn is_walkable_pos(&self, pos: Vec2) -> bool {
+        let (x, y) = MapWalkingInfo::cell_of(pos);
+        self.is_empty() || self.is_walkable(x, y)
+    }
+
+    /// Moves the circle out of the walls it overlaps
+    pub fn push_out_of_walls(&self, mut center: Vec2, radius: f32) -> Vec2 {
+        let mut nearby_walls = Vec::new();
+        for _ in 0..MAX_WALL_RESOLUTION_ROUNDS {
+            self.collect_walls_around(center, radius, &mut nearby_walls);
+            let mut pushed = false;
+            for wall_index in &nearby_walls {
+                if let Some(penetration) =
+                    self.walls[*wall_index as usize].circle_penetration(center, radius)
+                {
+                    center += penetration;
+                    pushed = true;
+                }
+            }
+            if !pushed {
+                break;
+            }
+        }
+        center
+    }
+
+    /// In ascending order, so the walls are resolved in the same order everywhere
+    fn collect_walls_around(&self, center: Vec2, radius: f32, out: &mut Vec<u32>) {
+        out.clear();
+        let (min_x, min_y) = MapWalkingInfo::cell_of(v2(center.x - radius, center.y + radius));
+        let (max_x, max_y) = MapWalkingInfo::cell_of(v2(center.x + radius, center.y - radius));
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                if let Some(index) = self.index_of(x, y) {
+                    let wall_index = self.wall_of_cells[index];
+                    if wall_index != NO_WALL {
+                        out.push(wall_index);
+                    }
+                }
+            }
+        }
+        out.sort_unstable();
+        out.dedup();
+    }
+
+    pub fn cell_of(pos: Vec2) -> (i32, i32) {
+        (pos.x.floor() as i32, (-pos.y).floor() as i32)
+    }
+
+    pub fn cell_center(x: i32, y: i32) -> Vec2 {
+        v2(x as f32 + 0.5, -(y as f32 + 0.5))
     }
 }
@@ -1,17 +1,15 @@
-use crate::attack::HpModificationResultType;
-use crate::common::{GameTime, Local, NetworkedObj, Remote, SimulationTick};
+use crate::attack::{HpModificationResultType, WeaponType};
+use crate::common::{GameTime, NetworkedObj, Remote, SimulationTick, Vec2};
 use crate::components::char::{
-    CharDir, CharOutlook, CharType, EntityId, JobId, LocalCharStateComp, Team,
+    CharOutlook, CharType, EntityId, JobId, LocalCharStateComp, StaticCharDataComponent, Team,
 };
-use crate::config::CommonConfigs;
+use crate::components::skills::skills::Skills;
+use crate::config::{CommonConfigs, ConfigSection};
+use crate::packets::snapshot_delta::SnapshotDelta;
 use crate::packets::to_server::{Packet, PacketReadErr};
-use crate::packets::SocketBuffer;
-use serde::export::TryFrom;
+use crate::packets::{Delivery, SocketBuffer};
 use serde::Deserialize;
 use serde::Serialize;
-use std::io::{Error, ErrorKind};
-use std::time::Instant;
-use strum::EnumCount;
 use strum_macros::EnumCount;
 use strum_macros::EnumDiscriminants;
 
@@ -21,21 +19,41 @@ pub struct ServerEntityState<T: 'static + NetworkedObj> {
     pub char_snapshot: LocalCharStateComp<T>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LobbyPlayerInfo {
+    pub name: String,
+    pub team: Team,
+    pub job_id: JobId,
+    pub ready: bool,
+}
+
 #[derive(Clone, Debug, EnumDiscriminants, EnumCount, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
 pub enum FromServerPacket {
+    /// It must remain the first variant, so clients of any version can decode it.
+    /// The connection is closed by the server after sending it.
+    Rejected {
+        reason: String,
+    },
     Init {
+        protocol_version: u32,
         map_name: String,
         start_x: f32,
         start_y: f32,
+        session_token: u64,
     },
     Configs(CommonConfigs),
+    /// The sections of the configs which have been modified by a reload on the server
+    ConfigsChanged(Vec<ConfigSection>),
     Pong {
         server_time: GameTime<Remote>,
         server_tick: SimulationTick,
     },
     Ack {
         cid: u32,
-        entries: Vec<ServerEntityState<Remote>>,
+        snapshot: SnapshotDelta<Remote>,
+        /// `world_checksum` of the states in the snapshot, as the client reconstructs them
+        checksum: u64,
     },
     NewEntity {
         id: EntityId<Remote>,
@@ -51,24 +69,83 @@ pub enum FromServerPacket {
         src_id: EntityId<Remote>,
         dst_id: EntityId<Remote>,
         typ: HpModificationResultType,
-    }, // EntityDisappeared {
-       //     id: EntityId<Remote>,
-       // },
-       // EntityAppeared {
-       //     id: EntityId<Remote>,
-       // },
-       // died?
+    },
+    /// `respawn_at` is None if the character will not respawn, e.g. the corpse of a minion
+    CharDied {
+        id: EntityId<Remote>,
+        respawn_at: Option<GameTime<Remote>>,
+    },
+    /// The client renders the projectile itself, its damage arrives in a `Damage` packet
+    ProjectileFired {
+        caster_id: EntityId<Remote>,
+        target_id: EntityId<Remote>,
+        weapon_type: WeaponType,
+        pos: Vec2,
+    },
+    /// An entity whose static data the client already knows has got into its view radius
+    EntityAppeared {
+        id: EntityId<Remote>,
+        state: LocalCharStateComp<Remote>,
+    },
+    /// The entity has left the view radius of the client, it won't be present in the snapshots
+    EntityDisappeared {
+        id: EntityId<Remote>,
+    },
+    /// Sent to everyone whenever a player joins, leaves or changes something in the lobby
+    LobbyState {
+        players: Vec<LobbyPlayerInfo>,
+    },
+    /// Sent after the `NewEntity` of the controlled character
+    MatchStarted {
+        controlled_entity: EntityId<Remote>,
+    },
+    /// Every character of the players is removed and the players are moved back to the lobby,
+    /// `None` means a draw
+    MatchEnded {
+        winner: Option<Team>,
+    },
+    /// An admin has changed e.g. the team or the job of the entity
+    StaticDataChanged {
+        id: EntityId<Remote>,
+        data: StaticCharDataComponent,
+    },
+    /// A tower or a core has been destroyed, it is followed by `MatchEnded` in case of a core
+    StructureDestroyed {
+        id: EntityId<Remote>,
+        team: Team,
+        job_id: JobId,
+    },
+    /// The client renders the manifestation of the skill, its effects arrive in the snapshots
+    /// and in the `Damage` packets
+    SkillManifested {
+        id: EntityId<Remote>,
+        skill: Skills,
+        caster_id: EntityId<Remote>,
+        caster_pos: Vec2,
+        skill_pos: Option<Vec2>,
+        char_to_skill_dir: Vec2,
+        target_id: Option<EntityId<Remote>>,
+    },
+    SkillManifestationEnded {
+        id: EntityId<Remote>,
+    },
+    // died?
 }
 
 impl Packet for FromServerPacket {
+    fn delivery(&self) -> Delivery {
+        match self {
+            // a lost snapshot is replaced by the next one
+            FromServerPacket::Ack { .. } => Delivery::Unreliable,
+            _ => Delivery::ReliableOrdered,
+        }
+    }
+
     fn write_into(&self, buf: &mut SocketBuffer) -> bincode::Result<()> {
         bincode::serialize_into(buf, self)
     }
 
     fn read_from(buf: &mut SocketBuffer) -> Result<Self, PacketReadErr> {
-        return match bincode::deserialize_from(buf) {
-            Ok(packet) => Ok(packet),
-            Err(e) => Err(PacketReadErr::NotEnoughBytes),
-        };
+        buf.read_bincode()
     }
 }
@@ -1,37 +1,67 @@
 use crate::common::{v2, Vec2};
-use crate::grf::binary_reader::BinaryReader;
-use crate::packets::from_server::FromServerPacket;
-use crate::packets::to_server::{Packet, PacketReadErr, ToServerPacket};
+use crate::packets::to_server::{Packet, PacketReadErr};
+use bincode::Options;
 use hexplay::{HexView, HexViewBuilder};
-use serde::export::fmt::Debug;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::fmt::Debug;
 use std::io::prelude::*;
 use std::io::{Error, Read};
-use std::net::TcpStream;
-use std::sync::mpsc::RecvError;
-use std::time::Duration;
+use std::net::{SocketAddr, TcpStream, UdpSocket};
+use std::time::{Duration, Instant};
 
 pub mod from_server;
+pub mod snapshot_delta;
 pub mod to_server;
+pub mod udp;
+
+/// Must be increased whenever the layout of any packet changes.
+/// Clients and servers with different versions refuse each other during the handshake.
+pub const PROTOCOL_VERSION: u32 = 12;
+/// Informational only, a different build with the same protocol version can connect
+pub const BUILD_ID: &str = env!("CARGO_PKG_VERSION");
+/// A removed TCP connection is kept open at most this long to flush its last packets,
+/// e.g. the reason of a rejection
+const CLOSE_FLUSH_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+pub enum Transport {
+    Tcp,
+    /// Snapshots are not blocked by lost segments, see `Delivery`
+    Udp,
+}
+
+/// Only the UDP transport takes it into account, TCP is always reliable and ordered
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Delivery {
+    /// Might be lost or arrive out of order, it is used for data which is outdated quickly (snapshots)
+    Unreliable,
+    ReliableOrdered,
+}
+
+/// Frames are prefixed by their length as a little endian u32
+const FRAME_HEADER_SIZE: usize = 4;
+pub const DEFAULT_MAX_PACKET_SIZE: usize = 64 * 1024;
+// the minimum free space before reading from a socket
+const READ_CHUNK_SIZE: usize = 4096;
 
+/// Growable byte queue, the readable data is between `read_pos` and `write_pos`.
+/// For incoming data the OS writes and the application reads it, for outgoing data it is
+/// the other way around. The already consumed space is reused by moving the unread bytes
+/// to the front instead of growing the buffer.
 pub struct SocketBuffer {
-    buf: [u8; 2048],
-    /// pointer at which the OS writes/reads the data during send/recv
-    os_pointer: usize,
-    /// pointer at which the application writes/reads the data during send/recv
-    user_pointer: usize,
+    buf: Vec<u8>,
+    read_pos: usize,
+    write_pos: usize,
+    max_packet_size: usize,
 }
 
 impl Write for SocketBuffer {
     fn write(&mut self, src: &[u8]) -> Result<usize, Error> {
-        //        pub fn write_u8(&mut self, value: u8) {
-        //            self.buff[self.user_pointer] = value;
-        //            self.user_pointer += 1;
-        //        }
-        let from = self.user_pointer;
         let len = src.len();
-        let to = self.user_pointer + len;
-        self.buf[from..to].copy_from_slice(src);
-        self.user_pointer += len;
+        self.reserve(len);
+        self.buf[self.write_pos..self.write_pos + len].copy_from_slice(src);
+        self.write_pos += len;
         Ok(len)
     }
 
@@ -42,63 +72,98 @@ impl Write for SocketBuffer {
 
 impl Read for SocketBuffer {
     fn read(&mut self, dst: &mut [u8]) -> Result<usize, Error> {
-        let from = self.user_pointer;
-        let full_len = self.os_pointer - self.user_pointer;
-        let copy_len = full_len.min(dst.len());
-        let to = from + copy_len;
-
-        dst[0..copy_len].copy_from_slice(&mut self.buf[from..to]);
-        self.user_pointer += copy_len;
+        let copy_len = self.data_len().min(dst.len());
+        let from = self.read_pos;
+        dst[0..copy_len].copy_from_slice(&self.buf[from..from + copy_len]);
+        self.read_pos += copy_len;
         return Ok(copy_len);
     }
 }
 
 impl SocketBuffer {
     pub fn new() -> SocketBuffer {
+        SocketBuffer::with_max_packet_size(DEFAULT_MAX_PACKET_SIZE)
+    }
+
+    pub fn with_max_packet_size(max_packet_size: usize) -> SocketBuffer {
         SocketBuffer {
-            buf: [0; 2048],
-            os_pointer: 0,
-            user_pointer: 0,
+            buf: vec![0; 2048],
+            read_pos: 0,
+            write_pos: 0,
+            max_packet_size,
         }
     }
 
+    /// Makes sure that at least `len` bytes can be written after `write_pos`
+    fn reserve(&mut self, len: usize) {
+        if self.write_pos + len <= self.buf.len() {
+            return;
+        }
+        if self.read_pos > 0 {
+            self.buf.copy_within(self.read_pos..self.write_pos, 0);
+            self.write_pos -= self.read_pos;
+            self.read_pos = 0;
+        }
+        if self.write_pos + len > self.buf.len() {
+            let new_len = (self.buf.len() * 2).max(self.write_pos + len);
+            self.buf.resize(new_len, 0);
+        }
+    }
+
+    fn data_len(&self) -> usize {
+        self.write_pos - self.read_pos
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.buf[self.read_pos..self.write_pos]
+    }
+
+    /// The free space is filled by the caller, who must call `commit_written` afterwards
+    fn free_space_mut(&mut self, min_len: usize) -> &mut [u8] {
+        self.reserve(min_len);
+        &mut self.buf[self.write_pos..]
+    }
+
+    fn commit_written(&mut self, len: usize) {
+        self.write_pos += len;
+    }
+
     pub fn read_incoming_data(
         &mut self,
         socket_stream: &mut TcpStream,
     ) -> Result<usize, std::io::Error> {
-        let n = socket_stream.read(&mut self.buf[self.os_pointer..])?;
-        self.os_pointer += n;
+        let n = socket_stream.read(self.free_space_mut(READ_CHUNK_SIZE))?;
+        self.commit_written(n);
         Ok(n)
     }
 
     pub fn incoming_data_len(&self) -> usize {
-        self.os_pointer - self.user_pointer
+        self.data_len()
     }
 
     pub fn outgoing_data_len(&self) -> usize {
-        self.user_pointer - self.os_pointer
+        self.data_len()
     }
 
     pub fn eof(&self) -> bool {
-        self.os_pointer == self.user_pointer
+        self.read_pos == self.write_pos
     }
 
-    pub fn get_debug_string_for_incoming_data(&self) -> HexView {
-        let data = &self.buf[self.user_pointer..self.os_pointer];
-        return HexViewBuilder::new(data)
+    pub fn get_debug_string_for_incoming_data(&self) -> HexView<'_> {
+        return HexViewBuilder::new(self.data())
             .address_offset(40)
             .row_width(16)
             .finish();
     }
 
-    pub fn get_debug_string_for_outgoing_data(&self) -> HexView {
-        let data = &self.buf[self.os_pointer..self.user_pointer];
-        return HexViewBuilder::new(data)
+    pub fn get_debug_string_for_outgoing_data(&self) -> HexView<'_> {
+        return HexViewBuilder::new(self.data())
             .address_offset(40)
             .row_width(16)
             .finish();
     }
 
+    /// Sends as much as the socket accepts, the rest remains in the buffer
     #[inline]
     pub fn send_outgoing_data(
         &mut self,
@@ -106,69 +171,132 @@ impl SocketBuffer {
     ) -> Result<usize, std::io::Error> {
         let mut all_sent_data = 0;
         while !self.eof() {
-            let sending_buf = &self.buf[self.os_pointer..self.user_pointer];
-            let sent_data = socket_stream.write(sending_buf)?;
-            self.os_pointer += sent_data;
+            let sent_data = match socket_stream.write(self.data()) {
+                Ok(0) => break,
+                Ok(sent_data) => sent_data,
+                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e),
+            };
+            self.read_pos += sent_data;
             all_sent_data += sent_data;
         }
         Ok(all_sent_data)
     }
 
-    pub fn read_u8(&mut self) -> u8 {
-        let result = self.buf[self.user_pointer];
-        self.user_pointer += 1;
-        return result;
+    /// Writes the packet prefixed by its length
+    pub fn write_frame<P: Packet>(&mut self, packet: &P) -> bincode::Result<()> {
+        let frame_start = self.write_pos;
+        self.write_u32(0);
+        let result = packet.write_into(self);
+        let len = self.write_pos - frame_start - FRAME_HEADER_SIZE;
+        if let Err(e) = result {
+            self.write_pos = frame_start;
+            return Err(e);
+        } else if len > self.max_packet_size {
+            self.write_pos = frame_start;
+            return Err(Box::new(bincode::ErrorKind::SizeLimit));
+        }
+        self.buf[frame_start..frame_start + FRAME_HEADER_SIZE]
+            .copy_from_slice(&(len as u32).to_le_bytes());
+        Ok(())
+    }
+
+    /// Returns `NotEnoughBytes` until the whole frame has arrived, in which case nothing
+    /// is consumed from the buffer. Any decoding error of a complete frame means invalid data.
+    pub fn read_frame<P: Packet>(&mut self) -> Result<P, PacketReadErr> {
+        if self.data_len() < FRAME_HEADER_SIZE {
+            return Err(PacketReadErr::NotEnoughBytes);
+        }
+        let mut len_bytes = [0; FRAME_HEADER_SIZE];
+        len_bytes.copy_from_slice(&self.buf[self.read_pos..self.read_pos + FRAME_HEADER_SIZE]);
+        let len = u32::from_le_bytes(len_bytes) as usize;
+        if len > self.max_packet_size {
+            return Err(PacketReadErr::InvalidValues);
+        }
+        if self.data_len() < FRAME_HEADER_SIZE + len {
+            return Err(PacketReadErr::NotEnoughBytes);
+        }
+        let frame_end = self.read_pos + FRAME_HEADER_SIZE + len;
+        let data_end = self.write_pos;
+        // the packet must not read into the next frame
+        self.read_pos += FRAME_HEADER_SIZE;
+        self.write_pos = frame_end;
+        let result = P::read_from(self);
+        let whole_frame_was_read = self.eof();
+        self.read_pos = frame_end;
+        self.write_pos = data_end;
+        return match result {
+            Ok(packet) if whole_frame_was_read => Ok(packet),
+            _ => Err(PacketReadErr::InvalidValues),
+        };
+    }
+
+    /// The unread data limits the allocations, so a corrupted length prefix (e.g. of a string)
+    /// is an error instead of an attempt to allocate gigabytes
+    pub fn read_bincode<T: DeserializeOwned>(&mut self) -> Result<T, PacketReadErr> {
+        let limit = self.data_len() as u64;
+        bincode::options()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+            .with_limit(limit)
+            .deserialize_from(self)
+            .map_err(PacketReadErr::from)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, PacketReadErr> {
+        let mut bytes = [0; 1];
+        self.read_bytes(&mut bytes)?;
+        Ok(bytes[0])
+    }
+
+    pub fn read_f32(&mut self) -> Result<f32, PacketReadErr> {
+        let mut bytes = [0; 4];
+        self.read_bytes(&mut bytes)?;
+        Ok(f32::from_le_bytes(bytes))
     }
 
-    pub fn read_f32(&mut self) -> f32 {
-        let result =
-            unsafe { *(self.buf.as_ptr().offset(self.user_pointer as isize) as *const f32) };
-        self.user_pointer += 4;
-        return result;
+    pub fn read_v2(&mut self) -> Result<Vec2, PacketReadErr> {
+        Ok(v2(self.read_f32()?, self.read_f32()?))
     }
 
-    pub fn read_v2(&mut self) -> Vec2 {
-        v2(self.read_f32(), self.read_f32())
+    pub fn read_i32(&mut self) -> Result<i32, PacketReadErr> {
+        let mut bytes = [0; 4];
+        self.read_bytes(&mut bytes)?;
+        Ok(i32::from_le_bytes(bytes))
     }
 
-    pub fn read_i32(&mut self) -> i32 {
-        let result =
-            unsafe { *(self.buf.as_ptr().offset(self.user_pointer as isize) as *const i32) };
-        self.user_pointer += 4;
-        return result;
+    pub fn read_u32(&mut self) -> Result<u32, PacketReadErr> {
+        let mut bytes = [0; 4];
+        self.read_bytes(&mut bytes)?;
+        Ok(u32::from_le_bytes(bytes))
     }
 
-    pub fn read_u32(&mut self) -> u32 {
-        let result =
-            unsafe { *(self.buf.as_ptr().offset(self.user_pointer as isize) as *const u32) };
-        self.user_pointer += 4;
-        return result;
+    pub fn read_u64(&mut self) -> Result<u64, PacketReadErr> {
+        let mut bytes = [0; 8];
+        self.read_bytes(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
     }
 
-    pub fn read_u64(&mut self) -> u64 {
-        let result =
-            unsafe { *(self.buf.as_ptr().offset(self.user_pointer as isize) as *const u64) };
-        self.user_pointer += 8;
-        return result;
+    pub fn read_u16(&mut self) -> Result<u16, PacketReadErr> {
+        let mut bytes = [0; 2];
+        self.read_bytes(&mut bytes)?;
+        Ok(u16::from_le_bytes(bytes))
     }
 
-    pub fn read_u16(&mut self) -> u16 {
-        let result =
-            unsafe { *(self.buf.as_ptr().offset(self.user_pointer as isize) as *const u16) };
-        self.user_pointer += 2;
-        return result;
+    fn read_bytes(&mut self, dst: &mut [u8]) -> Result<(), PacketReadErr> {
+        self.ensure_size(dst.len())?;
+        let from = self.read_pos;
+        dst.copy_from_slice(&self.buf[from..from + dst.len()]);
+        self.read_pos += dst.len();
+        Ok(())
     }
 
     pub fn write_u8(&mut self, value: u8) {
-        self.buf[self.user_pointer] = value;
-        self.user_pointer += 1;
+        self.write_bytes(&[value]);
     }
 
     pub fn write_f32(&mut self, value: f32) {
-        unsafe {
-            *(self.buf.as_mut_ptr().offset(self.user_pointer as isize) as *mut f32) = value;
-        }
-        self.user_pointer += 4;
+        self.write_bytes(&value.to_le_bytes());
     }
 
     pub fn write_v2(&mut self, value: &Vec2) {
@@ -177,42 +305,35 @@ impl SocketBuffer {
     }
 
     pub fn write_i32(&mut self, value: i32) {
-        unsafe {
-            *(self.buf.as_mut_ptr().offset(self.user_pointer as isize) as *mut i32) = value;
-        }
-        self.user_pointer += 4;
+        self.write_bytes(&value.to_le_bytes());
     }
 
     pub fn write_u32(&mut self, value: u32) {
-        unsafe {
-            *(self.buf.as_mut_ptr().offset(self.user_pointer as isize) as *mut u32) = value;
-        }
-        self.user_pointer += 4;
+        self.write_bytes(&value.to_le_bytes());
     }
 
     pub fn write_u64(&mut self, value: u64) {
-        unsafe {
-            *(self.buf.as_mut_ptr().offset(self.user_pointer as isize) as *mut u64) = value;
-        }
-        self.user_pointer += 8;
+        self.write_bytes(&value.to_le_bytes());
     }
 
     pub fn write_u16(&mut self, value: u16) {
-        unsafe {
-            *(self.buf.as_mut_ptr().offset(self.user_pointer as isize) as *mut u16) = value;
-        }
-        self.user_pointer += 2;
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    fn write_bytes(&mut self, src: &[u8]) {
+        // it can fail only if the memory is exhausted
+        let _ = self.write_all(src);
     }
 
     pub fn read_str(&mut self) -> Result<&str, PacketReadErr> {
-        let len = self.read_u16();
+        let len = self.read_u16()?;
         if len > 1024 {
             return Err(PacketReadErr::InvalidValues);
         }
         self.ensure_size(len as usize)?;
-        let start_index = self.user_pointer;
-        let end_index = self.user_pointer + len as usize;
-        self.user_pointer = end_index;
+        let start_index = self.read_pos;
+        let end_index = self.read_pos + len as usize;
+        self.read_pos = end_index;
         return match std::str::from_utf8(&self.buf[start_index..end_index]) {
             Ok(str) => Ok(str),
             _ => Err(PacketReadErr::InvalidValues),
@@ -220,22 +341,18 @@ impl SocketBuffer {
     }
 
     pub fn write_str(&mut self, text: &str) {
-        let len = text.len();
-        self.write_u16(len as u16);
-        let start = self.user_pointer;
-        let end = self.user_pointer + text.len();
-        self.buf[start..end].clone_from_slice(text.as_bytes());
-        self.user_pointer = end;
+        self.write_u16(text.len() as u16);
+        self.write_bytes(text.as_bytes());
     }
 
     pub fn reset(&mut self) {
-        self.os_pointer = 0;
-        self.user_pointer = 0;
+        self.read_pos = 0;
+        self.write_pos = 0;
     }
 
     #[inline]
     pub fn ensure_size(&self, requried_size: usize) -> Result<(), PacketReadErr> {
-        if self.os_pointer - self.user_pointer >= requried_size {
+        if self.data_len() >= requried_size {
             Ok(())
         } else {
             Err(PacketReadErr::NotEnoughBytes)
@@ -247,14 +364,26 @@ pub struct RemoteSocket {
     socket_stream: TcpStream,
     out_buff: SocketBuffer,
     in_buff: SocketBuffer,
+    /// Set when the connection has been removed, it is dropped when
+    /// `out_buff` has been flushed or at this deadline
+    closing_deadline: Option<Instant>,
 }
 
 impl RemoteSocket {
-    pub fn new(stream: TcpStream) -> RemoteSocket {
+    pub fn new(stream: TcpStream, max_packet_size: usize) -> RemoteSocket {
         RemoteSocket {
             socket_stream: stream,
-            out_buff: SocketBuffer::new(),
-            in_buff: SocketBuffer::new(),
+            out_buff: SocketBuffer::with_max_packet_size(max_packet_size),
+            in_buff: SocketBuffer::with_max_packet_size(max_packet_size),
+            closing_deadline: None,
+        }
+    }
+
+    /// It has been removed, and its last packets have been sent or it ran out of time
+    fn is_closed(&self, now: Instant) -> bool {
+        match self.closing_deadline {
+            Some(deadline) => self.out_buff.eof() || deadline <= now,
+            None => false,
         }
     }
 }
@@ -266,6 +395,12 @@ impl SocketId {
     pub fn as_usize(&self) -> usize {
         self.0
     }
+
+    /// Only the packet handlers assign the ids, the tests can fake them
+    #[cfg(any(test, feature = "test_utils"))]
+    pub fn from_usize(index: usize) -> SocketId {
+        SocketId(index)
+    }
 }
 
 pub struct PacketHandlerThread<I, O>
@@ -283,13 +418,16 @@ where
     I: Send + Packet + Debug + 'static,
     O: Send + Packet + Debug + 'static,
 {
-    pub fn start_thread(socket_capacity: usize) -> PacketHandlerThread<I, O> {
+    pub fn start_thread(
+        socket_capacity: usize,
+        max_packet_size: usize,
+    ) -> PacketHandlerThread<I, O> {
         let (send_to_incoming_ch, read_from_incoming_ch) =
             std::sync::mpsc::channel::<(SocketId, NetworkTrafficEvent<I>)>();
         let (send_to_outgoing_ch, read_from_outgoing_ch) =
             std::sync::mpsc::channel::<NetworkTrafficHandlerMsg<O>>();
         std::thread::spawn(move || {
-            network_traffic_handler(send_to_incoming_ch, read_from_outgoing_ch)
+            network_traffic_handler(send_to_incoming_ch, read_from_outgoing_ch, max_packet_size)
         });
         PacketHandlerThread {
             incoming_channel: read_from_incoming_ch,
@@ -299,8 +437,8 @@ where
     }
 
     pub fn handle_socket(&mut self, socket_stream: TcpStream) -> SocketId {
-        socket_stream.set_nonblocking(true);
-        socket_stream.set_nodelay(true);
+        let _ = socket_stream.set_nonblocking(true);
+        let _ = socket_stream.set_nodelay(true);
         // TODO: what is it? is it increasing infinitely??
         let id = SocketId(self.sockets.len());
         self.sockets.push(Some(()));
@@ -310,6 +448,53 @@ where
         return id;
     }
 
+    /// `accept_new_peers` should be true on the server, a new `SocketId` is assigned to every
+    /// unknown address and a `NetworkTrafficEvent::Connected` event is sent for it.
+    pub fn start_udp_thread(
+        socket_capacity: usize,
+        max_packet_size: usize,
+        socket: UdpSocket,
+        accept_new_peers: bool,
+    ) -> PacketHandlerThread<I, O> {
+        let (send_to_incoming_ch, read_from_incoming_ch) =
+            std::sync::mpsc::channel::<(SocketId, NetworkTrafficEvent<I>)>();
+        let (send_to_outgoing_ch, read_from_outgoing_ch) =
+            std::sync::mpsc::channel::<NetworkTrafficHandlerMsg<O>>();
+        socket
+            .set_nonblocking(true)
+            .expect("Cannot set non-blocking");
+        std::thread::spawn(move || {
+            udp::udp_traffic_handler(
+                socket,
+                accept_new_peers,
+                send_to_incoming_ch,
+                read_from_outgoing_ch,
+                max_packet_size,
+            )
+        });
+        PacketHandlerThread {
+            incoming_channel: read_from_incoming_ch,
+            outgoing_channel: send_to_outgoing_ch,
+            sockets: Vec::with_capacity(socket_capacity),
+        }
+    }
+
+    pub fn connect_udp(&mut self, addr: SocketAddr) -> SocketId {
+        let id = SocketId(self.sockets.len());
+        self.sockets.push(Some(()));
+        self.outgoing_channel
+            .send(NetworkTrafficHandlerMsg::NewUdpPeer(id, addr))
+            .unwrap();
+        return id;
+    }
+
+    /// The packets sent before are still delivered (without guarantee in case of UDP)
+    pub fn disconnect(&self, socket_id: SocketId) {
+        self.outgoing_channel
+            .send(NetworkTrafficHandlerMsg::RemoveConnection(socket_id))
+            .unwrap();
+    }
+
     pub fn send(&self, socket_id: SocketId, packet: O) {
         self.outgoing_channel
             .send(NetworkTrafficHandlerMsg::SendPacket(socket_id, packet))
@@ -317,12 +502,8 @@ where
     }
 
     pub fn receive_into(&self, out: &mut Vec<(SocketId, NetworkTrafficEvent<I>)>) {
-        loop {
-            if let Ok(id_and_packet) = self.incoming_channel.try_recv() {
-                out.push(id_and_packet)
-            } else {
-                break;
-            }
+        while let Ok(id_and_packet) = self.incoming_channel.try_recv() {
+            out.push(id_and_packet)
         }
     }
 
@@ -331,7 +512,7 @@ where
         out: &mut Vec<(SocketId, NetworkTrafficEvent<I>)>,
         count: usize,
     ) {
-        for i in 0..count {
+        for _i in 0..count {
             if let Ok(id_and_packet) = self.incoming_channel.try_recv() {
                 out.push(id_and_packet)
             } else {
@@ -347,24 +528,34 @@ where
     O: Send + Packet + Debug + 'static,
 {
     NewConnection(SocketId, TcpStream),
+    NewUdpPeer(SocketId, SocketAddr),
     RemoveConnection(SocketId),
     SendPacket(SocketId, O),
 }
 
 #[derive(Debug)]
 pub enum NetworkTrafficEvent<P: Send + Packet + Debug + 'static> {
+    /// Only the UDP transport sends it, TCP connections are accepted by the application
+    Connected(SocketAddr),
     LocalError(std::io::Error),
     Disconnected,
-    OutgoingTraffic { sent_data_len: usize },
-    IncomingTraffic { received_data_len: usize },
+    OutgoingTraffic {
+        sent_data_len: usize,
+    },
+    IncomingTraffic {
+        received_data_len: usize,
+    },
     Packet(P),
 }
 
+// the sockets are dropped by their index while iterating over them
+#[allow(clippy::needless_range_loop)]
 fn network_traffic_handler<I, O>(
     // this is the channel the client app reads for incoming packets
     send_to_incoming_ch: std::sync::mpsc::Sender<(SocketId, NetworkTrafficEvent<I>)>,
     // the channel which is filled by the client app with outgoing packets
     read_from_outgoing_ch: std::sync::mpsc::Receiver<NetworkTrafficHandlerMsg<O>>,
+    max_packet_size: usize,
 ) where
     I: Send + Packet + Debug + 'static,
     O: Send + Packet + Debug + 'static,
@@ -376,31 +567,43 @@ fn network_traffic_handler<I, O>(
             Ok(NetworkTrafficHandlerMsg::NewConnection(socket_id, socket_stream)) => {
                 log::info!("adding new connection: {:?}", socket_id);
                 if socket_id.0 >= sockets.len() {
-                    sockets.push(Some(RemoteSocket::new(socket_stream)));
+                    sockets.push(Some(RemoteSocket::new(socket_stream, max_packet_size)));
                 } else {
-                    sockets[socket_id.0] = Some(RemoteSocket::new(socket_stream));
+                    sockets[socket_id.0] = Some(RemoteSocket::new(socket_stream, max_packet_size));
                 }
             }
+            Ok(NetworkTrafficHandlerMsg::NewUdpPeer(socket_id, _addr)) => {
+                log::error!(
+                    "{:?}: UDP peers are not supported by the TCP transport",
+                    socket_id
+                );
+            }
             Ok(NetworkTrafficHandlerMsg::RemoveConnection(socket_id)) => {
-                sockets[socket_id.0] = None;
+                if let Some(socket) = sockets.get_mut(socket_id.0) {
+                    if let Some(remote_socket) = socket.as_mut() {
+                        // the last packets (e.g. the reason of a rejection) are flushed
+                        // by the sending loop below, without blocking the other sockets
+                        remote_socket.closing_deadline = Some(Instant::now() + CLOSE_FLUSH_TIMEOUT);
+                    }
+                }
             }
             Ok(NetworkTrafficHandlerMsg::SendPacket(socket_id, packet)) => {
-                if let Some(socket) = sockets[socket_id.0].as_mut() {
+                let socket = sockets[socket_id.0]
+                    .as_mut()
+                    .filter(|it| it.closing_deadline.is_none());
+                if let Some(socket) = socket {
                     let socket_buffer = &mut socket.out_buff;
                     //                    log::trace!("Outgoing Packet: {:?}", packet);
-                    if let Err(e) = packet.write_into(socket_buffer) {
+                    if let Err(e) = socket_buffer.write_frame(&packet) {
                         sockets[socket_id.0] = None;
-                        send_to_incoming_ch.send((
+                        let _ = send_to_incoming_ch.send((
                             SocketId(socket_id.0),
-                            NetworkTrafficEvent::LocalError(std::io::Error::new(
-                                std::io::ErrorKind::Other,
-                                e.to_string(),
-                            )),
+                            NetworkTrafficEvent::LocalError(std::io::Error::other(e.to_string())),
                         ));
                     }
                 }
             }
-            Err(e) => {}
+            Err(_e) => {}
         }
         for i in 0..sockets.len() {
             if let Some(ref mut socket) = &mut sockets[i] {
@@ -415,7 +618,7 @@ fn network_traffic_handler<I, O>(
                     match send_result {
                         Err(e) => {
                             sockets[i] = None;
-                            send_to_incoming_ch
+                            let _ = send_to_incoming_ch
                                 .send((SocketId(i), NetworkTrafficEvent::LocalError(e)));
                         }
                         Ok(sent_bytes) => {
@@ -423,7 +626,7 @@ fn network_traffic_handler<I, O>(
                                 // all the data has been sent, the buffer is empty
                                 socket.out_buff.reset();
                             }
-                            send_to_incoming_ch.send((
+                            let _ = send_to_incoming_ch.send((
                                 SocketId(i),
                                 NetworkTrafficEvent::OutgoingTraffic {
                                     sent_data_len: sent_bytes,
@@ -434,21 +637,33 @@ fn network_traffic_handler<I, O>(
                 }
             }
         }
+        let now = Instant::now();
+        for socket in sockets.iter_mut() {
+            if matches!(socket, Some(remote_socket) if remote_socket.is_closed(now)) {
+                *socket = None;
+            }
+        }
         'sockets_loop: for i in 0..sockets.len() {
             if let Some(ref mut socket) = &mut sockets[i] {
+                if socket.closing_deadline.is_some() {
+                    // its packets would be dropped anyway
+                    continue;
+                }
                 let len = match socket.in_buff.read_incoming_data(&mut socket.socket_stream) {
                     Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
                         continue;
                     }
                     Err(e) => {
                         log::error!("Error during socket reading: {}", e);
-                        send_to_incoming_ch.send((SocketId(i), NetworkTrafficEvent::LocalError(e)));
+                        let _ = send_to_incoming_ch
+                            .send((SocketId(i), NetworkTrafficEvent::LocalError(e)));
                         sockets[i] = None;
                         continue;
                     }
                     Ok(0) => {
                         let socket_id = SocketId(i);
-                        send_to_incoming_ch.send((socket_id, NetworkTrafficEvent::Disconnected));
+                        let _ = send_to_incoming_ch
+                            .send((socket_id, NetworkTrafficEvent::Disconnected));
                         sockets[i] = None;
                         continue;
                     }
@@ -458,7 +673,7 @@ fn network_traffic_handler<I, O>(
                 //                    "INCOMING\n{}",
                 //                    socket.in_buff.get_debug_string_for_incoming_data()
                 //                );
-                send_to_incoming_ch.send((
+                let _ = send_to_incoming_ch.send((
                     SocketId(i),
                     NetworkTrafficEvent::IncomingTraffic {
                         received_data_len: len,
@@ -467,10 +682,10 @@ fn network_traffic_handler<I, O>(
 
                 let socket_id = SocketId(i);
                 while !socket.in_buff.eof() {
-                    match I::read_from(&mut socket.in_buff) {
+                    match socket.in_buff.read_frame::<I>() {
                         Ok(packet) => {
                             //                            log::trace!("Incoming Packet: {:?}", packet);
-                            send_to_incoming_ch
+                            let _ = send_to_incoming_ch
                                 .send((socket_id, NetworkTrafficEvent::Packet(packet)));
                         }
                         Err(err) => {
@@ -496,3 +711,250 @@ fn network_traffic_handler<I, O>(
         std::thread::sleep(Duration::from_millis(10));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::from_server::FromServerPacket;
+    use crate::packets::to_server::ToServerPacket;
+
+    fn welcome(name: &str) -> ToServerPacket {
+        ToServerPacket::Welcome {
+            protocol_version: PROTOCOL_VERSION,
+            build: BUILD_ID.to_owned(),
+            name: name.to_owned(),
+            session_token: None,
+        }
+    }
+
+    fn name_of(packet: ToServerPacket) -> String {
+        match packet {
+            ToServerPacket::Welcome { name, .. } => name,
+            _ => panic!("unexpected packet: {:?}", packet),
+        }
+    }
+
+    // deterministic pseudo random numbers (xorshift), so failures can be reproduced
+    fn next_random(state: &mut u32) -> u32 {
+        *state ^= *state << 13;
+        *state ^= *state >> 17;
+        *state ^= *state << 5;
+        *state
+    }
+
+    #[test]
+    fn frames_survive_arbitrary_chunking() {
+        let names: Vec<String> = (0..50).map(|i| "x".repeat(i * 37)).collect();
+        let mut out_buf = SocketBuffer::new();
+        for name in &names {
+            out_buf.write_frame(&welcome(name)).unwrap();
+        }
+        let stream = out_buf.data().to_vec();
+
+        for seed in 1..20 {
+            let mut random_state = seed;
+            let mut in_buf = SocketBuffer::new();
+            let mut received = Vec::new();
+            let mut pos = 0;
+            while pos < stream.len() {
+                let chunk_len =
+                    (next_random(&mut random_state) as usize % 300 + 1).min(stream.len() - pos);
+                in_buf.write_all(&stream[pos..pos + chunk_len]).unwrap();
+                pos += chunk_len;
+                loop {
+                    match in_buf.read_frame::<ToServerPacket>() {
+                        Ok(packet) => received.push(name_of(packet)),
+                        Err(PacketReadErr::NotEnoughBytes) => break,
+                        Err(e) => panic!("seed {}: {:?}", seed, e),
+                    }
+                }
+            }
+            assert_eq!(received, names, "seed {}", seed);
+            assert!(in_buf.eof());
+        }
+    }
+
+    #[test]
+    fn removed_connection_flushes_its_last_packets() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server_side, _addr) = listener.accept().unwrap();
+        let mut packet_handler_thread =
+            PacketHandlerThread::<ToServerPacket, FromServerPacket>::start_thread(1, 1024);
+        let socket_id = packet_handler_thread.handle_socket(server_side);
+
+        let reason = "x".repeat(500);
+        packet_handler_thread.send(
+            socket_id,
+            FromServerPacket::Rejected {
+                reason: reason.clone(),
+            },
+        );
+        packet_handler_thread.disconnect(socket_id);
+
+        // the connection is closed after the packet
+        client
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let mut stream = Vec::new();
+        client.read_to_end(&mut stream).unwrap();
+        let mut in_buf = SocketBuffer::new();
+        in_buf.write_all(&stream).unwrap();
+        match in_buf.read_frame::<FromServerPacket>() {
+            Ok(FromServerPacket::Rejected { reason: received }) => assert_eq!(received, reason),
+            other => panic!("{:?}", other),
+        }
+        assert!(in_buf.eof());
+    }
+
+    #[test]
+    fn truncated_frame_is_not_consumed() {
+        let mut out_buf = SocketBuffer::new();
+        out_buf.write_frame(&welcome("sharp")).unwrap();
+        let stream = out_buf.data().to_vec();
+
+        let mut in_buf = SocketBuffer::new();
+        for i in 0..stream.len() - 1 {
+            in_buf.write_all(&stream[i..i + 1]).unwrap();
+            assert!(matches!(
+                in_buf.read_frame::<ToServerPacket>(),
+                Err(PacketReadErr::NotEnoughBytes)
+            ));
+            assert_eq!(in_buf.incoming_data_len(), i + 1);
+        }
+        in_buf.write_all(&stream[stream.len() - 1..]).unwrap();
+        assert_eq!(
+            name_of(in_buf.read_frame::<ToServerPacket>().unwrap()),
+            "sharp"
+        );
+    }
+
+    #[test]
+    fn too_long_frame_is_invalid() {
+        let mut in_buf = SocketBuffer::with_max_packet_size(1024);
+        in_buf.write_u32(1025);
+        assert!(matches!(
+            in_buf.read_frame::<ToServerPacket>(),
+            Err(PacketReadErr::InvalidValues)
+        ));
+    }
+
+    #[test]
+    fn malformed_frames_are_invalid() {
+        // not enough bytes for the packet inside a complete frame
+        let mut in_buf = SocketBuffer::new();
+        in_buf.write_u32(3);
+        in_buf.write_all(&[0, 0, 0]).unwrap();
+        assert!(matches!(
+            in_buf.read_frame::<ToServerPacket>(),
+            Err(PacketReadErr::InvalidValues)
+        ));
+
+        // unknown packet type
+        let mut in_buf = SocketBuffer::new();
+        in_buf.write_u32(4);
+        in_buf.write_u32(255);
+        assert!(matches!(
+            in_buf.read_frame::<ToServerPacket>(),
+            Err(PacketReadErr::InvalidValues)
+        ));
+
+        // trailing bytes after the packet
+        let mut out_buf = SocketBuffer::new();
+        ToServerPacket::Ping.write_into(&mut out_buf).unwrap();
+        let packet = out_buf.data().to_vec();
+        let mut in_buf = SocketBuffer::new();
+        in_buf.write_u32(packet.len() as u32 + 1);
+        in_buf.write_all(&packet).unwrap();
+        in_buf.write_u8(0);
+        assert!(matches!(
+            in_buf.read_frame::<ToServerPacket>(),
+            Err(PacketReadErr::InvalidValues)
+        ));
+    }
+
+    #[test]
+    fn random_bytes_do_not_panic() {
+        let mut random_state = 42;
+        for _ in 0..1000 {
+            let mut in_buf = SocketBuffer::with_max_packet_size(256);
+            let len = next_random(&mut random_state) % 64;
+            in_buf.write_u32(len);
+            for _ in 0..len {
+                in_buf.write_u8(next_random(&mut random_state) as u8);
+            }
+            let _ = in_buf.read_frame::<ToServerPacket>();
+        }
+    }
+
+    #[test]
+    fn huge_length_prefix_is_rejected_without_allocation() {
+        // the first string of the packet claims to be 1 TB long
+        fn frame_with_huge_string(fields_before_string: &[u8]) -> SocketBuffer {
+            let mut in_buf = SocketBuffer::new();
+            in_buf.write_u32((fields_before_string.len() + 8 + 8) as u32);
+            in_buf.write_all(fields_before_string).unwrap();
+            in_buf.write_all(&(1u64 << 40).to_le_bytes()).unwrap();
+            in_buf.write_all(b"rustarok").unwrap();
+            in_buf
+        }
+        // Welcome { protocol_version, build, .. }
+        let mut welcome_fields = 0u32.to_le_bytes().to_vec();
+        welcome_fields.extend_from_slice(&PROTOCOL_VERSION.to_le_bytes());
+        assert!(matches!(
+            frame_with_huge_string(&welcome_fields).read_frame::<ToServerPacket>(),
+            Err(PacketReadErr::InvalidValues)
+        ));
+        // Rejected { reason }
+        assert!(matches!(
+            frame_with_huge_string(&0u32.to_le_bytes()).read_frame::<FromServerPacket>(),
+            Err(PacketReadErr::InvalidValues)
+        ));
+    }
+
+    #[test]
+    fn too_big_packet_is_not_written() {
+        let mut out_buf = SocketBuffer::with_max_packet_size(16);
+        out_buf.write_frame(&ToServerPacket::Ping).unwrap();
+        let len_before = out_buf.outgoing_data_len();
+        assert!(out_buf.write_frame(&welcome(&"x".repeat(100))).is_err());
+        assert_eq!(out_buf.outgoing_data_len(), len_before);
+    }
+
+    #[test]
+    fn buffer_grows_for_big_packets() {
+        let name = "x".repeat(10_000);
+        let mut buf = SocketBuffer::new();
+        buf.write_frame(&welcome(&name)).unwrap();
+        assert_eq!(name_of(buf.read_frame::<ToServerPacket>().unwrap()), name);
+        assert!(buf.eof());
+    }
+
+    #[test]
+    fn handshake_packets_have_stable_layout() {
+        // peers of any version must be able to decode these
+        let mut buf = SocketBuffer::new();
+        welcome("sharp").write_into(&mut buf).unwrap();
+        assert_eq!(buf.read_u32().unwrap(), 0);
+        assert_eq!(buf.read_u32().unwrap(), PROTOCOL_VERSION);
+
+        let mut buf = SocketBuffer::new();
+        FromServerPacket::Rejected {
+            reason: "old client".to_owned(),
+        }
+        .write_into(&mut buf)
+        .unwrap();
+        assert_eq!(buf.read_u32().unwrap(), 0);
+    }
+
+    #[test]
+    fn typed_reads_are_bounds_checked() {
+        let mut buf = SocketBuffer::new();
+        buf.write_u16(1);
+        buf.write_f32(2.5);
+        assert_eq!(buf.read_u16().unwrap(), 1);
+        assert!(buf.read_u64().is_err());
+        assert_eq!(buf.read_f32().unwrap(), 2.5);
+        assert!(buf.read_u8().is_err());
+    }
+}
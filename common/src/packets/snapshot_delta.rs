@@ -0,0 +1,681 @@
+use crate::char_attr::CharAttributes;
+use crate::common::{GameTime, NetworkedObj, Remote, SimulationTick, Vec2};
+use crate::components::char::{CharDir, CharState, EntityId, EntityTarget, LocalCharStateComp};
+use crate::components::skills::skills::Skills;
+use crate::components::status::status::ReplicatedStatus;
+use crate::packets::from_server::ServerEntityState;
+use serde::de::{Error, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+
+/// Positions are sent as the difference to the baseline in 1/128 units,
+/// so the error is always less than `ALLOWED_F32_DIFF`
+pub const POS_QUANTIZATION: f32 = 128.0;
+
+const POS_QUANTIZED: u16 = 1 << 0;
+const POS_FULL: u16 = 1 << 1;
+const DIR: u16 = 1 << 2;
+const STATE: u16 = 1 << 3;
+const TARGET: u16 = 1 << 4;
+const CALCULATED_ATTRIBS: u16 = 1 << 5;
+const ATTACK_DELAY_ENDS_AT: u16 = 1 << 6;
+const SKILL_CAST_ALLOWED_AT: u16 = 1 << 7;
+const CANNOT_CONTROL_UNTIL: u16 = 1 << 8;
+const HP: u16 = 1 << 9;
+const STATUSES: u16 = 1 << 10;
+const FIELD_COUNT: usize = 11;
+
+#[derive(Clone, Debug, PartialEq)]
+enum PosDelta {
+    Quantized(i16, i16),
+    Full(Vec2),
+}
+
+/// The changed fields of a `LocalCharStateComp` compared to a baseline.
+/// On the wire it is a change mask followed by the changed fields only.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CharSnapshotDelta<T: 'static + NetworkedObj> {
+    pos: Option<PosDelta>,
+    dir: Option<CharDir>,
+    state: Option<CharState<T>>,
+    target: Option<Option<EntityTarget<T>>>,
+    calculated_attribs: Option<CharAttributes>,
+    attack_delay_ends_at: Option<GameTime<T>>,
+    skill_cast_allowed_at: Option<HashMap<Skills, GameTime<T>>>,
+    cannot_control_until: Option<GameTime<T>>,
+    hp: Option<i32>,
+    statuses: Option<Vec<ReplicatedStatus<T>>>,
+}
+
+impl<T: 'static + NetworkedObj> CharSnapshotDelta<T> {
+    pub fn diff(
+        baseline: &LocalCharStateComp<T>,
+        current: &LocalCharStateComp<T>,
+    ) -> CharSnapshotDelta<T> {
+        fn changed<V: PartialEq + Clone>(baseline: &V, current: &V) -> Option<V> {
+            if baseline != current {
+                Some(current.clone())
+            } else {
+                None
+            }
+        }
+        CharSnapshotDelta {
+            pos: CharSnapshotDelta::<T>::diff_pos(&baseline.pos, &current.pos),
+            dir: changed(&baseline.dir, &current.dir),
+            state: changed(&baseline.state, &current.state),
+            target: changed(&baseline.target, &current.target),
+            calculated_attribs: changed(&baseline.calculated_attribs, &current.calculated_attribs),
+            attack_delay_ends_at: changed(
+                &baseline.attack_delay_ends_at,
+                &current.attack_delay_ends_at,
+            ),
+            skill_cast_allowed_at: changed(
+                &baseline.skill_cast_allowed_at,
+                &current.skill_cast_allowed_at,
+            ),
+            cannot_control_until: changed(
+                &baseline.cannot_control_until,
+                &current.cannot_control_until,
+            ),
+            hp: changed(&baseline.hp, &current.hp),
+            statuses: changed(&baseline.statuses, &current.statuses),
+        }
+    }
+
+    fn diff_pos(baseline: &Vec2, current: &Vec2) -> Option<PosDelta> {
+        let dx = ((current.x - baseline.x) * POS_QUANTIZATION).round();
+        let dy = ((current.y - baseline.y) * POS_QUANTIZATION).round();
+        if dx == 0.0 && dy == 0.0 {
+            None
+        } else if dx.abs() <= i16::MAX as f32 && dy.abs() <= i16::MAX as f32 {
+            Some(PosDelta::Quantized(dx as i16, dy as i16))
+        } else {
+            Some(PosDelta::Full(*current))
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changed_fields() == 0
+    }
+
+    pub fn apply(&self, baseline: &LocalCharStateComp<T>) -> LocalCharStateComp<T> {
+        let mut result = baseline.clone();
+        match &self.pos {
+            Some(PosDelta::Quantized(dx, dy)) => {
+                result.pos.x += *dx as f32 / POS_QUANTIZATION;
+                result.pos.y += *dy as f32 / POS_QUANTIZATION;
+            }
+            Some(PosDelta::Full(pos)) => result.pos = *pos,
+            None => {}
+        }
+        if let Some(dir) = self.dir {
+            result.dir = dir;
+        }
+        if let Some(state) = &self.state {
+            result.state = state.clone();
+        }
+        if let Some(target) = &self.target {
+            result.target = target.clone();
+        }
+        if let Some(calculated_attribs) = &self.calculated_attribs {
+            result.calculated_attribs = calculated_attribs.clone();
+        }
+        if let Some(attack_delay_ends_at) = self.attack_delay_ends_at {
+            result.attack_delay_ends_at = attack_delay_ends_at;
+        }
+        if let Some(skill_cast_allowed_at) = &self.skill_cast_allowed_at {
+            result.skill_cast_allowed_at = skill_cast_allowed_at.clone();
+        }
+        if let Some(cannot_control_until) = self.cannot_control_until {
+            result.cannot_control_until = cannot_control_until;
+        }
+        if let Some(hp) = self.hp {
+            result.hp = hp;
+        }
+        if let Some(statuses) = &self.statuses {
+            result.statuses = statuses.clone();
+        }
+        result
+    }
+
+    fn changed_fields(&self) -> u16 {
+        let mut mask = 0;
+        mask |= match self.pos {
+            Some(PosDelta::Quantized(..)) => POS_QUANTIZED,
+            Some(PosDelta::Full(..)) => POS_FULL,
+            None => 0,
+        };
+        let flags = [
+            (self.dir.is_some(), DIR),
+            (self.state.is_some(), STATE),
+            (self.target.is_some(), TARGET),
+            (self.calculated_attribs.is_some(), CALCULATED_ATTRIBS),
+            (self.attack_delay_ends_at.is_some(), ATTACK_DELAY_ENDS_AT),
+            (self.skill_cast_allowed_at.is_some(), SKILL_CAST_ALLOWED_AT),
+            (self.cannot_control_until.is_some(), CANNOT_CONTROL_UNTIL),
+            (self.hp.is_some(), HP),
+            (self.statuses.is_some(), STATUSES),
+        ];
+        for (is_changed, flag) in flags.iter() {
+            if *is_changed {
+                mask |= flag;
+            }
+        }
+        mask
+    }
+}
+
+impl<T: 'static + NetworkedObj + Serialize> Serialize for CharSnapshotDelta<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mask = self.changed_fields();
+        // the length is not written by bincode, the mask tells the reader which fields follow
+        let mut tuple = serializer.serialize_tuple(1 + mask.count_ones() as usize)?;
+        tuple.serialize_element(&mask)?;
+        match &self.pos {
+            Some(PosDelta::Quantized(dx, dy)) => tuple.serialize_element(&(dx, dy))?,
+            Some(PosDelta::Full(pos)) => tuple.serialize_element(&(pos.x, pos.y))?,
+            None => {}
+        }
+        if let Some(dir) = &self.dir {
+            tuple.serialize_element(dir)?;
+        }
+        if let Some(state) = &self.state {
+            tuple.serialize_element(state)?;
+        }
+        if let Some(target) = &self.target {
+            tuple.serialize_element(target)?;
+        }
+        if let Some(calculated_attribs) = &self.calculated_attribs {
+            tuple.serialize_element(calculated_attribs)?;
+        }
+        if let Some(attack_delay_ends_at) = &self.attack_delay_ends_at {
+            tuple.serialize_element(attack_delay_ends_at)?;
+        }
+        if let Some(skill_cast_allowed_at) = &self.skill_cast_allowed_at {
+            tuple.serialize_element(skill_cast_allowed_at)?;
+        }
+        if let Some(cannot_control_until) = &self.cannot_control_until {
+            tuple.serialize_element(cannot_control_until)?;
+        }
+        if let Some(hp) = &self.hp {
+            tuple.serialize_element(hp)?;
+        }
+        if let Some(statuses) = &self.statuses {
+            tuple.serialize_element(statuses)?;
+        }
+        tuple.end()
+    }
+}
+
+struct CharSnapshotDeltaVisitor<T>(PhantomData<T>);
+
+impl<'de, T: 'static + NetworkedObj + Deserialize<'de>> Visitor<'de>
+    for CharSnapshotDeltaVisitor<T>
+{
+    type Value = CharSnapshotDelta<T>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a change mask followed by the changed fields")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        fn next_if<'de, A: SeqAccess<'de>, V: Deserialize<'de>>(
+            seq: &mut A,
+            mask: u16,
+            flag: u16,
+        ) -> Result<Option<V>, A::Error> {
+            if mask & flag == 0 {
+                return Ok(None);
+            }
+            seq.next_element()?
+                .map(Some)
+                .ok_or_else(|| A::Error::custom("missing changed field"))
+        }
+        let mask: u16 = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::custom("missing change mask"))?;
+        let pos = if let Some((dx, dy)) = next_if::<A, (i16, i16)>(&mut seq, mask, POS_QUANTIZED)? {
+            Some(PosDelta::Quantized(dx, dy))
+        } else if let Some((x, y)) = next_if::<A, (f32, f32)>(&mut seq, mask, POS_FULL)? {
+            Some(PosDelta::Full(Vec2::new(x, y)))
+        } else {
+            None
+        };
+        Ok(CharSnapshotDelta {
+            pos,
+            dir: next_if(&mut seq, mask, DIR)?,
+            state: next_if(&mut seq, mask, STATE)?,
+            target: next_if(&mut seq, mask, TARGET)?,
+            calculated_attribs: next_if(&mut seq, mask, CALCULATED_ATTRIBS)?,
+            attack_delay_ends_at: next_if(&mut seq, mask, ATTACK_DELAY_ENDS_AT)?,
+            skill_cast_allowed_at: next_if(&mut seq, mask, SKILL_CAST_ALLOWED_AT)?,
+            cannot_control_until: next_if(&mut seq, mask, CANNOT_CONTROL_UNTIL)?,
+            hp: next_if(&mut seq, mask, HP)?,
+            statuses: next_if(&mut seq, mask, STATUSES)?,
+        })
+    }
+}
+
+impl<'de, T: 'static + NetworkedObj + Deserialize<'de>> Deserialize<'de> for CharSnapshotDelta<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(1 + FIELD_COUNT, CharSnapshotDeltaVisitor(PhantomData))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServerEntityDelta<T: 'static + NetworkedObj> {
+    pub id: EntityId<Remote>,
+    pub delta: CharSnapshotDelta<T>,
+}
+
+/// The content of a snapshot packet, encoded against a snapshot the client has already acknowledged.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SnapshotDelta<T: 'static + NetworkedObj> {
+    pub tick: SimulationTick,
+    /// None means that the entries are encoded against the default state
+    pub baseline_tick: Option<SimulationTick>,
+    /// only the changed or new entities are listed
+    pub entries: Vec<ServerEntityDelta<T>>,
+    pub removed: Vec<EntityId<Remote>>,
+}
+
+impl<T: 'static + NetworkedObj> SnapshotDelta<T> {
+    /// Returns the delta and the states as the client will see them after applying it
+    /// (positions are quantized), the latter must be stored as the baseline for later deltas.
+    pub fn encode(
+        tick: SimulationTick,
+        baseline: Option<(SimulationTick, &[ServerEntityState<T>])>,
+        current: Vec<ServerEntityState<T>>,
+    ) -> (SnapshotDelta<T>, Vec<ServerEntityState<T>>) {
+        // an entity which has left the view and came back since the baseline would be decoded
+        // at its old place, in that case the snapshot is sent in full
+        let baseline = baseline.filter(|(_tick, states)| is_order_kept(states, &current));
+        let default_state = LocalCharStateComp::default();
+        let baseline_states = baseline.map(|it| it.1).unwrap_or(&[]);
+        let mut baseline_by_id: HashMap<EntityId<Remote>, &LocalCharStateComp<T>> = baseline_states
+            .iter()
+            .map(|it| (it.id, &it.char_snapshot))
+            .collect();
+        let mut entries = Vec::with_capacity(current.len());
+        let mut reconstructed = Vec::with_capacity(current.len());
+        for current_state in current {
+            // what remains in the map at the end has been removed
+            let baseline_state = baseline_by_id.remove(&current_state.id);
+            let is_new = baseline_state.is_none();
+            let baseline_state = baseline_state.unwrap_or(&default_state);
+            let delta = CharSnapshotDelta::diff(baseline_state, &current_state.char_snapshot);
+            reconstructed.push(ServerEntityState {
+                id: current_state.id,
+                char_snapshot: delta.apply(baseline_state),
+            });
+            if is_new || !delta.is_empty() {
+                entries.push(ServerEntityDelta {
+                    id: current_state.id,
+                    delta,
+                });
+            }
+        }
+        let removed = baseline_states
+            .iter()
+            .filter(|base| baseline_by_id.contains_key(&base.id))
+            .map(|it| it.id)
+            .collect();
+        (
+            SnapshotDelta {
+                tick,
+                baseline_tick: baseline.map(|it| it.0),
+                entries,
+                removed,
+            },
+            reconstructed,
+        )
+    }
+
+    /// The order of the result is the order of the baseline, the new entities are appended at its end.
+    pub fn decode(
+        &self,
+        baseline: Option<&[ServerEntityState<T>]>,
+    ) -> Result<Vec<ServerEntityState<T>>, String> {
+        let baseline = match (self.baseline_tick, baseline) {
+            (None, _) => &[],
+            (Some(_), Some(baseline)) => baseline,
+            (Some(tick), None) => {
+                return Err(format!("Baseline snapshot is missing: {:?}", tick));
+            }
+        };
+        let removed: HashSet<EntityId<Remote>> = self.removed.iter().copied().collect();
+        let mut result: Vec<ServerEntityState<T>> = baseline
+            .iter()
+            .filter(|it| !removed.contains(&it.id))
+            .cloned()
+            .collect();
+        let mut index_by_id: HashMap<EntityId<Remote>, usize> = result
+            .iter()
+            .enumerate()
+            .map(|(i, it)| (it.id, i))
+            .collect();
+        let default_state = LocalCharStateComp::default();
+        for entry in &self.entries {
+            if let Some(&i) = index_by_id.get(&entry.id) {
+                let existing = &mut result[i];
+                existing.char_snapshot = entry.delta.apply(&existing.char_snapshot);
+            } else {
+                index_by_id.insert(entry.id, result.len());
+                result.push(ServerEntityState {
+                    id: entry.id,
+                    char_snapshot: entry.delta.apply(&default_state),
+                });
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// `decode` keeps the order of the baseline and appends the new entities at its end,
+/// returns true if this results in the order of `current`
+fn is_order_kept<T: 'static + NetworkedObj>(
+    baseline: &[ServerEntityState<T>],
+    current: &[ServerEntityState<T>],
+) -> bool {
+    let baseline_index_by_id: HashMap<EntityId<Remote>, usize> = baseline
+        .iter()
+        .enumerate()
+        .map(|(i, it)| (it.id, i))
+        .collect();
+    let mut prev_index = None;
+    let mut new_entity_found = false;
+    for state in current {
+        match baseline_index_by_id.get(&state.id) {
+            Some(&index) => {
+                if new_entity_found || prev_index.map(|prev| prev > index).unwrap_or(false) {
+                    return false;
+                }
+                prev_index = Some(index);
+            }
+            None => new_entity_found = true,
+        }
+    }
+    true
+}
+
+/// The last few snapshots which were sent to (or received from) the server, deltas are
+/// encoded against them.
+pub struct SnapshotHistory<T: 'static + NetworkedObj> {
+    snapshots: VecDeque<(SimulationTick, Vec<ServerEntityState<T>>)>,
+}
+
+impl<T: 'static + NetworkedObj> SnapshotHistory<T> {
+    // 1 sec on 30 fps
+    const CAPACITY: usize = 32;
+
+    pub fn new() -> SnapshotHistory<T> {
+        SnapshotHistory {
+            snapshots: VecDeque::with_capacity(SnapshotHistory::<T>::CAPACITY),
+        }
+    }
+
+    pub fn push(&mut self, tick: SimulationTick, states: Vec<ServerEntityState<T>>) {
+        if self.snapshots.len() == SnapshotHistory::<T>::CAPACITY {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back((tick, states));
+    }
+
+    pub fn get(&self, tick: SimulationTick) -> Option<&[ServerEntityState<T>]> {
+        self.snapshots
+            .iter()
+            .find(|it| it.0 == tick)
+            .map(|it| it.1.as_slice())
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::attack::BasicAttackType;
+    use crate::common::{float_cmp, v2, GameTime, Local, SimulationTick};
+    use crate::components::char::{
+        CastingSkillData, CharDir, CharState, EntityId, EntityTarget, LocalCharStateComp,
+    };
+    use crate::components::skills::skills::Skills;
+    use crate::components::status::status::{ReplicatedStatus, StatusEnumDiscriminants};
+    use crate::packets::from_server::ServerEntityState;
+    use crate::packets::snapshot_delta::{SnapshotDelta, POS_QUANTIZATION};
+    use specs::prelude::*;
+
+    fn create_ids(count: usize) -> Vec<EntityId<Local>> {
+        let mut world = World::new();
+        (0..count)
+            .map(|_| EntityId::new(world.create_entity().build()))
+            .collect()
+    }
+
+    fn entity(id: EntityId<Local>, x: f32, y: f32) -> ServerEntityState<Local> {
+        ServerEntityState {
+            id: id.as_remote(),
+            char_snapshot: LocalCharStateComp::new(v2(x, y), Default::default()),
+        }
+    }
+
+    fn send_and_receive(
+        baseline: Option<&[ServerEntityState<Local>]>,
+        current: Vec<ServerEntityState<Local>>,
+    ) -> (
+        Vec<u8>,
+        Vec<ServerEntityState<Local>>,
+        Vec<ServerEntityState<Local>>,
+    ) {
+        let (delta, reconstructed_on_server) = SnapshotDelta::encode(
+            SimulationTick::new(),
+            baseline.map(|it| (SimulationTick::new(), it)),
+            current,
+        );
+        let bytes = bincode::serialize(&delta).unwrap();
+        let received: SnapshotDelta<Local> = bincode::deserialize(&bytes).unwrap();
+        let decoded = received.decode(baseline).unwrap();
+        (bytes, decoded, reconstructed_on_server)
+    }
+
+    fn assert_states_eq(
+        expected: &[ServerEntityState<Local>],
+        actual: &[ServerEntityState<Local>],
+    ) {
+        assert_eq!(expected.len(), actual.len());
+        for (expected, actual) in expected.iter().zip(actual.iter()) {
+            assert_eq!(expected.id, actual.id);
+            let (expected, actual) = (&expected.char_snapshot, &actual.char_snapshot);
+            assert!(float_cmp(expected.pos.x, actual.pos.x));
+            assert!(float_cmp(expected.pos.y, actual.pos.y));
+            assert_eq!(expected.dir, actual.dir);
+            assert_eq!(expected.state, actual.state);
+            assert_eq!(expected.target, actual.target);
+            assert_eq!(expected.calculated_attribs, actual.calculated_attribs);
+            assert_eq!(expected.attack_delay_ends_at, actual.attack_delay_ends_at);
+            assert_eq!(expected.skill_cast_allowed_at, actual.skill_cast_allowed_at);
+            assert_eq!(expected.cannot_control_until, actual.cannot_control_until);
+            assert_eq!(expected.hp, actual.hp);
+            assert_eq!(expected.statuses, actual.statuses);
+        }
+    }
+
+    #[test]
+    fn full_snapshot_without_baseline() {
+        let ids = create_ids(2);
+        let current = vec![entity(ids[0], 238.0, -65.0), entity(ids[1], 10.5, 20.25)];
+
+        let (_bytes, decoded, reconstructed) = send_and_receive(None, current.clone());
+
+        assert_states_eq(&current, &decoded);
+        assert_states_eq(&reconstructed, &decoded);
+    }
+
+    #[test]
+    fn applying_delta_to_baseline_gives_the_full_state() {
+        let ids = create_ids(2);
+        let baseline = vec![entity(ids[0], 238.0, -65.0), entity(ids[1], 10.0, 20.0)];
+        let mut current = baseline.clone();
+        {
+            let state = &mut current[0].char_snapshot;
+            state.set_pos(v2(238.37, -65.11));
+            state.set_state_and_dir(
+                CharState::CastingSkill(CastingSkillData {
+                    target_area_pos: Some(v2(240.0, -60.0)),
+                    char_to_skill_dir_when_casted: v2(0.0, 1.0),
+                    target_entity: Some(ids[1]),
+                    cast_started: GameTime::from(1000u32),
+                    cast_ends: GameTime::from(1500u32),
+                    can_move: false,
+                    skill: Skills::FireWall,
+                }),
+                CharDir::North,
+            );
+            state
+                .skill_cast_allowed_at
+                .insert(Skills::FireWall, GameTime::from(4321u32));
+            state.hp -= 50;
+        }
+        {
+            let state = &mut current[1].char_snapshot;
+            state.target = Some(EntityTarget::OtherEntity(ids[0]));
+            state.set_state(CharState::Attacking {
+                target: ids[0],
+                damage_occurs_at: GameTime::from(1234u32),
+                basic_attack: BasicAttackType::MeleeSimple,
+            });
+            state.attack_delay_ends_at = GameTime::from(2000u32);
+            state.statuses = vec![
+                ReplicatedStatus {
+                    typ: StatusEnumDiscriminants::MountedStatus,
+                    until: None,
+                },
+                ReplicatedStatus {
+                    typ: StatusEnumDiscriminants::StunStatus,
+                    until: Some(GameTime::from(3000u32)),
+                },
+            ];
+        }
+
+        let (_bytes, decoded, reconstructed) = send_and_receive(Some(&baseline), current.clone());
+
+        assert_states_eq(&current, &decoded);
+        assert_states_eq(&reconstructed, &decoded);
+    }
+
+    #[test]
+    fn unchanged_entities_are_not_sent() {
+        let ids = create_ids(3);
+        let baseline = vec![
+            entity(ids[0], 1.0, 1.0),
+            entity(ids[1], 2.0, 2.0),
+            entity(ids[2], 3.0, 3.0),
+        ];
+        let mut current = baseline.clone();
+        current[1].char_snapshot.hp = 1;
+
+        let (delta, _reconstructed) = SnapshotDelta::encode(
+            SimulationTick::new(),
+            Some((SimulationTick::new(), &baseline)),
+            current.clone(),
+        );
+
+        assert_eq!(1, delta.entries.len());
+        assert_eq!(current[1].id, delta.entries[0].id);
+        assert_states_eq(&current, &delta.decode(Some(&baseline)).unwrap());
+    }
+
+    #[test]
+    fn removed_and_new_entities() {
+        let ids = create_ids(3);
+        let baseline = vec![entity(ids[0], 1.0, 1.0), entity(ids[1], 2.0, 2.0)];
+        let current = vec![entity(ids[0], 1.0, 1.0), entity(ids[2], 3.0, 3.0)];
+
+        let (delta, _reconstructed) = SnapshotDelta::encode(
+            SimulationTick::new(),
+            Some((SimulationTick::new(), &baseline)),
+            current.clone(),
+        );
+
+        assert_eq!(vec![baseline[1].id], delta.removed);
+        assert_states_eq(&current, &delta.decode(Some(&baseline)).unwrap());
+    }
+
+    #[test]
+    fn reappeared_entity_keeps_the_current_order() {
+        let ids = create_ids(3);
+        let baseline = vec![
+            entity(ids[0], 1.0, 1.0),
+            entity(ids[1], 2.0, 2.0),
+            entity(ids[2], 3.0, 3.0),
+        ];
+        // ids[0] has left the view and came back, so it is at the end of the visible list
+        let current = vec![
+            entity(ids[1], 2.0, 2.0),
+            entity(ids[2], 3.0, 3.0),
+            entity(ids[0], 1.0, 1.0),
+        ];
+
+        let (delta, _reconstructed) = SnapshotDelta::encode(
+            SimulationTick::new(),
+            Some((SimulationTick::new(), &baseline)),
+            current.clone(),
+        );
+
+        assert_eq!(None, delta.baseline_tick);
+        assert_states_eq(&current, &delta.decode(Some(&baseline)).unwrap());
+    }
+
+    #[test]
+    fn missing_baseline_is_an_error() {
+        let ids = create_ids(1);
+        let baseline = vec![entity(ids[0], 1.0, 1.0)];
+        let (delta, _reconstructed) = SnapshotDelta::encode(
+            SimulationTick::new(),
+            Some((SimulationTick::new(), &baseline)),
+            baseline.clone(),
+        );
+
+        assert!(delta.decode(None).is_err());
+    }
+
+    #[test]
+    fn quantization_error_does_not_accumulate() {
+        let ids = create_ids(1);
+        let mut baseline = vec![entity(ids[0], 0.0, 0.0)];
+        let mut current = baseline.clone();
+        for _ in 0..1000 {
+            current[0].char_snapshot.add_pos(v2(0.0123, -0.0077));
+            let (delta, reconstructed) = SnapshotDelta::encode(
+                SimulationTick::new(),
+                Some((SimulationTick::new(), &baseline)),
+                current.clone(),
+            );
+            let decoded = delta.decode(Some(&baseline)).unwrap();
+            assert_states_eq(&reconstructed, &decoded);
+            baseline = decoded;
+        }
+        let max_error = 0.5 / POS_QUANTIZATION + 0.0001;
+        let pos = baseline[0].char_snapshot.pos();
+        assert!((pos.x - current[0].char_snapshot.pos().x).abs() < max_error);
+        assert!((pos.y - current[0].char_snapshot.pos().y).abs() < max_error);
+    }
+
+    #[test]
+    fn unchanged_snapshot_is_small() {
+        let ids = create_ids(50);
+        let baseline: Vec<ServerEntityState<Local>> =
+            ids.iter().map(|id| entity(*id, 100.0, -100.0)).collect();
+
+        let (full_bytes, _decoded, _reconstructed) = send_and_receive(None, baseline.clone());
+        let (delta_bytes, decoded, _reconstructed) =
+            send_and_receive(Some(&baseline), baseline.clone());
+
+        assert_states_eq(&baseline, &decoded);
+        assert!(delta_bytes.len() * 10 < full_bytes.len());
+    }
+}
@@ -1,22 +1,32 @@
 use crate::common::{Remote, SimulationTick};
+use crate::components::char::{JobId, Team};
 use crate::components::controller::PlayerIntention;
 use crate::console::CommandArguments;
-use crate::packets::SocketBuffer;
+use crate::packets::{Delivery, SocketBuffer};
 use serde::Deserialize;
 use serde::Serialize;
-use std::io::Error;
 use strum_macros::EnumCount;
 use strum_macros::EnumDiscriminants;
 
 pub trait Packet: Sized {
     fn write_into(&self, buf: &mut SocketBuffer) -> bincode::Result<()>;
     fn read_from(buf: &mut SocketBuffer) -> Result<Self, PacketReadErr>;
+    fn delivery(&self) -> Delivery {
+        Delivery::ReliableOrdered
+    }
 }
 
 #[derive(Debug, EnumDiscriminants, EnumCount, Serialize, Deserialize)]
 pub enum ToServerPacket {
+    /// It must remain the first variant with the protocol version as its first field,
+    /// so servers of any version can decode it
     Welcome {
+        protocol_version: u32,
+        build: String,
         name: String,
+        /// Issued by the server in `Init`, the client re-attaches to its character with it
+        /// after a lost connection
+        session_token: Option<u64>,
         //        job: JobId
     },
     Ping,
@@ -27,6 +37,18 @@ pub enum ToServerPacket {
         intention: PlayerIntention<Remote>,
     },
     ConsoleCommand(CommandArguments),
+    /// The client stores the snapshot of this tick, later snapshots can be encoded against it
+    SnapshotReceived {
+        tick: SimulationTick,
+    },
+    /// Only allowed in the lobby, the job must be a playable one
+    LobbyChoice {
+        name: String,
+        team: Team,
+        job_id: JobId,
+    },
+    /// The match starts when every player in the lobby is ready
+    ReadyCheck(bool),
 }
 
 #[derive(Debug)]
@@ -35,7 +57,28 @@ pub enum PacketReadErr {
     InvalidValues,
 }
 
+impl From<bincode::Error> for PacketReadErr {
+    fn from(e: bincode::Error) -> Self {
+        match *e {
+            bincode::ErrorKind::Io(ref io_err)
+                if io_err.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                PacketReadErr::NotEnoughBytes
+            }
+            _ => PacketReadErr::InvalidValues,
+        }
+    }
+}
+
 impl Packet for ToServerPacket {
+    fn delivery(&self) -> Delivery {
+        match self {
+            // the next one acknowledges a newer snapshot anyway
+            ToServerPacket::SnapshotReceived { .. } => Delivery::Unreliable,
+            _ => Delivery::ReliableOrdered,
+        }
+    }
+
     fn write_into(&self, buf: &mut SocketBuffer) -> bincode::Result<()> {
         bincode::serialize_into(buf, self)
         //        let discr: ToServerPacketDiscriminants = self.into();
@@ -72,10 +115,7 @@ impl Packet for ToServerPacket {
         //        };
     }
     fn read_from(buf: &mut SocketBuffer) -> Result<ToServerPacket, PacketReadErr> {
-        return match bincode::deserialize_from(buf) {
-            Ok(packet) => Ok(packet),
-            Err(_e) => Err(PacketReadErr::NotEnoughBytes),
-        };
+        buf.read_bincode()
         //        let packet_id = buf.read_u8() - 1;
         //        if packet_id >= ToServerPacket::count() as u8 {
         //            return Err(PacketReadErr::InvalidValues);
@@ -0,0 +1,1047 @@
+use crate::packets::to_server::{Packet, PacketReadErr};
+use crate::packets::{
+    Delivery, NetworkTrafficEvent, NetworkTrafficHandlerMsg, SocketBuffer, SocketId,
+};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::BuildHasher;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+// Every datagram starts with the following header:
+// protocol id: u16, sequence: u16, ack: u16, ack bits: u32, channel: u8
+// Reliable datagrams continue with the reliable message id (u16), then the packet itself follows.
+// The ack is the latest sequence received from the peer, the nth bit of the ack bits means
+// that (ack - n - 1) has also been received.
+//
+// Before that, the client has to prove that it receives the datagrams sent to its address,
+// otherwise anyone could occupy the peer slots of the server with spoofed datagrams.
+// The handshake datagrams are: handshake id: u16, kind: u8, cookie: u64
+// 1. client: CONNECT (cookie is 0)
+// 2. server: CHALLENGE with a cookie derived from the address of the client, no state is kept
+// 3. client: RESPONSE with the cookie
+// 4. server: allocates the peer slot and sends ACCEPTED
+// Requests and replies have the same size, so the server cannot be used for amplification.
+const PROTOCOL_ID: u16 = 0x5241;
+const HANDSHAKE_ID: u16 = 0x5248;
+const HANDSHAKE_CONNECT: u8 = 0;
+const HANDSHAKE_CHALLENGE: u8 = 1;
+const HANDSHAKE_RESPONSE: u8 = 2;
+const HANDSHAKE_ACCEPTED: u8 = 3;
+const HANDSHAKE_DATAGRAM_SIZE: usize = 11;
+const CHANNEL_UNRELIABLE: u8 = 0;
+const CHANNEL_RELIABLE_ORDERED: u8 = 1;
+const CHANNEL_ACK_ONLY: u8 = 2;
+// set in the channel byte if the ack fields are valid, i.e. the sender has already received a datagram
+const ACK_IS_VALID_FLAG: u8 = 0x80;
+
+pub const MAX_DATAGRAM_SIZE: usize = 1400;
+// anything bigger would be truncated silently by the OS
+const MAX_UDP_PAYLOAD_SIZE: usize = 65507;
+const RESEND_AFTER: Duration = Duration::from_millis(100);
+const SEND_ACK_AFTER: Duration = Duration::from_millis(50);
+const DISCONNECT_AFTER: Duration = Duration::from_secs(5);
+// datagrams from new addresses are dropped above this
+const MAX_PEERS: usize = 64;
+// reliable messages arriving too far ahead are dropped, the sender resends them later
+const MAX_OUT_OF_ORDER: u16 = 64;
+// the peer is disconnected above this, it does not ack the reliable messages
+const MAX_UNACKED: usize = 1024;
+
+/// Wrapping comparison, so 0 is greater than 65535
+fn sequence_greater_than(s1: u16, s2: u16) -> bool {
+    ((s1 > s2) && (s1 - s2 <= 32768)) || ((s1 < s2) && (s2 - s1 > 32768))
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Handshake {
+    Connecting,
+    Challenged(u64),
+    Established,
+}
+
+struct PendingReliable {
+    id: u16,
+    payload: Vec<u8>,
+    // the sequences of the datagrams this message was sent in, any of them can be acked
+    sent_in: Vec<u16>,
+    last_sent_at: Instant,
+}
+
+/// The reliability layer for a single peer. It does not own the socket,
+/// the datagrams are written into and read from `SocketBuffer`s.
+pub(super) struct UdpConnection<I> {
+    addr: SocketAddr,
+    local_seq: u16,
+    remote_seq: Option<u16>,
+    received_bits: u32,
+    next_reliable_id: u16,
+    expected_reliable_id: u16,
+    unacked: Vec<PendingReliable>,
+    out_of_order: HashMap<u16, I>,
+    last_received_at: Instant,
+    last_sent_at: Instant,
+    ack_pending: bool,
+    handshake: Handshake,
+    handshake_sent_at: Option<Instant>,
+}
+
+impl<I: Packet> UdpConnection<I> {
+    /// The connection of the server to a client which has completed the handshake
+    pub fn new(addr: SocketAddr, now: Instant) -> UdpConnection<I> {
+        UdpConnection {
+            addr,
+            local_seq: 0,
+            remote_seq: None,
+            received_bits: 0,
+            next_reliable_id: 0,
+            expected_reliable_id: 0,
+            unacked: Vec::with_capacity(32),
+            out_of_order: HashMap::with_capacity(32),
+            last_received_at: now,
+            last_sent_at: now,
+            ack_pending: false,
+            handshake: Handshake::Established,
+            handshake_sent_at: None,
+        }
+    }
+
+    /// The connection of a client to the server, the handshake is initiated by the client
+    pub fn connecting(addr: SocketAddr, now: Instant) -> UdpConnection<I> {
+        UdpConnection {
+            handshake: Handshake::Connecting,
+            ..UdpConnection::new(addr, now)
+        }
+    }
+
+    pub fn is_established(&self) -> bool {
+        self.handshake == Handshake::Established
+    }
+
+    /// The peer does not ack the reliable messages, e.g. it has gone without disconnecting
+    pub fn has_too_many_unacked(&self) -> bool {
+        self.unacked.len() > MAX_UNACKED
+    }
+
+    /// The client repeats its last handshake datagram until the server accepts it
+    pub fn write_handshake_if_needed(&mut self, now: Instant, buf: &mut SocketBuffer) -> bool {
+        let (kind, cookie) = match self.handshake {
+            Handshake::Connecting => (HANDSHAKE_CONNECT, 0),
+            Handshake::Challenged(cookie) => (HANDSHAKE_RESPONSE, cookie),
+            Handshake::Established => return false,
+        };
+        let is_due = self
+            .handshake_sent_at
+            .map(|sent_at| now.duration_since(sent_at) >= RESEND_AFTER)
+            .unwrap_or(true);
+        if is_due {
+            self.handshake_sent_at = Some(now);
+            write_handshake(kind, cookie, buf);
+        }
+        is_due
+    }
+
+    /// Returns the reply to the handshake datagram of the peer
+    fn read_handshake(&mut self, kind: u8, cookie: u64, now: Instant) -> Option<(u8, u64)> {
+        match (kind, self.handshake) {
+            (HANDSHAKE_CHALLENGE, Handshake::Connecting) => {
+                self.last_received_at = now;
+                self.handshake = Handshake::Challenged(cookie);
+                self.handshake_sent_at = Some(now);
+                Some((HANDSHAKE_RESPONSE, cookie))
+            }
+            (HANDSHAKE_ACCEPTED, _) => {
+                self.last_received_at = now;
+                self.handshake = Handshake::Established;
+                None
+            }
+            // the previous ACCEPTED has been lost
+            (HANDSHAKE_RESPONSE, Handshake::Established) => Some((HANDSHAKE_ACCEPTED, 0)),
+            _ => None,
+        }
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn is_timed_out(&self, now: Instant) -> bool {
+        now.duration_since(self.last_received_at) > DISCONNECT_AFTER
+    }
+
+    fn write_header(&mut self, channel: u8, now: Instant, buf: &mut SocketBuffer) -> u16 {
+        let seq = self.local_seq;
+        self.local_seq = self.local_seq.wrapping_add(1);
+        self.last_sent_at = now;
+        self.ack_pending = false;
+
+        buf.reset();
+        let (ack, ack_bits, flag) = match self.remote_seq {
+            Some(remote_seq) => (remote_seq, self.received_bits, ACK_IS_VALID_FLAG),
+            None => (0, 0, 0),
+        };
+        // writing into the SocketBuffer cannot fail
+        let _ = buf.write_all(&PROTOCOL_ID.to_le_bytes());
+        let _ = buf.write_all(&seq.to_le_bytes());
+        let _ = buf.write_all(&ack.to_le_bytes());
+        let _ = buf.write_all(&ack_bits.to_le_bytes());
+        let _ = buf.write_all(&[channel | flag]);
+        return seq;
+    }
+
+    pub fn write_packet<O: Packet>(
+        &mut self,
+        packet: &O,
+        now: Instant,
+        buf: &mut SocketBuffer,
+    ) -> bincode::Result<()> {
+        // serialized first, so a packet which cannot be sent does not consume
+        // a sequence or a reliable id, the peer would wait for them forever
+        buf.reset();
+        packet.write_into(buf)?;
+        if buf.data_len() > buf.max_packet_size {
+            buf.reset();
+            return Err(Box::new(bincode::ErrorKind::SizeLimit));
+        }
+        let payload = buf.data().to_vec();
+        match packet.delivery() {
+            Delivery::Unreliable => {
+                self.write_header(CHANNEL_UNRELIABLE, now, buf);
+                let _ = buf.write_all(&payload);
+            }
+            Delivery::ReliableOrdered => {
+                let seq = self.write_header(CHANNEL_RELIABLE_ORDERED, now, buf);
+                let id = self.next_reliable_id;
+                self.next_reliable_id = self.next_reliable_id.wrapping_add(1);
+                let _ = buf.write_all(&id.to_le_bytes());
+                let _ = buf.write_all(&payload);
+                self.unacked.push(PendingReliable {
+                    id,
+                    payload,
+                    sent_in: vec![seq],
+                    last_sent_at: now,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the oldest reliable message which has not been acked in time.
+    /// Returns false if there is nothing to resend.
+    pub fn write_next_resend(&mut self, now: Instant, buf: &mut SocketBuffer) -> bool {
+        if !self.is_established() {
+            return false;
+        }
+        let index = match self
+            .unacked
+            .iter()
+            .position(|it| now.duration_since(it.last_sent_at) >= RESEND_AFTER)
+        {
+            Some(index) => index,
+            None => return false,
+        };
+        let seq = self.write_header(CHANNEL_RELIABLE_ORDERED, now, buf);
+        let pending = &mut self.unacked[index];
+        pending.sent_in.push(seq);
+        pending.last_sent_at = now;
+        let _ = buf.write_all(&pending.id.to_le_bytes());
+        let _ = buf.write_all(&pending.payload);
+        return true;
+    }
+
+    /// If nothing has been sent to the peer for a while, the received datagrams
+    /// are acked by an empty datagram
+    pub fn write_ack_if_needed(&mut self, now: Instant, buf: &mut SocketBuffer) -> bool {
+        if self.ack_pending && now.duration_since(self.last_sent_at) >= SEND_ACK_AFTER {
+            self.write_header(CHANNEL_ACK_ONLY, now, buf);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns false if the datagram had already been received
+    fn mark_received(&mut self, seq: u16) -> bool {
+        let remote_seq = match self.remote_seq {
+            Some(remote_seq) => remote_seq,
+            None => {
+                self.remote_seq = Some(seq);
+                return true;
+            }
+        };
+        if seq == remote_seq {
+            return false;
+        } else if sequence_greater_than(seq, remote_seq) {
+            let shift = seq.wrapping_sub(remote_seq) as u64;
+            self.received_bits = if shift <= 32 {
+                ((self.received_bits as u64) << shift | 1 << (shift - 1)) as u32
+            } else {
+                0
+            };
+            self.remote_seq = Some(seq);
+            return true;
+        } else {
+            let diff = remote_seq.wrapping_sub(seq) as u32;
+            if diff > 32 {
+                // too old to tell, unreliable data is outdated anyway and
+                // reliable messages are filtered by their ids
+                return false;
+            }
+            let bit = 1 << (diff - 1);
+            let already_received = self.received_bits & bit != 0;
+            self.received_bits |= bit;
+            return !already_received;
+        }
+    }
+
+    fn process_ack(&mut self, ack: u16, ack_bits: u32) {
+        let is_acked = |seq: u16| {
+            if seq == ack {
+                return true;
+            }
+            let diff = ack.wrapping_sub(seq) as u32;
+            (1..=32).contains(&diff) && (ack_bits & (1 << (diff - 1))) != 0
+        };
+        self.unacked
+            .retain(|pending| !pending.sent_in.iter().any(|seq| is_acked(*seq)));
+    }
+
+    /// The buffer must contain exactly one datagram.
+    /// The packets which can be delivered to the application are pushed into `out`.
+    pub fn read_datagram(
+        &mut self,
+        buf: &mut SocketBuffer,
+        now: Instant,
+        out: &mut Vec<I>,
+    ) -> Result<(), PacketReadErr> {
+        let protocol_id = read_u16(buf)?;
+        if protocol_id != PROTOCOL_ID {
+            return Err(PacketReadErr::InvalidValues);
+        }
+        let seq = read_u16(buf)?;
+        let ack = read_u16(buf)?;
+        let ack_bits = read_u32(buf)?;
+        let channel = read_u8(buf)?;
+        self.last_received_at = now;
+        // the ACCEPTED datagram of the server might have been lost
+        self.handshake = Handshake::Established;
+        if channel & ACK_IS_VALID_FLAG != 0 {
+            self.process_ack(ack, ack_bits);
+        }
+        let is_new = self.mark_received(seq);
+        match channel & !ACK_IS_VALID_FLAG {
+            CHANNEL_ACK_ONLY => {}
+            CHANNEL_UNRELIABLE => {
+                self.ack_pending = true;
+                if is_new {
+                    out.push(I::read_from(buf)?);
+                }
+            }
+            CHANNEL_RELIABLE_ORDERED => {
+                self.ack_pending = true;
+                let id = read_u16(buf)?;
+                if id == self.expected_reliable_id {
+                    out.push(I::read_from(buf)?);
+                    self.expected_reliable_id = self.expected_reliable_id.wrapping_add(1);
+                    while let Some(packet) = self.out_of_order.remove(&self.expected_reliable_id) {
+                        out.push(packet);
+                        self.expected_reliable_id = self.expected_reliable_id.wrapping_add(1);
+                    }
+                } else if sequence_greater_than(id, self.expected_reliable_id)
+                    && id.wrapping_sub(self.expected_reliable_id) <= MAX_OUT_OF_ORDER
+                    && !self.out_of_order.contains_key(&id)
+                {
+                    let packet = I::read_from(buf)?;
+                    self.out_of_order.insert(id, packet);
+                }
+                // else it is a resent message which has already been delivered
+            }
+            _ => return Err(PacketReadErr::InvalidValues),
+        }
+        return Ok(());
+    }
+}
+
+fn write_handshake(kind: u8, cookie: u64, buf: &mut SocketBuffer) {
+    buf.reset();
+    // writing into the SocketBuffer cannot fail
+    let _ = buf.write_all(&HANDSHAKE_ID.to_le_bytes());
+    let _ = buf.write_all(&[kind]);
+    let _ = buf.write_all(&cookie.to_le_bytes());
+}
+
+/// Returns the kind and the cookie if it is a handshake datagram
+fn read_handshake(datagram: &[u8]) -> Option<(u8, u64)> {
+    if datagram.len() != HANDSHAKE_DATAGRAM_SIZE
+        || !datagram.starts_with(&HANDSHAKE_ID.to_le_bytes())
+    {
+        return None;
+    }
+    let mut cookie = [0; 8];
+    cookie.copy_from_slice(&datagram[3..]);
+    Some((datagram[2], u64::from_le_bytes(cookie)))
+}
+
+/// Only the one who receives the datagrams sent to `addr` can know it
+fn cookie_of(addr: SocketAddr, secret: &RandomState) -> u64 {
+    secret.hash_one(addr)
+}
+
+/// Returns None if every slot is occupied and the limit has been reached
+fn free_peer_slot<I>(peers: &mut Vec<Option<UdpConnection<I>>>) -> Option<usize> {
+    match peers.iter().position(|it| it.is_none()) {
+        Some(index) => Some(index),
+        None if peers.len() < MAX_PEERS => {
+            peers.push(None);
+            Some(peers.len() - 1)
+        }
+        None => None,
+    }
+}
+
+fn read_u8(buf: &mut SocketBuffer) -> Result<u8, PacketReadErr> {
+    let mut bytes = [0; 1];
+    buf.read_exact(&mut bytes)
+        .map_err(|_e| PacketReadErr::NotEnoughBytes)?;
+    Ok(bytes[0])
+}
+
+fn read_u16(buf: &mut SocketBuffer) -> Result<u16, PacketReadErr> {
+    let mut bytes = [0; 2];
+    buf.read_exact(&mut bytes)
+        .map_err(|_e| PacketReadErr::NotEnoughBytes)?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+fn read_u32(buf: &mut SocketBuffer) -> Result<u32, PacketReadErr> {
+    let mut bytes = [0; 4];
+    buf.read_exact(&mut bytes)
+        .map_err(|_e| PacketReadErr::NotEnoughBytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn send_datagram<I>(
+    socket: &UdpSocket,
+    buf: &SocketBuffer,
+    socket_id: SocketId,
+    addr: SocketAddr,
+    send_to_incoming_ch: &std::sync::mpsc::Sender<(SocketId, NetworkTrafficEvent<I>)>,
+) where
+    I: Send + Packet + Debug + 'static,
+{
+    let datagram = buf.data();
+    if datagram.len() > MAX_DATAGRAM_SIZE {
+        log::warn!(
+            "Socket({}): datagram is too big ({} bytes), it might be fragmented",
+            socket_id.0,
+            datagram.len()
+        );
+    }
+    match socket.send_to(datagram, addr) {
+        Ok(sent_bytes) => {
+            let _ = send_to_incoming_ch.send((
+                socket_id,
+                NetworkTrafficEvent::OutgoingTraffic {
+                    sent_data_len: sent_bytes,
+                },
+            ));
+        }
+        Err(e) => {
+            // UDP is connectionless, the peer is dropped only when it times out
+            log::error!("Socket({}): error during sending: {}", socket_id.0, e);
+        }
+    }
+}
+
+/// The server side of the handshake for the addresses without a peer slot.
+/// Returns the reply to the datagram.
+fn accept_handshake<I>(
+    kind: u8,
+    cookie: u64,
+    addr: SocketAddr,
+    cookie_secret: &RandomState,
+    now: Instant,
+    peers: &mut Vec<Option<UdpConnection<I>>>,
+    send_to_incoming_ch: &std::sync::mpsc::Sender<(SocketId, NetworkTrafficEvent<I>)>,
+) -> Option<(u8, u64)>
+where
+    I: Send + Packet + Debug + 'static,
+{
+    match kind {
+        HANDSHAKE_CONNECT => Some((HANDSHAKE_CHALLENGE, cookie_of(addr, cookie_secret))),
+        HANDSHAKE_RESPONSE if cookie == cookie_of(addr, cookie_secret) => {
+            let index = match free_peer_slot(peers) {
+                Some(index) => index,
+                None => {
+                    log::warn!("Handshake of {} is dropped, too many peers", addr);
+                    return None;
+                }
+            };
+            peers[index] = Some(UdpConnection::new(addr, now));
+            let _ =
+                send_to_incoming_ch.send((SocketId(index), NetworkTrafficEvent::Connected(addr)));
+            Some((HANDSHAKE_ACCEPTED, 0))
+        }
+        _ => {
+            log::debug!("Invalid handshake datagram from {}", addr);
+            None
+        }
+    }
+}
+
+pub(super) fn udp_traffic_handler<I, O>(
+    socket: UdpSocket,
+    // the server accepts anyone, the client only talks to the peers it was connected to
+    accept_new_peers: bool,
+    send_to_incoming_ch: std::sync::mpsc::Sender<(SocketId, NetworkTrafficEvent<I>)>,
+    read_from_outgoing_ch: std::sync::mpsc::Receiver<NetworkTrafficHandlerMsg<O>>,
+    max_packet_size: usize,
+) where
+    I: Send + Packet + Debug + 'static,
+    O: Send + Packet + Debug + 'static,
+{
+    let mut peers: Vec<Option<UdpConnection<I>>> = Vec::with_capacity(64);
+    let mut buf = SocketBuffer::with_max_packet_size(max_packet_size);
+    // randomly keyed, so the cookies cannot be computed by the clients
+    let cookie_secret = RandomState::new();
+    let mut incoming_packets = Vec::with_capacity(32);
+    loop {
+        let now = Instant::now();
+        while let Ok(command) = read_from_outgoing_ch.try_recv() {
+            match command {
+                NetworkTrafficHandlerMsg::NewUdpPeer(socket_id, addr) => {
+                    log::info!("adding new udp peer: {:?} ({})", socket_id, addr);
+                    if socket_id.0 >= peers.len() {
+                        peers.resize_with(socket_id.0 + 1, || None);
+                    }
+                    peers[socket_id.0] = Some(UdpConnection::connecting(addr, now));
+                }
+                NetworkTrafficHandlerMsg::NewConnection(socket_id, _stream) => {
+                    log::error!(
+                        "{:?}: TCP streams are not supported by the UDP transport",
+                        socket_id
+                    );
+                }
+                NetworkTrafficHandlerMsg::RemoveConnection(socket_id) => {
+                    if let Some(peer) = peers.get_mut(socket_id.0) {
+                        *peer = None;
+                    }
+                }
+                NetworkTrafficHandlerMsg::SendPacket(socket_id, packet) => {
+                    let slot = match peers.get_mut(socket_id.0) {
+                        Some(slot) => slot,
+                        None => continue,
+                    };
+                    if let Some(peer) = slot.as_mut() {
+                        if let Err(e) = peer.write_packet(&packet, now, &mut buf) {
+                            let _ = send_to_incoming_ch.send((
+                                socket_id,
+                                NetworkTrafficEvent::LocalError(std::io::Error::other(
+                                    e.to_string(),
+                                )),
+                            ));
+                            continue;
+                        }
+                        if peer.has_too_many_unacked() {
+                            log::warn!("Socket({}) does not ack, disconnect it", socket_id.0);
+                            let _ = send_to_incoming_ch
+                                .send((socket_id, NetworkTrafficEvent::Disconnected));
+                            *slot = None;
+                            continue;
+                        }
+                        // the reliable messages are resent after the handshake
+                        if peer.is_established() {
+                            send_datagram(
+                                &socket,
+                                &buf,
+                                socket_id,
+                                peer.addr(),
+                                &send_to_incoming_ch,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        for (i, slot) in peers.iter_mut().enumerate() {
+            if let Some(peer) = slot.as_mut() {
+                if peer.is_timed_out(now) {
+                    log::info!("Socket({}) has timed out", i);
+                    let _ =
+                        send_to_incoming_ch.send((SocketId(i), NetworkTrafficEvent::Disconnected));
+                    *slot = None;
+                    continue;
+                }
+                if peer.write_handshake_if_needed(now, &mut buf) {
+                    send_datagram(
+                        &socket,
+                        &buf,
+                        SocketId(i),
+                        peer.addr(),
+                        &send_to_incoming_ch,
+                    );
+                }
+                while peer.write_next_resend(now, &mut buf) {
+                    send_datagram(
+                        &socket,
+                        &buf,
+                        SocketId(i),
+                        peer.addr(),
+                        &send_to_incoming_ch,
+                    );
+                }
+                if peer.write_ack_if_needed(now, &mut buf) {
+                    send_datagram(
+                        &socket,
+                        &buf,
+                        SocketId(i),
+                        peer.addr(),
+                        &send_to_incoming_ch,
+                    );
+                }
+            }
+        }
+
+        loop {
+            buf.reset();
+            let (len, addr) = match socket.recv_from(buf.free_space_mut(MAX_UDP_PAYLOAD_SIZE)) {
+                Ok(result) => result,
+                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    // e.g. ICMP port unreachable on Windows, it does not mean anything for UDP
+                    log::debug!("Error during socket reading: {}", e);
+                    continue;
+                }
+            };
+            buf.commit_written(len);
+            let socket_index = peers
+                .iter()
+                .position(|it| it.as_ref().map(|it| it.addr() == addr).unwrap_or(false));
+            if let Some((kind, cookie)) = read_handshake(buf.data()) {
+                let reply = match socket_index {
+                    Some(index) => peers[index]
+                        .as_mut()
+                        .unwrap()
+                        .read_handshake(kind, cookie, now),
+                    None if accept_new_peers => accept_handshake(
+                        kind,
+                        cookie,
+                        addr,
+                        &cookie_secret,
+                        now,
+                        &mut peers,
+                        &send_to_incoming_ch,
+                    ),
+                    None => None,
+                };
+                if let Some((kind, cookie)) = reply {
+                    write_handshake(kind, cookie, &mut buf);
+                    if let Err(e) = socket.send_to(buf.data(), addr) {
+                        log::debug!("Error during sending the handshake to {}: {}", addr, e);
+                    }
+                }
+                continue;
+            }
+            let socket_index = match socket_index {
+                Some(index) => index,
+                None => {
+                    log::debug!("Datagram from unknown address: {}", addr);
+                    continue;
+                }
+            };
+            let socket_id = SocketId(socket_index);
+            let _ = send_to_incoming_ch.send((
+                socket_id,
+                NetworkTrafficEvent::IncomingTraffic {
+                    received_data_len: len,
+                },
+            ));
+            let peer = peers[socket_index].as_mut().unwrap();
+            if let Err(e) = peer.read_datagram(&mut buf, now, &mut incoming_packets) {
+                // a single corrupted datagram does not break the connection
+                log::error!("Socket({}) sent an invalid datagram: {:?}", socket_index, e);
+            }
+            for packet in incoming_packets.drain(..) {
+                let _ = send_to_incoming_ch.send((socket_id, NetworkTrafficEvent::Packet(packet)));
+            }
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::SimulationTick;
+    use crate::packets::from_server::FromServerPacket;
+    use crate::packets::to_server::ToServerPacket;
+    use crate::packets::{
+        PacketHandlerThread, BUILD_ID, DEFAULT_MAX_PACKET_SIZE, PROTOCOL_VERSION,
+    };
+
+    fn addr() -> SocketAddr {
+        "127.0.0.1:6969".parse().unwrap()
+    }
+
+    fn welcome(name: &str) -> ToServerPacket {
+        ToServerPacket::Welcome {
+            protocol_version: PROTOCOL_VERSION,
+            build: BUILD_ID.to_owned(),
+            name: name.to_owned(),
+            session_token: None,
+        }
+    }
+
+    fn names(packets: &[ToServerPacket]) -> Vec<String> {
+        packets
+            .iter()
+            .map(|it| match it {
+                ToServerPacket::Welcome { name, .. } => name.clone(),
+                _ => panic!("unexpected packet: {:?}", it),
+            })
+            .collect()
+    }
+
+    // the datagram in the buffer is transferred into the other buffer as if it went through a socket
+    fn transfer(from: &SocketBuffer, to: &mut SocketBuffer) {
+        to.reset();
+        to.write_all(from.data()).unwrap();
+    }
+
+    #[test]
+    fn sequence_numbers_wrap_around() {
+        assert!(sequence_greater_than(1, 0));
+        assert!(!sequence_greater_than(0, 1));
+        assert!(sequence_greater_than(0, 65535));
+        assert!(sequence_greater_than(10, 65530));
+        assert!(!sequence_greater_than(65530, 10));
+    }
+
+    #[test]
+    fn reliable_messages_are_resent_and_delivered_in_order() {
+        let now = Instant::now();
+        let mut client = UdpConnection::<FromServerPacket>::new(addr(), now);
+        let mut server = UdpConnection::<ToServerPacket>::new(addr(), now);
+        let mut out_buf = SocketBuffer::new();
+        let mut in_buf = SocketBuffer::new();
+        let mut received = Vec::new();
+
+        for (i, name) in ["a", "b", "c"].iter().enumerate() {
+            client
+                .write_packet(&welcome(name), now, &mut out_buf)
+                .unwrap();
+            // the second datagram is lost
+            if i != 1 {
+                transfer(&out_buf, &mut in_buf);
+                server
+                    .read_datagram(&mut in_buf, now, &mut received)
+                    .unwrap();
+            }
+        }
+        // "c" must wait for "b"
+        assert_eq!(names(&received), vec!["a"]);
+
+        let now = now + SEND_ACK_AFTER;
+        assert!(server.write_ack_if_needed(now, &mut out_buf));
+        transfer(&out_buf, &mut in_buf);
+        client
+            .read_datagram(&mut in_buf, now, &mut Vec::new())
+            .unwrap();
+        // only the lost one is waiting for an ack
+        assert_eq!(client.unacked.len(), 1);
+
+        let now = now + RESEND_AFTER;
+        assert!(client.write_next_resend(now, &mut out_buf));
+        assert!(!client.write_next_resend(now, &mut SocketBuffer::new()));
+        transfer(&out_buf, &mut in_buf);
+        server
+            .read_datagram(&mut in_buf, now, &mut received)
+            .unwrap();
+        assert_eq!(names(&received), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn resent_messages_are_delivered_only_once() {
+        let now = Instant::now();
+        let mut client = UdpConnection::<FromServerPacket>::new(addr(), now);
+        let mut server = UdpConnection::<ToServerPacket>::new(addr(), now);
+        let mut out_buf = SocketBuffer::new();
+        let mut in_buf = SocketBuffer::new();
+        let mut received = Vec::new();
+
+        client
+            .write_packet(&welcome("a"), now, &mut out_buf)
+            .unwrap();
+        transfer(&out_buf, &mut in_buf);
+        server
+            .read_datagram(&mut in_buf, now, &mut received)
+            .unwrap();
+        // the ack is lost, so the client resends it
+        let now = now + RESEND_AFTER;
+        assert!(client.write_next_resend(now, &mut out_buf));
+        transfer(&out_buf, &mut in_buf);
+        server
+            .read_datagram(&mut in_buf, now, &mut received)
+            .unwrap();
+
+        assert_eq!(names(&received), vec!["a"]);
+    }
+
+    #[test]
+    fn unreliable_duplicates_are_dropped() {
+        let now = Instant::now();
+        let mut client = UdpConnection::<FromServerPacket>::new(addr(), now);
+        let mut server = UdpConnection::<ToServerPacket>::new(addr(), now);
+        let mut out_buf = SocketBuffer::new();
+        let mut in_buf = SocketBuffer::new();
+        let mut received = Vec::new();
+
+        client
+            .write_packet(
+                &ToServerPacket::SnapshotReceived {
+                    tick: SimulationTick::new(),
+                },
+                now,
+                &mut out_buf,
+            )
+            .unwrap();
+        for _ in 0..2 {
+            transfer(&out_buf, &mut in_buf);
+            server
+                .read_datagram(&mut in_buf, now, &mut received)
+                .unwrap();
+        }
+
+        assert_eq!(received.len(), 1);
+        assert!(client.unacked.is_empty());
+    }
+
+    #[test]
+    fn reliable_messages_too_far_ahead_are_not_buffered() {
+        let now = Instant::now();
+        let mut client = UdpConnection::<FromServerPacket>::new(addr(), now);
+        let mut server = UdpConnection::<ToServerPacket>::new(addr(), now);
+        let mut out_buf = SocketBuffer::new();
+        let mut in_buf = SocketBuffer::new();
+        let mut received = Vec::new();
+
+        // the first one is lost, everything else has to wait for it
+        for i in 0..(MAX_OUT_OF_ORDER as usize + 10) {
+            client
+                .write_packet(&welcome(&i.to_string()), now, &mut out_buf)
+                .unwrap();
+            if i != 0 {
+                transfer(&out_buf, &mut in_buf);
+                server
+                    .read_datagram(&mut in_buf, now, &mut received)
+                    .unwrap();
+            }
+        }
+
+        assert!(received.is_empty());
+        assert_eq!(server.out_of_order.len(), MAX_OUT_OF_ORDER as usize);
+    }
+
+    #[test]
+    fn too_big_packet_does_not_consume_the_reliable_id() {
+        let now = Instant::now();
+        let mut client = UdpConnection::<FromServerPacket>::new(addr(), now);
+        let mut server = UdpConnection::<ToServerPacket>::new(addr(), now);
+        let mut out_buf = SocketBuffer::with_max_packet_size(64);
+        let mut in_buf = SocketBuffer::new();
+        let mut received = Vec::new();
+
+        assert!(client
+            .write_packet(&welcome(&"x".repeat(100)), now, &mut out_buf)
+            .is_err());
+        assert!(client.unacked.is_empty());
+        client
+            .write_packet(&welcome("a"), now, &mut out_buf)
+            .unwrap();
+        transfer(&out_buf, &mut in_buf);
+        server
+            .read_datagram(&mut in_buf, now, &mut received)
+            .unwrap();
+
+        assert_eq!(names(&received), vec!["a"]);
+        assert_eq!(server.remote_seq, Some(0));
+    }
+
+    #[test]
+    fn only_handshake_datagrams_are_read_as_handshake() {
+        let mut buf = SocketBuffer::new();
+        UdpConnection::<FromServerPacket>::new(addr(), Instant::now())
+            .write_packet(&welcome("a"), Instant::now(), &mut buf)
+            .unwrap();
+        assert_eq!(read_handshake(buf.data()), None);
+        assert_eq!(read_handshake(b"GET / HTTP/1.1"), None);
+        assert_eq!(read_handshake(&[]), None);
+
+        write_handshake(HANDSHAKE_RESPONSE, 42, &mut buf);
+        assert_eq!(buf.data().len(), HANDSHAKE_DATAGRAM_SIZE);
+        assert_eq!(read_handshake(buf.data()), Some((HANDSHAKE_RESPONSE, 42)));
+    }
+
+    #[test]
+    fn peer_slot_is_allocated_only_for_the_echoed_cookie() {
+        let secret = RandomState::new();
+        let (sender, events) = std::sync::mpsc::channel();
+        let mut peers: Vec<Option<UdpConnection<ToServerPacket>>> = Vec::new();
+        let handshake = |kind, cookie, peers: &mut Vec<_>| {
+            accept_handshake(
+                kind,
+                cookie,
+                addr(),
+                &secret,
+                Instant::now(),
+                peers,
+                &sender,
+            )
+        };
+
+        let cookie = match handshake(HANDSHAKE_CONNECT, 0, &mut peers) {
+            Some((HANDSHAKE_CHALLENGE, cookie)) => cookie,
+            other => panic!("{:?}", other),
+        };
+        assert!(peers.is_empty());
+        // a spoofed datagram does not know the cookie sent to the address
+        assert_eq!(
+            handshake(HANDSHAKE_RESPONSE, cookie.wrapping_add(1), &mut peers),
+            None
+        );
+        assert!(peers.is_empty());
+        assert!(events.try_recv().is_err());
+
+        assert_eq!(
+            handshake(HANDSHAKE_RESPONSE, cookie, &mut peers),
+            Some((HANDSHAKE_ACCEPTED, 0))
+        );
+        assert!(peers[0].as_ref().unwrap().is_established());
+        assert!(matches!(
+            events.try_recv(),
+            Ok((SocketId(0), NetworkTrafficEvent::Connected(_)))
+        ));
+    }
+
+    #[test]
+    fn client_repeats_the_handshake_until_it_is_accepted() {
+        let now = Instant::now();
+        let mut client = UdpConnection::<FromServerPacket>::connecting(addr(), now);
+        let mut buf = SocketBuffer::new();
+
+        assert!(client.write_handshake_if_needed(now, &mut buf));
+        assert_eq!(read_handshake(buf.data()), Some((HANDSHAKE_CONNECT, 0)));
+        assert!(!client.write_handshake_if_needed(now, &mut buf));
+
+        assert_eq!(
+            client.read_handshake(HANDSHAKE_CHALLENGE, 42, now),
+            Some((HANDSHAKE_RESPONSE, 42))
+        );
+        let now = now + RESEND_AFTER;
+        assert!(client.write_handshake_if_needed(now, &mut buf));
+        assert_eq!(read_handshake(buf.data()), Some((HANDSHAKE_RESPONSE, 42)));
+
+        assert_eq!(client.read_handshake(HANDSHAKE_ACCEPTED, 0, now), None);
+        assert!(client.is_established());
+        assert!(!client.write_handshake_if_needed(now + RESEND_AFTER, &mut buf));
+    }
+
+    #[test]
+    fn unacked_reliable_messages_are_limited() {
+        let now = Instant::now();
+        let mut client = UdpConnection::<FromServerPacket>::new(addr(), now);
+        let mut buf = SocketBuffer::new();
+        for _ in 0..MAX_UNACKED {
+            client.write_packet(&welcome("a"), now, &mut buf).unwrap();
+        }
+        assert!(!client.has_too_many_unacked());
+
+        client.write_packet(&welcome("a"), now, &mut buf).unwrap();
+        assert!(client.has_too_many_unacked());
+    }
+
+    #[test]
+    fn peer_slots_are_reused_and_limited() {
+        let mut peers: Vec<Option<UdpConnection<ToServerPacket>>> = Vec::new();
+        for i in 0..MAX_PEERS {
+            assert_eq!(free_peer_slot(&mut peers), Some(i));
+            peers[i] = Some(UdpConnection::new(addr(), Instant::now()));
+        }
+        assert_eq!(free_peer_slot(&mut peers), None);
+
+        peers[3] = None;
+        assert_eq!(free_peer_slot(&mut peers), Some(3));
+        assert_eq!(peers.len(), MAX_PEERS);
+    }
+
+    #[test]
+    fn packets_are_exchanged_over_loopback() {
+        let server_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server_socket.set_nonblocking(true).unwrap();
+        let server_addr = server_socket.local_addr().unwrap();
+        let client_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client_socket.set_nonblocking(true).unwrap();
+
+        let server_thread =
+            PacketHandlerThread::<ToServerPacket, FromServerPacket>::start_udp_thread(
+                1,
+                DEFAULT_MAX_PACKET_SIZE,
+                server_socket,
+                true,
+            );
+        let mut client_thread =
+            PacketHandlerThread::<FromServerPacket, ToServerPacket>::start_udp_thread(
+                1,
+                DEFAULT_MAX_PACKET_SIZE,
+                client_socket,
+                false,
+            );
+        let server_id = client_thread.connect_udp(server_addr);
+        client_thread.send(server_id, welcome("sharp"));
+
+        let started = Instant::now();
+        let mut events = Vec::new();
+        let mut welcomed_client = None;
+        while welcomed_client.is_none() && started.elapsed() < Duration::from_secs(2) {
+            server_thread.receive_into(&mut events);
+            for (client_id, event) in events.drain(..) {
+                if let NetworkTrafficEvent::Packet(ToServerPacket::Welcome { name, .. }) = event {
+                    assert_eq!(name, "sharp");
+                    welcomed_client = Some(client_id);
+                }
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        let client_id = welcomed_client.expect("The server has not received the welcome packet");
+
+        server_thread.send(
+            client_id,
+            FromServerPacket::Init {
+                protocol_version: PROTOCOL_VERSION,
+                map_name: "prontera".to_owned(),
+                start_x: 1.0,
+                start_y: 2.0,
+                session_token: 42,
+            },
+        );
+        let started = Instant::now();
+        let mut events = Vec::new();
+        let mut map_name = None;
+        while map_name.is_none() && started.elapsed() < Duration::from_secs(2) {
+            client_thread.receive_into(&mut events);
+            for (_server_id, event) in events.drain(..) {
+                if let NetworkTrafficEvent::Packet(FromServerPacket::Init {
+                    map_name: name, ..
+                }) = event
+                {
+                    map_name = Some(name);
+                }
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(map_name.as_deref(), Some("prontera"));
+    }
+}
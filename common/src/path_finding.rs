@@ -0,0 +1,444 @@
+use crate::common::Vec2;
+use crate::map::MapWalkingInfo;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+// integers, so the server and the clients find exactly the same path
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+/// Bounds the work of a single search, the path leads to the closest cell found until then
+const MAX_EXPANDED_CELLS: usize = 40_000;
+/// The character is close enough to a waypoint to head to the next one
+pub const WAYPOINT_REACHED_DISTANCE: f32 = 0.3;
+
+const NEIGHBOURS: [(i32, i32); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// The scratch memory of the searches, stored as a resource so the per cell buffers
+/// are allocated only once for a map instead of for every path.
+#[derive(Default)]
+pub struct PathFindingBuffers {
+    costs: Vec<u32>,
+    came_from: Vec<usize>,
+    open: BinaryHeap<Reverse<(u32, u32, usize)>>,
+}
+
+/// The remaining part of the path of a walking character.
+/// It is recalculated when the destination moves into another cell or the character
+/// starts a new walk.
+#[derive(Clone, Debug)]
+pub struct WalkingPath {
+    destination_cell: (i32, i32),
+    /// In reverse order, the next one is the last
+    waypoints: Vec<Vec2>,
+    /// If false, the last waypoint is the closest reachable point to the destination
+    reaches_destination: bool,
+}
+
+impl WalkingPath {
+    pub fn new(
+        map: &MapWalkingInfo,
+        buffers: &mut PathFindingBuffers,
+        pos: Vec2,
+        destination: Vec2,
+    ) -> WalkingPath {
+        let (mut waypoints, reaches_destination) = if has_line_of_sight(map, pos, destination) {
+            (vec![], true)
+        } else {
+            // None if the character is stuck in a wall, it can walk out of it in a straight line
+            search(map, buffers, pos, destination).unwrap_or((vec![], true))
+        };
+        waypoints.reverse();
+        WalkingPath {
+            destination_cell: MapWalkingInfo::cell_of(destination),
+            waypoints,
+            reaches_destination,
+        }
+    }
+
+    /// None if the character can not get closer to the destination
+    pub fn next_waypoint(&mut self, pos: Vec2, destination: Vec2) -> Option<Vec2> {
+        while let Some(waypoint) = self.waypoints.last() {
+            if (waypoint - pos).magnitude() > WAYPOINT_REACHED_DISTANCE {
+                return Some(*waypoint);
+            }
+            self.waypoints.pop();
+        }
+        // the destination might have moved inside its cell since the calculation
+        if self.reaches_destination {
+            Some(destination)
+        } else {
+            None
+        }
+    }
+
+    pub fn leads_to(&self, destination: Vec2) -> bool {
+        self.destination_cell == MapWalkingInfo::cell_of(destination)
+    }
+}
+
+fn heuristic(from: (i32, i32), to: (i32, i32)) -> u32 {
+    let dx = (from.0 - to.0).unsigned_abs();
+    let dy = (from.1 - to.1).unsigned_abs();
+    STRAIGHT_COST * dx.max(dy) + (DIAGONAL_COST - STRAIGHT_COST) * dx.min(dy)
+}
+
+/// A* search over the walkable cells, followed by smoothing the path.
+/// Returns the waypoints after `from`, the last one is `to`, or the center of the
+/// closest reachable cell if `to` can not be reached.
+/// None if `from` is not on a walkable cell.
+pub fn find_path(
+    map: &MapWalkingInfo,
+    buffers: &mut PathFindingBuffers,
+    from: Vec2,
+    to: Vec2,
+) -> Option<Vec<Vec2>> {
+    search(map, buffers, from, to).map(|(mut waypoints, reaches_destination)| {
+        if reaches_destination {
+            waypoints.push(to);
+        }
+        waypoints
+    })
+}
+
+/// `to` is not among the returned waypoints, the flag tells whether it can be reached
+fn search(
+    map: &MapWalkingInfo,
+    buffers: &mut PathFindingBuffers,
+    from: Vec2,
+    to: Vec2,
+) -> Option<(Vec<Vec2>, bool)> {
+    let start = MapWalkingInfo::cell_of(from);
+    let goal = MapWalkingInfo::cell_of(to);
+    if !map.is_walkable(start.0, start.1) {
+        return None;
+    }
+    let width = map.width as i32;
+    let index_of = |cell: (i32, i32)| (cell.1 * width + cell.0) as usize;
+    let cell_of_index = |index: usize| (index as i32 % width, index as i32 / width);
+
+    let cell_count = map.width as usize * map.height as usize;
+    let PathFindingBuffers {
+        costs,
+        came_from,
+        open,
+    } = buffers;
+    costs.clear();
+    costs.resize(cell_count, u32::MAX);
+    came_from.clear();
+    came_from.resize(cell_count, usize::MAX);
+    open.clear();
+    let start_index = index_of(start);
+    costs[start_index] = 0;
+    open.push(Reverse((
+        heuristic(start, goal),
+        heuristic(start, goal),
+        start_index,
+    )));
+    // (distance to the goal, index), the index makes the choice deterministic
+    let mut closest = (heuristic(start, goal), start_index);
+    let mut expanded_count = 0;
+    while let Some(Reverse((estimated_cost, h, index))) = open.pop() {
+        if estimated_cost - h > costs[index] {
+            // a shorter route has been found to it since it was pushed
+            continue;
+        }
+        closest = closest.min((h, index));
+        let cell = cell_of_index(index);
+        if cell == goal {
+            break;
+        }
+        expanded_count += 1;
+        if expanded_count > MAX_EXPANDED_CELLS {
+            break;
+        }
+        for (dx, dy) in NEIGHBOURS.iter() {
+            let next = (cell.0 + dx, cell.1 + dy);
+            if !map.is_walkable(next.0, next.1) {
+                continue;
+            }
+            let is_diagonal = *dx != 0 && *dy != 0;
+            // no corner cutting
+            if is_diagonal
+                && (!map.is_walkable(cell.0 + dx, cell.1) || !map.is_walkable(cell.0, cell.1 + dy))
+            {
+                continue;
+            }
+            let next_cost = costs[index]
+                + if is_diagonal {
+                    DIAGONAL_COST
+                } else {
+                    STRAIGHT_COST
+                };
+            let next_index = index_of(next);
+            if next_cost < costs[next_index] {
+                costs[next_index] = next_cost;
+                came_from[next_index] = index;
+                let next_h = heuristic(next, goal);
+                open.push(Reverse((next_cost + next_h, next_h, next_index)));
+            }
+        }
+    }
+
+    let end_index = closest.1;
+    let reaches_destination = cell_of_index(end_index) == goal;
+    let mut cells = Vec::new();
+    let mut index = end_index;
+    while index != start_index {
+        cells.push(cell_of_index(index));
+        index = came_from[index];
+    }
+    cells.reverse();
+    let mut waypoints: Vec<Vec2> = cells
+        .into_iter()
+        .map(|(x, y)| MapWalkingInfo::cell_center(x, y))
+        .collect();
+    if reaches_destination {
+        // `to` is inside the last cell
+        waypoints.pop();
+        waypoints.push(to);
+    }
+    let mut waypoints = smooth(map, from, &waypoints);
+    if reaches_destination {
+        waypoints.pop();
+    }
+    Some((waypoints, reaches_destination))
+}
+
+/// Skips the waypoints which can be bypassed in a straight line
+fn smooth(map: &MapWalkingInfo, from: Vec2, waypoints: &[Vec2]) -> Vec<Vec2> {
+    let mut smoothed = Vec::with_capacity(waypoints.len());
+    let mut anchor = from;
+    let mut i = 0;
+    while i < waypoints.len() {
+        let mut farthest = i;
+        while farthest + 1 < waypoints.len()
+            && has_line_of_sight(map, anchor, waypoints[farthest + 1])
+        {
+            farthest += 1;
+        }
+        anchor = waypoints[farthest];
+        smoothed.push(anchor);
+        i = farthest + 1;
+    }
+    smoothed
+}
+
+/// Whether every cell is walkable along the segment.
+/// The cells are traversed one by one, passing exactly through a corner requires
+/// both of its side cells to be walkable.
+pub fn has_line_of_sight(map: &MapWalkingInfo, from: Vec2, to: Vec2) -> bool {
+    let (mut x, mut y) = MapWalkingInfo::cell_of(from);
+    let end = MapWalkingInfo::cell_of(to);
+    if !map.is_walkable(x, y) {
+        return false;
+    }
+    // the y axis of the grid points to the opposite direction
+    let (dx, dy) = (to.x - from.x, from.y - to.y);
+    let (grid_x, grid_y) = (from.x, -from.y);
+    let step_x = if dx > 0.0 { 1 } else { -1 };
+    let step_y = if dy > 0.0 { 1 } else { -1 };
+    let t_delta_x = if dx != 0.0 {
+        1.0 / dx.abs()
+    } else {
+        f32::INFINITY
+    };
+    let t_delta_y = if dy != 0.0 {
+        1.0 / dy.abs()
+    } else {
+        f32::INFINITY
+    };
+    let mut t_max_x = if dx > 0.0 {
+        (x as f32 + 1.0 - grid_x) / dx
+    } else if dx < 0.0 {
+        (x as f32 - grid_x) / dx
+    } else {
+        f32::INFINITY
+    };
+    let mut t_max_y = if dy > 0.0 {
+        (y as f32 + 1.0 - grid_y) / dy
+    } else if dy < 0.0 {
+        (y as f32 - grid_y) / dy
+    } else {
+        f32::INFINITY
+    };
+
+    let max_steps = (end.0 - x).abs() + (end.1 - y).abs();
+    for _ in 0..max_steps {
+        if (x, y) == end {
+            break;
+        }
+        if t_max_x < t_max_y {
+            x += step_x;
+            t_max_x += t_delta_x;
+        } else if t_max_y < t_max_x {
+            y += step_y;
+            t_max_y += t_delta_y;
+        } else {
+            if !map.is_walkable(x + step_x, y) || !map.is_walkable(x, y + step_y) {
+                return false;
+            }
+            x += step_x;
+            y += step_y;
+            t_max_x += t_delta_x;
+            t_max_y += t_delta_y;
+        }
+        if !map.is_walkable(x, y) {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::v2;
+    use crate::map::CellType;
+
+    /// '#' is a wall, the first row is y = 0
+    fn create_map(rows: &[&str]) -> MapWalkingInfo {
+        MapWalkingInfo::from_cells(
+            rows[0].len() as u32,
+            rows.len() as u32,
+            rows.iter()
+                .flat_map(|row| row.chars())
+                .map(|cell| {
+                    if cell == '#' {
+                        CellType::None as u8
+                    } else {
+                        CellType::Walkable as u8
+                    }
+                })
+                .collect(),
+            &[],
+        )
+    }
+
+    fn cells_of(path: &[Vec2]) -> Vec<(i32, i32)> {
+        path.iter().map(|it| MapWalkingInfo::cell_of(*it)).collect()
+    }
+
+    #[test]
+    fn straight_path_has_only_the_destination() {
+        let map = create_map(&[
+            "......", //
+            "......", //
+            "......", //
+        ]);
+        let to = MapWalkingInfo::cell_center(5, 2);
+        assert_eq!(
+            find_path(
+                &map,
+                &mut PathFindingBuffers::default(),
+                MapWalkingInfo::cell_center(0, 0),
+                to
+            ),
+            Some(vec![to])
+        );
+    }
+
+    #[test]
+    fn path_goes_around_the_wall() {
+        let map = create_map(&[
+            "......", //
+            ".#####", //
+            "......", //
+        ]);
+        let from = MapWalkingInfo::cell_center(5, 2);
+        let to = MapWalkingInfo::cell_center(5, 0);
+        let path = find_path(&map, &mut PathFindingBuffers::default(), from, to).unwrap();
+
+        assert_eq!(*path.last().unwrap(), to);
+        let mut prev = from;
+        for waypoint in &path {
+            assert!(has_line_of_sight(&map, prev, *waypoint));
+            prev = *waypoint;
+        }
+        // smoothed into the two corners
+        assert_eq!(cells_of(&path), vec![(0, 2), (0, 0), (5, 0)]);
+    }
+
+    #[test]
+    fn corners_are_not_cut() {
+        let map = create_map(&[
+            ".#", //
+            "#.", //
+        ]);
+        assert!(!has_line_of_sight(
+            &map,
+            MapWalkingInfo::cell_center(0, 0),
+            MapWalkingInfo::cell_center(1, 1)
+        ));
+        assert_eq!(
+            find_path(
+                &map,
+                &mut PathFindingBuffers::default(),
+                MapWalkingInfo::cell_center(0, 0),
+                MapWalkingInfo::cell_center(1, 1)
+            ),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn unreachable_destination_leads_to_the_closest_cell() {
+        let map = create_map(&[
+            "...#.", //
+            "...#.", //
+            "...#.", //
+        ]);
+        let path = find_path(
+            &map,
+            &mut PathFindingBuffers::default(),
+            MapWalkingInfo::cell_center(0, 1),
+            MapWalkingInfo::cell_center(4, 1),
+        )
+        .unwrap();
+        assert_eq!(cells_of(&path), vec![(2, 1)]);
+    }
+
+    #[test]
+    fn no_path_from_inside_a_wall() {
+        let map = create_map(&[
+            "#..", //
+        ]);
+        assert!(find_path(
+            &map,
+            &mut PathFindingBuffers::default(),
+            v2(0.5, -0.5),
+            v2(2.5, -0.5)
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn walking_path_is_followed_waypoint_by_waypoint() {
+        let map = create_map(&[
+            "......", //
+            ".#####", //
+            "......", //
+        ]);
+        let from = MapWalkingInfo::cell_center(5, 2);
+        let to = MapWalkingInfo::cell_center(5, 0);
+        let mut path = WalkingPath::new(&map, &mut PathFindingBuffers::default(), from, to);
+        let first_corner = MapWalkingInfo::cell_center(0, 2);
+        let corner = MapWalkingInfo::cell_center(0, 0);
+
+        assert_eq!(path.next_waypoint(from, to), Some(first_corner));
+        assert_eq!(path.next_waypoint(first_corner, to), Some(corner));
+        assert_eq!(path.next_waypoint(corner, to), Some(to));
+        // the destination has moved inside its cell
+        let moved_to = to + v2(0.2, 0.2);
+        assert!(path.leads_to(moved_to));
+        assert_eq!(path.next_waypoint(corner, moved_to), Some(moved_to));
+    }
+}
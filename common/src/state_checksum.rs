@@ -0,0 +1,260 @@
+use crate::common::{Local, NetworkedObj};
+use crate::components::char::{CharState, EntityId, EntityTarget, LocalCharStateComp};
+
+/// Coarser than the snapshot quantization, so the rounding errors of the
+/// snapshots and of the prediction do not count as desync
+pub const CHECKSUM_POS_QUANTIZATION: f32 = 16.0;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a, its result does not depend on the platform or the build
+pub struct StableHasher(u64);
+
+impl StableHasher {
+    pub fn new() -> StableHasher {
+        StableHasher(FNV_OFFSET_BASIS)
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.write(&[value]);
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_pos(&mut self, x: f32, y: f32) {
+        self.write_i32(quantize(x));
+        self.write_i32(quantize(y));
+    }
+}
+
+fn quantize(value: f32) -> i32 {
+    (value * CHECKSUM_POS_QUANTIZATION).round() as i32
+}
+
+fn state_kind<T: NetworkedObj>(state: &CharState<T>) -> u8 {
+    match state {
+        CharState::Idle => 0,
+        CharState::Walking(..) => 1,
+        CharState::StandBy => 2,
+        CharState::Attacking { .. } => 3,
+        CharState::ReceivingDamage => 4,
+        CharState::Dead => 5,
+        CharState::CastingSkill(..) => 6,
+    }
+}
+
+/// `server_id_of` maps the entity ids inside the state to the ids of the server,
+/// the client stores its local ids in the targets.
+pub fn char_checksum<T: 'static + NetworkedObj>(
+    server_id: u64,
+    state: &LocalCharStateComp<T>,
+    server_id_of: &dyn Fn(EntityId<T>) -> u64,
+) -> u64 {
+    let mut hasher = StableHasher::new();
+    hasher.write_u64(server_id);
+    hasher.write_pos(state.pos().x, state.pos().y);
+    hasher.write_u8(state_kind(&state.state));
+    hasher.write_i32(state.hp);
+    match &state.target {
+        None => hasher.write_u8(0),
+        Some(EntityTarget::OtherEntity(id)) => {
+            hasher.write_u8(1);
+            hasher.write_u64(server_id_of(*id));
+        }
+        Some(EntityTarget::Pos(pos)) => {
+            hasher.write_u8(2);
+            hasher.write_pos(pos.x, pos.y);
+        }
+        Some(EntityTarget::PosWhileAttacking(pos, id)) => {
+            hasher.write_u8(3);
+            hasher.write_pos(pos.x, pos.y);
+            hasher.write_u64(id.map(server_id_of).unwrap_or(0));
+        }
+    }
+    hasher.0
+}
+
+/// Bit exact, unlike `char_checksum`, the replays use it to compare the simulated states.
+/// The cooldowns are hashed sorted by skill, the iteration order of their map differs
+/// between the runs
+pub fn write_exact_char_state(
+    hasher: &mut StableHasher,
+    state: &LocalCharStateComp<Local>,
+) -> bincode::Result<()> {
+    let mut cooldowns: Vec<_> = state.skill_cast_allowed_at.iter().collect();
+    cooldowns.sort_by_key(|(skill, _allowed_at)| **skill as usize);
+    let bytes = bincode::serialize(&(
+        &state.pos,
+        &state.dir,
+        &state.state,
+        &state.target,
+        &state.calculated_attribs,
+        &state.attack_delay_ends_at,
+        &cooldowns,
+        &state.cannot_control_until,
+        &state.hp,
+        &state.statuses,
+    ))?;
+    hasher.write(&bytes);
+    Ok(())
+}
+
+/// The checksums of the characters are summed, so the order of the states does not matter
+pub fn world_checksum<'a, T: 'static + NetworkedObj>(
+    states: impl IntoIterator<Item = (u64, &'a LocalCharStateComp<T>)>,
+    server_id_of: &dyn Fn(EntityId<T>) -> u64,
+) -> u64 {
+    states.into_iter().fold(0, |sum, (server_id, state)| {
+        sum.wrapping_add(char_checksum(server_id, state, server_id_of))
+    })
+}
+
+/// One line for every character whose checksum differs on the two sides
+pub fn checksum_diff<'a, T: 'static + NetworkedObj>(
+    authoritative: &[(u64, &'a LocalCharStateComp<T>)],
+    local: &[(u64, &'a LocalCharStateComp<T>)],
+    server_id_of: &dyn Fn(EntityId<T>) -> u64,
+) -> String {
+    fn describe<T: 'static + NetworkedObj>(state: Option<&LocalCharStateComp<T>>) -> String {
+        match state {
+            Some(state) => format!(
+                "pos: ({:.2}, {:.2}), state: {}, hp: {}, target: {}",
+                state.pos().x,
+                state.pos().y,
+                state.state,
+                state.hp,
+                state
+                    .target
+                    .as_ref()
+                    .map(|it| it.to_string())
+                    .unwrap_or_else(|| "-".to_owned())
+            ),
+            None => "missing".to_owned(),
+        }
+    }
+    let mut ids: Vec<u64> = authoritative
+        .iter()
+        .chain(local.iter())
+        .map(|(id, _state)| *id)
+        .collect();
+    ids.sort();
+    ids.dedup();
+    let mut lines = Vec::with_capacity(ids.len());
+    for id in ids {
+        let authoritative_state = authoritative.iter().find(|it| it.0 == id).map(|it| it.1);
+        let local_state = local.iter().find(|it| it.0 == id).map(|it| it.1);
+        let checksum_of = |state: Option<&LocalCharStateComp<T>>| {
+            state.map(|state| char_checksum(id, state, server_id_of))
+        };
+        if checksum_of(authoritative_state) != checksum_of(local_state) {
+            lines.push(format!(
+                "{}: server: {{{}}}, client: {{{}}}",
+                id,
+                describe(authoritative_state),
+                describe(local_state)
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{v2, Local};
+    use specs::prelude::*;
+
+    fn state(x: f32, y: f32, hp: i32) -> LocalCharStateComp<Local> {
+        let mut state = LocalCharStateComp::new(v2(x, y), Default::default());
+        state.hp = hp;
+        state
+    }
+
+    fn id_of(id: EntityId<Local>) -> u64 {
+        id.as_u64()
+    }
+
+    #[test]
+    fn world_checksum_does_not_depend_on_the_order() {
+        let a = state(1.0, 2.0, 100);
+        let b = state(3.0, 4.0, 50);
+        assert_eq!(
+            world_checksum(vec![(1, &a), (2, &b)], &id_of),
+            world_checksum(vec![(2, &b), (1, &a)], &id_of)
+        );
+        assert_ne!(
+            world_checksum(vec![(1, &a), (2, &b)], &id_of),
+            world_checksum(vec![(2, &a), (1, &b)], &id_of)
+        );
+    }
+
+    #[test]
+    fn small_position_errors_are_ignored() {
+        let acked = state(1.0, 2.0, 100);
+        let predicted = state(1.001, 2.0, 100);
+        let moved = state(1.5, 2.0, 100);
+        let damaged = state(1.0, 2.0, 90);
+        let checksum = char_checksum(1, &acked, &id_of);
+        assert_eq!(checksum, char_checksum(1, &predicted, &id_of));
+        assert_ne!(checksum, char_checksum(1, &moved, &id_of));
+        assert_ne!(checksum, char_checksum(1, &damaged, &id_of));
+    }
+
+    #[test]
+    fn target_ids_are_mapped_to_server_ids() {
+        let mut world = World::new();
+        let local_target = EntityId::new(world.create_entity().build());
+        let server_target = EntityId::new(world.create_entity().build());
+        let mut client_state = state(1.0, 2.0, 100);
+        client_state.target = Some(EntityTarget::OtherEntity(local_target));
+        let mut server_state = state(1.0, 2.0, 100);
+        server_state.target = Some(EntityTarget::OtherEntity(server_target));
+
+        let to_server_id = |id: EntityId<Local>| {
+            if id == local_target {
+                server_target.as_u64()
+            } else {
+                id.as_u64()
+            }
+        };
+        assert_eq!(
+            char_checksum(1, &server_state, &id_of),
+            char_checksum(1, &client_state, &to_server_id)
+        );
+    }
+
+    #[test]
+    fn diff_lists_only_the_diverged_characters() {
+        let a = state(1.0, 2.0, 100);
+        let b = state(3.0, 4.0, 50);
+        let b_damaged = state(3.0, 4.0, 40);
+        let diff = checksum_diff(
+            &[(1, &a), (2, &b)],
+            &[(1, &a), (2, &b_damaged), (3, &a)],
+            &id_of,
+        );
+        let lines: Vec<&str> = diff.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("2: "));
+        assert!(lines[0].contains("hp: 50") && lines[0].contains("hp: 40"));
+        assert!(lines[1].starts_with("3: server: {missing}"));
+    }
+}
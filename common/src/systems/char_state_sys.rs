@@ -3,8 +3,14 @@ use specs::prelude::*;
 use crate::attack::HpModificationRequest;
 use crate::common::{v2_to_p2, EngineTime, GameTime, Local, Vec2};
 use crate::components::char::{
-    CharDir, CharState, EntityId, EntityTarget, LocalCharStateComp, StaticCharDataComponent, Team,
+    CharDied, CharDir, CharState, EntityId, EntityTarget, LocalCharStateComp,
+    StaticCharDataComponent, Team,
 };
+use crate::components::projectile::ProjectileComponent;
+use crate::components::skills::skills::FinishCast;
+use crate::lag_compensation::{compensated_distance, consume_compensation, LagCompensatedTargets};
+use crate::map::MapWalkingInfo;
+use crate::path_finding::PathFindingBuffers;
 use std::collections::HashMap;
 
 pub struct CharacterStateUpdateSystem;
@@ -16,8 +22,16 @@ impl<'a> System<'a> for CharacterStateUpdateSystem {
         WriteStorage<'a, LocalCharStateComp<Local>>,
         ReadStorage<'a, StaticCharDataComponent>,
         ReadExpect<'a, EngineTime>,
+        ReadExpect<'a, MapWalkingInfo>,
+        WriteExpect<'a, PathFindingBuffers>,
         WriteExpect<'a, Vec<HpModificationRequest>>,
+        WriteExpect<'a, Vec<FinishCast>>,
         Write<'a, LazyUpdate>,
+        Option<Write<'a, LagCompensatedTargets>>,
+        // only the server handles the deaths
+        Option<Write<'a, Vec<CharDied>>>,
+        // only the server simulates the projectiles
+        Option<Write<'a, Vec<ProjectileComponent>>>,
     );
 
     fn run(
@@ -27,8 +41,14 @@ impl<'a> System<'a> for CharacterStateUpdateSystem {
             mut char_state_storage,
             static_state_storage,
             time,
+            map,
+            mut path_finding_buffers,
             mut hp_mod_requests,
-            mut updater,
+            mut just_finished_skill_casts,
+            _updater,
+            mut lag_compensation,
+            mut deaths,
+            mut fired_projectiles,
         ): Self::SystemData,
     ) {
         let now = time.now();
@@ -41,10 +61,10 @@ impl<'a> System<'a> for CharacterStateUpdateSystem {
             for (char_entity_id, auth_state, static_state) in
                 (&entities, &char_state_storage, &static_state_storage).join()
             {
-                // TODO2
-                //                if char_comp.state().is_dead() {
-                //                    continue;
-                //                }
+                // the dead can not be targeted, including the ones dying in this tick
+                if auth_state.state().is_dead() || auth_state.hp <= 0 {
+                    continue;
+                }
                 let char_entity_id = EntityId::new(char_entity_id);
                 // the third arg is char_comp.team, move team field from charstate first
                 char_positions.insert(char_entity_id, (auth_state.pos(), static_state.team));
@@ -56,46 +76,16 @@ impl<'a> System<'a> for CharacterStateUpdateSystem {
             (&entities, &mut char_state_storage, &static_state_storage).join()
         {
             let char_entity_id = EntityId::new(char_entity_id);
-            // pakold külön componensbe ugy a dolgokat, hogy innen be tudjam álltiani a
-            // target et None-ra ha az halott, meg a fenti position hack se kelllejn
-            // TODO2
-            //            let is_dead = *char_comp.state() == CharState::Dead;
-            //            if char_comp.hp <= 0 && !is_dead {
-            //                log::debug!("Entity has died {:?}", char_entity_id);
-            //                char_comp.set_state(CharState::Dead, char_comp.dir());
-            //                char_comp.statuses.remove_all();
-            //                char_comp
-            //                    .statuses
-            //                    .add(StatusEnum::DeathStatus(DeathStatus::new(
-            //                        time.now(),
-            //                        npc_storage.get(char_entity_id.into()).is_some(),
-            //                    )));
-            //                // remove rigid bodies from the physic simulation
-            //                collisions_resource.remove_collider_handle(char_comp.collider_handle);
-            //                physics_world.bodies.remove(char_comp.body_handle);
-            //                continue;
-            //            } else if is_dead && npc_storage.get(char_entity_id.into()).is_some() {
-            //                if let StatusEnum::DeathStatus(status) = char_comp
-            //                    .statuses
-            //                    .get_status(StatusEnumDiscriminants::DeathStatus)
-            //                    .unwrap()
-            //                {
-            //                    if status.remove_char_at.has_already_passed(time.now()) {
-            //                        entities.delete(char_entity_id.into()).unwrap();
-            //                    }
-            //                    continue;
-            //                }
-            //            }
-
-            // TODO2
-            //            char_comp.update_statuses(
-            //                char_entity_id,
-            //                &mut sys_vars,
-            //                &time,
-            //                &entities,
-            //                &mut updater,
-            //                &mut physics_world,
-            //            );
+            if auth_state.hp <= 0 && auth_state.state().is_alive() {
+                log::debug!("Entity has died {:?}", char_entity_id);
+                auth_state.set_state_dbg(CharState::Dead, "hp <= 0");
+                auth_state.target = None;
+                if let Some(deaths) = &mut deaths {
+                    deaths.push(CharDied {
+                        char_id: char_entity_id,
+                    });
+                }
+            }
 
             if *auth_state.state() == CharState::Dead {
                 continue;
@@ -103,71 +93,65 @@ impl<'a> System<'a> for CharacterStateUpdateSystem {
 
             let char_pos = auth_state.pos();
             // TODO: why clone?
-            // TODO2
             match auth_state.state().clone() {
-                //                CharState::CastingSkill(casting_info) => {
-                //                    if casting_info.cast_ends.has_already_passed(now) {
-                //                        log::debug!("Skill cast has finished: {:?}", casting_info.skill);
-                //                        let skill_pos = if let Some(target_entity) = casting_info
-                //                            .target_entity
-                //                            .and_then(|it| all_char_data.get(&it))
-                //                        {
-                //                            Some(target_entity.0.clone())
-                //                        } else {
-                //                            casting_info.target_area_pos
-                //                        };
-                //                        sys_vars.just_finished_skill_casts.push(FinishCast {
-                //                            skill: casting_info.skill,
-                //                            caster_pos: char_pos,
-                //                            caster_entity_id: char_entity_id,
-                //                            skill_pos,
-                //                            char_to_skill_dir: casting_info.char_to_skill_dir_when_casted,
-                //                            target_entity: casting_info.target_entity,
-                //                            caster_team: char_comp.team,
-                //                        });
-                //
-                //                        char_comp.set_state(CharState::Idle, char_comp.dir());
-                //                    }
-                //                }
+                CharState::CastingSkill(casting_info)
+                    if casting_info.cast_ends.has_already_passed(now) =>
+                {
+                    log::debug!("Skill cast has finished: {:?}", casting_info.skill);
+                    let skill_pos = if let Some(target_entity) = casting_info
+                        .target_entity
+                        .and_then(|it| all_char_data.get(&it))
+                    {
+                        Some(target_entity.0)
+                    } else {
+                        casting_info.target_area_pos
+                    };
+                    just_finished_skill_casts.push(FinishCast {
+                        skill: casting_info.skill,
+                        caster_pos: char_pos,
+                        caster_entity_id: char_entity_id,
+                        skill_pos,
+                        char_to_skill_dir: casting_info.char_to_skill_dir_when_casted,
+                        target_entity: casting_info.target_entity,
+                        caster_team: static_state.team,
+                    });
+
+                    auth_state.set_state_dbg(CharState::Idle, "skill cast finished");
+                }
                 CharState::Attacking {
                     target,
                     damage_occurs_at,
                     basic_attack,
-                } => {
-                    if damage_occurs_at.has_already_passed(now) {
-                        auth_state.set_state_dbg(CharState::Idle, "attack finished");
-                        if let Some(target_pos) = all_char_data.get(&target) {
-                            if let Some(manifestation) = basic_attack.finish_attack(
-                                auth_state.calculated_attribs(),
-                                char_entity_id,
-                                char_pos,
-                                target_pos.0,
-                                target,
-                                &mut hp_mod_requests,
-                                &time,
-                            ) {
-                                // TODOasd
-                                //                                let skill_manifest_id = entities.create();
-                                //                                updater.insert(
-                                //                                    skill_manifest_id,
-                                //                                    SkillManifestationComponent::new(
-                                //                                        skill_manifest_id,
-                                //                                        manifestation,
-                                //                                    ),
-                                //                                );
-                            }
-                        } else {
-                            // target might have died
-                            // TODO: change state to idle?
+                } if damage_occurs_at.has_already_passed(now) => {
+                    auth_state.set_state_dbg(CharState::Idle, "attack finished");
+                    if all_char_data.contains_key(&target) {
+                        let combo_hits =
+                            auth_state.register_basic_attack_hit(basic_attack, target, now);
+                        let projectile = basic_attack.finish_attack(
+                            auth_state.calculated_attribs(),
+                            char_entity_id,
+                            char_pos,
+                            target,
+                            combo_hits,
+                            &mut hp_mod_requests,
+                            &time,
+                        );
+                        if let (Some(projectile), Some(fired_projectiles)) =
+                            (projectile, fired_projectiles.as_mut())
+                        {
+                            fired_projectiles.push(projectile);
                         }
+                    } else {
+                        // target might have died
+                        // TODO: change state to idle?
                     }
                 }
                 _ => {}
             }
 
-            // TODO2
-            if true {
-                // char_comp.can_move(now)
+            // TODO2 char_comp.can_move(now)
+            // the target is processed only after the casting has been finished
+            if !auth_state.state().is_casting() {
                 if let Some(target) = &auth_state.target.clone() {
                     if let EntityTarget::PosWhileAttacking(pos, current_target) = target {
                         // hack end
@@ -178,7 +162,7 @@ impl<'a> System<'a> for CharacterStateUpdateSystem {
                         let no_target_or_dead_or_out_of_range = match current_target_entity {
                             Some((pos, _team)) => {
                                 let current_distance = nalgebra::distance(
-                                    &v2_to_p2(&pos),
+                                    &v2_to_p2(pos),
                                     &v2_to_p2(&auth_state.pos()),
                                 );
                                 current_distance > 10.0
@@ -198,27 +182,39 @@ impl<'a> System<'a> for CharacterStateUpdateSystem {
                             CharacterStateUpdateSystem::act_based_on_target(
                                 now,
                                 &all_char_data,
+                                char_entity_id,
                                 auth_state,
                                 static_state,
                                 &EntityTarget::Pos(*pos),
+                                lag_compensation.as_deref_mut(),
+                                &map,
+                                &mut path_finding_buffers,
                             )
                         } else {
                             // there is an active target, move closer or attack it
                             CharacterStateUpdateSystem::act_based_on_target(
                                 now,
                                 &all_char_data,
+                                char_entity_id,
                                 auth_state,
                                 static_state,
                                 &EntityTarget::OtherEntity(current_target.unwrap()),
+                                lag_compensation.as_deref_mut(),
+                                &map,
+                                &mut path_finding_buffers,
                             )
                         }
                     } else {
                         CharacterStateUpdateSystem::act_based_on_target(
                             now,
                             &all_char_data,
+                            char_entity_id,
                             auth_state,
                             static_state,
                             target,
+                            lag_compensation.as_deref_mut(),
+                            &map,
+                            &mut path_finding_buffers,
                         )
                     }
                 } else {
@@ -231,6 +227,9 @@ impl<'a> System<'a> for CharacterStateUpdateSystem {
         // TODO: into a system
         // apply moving physics here, so that the prev loop does not have to borrow physics_storage
         for char_comp in (&mut char_state_storage).join() {
+            if char_comp.state().is_alive() {
+                char_comp.update_knockback(now);
+            }
             if let CharState::Walking(target_pos) = char_comp.state() {
                 if char_comp.can_move(now) {
                     // it is possible that the character is pushed away but stayed in WALKING state (e.g. because of she blocked the attack)
@@ -264,7 +263,7 @@ impl CharacterStateUpdateSystem {
             {
                 continue;
             }
-            let current_distance = nalgebra::distance(&center, &v2_to_p2(&pos));
+            let current_distance = nalgebra::distance(&center, &v2_to_p2(pos));
             if current_distance <= radius && current_distance < distance {
                 distance = current_distance;
                 ret = Some(*char_id);
@@ -276,21 +275,28 @@ impl CharacterStateUpdateSystem {
     fn act_based_on_target(
         now: GameTime<Local>,
         char_positions: &HashMap<EntityId<Local>, (Vec2, Team)>,
+        char_entity_id: EntityId<Local>,
         auth_state: &mut LocalCharStateComp<Local>,
         static_state: &StaticCharDataComponent,
         target: &EntityTarget<Local>,
+        lag_compensation: Option<&mut LagCompensatedTargets>,
+        map: &MapWalkingInfo,
+        path_finding_buffers: &mut PathFindingBuffers,
     ) {
         let char_pos = auth_state.pos();
         match target {
             EntityTarget::OtherEntity(target_entity) => {
                 let target_pos = char_positions.get(target_entity);
                 if let Some((target_pos, _team)) = target_pos {
-                    let distance = nalgebra::distance(
-                        &nalgebra::Point::from(char_pos),
-                        &v2_to_p2(&target_pos),
+                    let distance = compensated_distance(
+                        lag_compensation.as_deref(),
+                        char_entity_id,
+                        *target_entity,
+                        nalgebra::distance(&nalgebra::Point::from(char_pos), &v2_to_p2(target_pos)),
+                        now,
                     );
 
-                    if distance <= auth_state.calculated_attribs().attack_range.as_f32() * 2.0 {
+                    if distance <= auth_state.attack_distance() {
                         if auth_state.attack_delay_ends_at.has_already_passed(now) {
                             let attack_anim_duration =
                                 1.0 / auth_state.calculated_attribs().attack_speed.as_f32();
@@ -298,7 +304,7 @@ impl CharacterStateUpdateSystem {
                             let new_state = CharState::Attacking {
                                 damage_occurs_at,
                                 target: *target_entity,
-                                basic_attack: static_state.basic_attack_type,
+                                basic_attack: auth_state.basic_attack_type(static_state),
                             };
                             auth_state.set_state_dbg2(
                                 new_state,
@@ -307,14 +313,19 @@ impl CharacterStateUpdateSystem {
                             );
                             let attack_anim_duration = GameTime::from(attack_anim_duration);
                             auth_state.attack_delay_ends_at = now.add(attack_anim_duration);
+                            consume_compensation(lag_compensation, char_entity_id);
                         }
-                    } else {
+                    } else if let Some(waypoint) =
+                        auth_state.next_waypoint(map, path_finding_buffers, *target_pos)
+                    {
                         //                     move closer
                         auth_state.set_state_dbg2(
-                            CharState::Walking(*target_pos),
-                            CharDir::determine_dir(target_pos, &char_pos),
+                            CharState::Walking(waypoint),
+                            CharDir::determine_dir(&waypoint, &char_pos),
                             "move closer",
                         );
+                    } else {
+                        auth_state.set_state_dbg(CharState::Idle, "target can not be reached");
                     }
                 } else {
                     auth_state.set_state_dbg(CharState::Idle, "has target but it does not exist");
@@ -333,7 +344,9 @@ impl CharacterStateUpdateSystem {
                     // stop
                     auth_state.set_state_dbg(CharState::Idle, "target pos has reached");
                     auth_state.target = None;
-                } else {
+                } else if let Some(waypoint) =
+                    auth_state.next_waypoint(map, path_finding_buffers, *target_pos)
+                {
                     log::trace!(
                         "Still not there, keep moving, {:?}, target: {:?}",
                         char_pos,
@@ -341,13 +354,245 @@ impl CharacterStateUpdateSystem {
                     );
                     // move closer
                     auth_state.set_state_dbg2(
-                        CharState::Walking(*target_pos),
-                        CharDir::determine_dir(target_pos, &char_pos),
+                        CharState::Walking(waypoint),
+                        CharDir::determine_dir(&waypoint, &char_pos),
                         "move closer to target pos",
                     );
+                } else {
+                    // as close as it can get
+                    auth_state.set_state_dbg(CharState::Idle, "target pos can not be reached");
+                    auth_state.target = None;
                 }
             }
             EntityTarget::PosWhileAttacking(_pos, _current_target) => {}
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::{
+        ApplyForceComponent, BasicAttackType, DamageDisplayType, HpModificationType,
+    };
+    use crate::common::{percentage, v2};
+    use crate::components::char::CharType;
+    use crate::map::CellType;
+    use crate::test_utils::{attributes, create_char_from, static_data};
+
+    fn create_world() -> World {
+        let mut world = crate::test_utils::create_world();
+        world.insert(EngineTime::new(0));
+        world.insert(MapWalkingInfo::new());
+        world.insert(PathFindingBuffers::default());
+        world.insert(Vec::<HpModificationRequest>::new());
+        world.insert(Vec::<FinishCast>::new());
+        world.insert(Vec::<CharDied>::new());
+        world
+    }
+
+    fn create_char(world: &mut World, team: Team, pos: Vec2, hp: i32) -> EntityId<Local> {
+        let mut state = LocalCharStateComp::new(pos, attributes());
+        state.hp = hp;
+        create_char_from(world, state, static_data(CharType::Minion, team))
+    }
+
+    fn state_of(world: &World, id: EntityId<Local>) -> LocalCharStateComp<Local> {
+        world
+            .read_storage::<LocalCharStateComp<Local>>()
+            .get(id.into())
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn char_dies_once_when_its_hp_drops_to_zero() {
+        let mut world = create_world();
+        let alive = create_char(&mut world, Team::Left, v2(0.0, 0.0), 1);
+        let dying = create_char(&mut world, Team::Right, v2(5.0, 0.0), 0);
+
+        CharacterStateUpdateSystem.run_now(&world);
+        CharacterStateUpdateSystem.run_now(&world);
+
+        assert!(state_of(&world, alive).state().is_alive());
+        assert!(state_of(&world, dying).state().is_dead());
+        let deaths = world.read_resource::<Vec<CharDied>>();
+        assert_eq!(deaths.len(), 1);
+        assert_eq!(deaths[0].char_id, dying);
+    }
+
+    #[test]
+    fn dead_chars_can_not_be_targeted() {
+        let mut world = create_world();
+        let attacker = create_char(&mut world, Team::Left, v2(0.0, 0.0), 100);
+        let target = create_char(&mut world, Team::Right, v2(5.0, 0.0), 0);
+        world
+            .write_storage::<LocalCharStateComp<Local>>()
+            .get_mut(attacker.into())
+            .unwrap()
+            .target = Some(EntityTarget::OtherEntity(target));
+
+        CharacterStateUpdateSystem.run_now(&world);
+
+        let attacker_state = state_of(&world, attacker);
+        assert_eq!(*attacker_state.state(), CharState::Idle);
+        assert!(attacker_state.target.is_none());
+    }
+
+    #[test]
+    fn resurrected_char_has_full_hp() {
+        let mut world = create_world();
+        let char_id = create_char(&mut world, Team::Left, v2(0.0, 0.0), 0);
+        CharacterStateUpdateSystem.run_now(&world);
+
+        world
+            .write_storage::<LocalCharStateComp<Local>>()
+            .get_mut(char_id.into())
+            .unwrap()
+            .resurrect();
+        CharacterStateUpdateSystem.run_now(&world);
+
+        let state = state_of(&world, char_id);
+        assert!(state.state().is_alive());
+        assert_eq!(state.hp, 100);
+        assert_eq!(world.read_resource::<Vec<CharDied>>().len(), 1);
+    }
+
+    const COMBO: BasicAttackType = BasicAttackType::MeleeCombo {
+        combo_count: 3,
+        base_dmg_percentage_for_each_combo: percentage(100),
+        combo_window_seconds: 1.0,
+    };
+
+    fn land_combo_hit_at(
+        world: &mut World,
+        attacker: EntityId<Local>,
+        target: EntityId<Local>,
+        seconds: f32,
+    ) -> (u32, DamageDisplayType) {
+        world.write_resource::<EngineTime>().time = GameTime::from(seconds);
+        world
+            .write_storage::<LocalCharStateComp<Local>>()
+            .get_mut(attacker.into())
+            .unwrap()
+            .set_state(CharState::Attacking {
+                target,
+                damage_occurs_at: GameTime::from(seconds),
+                basic_attack: COMBO,
+            });
+        CharacterStateUpdateSystem.run_now(world);
+        match world
+            .write_resource::<Vec<HpModificationRequest>>()
+            .pop()
+            .unwrap()
+            .typ
+        {
+            HpModificationType::BasicDamage(dmg, display_type, _) => (dmg, display_type),
+            typ => panic!("unexpected hp modification: {:?}", typ),
+        }
+    }
+
+    #[test]
+    fn consecutive_hits_on_the_same_target_build_up_a_combo() {
+        let mut world = create_world();
+        let attacker = create_char(&mut world, Team::Left, v2(0.0, 0.0), 100);
+        let target = create_char(&mut world, Team::Right, v2(1.0, 0.0), 100);
+        world
+            .write_storage::<LocalCharStateComp<Local>>()
+            .get_mut(attacker.into())
+            .unwrap()
+            .calculated_attribs
+            .attack_damage = 10;
+
+        let hits: Vec<(u32, DamageDisplayType)> = [0.0, 0.5, 1.0, 1.5]
+            .iter()
+            .map(|seconds| land_combo_hit_at(&mut world, attacker, target, *seconds))
+            .collect();
+
+        assert_eq!(
+            hits,
+            vec![
+                (10, DamageDisplayType::SingleNumber),
+                (20, DamageDisplayType::Combo(2)),
+                (30, DamageDisplayType::Combo(3)),
+                (10, DamageDisplayType::SingleNumber),
+            ]
+        );
+    }
+
+    #[test]
+    fn combo_starts_over_after_its_window_or_on_a_new_target() {
+        let mut world = create_world();
+        let attacker = create_char(&mut world, Team::Left, v2(0.0, 0.0), 100);
+        let target = create_char(&mut world, Team::Right, v2(1.0, 0.0), 100);
+        let other_target = create_char(&mut world, Team::Right, v2(0.0, 1.0), 100);
+
+        land_combo_hit_at(&mut world, attacker, target, 0.0);
+        let (_, late_hit) = land_combo_hit_at(&mut world, attacker, target, 1.5);
+        let (_, second_hit) = land_combo_hit_at(&mut world, attacker, target, 2.0);
+        let (_, other_target_hit) = land_combo_hit_at(&mut world, attacker, other_target, 2.5);
+
+        assert_eq!(late_hit, DamageDisplayType::SingleNumber);
+        assert_eq!(second_hit, DamageDisplayType::Combo(2));
+        assert_eq!(other_target_hit, DamageDisplayType::SingleNumber);
+    }
+
+    #[test]
+    fn char_walks_around_the_wall() {
+        let mut world = create_world();
+        // 6x3 map, the wall blocks the middle row except its first cell
+        let mut cells = vec![CellType::Walkable as u8; 18];
+        for x in 1..6 {
+            cells[6 + x] = CellType::None as u8;
+        }
+        world.insert(MapWalkingInfo::from_cells(6, 3, cells, &[]));
+        let char_id = create_char(
+            &mut world,
+            Team::Left,
+            MapWalkingInfo::cell_center(5, 2),
+            100,
+        );
+        world
+            .write_storage::<LocalCharStateComp<Local>>()
+            .get_mut(char_id.into())
+            .unwrap()
+            .target = Some(EntityTarget::Pos(MapWalkingInfo::cell_center(5, 0)));
+
+        CharacterStateUpdateSystem.run_now(&world);
+
+        assert_eq!(
+            *state_of(&world, char_id).state(),
+            CharState::Walking(MapWalkingInfo::cell_center(0, 2))
+        );
+    }
+
+    #[test]
+    fn pushed_char_slows_down_until_it_stops() {
+        let mut world = create_world();
+        let char_id = create_char(&mut world, Team::Left, v2(0.0, 0.0), 100);
+        world
+            .write_storage::<LocalCharStateComp<Local>>()
+            .get_mut(char_id.into())
+            .unwrap()
+            .apply_force(
+                &ApplyForceComponent {
+                    src_entity: char_id,
+                    dst_entity: char_id,
+                    force: v2(6.0, 0.0),
+                    duration: 1.0,
+                },
+                GameTime::from(0.0),
+            );
+
+        // it stops after 0.1 seconds
+        for i in 1..=4 {
+            world.write_resource::<EngineTime>().time = GameTime::from(i as f32 * 0.05);
+            CharacterStateUpdateSystem.run_now(&world);
+        }
+
+        let state = state_of(&world, char_id);
+        assert!((state.pos().x - 0.45).abs() < 0.0001);
+        assert!(!state.can_move(GameTime::from(0.5)));
+        assert!(state.can_move(GameTime::from(1.0)));
+    }
+}
@@ -0,0 +1,162 @@
+use specs::prelude::*;
+
+use crate::collision::{
+    circle_circle_penetration, Aabb, BroadphaseGrid, BROADPHASE_CELL_SIZE, CHAR_COLLISION_RADIUS,
+};
+use crate::common::{Local, Vec2};
+use crate::components::char::{CollisionGroup, LocalCharStateComp, StaticCharDataComponent};
+use crate::map::MapWalkingInfo;
+
+/// Separates the overlapping characters and pushes them out of the walls,
+/// respecting their `CollisionGroup`s.
+/// Both the server and the client prediction run it, so the result must not depend on
+/// anything else than the positions and the map.
+pub struct CollisionSystem;
+
+impl<'a> System<'a> for CollisionSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, LocalCharStateComp<Local>>,
+        ReadStorage<'a, StaticCharDataComponent>,
+        ReadExpect<'a, MapWalkingInfo>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, mut char_state_storage, static_char_data_storage, map): Self::SystemData,
+    ) {
+        // the join order is the order of the entity ids
+        let chars: Vec<(Entity, Vec2, CollisionGroup)> =
+            (&entities, &char_state_storage, &static_char_data_storage)
+                .join()
+                .filter(|(_entity, char_state, _static_data)| char_state.state().is_alive())
+                .map(|(entity, char_state, static_data)| {
+                    let group = if char_state.is_noncollidable() {
+                        CollisionGroup::NonCollidablePlayer
+                    } else {
+                        static_data.collision_group()
+                    };
+                    (entity, char_state.pos(), group)
+                })
+                .collect();
+
+        let mut grid = BroadphaseGrid::new(BROADPHASE_CELL_SIZE);
+        for (i, (_entity, pos, _group)) in chars.iter().enumerate() {
+            grid.insert(i, &Aabb::of_circle(*pos, CHAR_COLLISION_RADIUS));
+        }
+        let mut corrections = vec![Vec2::zeros(); chars.len()];
+        for (i, j) in grid.potential_pairs() {
+            let (_, pos, group) = chars[i];
+            let (_, other_pos, other_group) = chars[j];
+            if !group.collides_with(other_group) {
+                continue;
+            }
+            if let Some(penetration) = circle_circle_penetration(
+                pos,
+                CHAR_COLLISION_RADIUS,
+                other_pos,
+                CHAR_COLLISION_RADIUS,
+            ) {
+                // both of them step aside, except the structures and barricades which never move
+                let (share, other_share) = match (group.is_immovable(), other_group.is_immovable())
+                {
+                    (false, true) => (1.0, 0.0),
+                    (true, false) => (0.0, 1.0),
+                    _ => (0.5, 0.5),
+                };
+                corrections[i] += penetration * share;
+                corrections[j] -= penetration * other_share;
+            }
+        }
+
+        for ((entity, pos, group), correction) in chars.into_iter().zip(corrections) {
+            let new_pos = if group.collides_with(CollisionGroup::StaticModel) {
+                map.push_out_of_walls(pos + correction, CHAR_COLLISION_RADIUS)
+            } else {
+                pos + correction
+            };
+            if new_pos != pos {
+                if let Some(char_state) = char_state_storage.get_mut(entity) {
+                    char_state.add_pos(new_pos - pos);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::v2;
+    use crate::components::char::{CharType, EntityId, Team};
+    use crate::map::CellType;
+    use crate::test_utils::create_char;
+
+    fn create_world(map: MapWalkingInfo) -> World {
+        let mut world = crate::test_utils::create_world();
+        world.insert(map);
+        world
+    }
+
+    fn pos_of(world: &World, id: EntityId<Local>) -> Vec2 {
+        world
+            .read_storage::<LocalCharStateComp<Local>>()
+            .get(id.into())
+            .unwrap()
+            .pos()
+    }
+
+    #[test]
+    fn overlapping_chars_are_pushed_apart() {
+        let mut world = create_world(MapWalkingInfo::new());
+        let left = create_char(&mut world, CharType::Player, Team::Left, v2(0.0, 0.0));
+        let right = create_char(&mut world, CharType::Player, Team::Left, v2(0.5, 0.0));
+
+        CollisionSystem.run_now(&world);
+
+        let distance = (pos_of(&world, right) - pos_of(&world, left)).magnitude();
+        assert!((distance - CHAR_COLLISION_RADIUS * 2.0).abs() < 0.0001);
+        assert!((pos_of(&world, left).x - -0.15).abs() < 0.0001);
+    }
+
+    #[test]
+    fn players_and_minions_pass_through_each_other() {
+        let mut world = create_world(MapWalkingInfo::new());
+        let player = create_char(&mut world, CharType::Player, Team::Left, v2(0.0, 0.0));
+        let minion = create_char(&mut world, CharType::Minion, Team::Left, v2(0.5, 0.0));
+
+        CollisionSystem.run_now(&world);
+
+        assert_eq!(pos_of(&world, player), v2(0.0, 0.0));
+        assert_eq!(pos_of(&world, minion), v2(0.5, 0.0));
+    }
+
+    #[test]
+    fn structures_are_not_pushed() {
+        let mut world = create_world(MapWalkingInfo::new());
+        let tower = create_char(&mut world, CharType::Structure, Team::Left, v2(0.0, 0.0));
+        let minion = create_char(&mut world, CharType::Minion, Team::Left, v2(0.5, 0.0));
+
+        CollisionSystem.run_now(&world);
+
+        assert_eq!(pos_of(&world, tower), v2(0.0, 0.0));
+        assert!((pos_of(&world, minion).x - CHAR_COLLISION_RADIUS * 2.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn char_is_pushed_out_of_the_wall() {
+        // the right column is a wall
+        let mut cells = vec![CellType::Walkable as u8; 9];
+        for y in 0..3 {
+            cells[y * 3 + 2] = CellType::None as u8;
+        }
+        let mut world = create_world(MapWalkingInfo::from_cells(3, 3, cells, &[]));
+        let char_id = create_char(&mut world, CharType::Player, Team::Left, v2(1.9, -1.5));
+
+        CollisionSystem.run_now(&world);
+
+        let pos = pos_of(&world, char_id);
+        assert!((pos.x - (2.0 - CHAR_COLLISION_RADIUS)).abs() < 0.0001);
+        assert!((pos.y - -1.5).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,274 @@
+use specs::prelude::*;
+
+use crate::common::{EngineTime, Local};
+use crate::components::char::{
+    CharAttacked, CharType, EntityId, EntityTarget, LocalCharStateComp, StaticCharDataComponent,
+};
+use crate::components::controller::{ControllerComponent, PlayerIntention};
+use crate::components::guardian::{GuardianComponent, GuardianConfig};
+use crate::components::minion::Provocation;
+use crate::systems::minion_ai_sys::closest_enemy_in_area;
+
+/// The guardian does not walk back for the last few steps
+const AT_HOME_DISTANCE: f32 = 1.0;
+
+/// Picks the targets of the towers and guards inside their guarded area.
+/// An enemy player who has damaged an allied player is preferred over everything,
+/// otherwise the minions are preferred over the players. The structures are never attacked.
+pub struct GuardianAiSystem;
+
+impl<'a> System<'a> for GuardianAiSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, ControllerComponent>,
+        WriteStorage<'a, GuardianComponent>,
+        ReadStorage<'a, StaticCharDataComponent>,
+        ReadStorage<'a, LocalCharStateComp<Local>>,
+        ReadExpect<'a, GuardianConfig>,
+        ReadExpect<'a, EngineTime>,
+        ReadExpect<'a, Vec<CharAttacked>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut controller_storage,
+            mut guardian_storage,
+            static_char_data_storage,
+            char_state_storage,
+            config,
+            time,
+            attacks,
+        ): Self::SystemData,
+    ) {
+        let now = time.now();
+        for (controller_id, controller, guardian) in
+            (&entities, &mut controller_storage, &mut guardian_storage).join()
+        {
+            let guardian_char = controller.controlled_entity.and_then(|char_id| {
+                let static_data = static_char_data_storage.get(char_id.into())?;
+                let char_state = char_state_storage.get(char_id.into())?;
+                Some((char_id, static_data, char_state))
+            });
+            let (char_id, static_data, char_state) = match guardian_char {
+                Some(guardian_char) => guardian_char,
+                None => {
+                    // the guardian has been removed
+                    if let Err(e) = entities.delete(controller_id) {
+                        log::error!("Could not remove guardian controller: {}", e);
+                    }
+                    continue;
+                }
+            };
+            if char_state.state().is_dead() {
+                controller.intention = None;
+                continue;
+            }
+            let pos = char_state.pos();
+            let team = static_data.team;
+            let home = guardian.home;
+            let radius = guardian
+                .guarded_radius
+                .unwrap_or_else(|| char_state.attack_distance());
+
+            let can_be_attacked = |target_id: EntityId<Local>| -> bool {
+                match (
+                    static_char_data_storage.get(target_id.into()),
+                    char_state_storage.get(target_id.into()),
+                ) {
+                    (Some(target_static_data), Some(target_state)) => {
+                        target_state.state().is_alive()
+                            && target_static_data.team.is_enemy_to(team)
+                            && target_static_data.typ != CharType::Structure
+                            && (target_state.pos() - home).magnitude() <= radius
+                    }
+                    _ => false,
+                }
+            };
+
+            for attack in attacks.iter() {
+                let is_player = |char_id: EntityId<Local>| {
+                    static_char_data_storage
+                        .get(char_id.into())
+                        .map(|it| it.typ == CharType::Player)
+                        .unwrap_or(false)
+                };
+                let ally_has_been_attacked = static_char_data_storage
+                    .get(attack.attacked_id.into())
+                    .map(|it| it.team == team)
+                    .unwrap_or(false);
+                if ally_has_been_attacked
+                    && is_player(attack.attacked_id)
+                    && is_player(attack.attacker_id)
+                    && can_be_attacked(attack.attacker_id)
+                {
+                    guardian.provoked_by = Some(Provocation {
+                        attacker_id: attack.attacker_id,
+                        until: now.add_seconds(config.provoked_seconds),
+                    });
+                }
+            }
+
+            let provocation_is_over = guardian
+                .provoked_by
+                .as_ref()
+                .map(|it| it.until.has_already_passed(now))
+                .unwrap_or(false);
+            if provocation_is_over {
+                guardian.provoked_by = None;
+            }
+            let provoker = guardian
+                .provoked_by
+                .as_ref()
+                .map(|it| it.attacker_id)
+                .filter(|attacker_id| can_be_attacked(*attacker_id));
+            let current_target = match char_state.target {
+                Some(EntityTarget::OtherEntity(target_id)) if can_be_attacked(target_id) => {
+                    Some(target_id)
+                }
+                _ => None,
+            };
+            let target = provoker.or(current_target).or_else(|| {
+                closest_enemy_in_area(
+                    &entities,
+                    &static_char_data_storage,
+                    &char_state_storage,
+                    home,
+                    radius,
+                    team,
+                    char_id,
+                    |it| match it.typ {
+                        CharType::Minion => Some(0),
+                        CharType::Structure => None,
+                        _ => Some(1),
+                    },
+                )
+            });
+
+            controller.intention = match target {
+                Some(target_id) => Some(PlayerIntention::Attack(target_id)),
+                None if char_state.target.is_some()
+                    || (pos - home).magnitude() > AT_HOME_DISTANCE =>
+                {
+                    Some(PlayerIntention::MoveTo(home))
+                }
+                None => None,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::char_attr::CharAttributes;
+    use crate::common::{percentage, v2, Vec2};
+    use crate::components::char::Team;
+    use crate::test_utils::{
+        assert_attacks, attributes, create_char_from, intention_of, static_data,
+    };
+    use std::time::Duration;
+
+    fn create_world() -> World {
+        let mut world = crate::test_utils::create_world();
+        world.register::<GuardianComponent>();
+        world.insert(EngineTime::new_for_tests(Duration::from_millis(100)));
+        world.insert(Vec::<CharAttacked>::new());
+        world.insert(GuardianConfig {
+            provoked_seconds: 3.0,
+        });
+        world
+    }
+
+    fn create_char(world: &mut World, typ: CharType, team: Team, pos: Vec2) -> EntityId<Local> {
+        let state = LocalCharStateComp::new(
+            pos,
+            CharAttributes {
+                // 10 units
+                attack_range: percentage(500),
+                ..attributes()
+            },
+        );
+        create_char_from(world, state, static_data(typ, team))
+    }
+
+    fn create_guardian(world: &mut World, pos: Vec2, guarded_radius: Option<f32>) -> Entity {
+        let char_id = create_char(world, CharType::Structure, Team::Left, pos);
+        world
+            .create_entity()
+            .with(ControllerComponent::new(char_id))
+            .with(GuardianComponent::new(pos, guarded_radius))
+            .build()
+    }
+
+    #[test]
+    fn tower_prefers_the_minions() {
+        let mut world = create_world();
+        let tower = create_guardian(&mut world, v2(0.0, 0.0), None);
+        let _player_id = create_char(&mut world, CharType::Player, Team::Right, v2(2.0, 0.0));
+        let minion_id = create_char(&mut world, CharType::Minion, Team::Right, v2(8.0, 0.0));
+        let _enemy_tower_id =
+            create_char(&mut world, CharType::Structure, Team::Right, v2(1.0, 0.0));
+
+        GuardianAiSystem.run_now(&world);
+
+        assert_attacks(&world, tower, minion_id);
+    }
+
+    #[test]
+    fn tower_attacks_the_player_who_hits_an_allied_player() {
+        let mut world = create_world();
+        let tower = create_guardian(&mut world, v2(0.0, 0.0), None);
+        let ally_id = create_char(&mut world, CharType::Player, Team::Left, v2(12.0, 0.0));
+        let attacker_id = create_char(&mut world, CharType::Player, Team::Right, v2(9.0, 0.0));
+        let _minion_id = create_char(&mut world, CharType::Minion, Team::Right, v2(3.0, 0.0));
+        world
+            .write_resource::<Vec<CharAttacked>>()
+            .push(CharAttacked {
+                attacker_id,
+                attacked_id: ally_id,
+            });
+
+        GuardianAiSystem.run_now(&world);
+
+        assert_attacks(&world, tower, attacker_id);
+    }
+
+    #[test]
+    fn tower_ignores_the_enemies_out_of_its_range() {
+        let mut world = create_world();
+        let tower = create_guardian(&mut world, v2(0.0, 0.0), None);
+        let _player_id = create_char(&mut world, CharType::Player, Team::Right, v2(10.5, 0.0));
+
+        GuardianAiSystem.run_now(&world);
+
+        assert!(intention_of(&world, tower).is_none());
+    }
+
+    #[test]
+    fn guard_returns_to_its_place() {
+        let mut world = create_world();
+        let guard = create_guardian(&mut world, v2(0.0, 0.0), Some(5.0));
+        let char_id = world
+            .read_storage::<ControllerComponent>()
+            .get(guard)
+            .unwrap()
+            .controlled_entity
+            .unwrap();
+        world
+            .write_storage::<LocalCharStateComp<Local>>()
+            .get_mut(char_id.into())
+            .unwrap()
+            .set_pos(v2(4.0, 0.0));
+        // it is guarding the area around its place, not around itself
+        let _player_id = create_char(&mut world, CharType::Player, Team::Right, v2(8.0, 0.0));
+
+        GuardianAiSystem.run_now(&world);
+
+        match intention_of(&world, guard) {
+            Some(PlayerIntention::MoveTo(pos)) => assert_eq!(pos, v2(0.0, 0.0)),
+            other => panic!("{:?}", other),
+        }
+    }
+}
@@ -1,6 +1,12 @@
-use crate::common::{v2, EngineTime, Local};
-use crate::components::char::{EntityId, EntityTarget, LocalCharStateComp};
+use crate::common::{GameTime, Local, Vec2};
+use crate::components::char::{
+    CastingSkillData, CharDir, CharState, EntityId, EntityTarget, LocalCharStateComp,
+    StaticCharDataComponent, Team,
+};
 use crate::components::controller::{ControllerComponent, PlayerIntention};
+use crate::components::skills::skills::{SkillTargetType, Skills};
+use crate::config::CommonConfigs;
+use crate::lag_compensation::{compensated_distance, consume_compensation, LagCompensatedTargets};
 
 pub struct ControllerIntentionToCharTarget;
 
@@ -8,16 +14,32 @@ impl ControllerIntentionToCharTarget {
     pub fn controller_intention_to_char_target(
         controller: &ControllerComponent,
         char_state_storage: &mut specs::WriteStorage<LocalCharStateComp<Local>>,
+        static_char_data_storage: &specs::ReadStorage<StaticCharDataComponent>,
+        now: GameTime<Local>,
+        configs: &CommonConfigs,
+        lag_compensation: Option<&mut LagCompensatedTargets>,
     ) {
         if let Some(controlled_entity) = controller.controlled_entity {
+            // the target of the skill must be queried before the caster is borrowed mutably
+            let skill_target = match controller.intention {
+                Some(PlayerIntention::Casting(_skill, _is_self_cast, _pos, Some(target_id))) => {
+                    char_state_storage
+                        .get(target_id.into())
+                        .filter(|target_state| target_state.state().is_alive())
+                        .and_then(|target_state| {
+                            static_char_data_storage
+                                .get(target_id.into())
+                                .map(|static_data| {
+                                    (target_id, target_state.pos(), static_data.team)
+                                })
+                        })
+                }
+                _ => None,
+            };
             let auth_char = char_state_storage.get_mut(controlled_entity.into());
 
             // the controlled character might have been removed due to death etc
-            if let Some(auth_char) = auth_char {
-                // TODO2 statuses
-                //                if char_state.statuses.can_be_controlled() == false {
-                //                    continue;
-                //                }
+            if let Some(auth_char) = auth_char.filter(|it| it.can_be_controlled(now)) {
                 match controller.intention {
                     Some(PlayerIntention::MoveTo(pos)) => {
                         auth_char.target = Some(EntityTarget::Pos(pos))
@@ -31,108 +53,150 @@ impl ControllerIntentionToCharTarget {
                     Some(PlayerIntention::AttackTowards(pos)) => {
                         auth_char.target = Some(EntityTarget::PosWhileAttacking(pos, None));
                     }
-                    None => {} // TODO2
-                               //                    Some(PlayerIntention::Casting(skill, is_self_cast, mouse_world_pos)) => {
-                               //                        NextActionApplierSystem::try_cast_skill(
-                               //                            skill,
-                               //                            now,
-                               //                            &dev_configs,
-                               //                            char_state,
-                               //                            &mouse_world_pos,
-                               //                            &controller.entities_below_cursor,
-                               //                            controller.controlled_entity,
-                               //                            is_self_cast,
-                               //                        )
-                               //                    }
+                    Some(PlayerIntention::Casting(skill, is_self_cast, mouse_world_pos, _)) => {
+                        if let Some(static_data) =
+                            static_char_data_storage.get(controlled_entity.into())
+                        {
+                            ControllerIntentionToCharTarget::try_cast_skill(
+                                skill,
+                                now,
+                                configs,
+                                auth_char,
+                                static_data,
+                                &mouse_world_pos,
+                                skill_target,
+                                controlled_entity,
+                                is_self_cast,
+                                lag_compensation,
+                            );
+                        }
+                    }
+                    None => {}
                 };
             }
         }
     }
 
-    //    pub fn try_cast_skill(
-    //        skill: Skills,
-    //        now: GameTime<Local>,
-    //        configs: &CommonConfigs,
-    //        char_state: &mut CharacterStateComponent,
-    //        mouse_world_pos: &Vec2,
-    //        entities_below_cursor: &EntitiesBelowCursor,
-    //        self_char_id: EntityId<Local>,
-    //        is_self_cast: bool,
-    //    ) -> bool {
-    //        if char_state
-    //            .skill_cast_allowed_at
-    //            .entry(skill)
-    //            .or_insert(GameTime::from(0.0))
-    //            .has_not_passed_yet(now)
-    //        {
-    //            return true;
-    //        }
-    //        let skill_def = skill.get_definition();
-    //        let skill_cast_attrs = skill.get_cast_attributes(configs, char_state);
-    //        let (target_pos, target_entity) = if is_self_cast {
-    //            (char_state.pos(), Some(self_char_id))
-    //        } else {
-    //            let target_entity = match skill_def.get_skill_target_type() {
-    //                SkillTargetType::AnyEntity => entities_below_cursor.get_enemy_or_friend(),
-    //                SkillTargetType::NoTarget => None,
-    //                SkillTargetType::Area => None,
-    //                SkillTargetType::Directional => None,
-    //                SkillTargetType::OnlyAllyButNoSelf => {
-    //                    entities_below_cursor.get_friend_except(self_char_id)
-    //                }
-    //                SkillTargetType::OnlyAllyAndSelf => entities_below_cursor.get_friend(),
-    //                SkillTargetType::OnlyEnemy => entities_below_cursor.get_enemy(),
-    //            };
-    //            (*mouse_world_pos, target_entity)
-    //        };
-    //        let distance = (char_state.pos() - target_pos).magnitude();
-    //        let allowed = Skills::is_casting_allowed_based_on_target(
-    //            skill_def.get_skill_target_type(),
-    //            skill_cast_attrs.casting_range,
-    //            self_char_id,
-    //            target_entity,
-    //            distance,
-    //        );
-    //        let can_move = char_state.can_cast(now);
-    //        if allowed && can_move {
-    //            log::debug!("Casting request for '{:?}' was allowed", skill);
-    //            let casting_time_seconds = skill_cast_attrs.casting_time;
-    //            let (target_pos, dir_vector) = Skills::limit_vector_into_range(
-    //                &char_state.pos(),
-    //                &target_pos,
-    //                skill_cast_attrs.casting_range,
-    //            );
-    //            let new_state = CharState::CastingSkill(CastingSkillData {
-    //                target_entity,
-    //                cast_started: now,
-    //                cast_ends: now.add(casting_time_seconds),
-    //                can_move: false,
-    //                skill,
-    //                target_area_pos: match skill_def.get_skill_target_type() {
-    //                    SkillTargetType::Area | SkillTargetType::Directional => Some(target_pos),
-    //                    _ => None,
-    //                },
-    //                char_to_skill_dir_when_casted: dir_vector,
-    //            });
-    //            let dir = if is_self_cast || target_entity.map(|it| it == self_char_id).is_some() {
-    //                // skill on self, don't change direction
-    //                char_state.dir()
-    //            } else {
-    //                let char_pos = char_state.pos();
-    //                NextActionApplierSystem::determine_dir(&target_pos, &char_pos)
-    //            };
-    //            char_state.set_state(new_state, dir);
-    //            *char_state.skill_cast_allowed_at.get_mut(&skill).unwrap() =
-    //                now.add(skill_cast_attrs.cast_delay);
-    //            return false;
-    //        } else {
-    //            log::debug!(
-    //                "Casting request for '{:?}' was rejected, allowed: {}, can_cast: {}",
-    //                skill,
-    //                allowed,
-    //                can_move
-    //            );
-    //            return !can_move; // try to repeat casting only when it was interrupted, but not when the target was invalid
-    //        }
-    //    }
+    /// Returns true if the casting has been started
+    pub fn try_cast_skill(
+        skill: Skills,
+        now: GameTime<Local>,
+        configs: &CommonConfigs,
+        char_state: &mut LocalCharStateComp<Local>,
+        static_data: &StaticCharDataComponent,
+        mouse_world_pos: &Vec2,
+        skill_target: Option<(EntityId<Local>, Vec2, Team)>,
+        self_char_id: EntityId<Local>,
+        is_self_cast: bool,
+        lag_compensation: Option<&mut LagCompensatedTargets>,
+    ) -> bool {
+        if char_state.is_skill_in_cooldown(skill, now) {
+            log::debug!("Casting request for '{:?}' was rejected, cooldown", skill);
+            return false;
+        }
+        if !configs.job(static_data.job_id).has_skill(skill) {
+            log::debug!(
+                "Casting request for '{:?}' was rejected, {:?} does not have it",
+                skill,
+                static_data.job_id
+            );
+            return false;
+        }
+        if !mouse_world_pos.x.is_finite() || !mouse_world_pos.y.is_finite() {
+            log::debug!(
+                "Casting request for '{:?}' was rejected, invalid position: {:?}",
+                skill,
+                mouse_world_pos
+            );
+            return false;
+        }
+        let skill_target_type = skill.get_skill_target_type();
+        let skill_cast_attrs = skill.get_cast_attributes(configs, char_state);
+        let (target_pos, target_entity) = if is_self_cast {
+            (char_state.pos(), Some((self_char_id, static_data.team)))
+        } else {
+            match skill_target_type {
+                SkillTargetType::NoTarget
+                | SkillTargetType::Area
+                | SkillTargetType::Directional => (*mouse_world_pos, None),
+                _ => match skill_target {
+                    Some((target_id, target_pos, target_team)) => {
+                        (target_pos, Some((target_id, target_team)))
+                    }
+                    None => (*mouse_world_pos, None),
+                },
+            }
+        };
+        let team_is_valid = match (skill_target_type, target_entity) {
+            (SkillTargetType::OnlyAllyButNoSelf, Some((_id, target_team)))
+            | (SkillTargetType::OnlyAllyAndSelf, Some((_id, target_team))) => {
+                static_data.team.can_support(target_team)
+            }
+            (SkillTargetType::OnlyEnemy, Some((_id, target_team))) => {
+                static_data.team.can_attack(target_team)
+            }
+            _ => true,
+        };
+        let target_entity = target_entity.map(|(id, _team)| id);
+        let distance = (char_state.pos() - target_pos).magnitude();
+        let distance = match target_entity {
+            Some(target_id) => compensated_distance(
+                lag_compensation.as_deref(),
+                self_char_id,
+                target_id,
+                distance,
+                now,
+            ),
+            None => distance,
+        };
+        let allowed = team_is_valid
+            && Skills::is_casting_allowed_based_on_target(
+                skill_target_type,
+                skill_cast_attrs.casting_range,
+                self_char_id,
+                target_entity,
+                distance,
+            );
+        let can_cast = char_state.can_cast(now);
+        if allowed && can_cast {
+            log::debug!("Casting request for '{:?}' was allowed", skill);
+            let (target_pos, dir_vector) = Skills::limit_vector_into_range(
+                &char_state.pos(),
+                &target_pos,
+                skill_cast_attrs.casting_range,
+            );
+            let new_state = CharState::CastingSkill(CastingSkillData {
+                target_entity,
+                cast_started: now,
+                cast_ends: now.add(skill_cast_attrs.casting_time),
+                can_move: false,
+                skill,
+                target_area_pos: match skill_target_type {
+                    SkillTargetType::Area | SkillTargetType::Directional => Some(target_pos),
+                    _ => None,
+                },
+                char_to_skill_dir_when_casted: dir_vector,
+            });
+            let dir = if is_self_cast || target_entity == Some(self_char_id) {
+                // skill on self, don't change direction
+                char_state.dir()
+            } else {
+                CharDir::determine_dir(&target_pos, &char_state.pos())
+            };
+            char_state.set_state_dbg2(new_state, dir, "casting request was allowed");
+            char_state
+                .skill_cast_allowed_at
+                .insert(skill, now.add(skill_cast_attrs.cast_delay));
+            consume_compensation(lag_compensation, self_char_id);
+            return true;
+        } else {
+            log::debug!(
+                "Casting request for '{:?}' was rejected, allowed: {}, can_cast: {}",
+                skill,
+                allowed,
+                can_cast
+            );
+            return false;
+        }
+    }
 }
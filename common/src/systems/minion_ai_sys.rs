@@ -0,0 +1,349 @@
+use specs::prelude::*;
+
+use crate::common::{EngineTime, Local, Vec2};
+use crate::components::char::{
+    CharAttacked, CharType, EntityId, EntityTarget, LocalCharStateComp, StaticCharDataComponent,
+    Team,
+};
+use crate::components::controller::{ControllerComponent, PlayerIntention};
+use crate::components::minion::{MinionComponent, MinionConfig, MinionLanes, Provocation};
+
+/// The closest living enemy inside the radius, the ones with lower `priority` are preferred
+/// regardless of their distance, the ones without priority are ignored.
+/// On equal distances the one with the lower entity id wins, so it is the same everywhere.
+pub fn closest_enemy_in_area(
+    entities: &Entities,
+    static_char_data_storage: &ReadStorage<StaticCharDataComponent>,
+    char_state_storage: &ReadStorage<LocalCharStateComp<Local>>,
+    center: Vec2,
+    radius: f32,
+    self_team: Team,
+    except: EntityId<Local>,
+    priority: impl Fn(&StaticCharDataComponent) -> Option<u8>,
+) -> Option<EntityId<Local>> {
+    let mut closest = None;
+    let mut closest_key = (u8::MAX, f32::INFINITY);
+    for (entity_id, static_data, char_state) in
+        (entities, static_char_data_storage, char_state_storage).join()
+    {
+        let entity_id = EntityId::from(entity_id);
+        if entity_id == except
+            || !static_data.team.is_enemy_to(self_team)
+            || char_state.state().is_dead()
+        {
+            continue;
+        }
+        let distance = (char_state.pos() - center).magnitude();
+        if distance > radius {
+            continue;
+        }
+        let priority = match priority(static_data) {
+            Some(priority) => priority,
+            None => continue,
+        };
+        let key = (priority, distance);
+        if key < closest_key {
+            closest_key = key;
+            closest = Some(entity_id);
+        }
+    }
+    closest
+}
+
+/// Walks the minions along their lanes and picks their targets.
+/// Enemy minions are preferred over the players, unless a player has attacked the minion
+/// or an ally next to it. The structures come last.
+pub struct MinionAiSystem;
+
+impl<'a> System<'a> for MinionAiSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, ControllerComponent>,
+        WriteStorage<'a, MinionComponent>,
+        ReadStorage<'a, StaticCharDataComponent>,
+        ReadStorage<'a, LocalCharStateComp<Local>>,
+        ReadExpect<'a, MinionConfig>,
+        ReadExpect<'a, MinionLanes>,
+        ReadExpect<'a, EngineTime>,
+        ReadExpect<'a, Vec<CharAttacked>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut controller_storage,
+            mut minion_storage,
+            static_char_data_storage,
+            char_state_storage,
+            config,
+            lanes,
+            time,
+            attacks,
+        ): Self::SystemData,
+    ) {
+        let now = time.now();
+        for attack in attacks.iter() {
+            let attacker_is_player = static_char_data_storage
+                .get(attack.attacker_id.into())
+                .map(|it| it.typ == CharType::Player)
+                .unwrap_or(false);
+            let attacked = static_char_data_storage
+                .get(attack.attacked_id.into())
+                .and_then(|static_data| {
+                    char_state_storage
+                        .get(attack.attacked_id.into())
+                        .map(|char_state| (static_data.team, char_state.pos()))
+                });
+            let (attacked_team, attacked_pos) = match attacked {
+                Some(attacked) if attacker_is_player => attacked,
+                _ => continue,
+            };
+            for (controller, minion) in (&controller_storage, &mut minion_storage).join() {
+                let char_id = match controller.controlled_entity {
+                    Some(char_id) => char_id,
+                    None => continue,
+                };
+                if let (Some(static_data), Some(char_state)) = (
+                    static_char_data_storage.get(char_id.into()),
+                    char_state_storage.get(char_id.into()),
+                ) {
+                    if static_data.team == attacked_team
+                        && (char_state.pos() - attacked_pos).magnitude() <= config.aggro_radius
+                    {
+                        minion.provoked_by = Some(Provocation {
+                            attacker_id: attack.attacker_id,
+                            until: now.add_seconds(config.provoked_seconds),
+                        });
+                    }
+                }
+            }
+        }
+
+        for (controller_id, controller, minion) in
+            (&entities, &mut controller_storage, &mut minion_storage).join()
+        {
+            let minion_char = controller.controlled_entity.and_then(|char_id| {
+                let static_data = static_char_data_storage.get(char_id.into())?;
+                let char_state = char_state_storage.get(char_id.into())?;
+                Some((char_id, static_data, char_state))
+            });
+            let (char_id, static_data, char_state) = match minion_char {
+                Some(minion_char) => minion_char,
+                None => {
+                    // the corpse has been removed
+                    if let Err(e) = entities.delete(controller_id) {
+                        log::error!("Could not remove minion controller: {}", e);
+                    }
+                    continue;
+                }
+            };
+            if char_state.state().is_dead() {
+                controller.intention = None;
+                continue;
+            }
+            let pos = char_state.pos();
+            let team = static_data.team;
+
+            // Some(is_minion) if the minion can keep attacking it
+            let chasable = |target_id: EntityId<Local>| -> Option<bool> {
+                let target_static_data = static_char_data_storage.get(target_id.into())?;
+                let target_state = char_state_storage.get(target_id.into())?;
+                let in_range = (target_state.pos() - pos).magnitude() <= config.chase_radius;
+                if target_state.state().is_alive()
+                    && target_static_data.team.is_enemy_to(team)
+                    && in_range
+                {
+                    Some(target_static_data.typ == CharType::Minion)
+                } else {
+                    None
+                }
+            };
+
+            let provocation_is_over = minion
+                .provoked_by
+                .as_ref()
+                .map(|it| it.until.has_already_passed(now))
+                .unwrap_or(false);
+            if provocation_is_over {
+                minion.provoked_by = None;
+            }
+            let provoker = minion
+                .provoked_by
+                .as_ref()
+                .map(|it| it.attacker_id)
+                .filter(|attacker_id| chasable(*attacker_id).is_some());
+            let current_target = match char_state.target {
+                Some(EntityTarget::OtherEntity(target_id)) => {
+                    chasable(target_id).map(|is_minion| (target_id, is_minion))
+                }
+                _ => None,
+            };
+            let closest_enemy = closest_enemy_in_area(
+                &entities,
+                &static_char_data_storage,
+                &char_state_storage,
+                pos,
+                config.aggro_radius,
+                team,
+                char_id,
+                |it| match it.typ {
+                    CharType::Minion => Some(0),
+                    CharType::Structure => Some(2),
+                    _ => Some(1),
+                },
+            )
+            .map(|enemy_id| (enemy_id, chasable(enemy_id) == Some(true)));
+            let target = provoker.or_else(|| match (current_target, closest_enemy) {
+                // a minion coming into range takes the attention from the players
+                (Some((_, false)), Some((enemy_id, true))) => Some(enemy_id),
+                (Some((target_id, _)), _) => Some(target_id),
+                (None, closest_enemy) => closest_enemy.map(|(enemy_id, _)| enemy_id),
+            });
+
+            let lane = minion.lane.and_then(|index| lanes.get(index));
+            controller.intention = match target {
+                Some(target_id) => Some(PlayerIntention::Attack(target_id)),
+                None => match lane {
+                    Some(lane) => minion
+                        .next_waypoint(pos, lane, config.waypoint_reached_radius)
+                        .map(PlayerIntention::MoveTo),
+                    // gives up the chase and stays where it is
+                    None if char_state.target.is_some() => Some(PlayerIntention::MoveTo(pos)),
+                    None => None,
+                },
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::v2;
+    use crate::components::minion::{LaneWaypoint, MinionLane};
+    use crate::test_utils::{assert_attacks, create_char, intention_of};
+    use std::time::Duration;
+
+    fn create_world() -> World {
+        let mut world = crate::test_utils::create_world();
+        world.register::<MinionComponent>();
+        world.insert(EngineTime::new_for_tests(Duration::from_millis(100)));
+        world.insert(Vec::<CharAttacked>::new());
+        world.insert(MinionConfig {
+            aggro_radius: 10.0,
+            chase_radius: 15.0,
+            provoked_seconds: 3.0,
+            waypoint_reached_radius: 1.0,
+            first_wave_seconds: 0.0,
+            wave_interval_seconds: 30.0,
+            melee_minions_per_wave: 1,
+            ranged_minions_per_wave: 1,
+        });
+        world.insert(MinionLanes {
+            lanes: vec![MinionLane {
+                map_name: "test".to_owned(),
+                team: Team::Left,
+                waypoints: vec![
+                    LaneWaypoint { x: 0.0, y: 0.0 },
+                    LaneWaypoint { x: 20.0, y: 0.0 },
+                    LaneWaypoint { x: 40.0, y: -20.0 },
+                ],
+            }],
+        });
+        world
+    }
+
+    fn create_minion(world: &mut World, pos: Vec2) -> (EntityId<Local>, Entity) {
+        let char_id = create_char(world, CharType::Minion, Team::Left, pos);
+        let controller_id = world
+            .create_entity()
+            .with(ControllerComponent::new(char_id))
+            .with(MinionComponent::new(Some(0), 0))
+            .build();
+        (char_id, controller_id)
+    }
+
+    fn assert_moves_to(world: &World, controller_id: Entity, expected: Vec2) {
+        match intention_of(world, controller_id) {
+            Some(PlayerIntention::MoveTo(pos)) => assert_eq!(pos, expected),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn minion_walks_along_its_lane() {
+        let mut world = create_world();
+        let (_char_id, controller_id) = create_minion(&mut world, v2(0.0, 0.0));
+
+        MinionAiSystem.run_now(&world);
+
+        assert_moves_to(&world, controller_id, v2(20.0, 0.0));
+    }
+
+    #[test]
+    fn passed_waypoints_are_skipped() {
+        let mut world = create_world();
+        // e.g. it has chased an enemy beyond the second waypoint
+        let (_char_id, controller_id) = create_minion(&mut world, v2(25.0, -3.0));
+
+        MinionAiSystem.run_now(&world);
+
+        assert_moves_to(&world, controller_id, v2(40.0, -20.0));
+    }
+
+    #[test]
+    fn enemy_minions_are_preferred_over_the_players() {
+        let mut world = create_world();
+        let (_char_id, controller_id) = create_minion(&mut world, v2(0.0, 0.0));
+        let _player_id = create_char(&mut world, CharType::Player, Team::Right, v2(2.0, 0.0));
+        let enemy_minion_id = create_char(&mut world, CharType::Minion, Team::Right, v2(8.0, 0.0));
+        // out of the aggro range
+        let _far_minion_id = create_char(&mut world, CharType::Minion, Team::Right, v2(12.0, 0.0));
+
+        MinionAiSystem.run_now(&world);
+
+        assert_attacks(&world, controller_id, enemy_minion_id);
+    }
+
+    #[test]
+    fn player_attacking_an_ally_provokes_the_minion() {
+        let mut world = create_world();
+        let (_char_id, controller_id) = create_minion(&mut world, v2(0.0, 0.0));
+        let ally_id = create_char(&mut world, CharType::Player, Team::Left, v2(5.0, 0.0));
+        let player_id = create_char(&mut world, CharType::Player, Team::Right, v2(9.0, 0.0));
+        let enemy_minion_id = create_char(&mut world, CharType::Minion, Team::Right, v2(3.0, 0.0));
+        world
+            .write_resource::<Vec<CharAttacked>>()
+            .push(CharAttacked {
+                attacker_id: player_id,
+                attacked_id: ally_id,
+            });
+
+        MinionAiSystem.run_now(&world);
+        assert_attacks(&world, controller_id, player_id);
+
+        // the provocation is over
+        world.write_resource::<Vec<CharAttacked>>().clear();
+        for _ in 0..31 {
+            world
+                .write_resource::<EngineTime>()
+                .tick(Duration::from_millis(100));
+        }
+        MinionAiSystem.run_now(&world);
+        assert_attacks(&world, controller_id, enemy_minion_id);
+    }
+
+    #[test]
+    fn controller_is_removed_with_the_corpse() {
+        let mut world = create_world();
+        let (char_id, controller_id) = create_minion(&mut world, v2(0.0, 0.0));
+        world.delete_entity(char_id.into()).unwrap();
+        world.maintain();
+
+        MinionAiSystem.run_now(&world);
+        world.maintain();
+
+        assert!(!world.is_alive(controller_id));
+    }
+}
@@ -1,2 +1,9 @@
 pub mod char_state_sys;
+pub mod collision_sys;
+pub mod guardian_ai_sys;
 pub mod intention_applier;
+pub mod minion_ai_sys;
+pub mod projectile_sys;
+pub mod skill_sys;
+pub mod status_sys;
+pub mod turret_ai_sys;
@@ -0,0 +1,143 @@
+use specs::prelude::*;
+
+use crate::attack::HpModificationRequest;
+use crate::common::{EngineTime, Local};
+use crate::components::char::LocalCharStateComp;
+use crate::components::projectile::ProjectileComponent;
+
+/// Moves the projectiles towards their targets, they miss if their target has died or disappeared
+pub struct ProjectileSystem;
+
+impl<'a> System<'a> for ProjectileSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, ProjectileComponent>,
+        ReadStorage<'a, LocalCharStateComp<Local>>,
+        ReadExpect<'a, EngineTime>,
+        WriteExpect<'a, Vec<HpModificationRequest>>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, mut projectile_storage, auth_char_storage, time, mut hp_mod_requests): Self::SystemData,
+    ) {
+        let now = time.now();
+        for (projectile_entity_id, projectile) in (&entities, &mut projectile_storage).join() {
+            let target_pos = auth_char_storage
+                .get(projectile.target_id.into())
+                .filter(|target| target.state().is_alive())
+                .map(|target| target.pos());
+            let finished = match target_pos {
+                Some(target_pos) => {
+                    let arrived = projectile.fly_towards(target_pos, now);
+                    if arrived {
+                        hp_mod_requests.push(projectile.hp_mod_request());
+                    }
+                    arrived
+                }
+                None => {
+                    log::trace!(
+                        "Projectile of {:?} has missed {:?}",
+                        projectile.caster_id,
+                        projectile.target_id
+                    );
+                    true
+                }
+            };
+            if finished {
+                if let Err(e) = entities.delete(projectile_entity_id) {
+                    log::error!("Could not remove projectile: {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::{HpModificationType, WeaponType};
+    use crate::common::v2;
+    use crate::components::char::{CharState, CharType, EntityId, Team};
+    use crate::test_utils::create_char;
+    use std::time::Duration;
+
+    fn create_world() -> World {
+        let mut world = crate::test_utils::create_world();
+        world.register::<ProjectileComponent>();
+        world.insert(EngineTime::new_for_tests(Duration::from_millis(500)));
+        world.insert(Vec::<HpModificationRequest>::new());
+        world
+    }
+
+    fn fire(world: &mut World, caster_id: EntityId<Local>, target_id: EntityId<Local>) {
+        let projectile = ProjectileComponent {
+            caster_id,
+            target_id,
+            weapon_type: WeaponType::Arrow,
+            pos: v2(0.0, 0.0),
+            damage: 30,
+            updated_at: world.read_resource::<EngineTime>().now(),
+        };
+        world.create_entity().with(projectile).build();
+    }
+
+    fn run_tick(world: &mut World) {
+        world
+            .write_resource::<EngineTime>()
+            .tick(Duration::from_millis(500));
+        ProjectileSystem.run_now(world);
+        world.maintain();
+    }
+
+    fn projectile_count(world: &World) -> usize {
+        world.read_storage::<ProjectileComponent>().join().count()
+    }
+
+    #[test]
+    fn projectile_damages_its_target_on_arrival() {
+        let mut world = create_world();
+        let caster_id = create_char(&mut world, CharType::Player, Team::Left, v2(0.0, 0.0));
+        // the arrow needs 0.8 seconds to arrive
+        let target_id = create_char(&mut world, CharType::Player, Team::Right, v2(20.0, 0.0));
+        fire(&mut world, caster_id, target_id);
+
+        run_tick(&mut world);
+        assert_eq!(projectile_count(&world), 1);
+        assert!(world
+            .read_resource::<Vec<HpModificationRequest>>()
+            .is_empty());
+
+        run_tick(&mut world);
+        assert_eq!(projectile_count(&world), 0);
+        let hp_mod_requests = world.read_resource::<Vec<HpModificationRequest>>();
+        assert_eq!(hp_mod_requests.len(), 1);
+        assert_eq!(hp_mod_requests[0].src_entity, caster_id);
+        assert_eq!(hp_mod_requests[0].dst_entity, target_id);
+        match hp_mod_requests[0].typ {
+            HpModificationType::BasicDamage(30, _, WeaponType::Arrow) => {}
+            typ => panic!("unexpected hp modification: {:?}", typ),
+        }
+    }
+
+    #[test]
+    fn projectile_misses_if_its_target_has_died() {
+        let mut world = create_world();
+        let caster_id = create_char(&mut world, CharType::Player, Team::Left, v2(0.0, 0.0));
+        let target_id = create_char(&mut world, CharType::Player, Team::Right, v2(20.0, 0.0));
+        fire(&mut world, caster_id, target_id);
+
+        run_tick(&mut world);
+        world
+            .write_storage::<LocalCharStateComp<Local>>()
+            .get_mut(target_id.into())
+            .unwrap()
+            .set_state(CharState::Dead);
+        run_tick(&mut world);
+
+        assert_eq!(projectile_count(&world), 0);
+        assert!(world
+            .read_resource::<Vec<HpModificationRequest>>()
+            .is_empty());
+    }
+}
@@ -0,0 +1,72 @@
+use specs::prelude::*;
+
+use crate::attack::{ApplyForceComponent, AreaAttackComponent, HpModificationRequest};
+use crate::common::{EngineTime, Local};
+use crate::components::char::{LocalCharStateComp, StaticCharDataComponent};
+use crate::components::controller::ControllerComponent;
+use crate::components::skills::skills::{
+    SkillManifestationComponent, SkillManifestationEnded, SkillManifestationUpdateParam,
+};
+use crate::components::status::status::{
+    ApplyStatusComponent, ApplyStatusInAreaComponent, RemoveStatusComponent,
+};
+
+pub struct SkillSystem;
+
+impl<'a> System<'a> for SkillSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, StaticCharDataComponent>,
+        WriteStorage<'a, LocalCharStateComp<Local>>,
+        ReadStorage<'a, ControllerComponent>,
+        ReadExpect<'a, EngineTime>,
+        WriteStorage<'a, SkillManifestationComponent>,
+        Write<'a, LazyUpdate>,
+        WriteExpect<'a, Vec<HpModificationRequest>>,
+        WriteExpect<'a, Vec<AreaAttackComponent>>,
+        WriteExpect<'a, Vec<ApplyForceComponent>>,
+        WriteExpect<'a, Vec<ApplyStatusComponent>>,
+        WriteExpect<'a, Vec<ApplyStatusInAreaComponent>>,
+        WriteExpect<'a, Vec<RemoveStatusComponent>>,
+        WriteExpect<'a, Vec<SkillManifestationEnded>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            static_data_storage,
+            mut auth_char_storage,
+            controller_storage,
+            time,
+            mut skill_storage,
+            updater,
+            mut hp_mod_requests,
+            mut area_hp_mod_requests,
+            mut pushes,
+            mut apply_statuses,
+            mut apply_area_statuses,
+            mut remove_statuses,
+            mut ended_manifestations,
+        ): Self::SystemData,
+    ) {
+        for (entity_id, skill) in (&entities, &mut skill_storage).join() {
+            skill.update(SkillManifestationUpdateParam::new(
+                entity_id,
+                &mut hp_mod_requests,
+                &mut area_hp_mod_requests,
+                &mut pushes,
+                &mut apply_statuses,
+                &mut apply_area_statuses,
+                &mut remove_statuses,
+                &mut ended_manifestations,
+                &time,
+                &entities,
+                &static_data_storage,
+                &mut auth_char_storage,
+                &controller_storage,
+                &updater,
+            ));
+        }
+    }
+}
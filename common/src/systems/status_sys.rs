@@ -0,0 +1,129 @@
+use specs::prelude::*;
+
+use crate::attack::{AreaAttackComponent, HpModificationRequest};
+use crate::collision::CHAR_COLLISION_RADIUS;
+use crate::common::{EngineTime, Local};
+use crate::components::char::{EntityId, LocalCharStateComp, StaticCharDataComponent};
+use crate::components::status::status::{
+    ApplyStatusComponent, ApplyStatusInAreaComponent, RemoveStatusComponent,
+    RemoveStatusComponentPayload, Statuses,
+};
+use crate::config::CommonConfigs;
+
+/// Applies the requested status changes and updates the active statuses of the characters,
+/// then recalculates the attributes and the replicated statuses of the character states
+pub struct StatusUpdateSystem;
+
+impl<'a> System<'a> for StatusUpdateSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, LocalCharStateComp<Local>>,
+        ReadStorage<'a, StaticCharDataComponent>,
+        WriteStorage<'a, Statuses>,
+        ReadExpect<'a, EngineTime>,
+        ReadExpect<'a, CommonConfigs>,
+        WriteExpect<'a, Vec<HpModificationRequest>>,
+        WriteExpect<'a, Vec<AreaAttackComponent>>,
+        WriteExpect<'a, Vec<ApplyStatusComponent>>,
+        WriteExpect<'a, Vec<ApplyStatusInAreaComponent>>,
+        WriteExpect<'a, Vec<RemoveStatusComponent>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut auth_char_storage,
+            static_char_data_storage,
+            mut statuses_storage,
+            time,
+            configs,
+            mut hp_mod_requests,
+            mut area_hp_mod_requests,
+            mut apply_statuses,
+            mut apply_area_statuses,
+            mut remove_statuses,
+        ): Self::SystemData,
+    ) {
+        for area_status_change in apply_area_statuses.drain(..) {
+            for (target_id, target_state, target_static_data) in
+                (&entities, &auth_char_storage, &static_char_data_storage).join()
+            {
+                let target_id = EntityId::new(target_id);
+                let is_target = area_status_change.except != Some(target_id)
+                    && area_status_change
+                        .caster_team
+                        .is_compatible(area_status_change.nature, target_static_data.team)
+                    && area_status_change
+                        .area_shape
+                        .overlaps_circle(&target_state.pos(), CHAR_COLLISION_RADIUS);
+                if is_target {
+                    apply_statuses.push(ApplyStatusComponent::from_status(
+                        area_status_change.source_entity_id,
+                        target_id,
+                        area_status_change.status.clone(),
+                    ));
+                }
+            }
+        }
+
+        for status_change in apply_statuses.drain(..) {
+            let is_alive = auth_char_storage
+                .get(status_change.target_entity_id.into())
+                .map(|it| it.state().is_alive())
+                .unwrap_or(false);
+            if !is_alive {
+                continue;
+            }
+            if let Some(statuses) = statuses_storage.get_mut(status_change.target_entity_id.into())
+            {
+                log::debug!(
+                    "Applying state '{:?}' on {:?}",
+                    status_change.status,
+                    status_change.target_entity_id
+                );
+                statuses.add(status_change.status);
+            }
+        }
+
+        for status_change in remove_statuses.drain(..) {
+            if let Some(statuses) = statuses_storage.get_mut(status_change.target_entity_id.into())
+            {
+                match &status_change.status {
+                    RemoveStatusComponentPayload::RemovingStatusType(status_type) => {
+                        statuses.remove_by_nature(*status_type);
+                    }
+                    RemoveStatusComponentPayload::RemovingStatusDiscr(discr) => {
+                        statuses.remove(*discr)
+                    }
+                }
+            }
+        }
+
+        for (char_entity_id, statuses, auth_state) in
+            (&entities, &mut statuses_storage, &mut auth_char_storage).join()
+        {
+            let removed_statuses = statuses.update(
+                EntityId::new(char_entity_id),
+                auth_state,
+                &time,
+                &mut hp_mod_requests,
+                &mut area_hp_mod_requests,
+                &mut apply_area_statuses,
+            );
+            if removed_statuses > 0 {
+                statuses.remove_statuses(removed_statuses);
+            }
+        }
+
+        for (auth_state, static_data, statuses) in (
+            &mut auth_char_storage,
+            &static_char_data_storage,
+            &mut statuses_storage,
+        )
+            .join()
+        {
+            auth_state.recalc_attribs_based_on_statuses(static_data.job_id, statuses, &configs);
+        }
+    }
+}
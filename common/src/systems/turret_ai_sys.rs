@@ -0,0 +1,189 @@
+use specs::prelude::*;
+
+use crate::common::Local;
+use crate::components::char::{
+    EntityId, EntityTarget, LocalCharStateComp, StaticCharDataComponent,
+};
+use crate::components::controller::{ControllerComponent, PlayerIntention};
+use crate::components::turret::{TurretComponent, TurretControllerComponent};
+use crate::systems::minion_ai_sys::closest_enemy_in_area;
+
+/// Picks the targets of the turrets. The target chosen by the owner of the turret
+/// is preferred while it is in range, otherwise the turret keeps attacking its current target
+/// until it dies or leaves the range, then it attacks the closest enemy.
+pub struct TurretAiSystem;
+
+impl<'a> System<'a> for TurretAiSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, ControllerComponent>,
+        ReadStorage<'a, TurretControllerComponent>,
+        ReadStorage<'a, TurretComponent>,
+        ReadStorage<'a, StaticCharDataComponent>,
+        ReadStorage<'a, LocalCharStateComp<Local>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut controller_storage,
+            turret_controller_storage,
+            turret_storage,
+            static_char_data_storage,
+            char_state_storage,
+        ): Self::SystemData,
+    ) {
+        for (controller_id, controller, _turret_controller) in (
+            &entities,
+            &mut controller_storage,
+            &turret_controller_storage,
+        )
+            .join()
+        {
+            let turret_char = controller.controlled_entity.and_then(|char_id| {
+                let static_data = static_char_data_storage.get(char_id.into())?;
+                let char_state = char_state_storage.get(char_id.into())?;
+                let turret = turret_storage.get(char_id.into())?;
+                Some((char_id, static_data, char_state, turret))
+            });
+            let (char_id, static_data, char_state, turret) = match turret_char {
+                Some(turret_char) => turret_char,
+                None => {
+                    // the turret has been removed
+                    if let Err(e) = entities.delete(controller_id) {
+                        log::error!("Could not remove turret controller: {}", e);
+                    }
+                    continue;
+                }
+            };
+            if char_state.state().is_dead() {
+                controller.intention = None;
+                continue;
+            }
+            let pos = char_state.pos();
+            let team = static_data.team;
+            let radius = char_state.attack_distance();
+
+            let can_be_attacked = |target_id: EntityId<Local>| -> bool {
+                match (
+                    static_char_data_storage.get(target_id.into()),
+                    char_state_storage.get(target_id.into()),
+                ) {
+                    (Some(target_static_data), Some(target_state)) => {
+                        target_state.state().is_alive()
+                            && target_static_data.team.is_enemy_to(team)
+                            && (target_state.pos() - pos).magnitude() <= radius
+                    }
+                    _ => false,
+                }
+            };
+
+            let preferred_target = turret
+                .preferred_target
+                .filter(|target_id| can_be_attacked(*target_id));
+            let current_target = match char_state.target {
+                Some(EntityTarget::OtherEntity(target_id)) if can_be_attacked(target_id) => {
+                    Some(target_id)
+                }
+                _ => None,
+            };
+            let target = preferred_target.or(current_target).or_else(|| {
+                closest_enemy_in_area(
+                    &entities,
+                    &static_char_data_storage,
+                    &char_state_storage,
+                    pos,
+                    radius,
+                    team,
+                    char_id,
+                    |_static_data| Some(0),
+                )
+            });
+            controller.intention = target.map(PlayerIntention::Attack);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::char_attr::CharAttributes;
+    use crate::common::{percentage, v2, Vec2};
+    use crate::components::char::{CharType, Team};
+    use crate::test_utils::{attributes, create_char_from, intention_of, static_data};
+
+    fn create_world() -> World {
+        let mut world = crate::test_utils::create_world();
+        world.register::<TurretComponent>();
+        world.register::<TurretControllerComponent>();
+        world
+    }
+
+    fn create_char(world: &mut World, team: Team, pos: Vec2) -> EntityId<Local> {
+        let state = LocalCharStateComp::new(
+            pos,
+            CharAttributes {
+                // 10 units
+                attack_range: percentage(500),
+                ..attributes()
+            },
+        );
+        create_char_from(world, state, static_data(CharType::Player, team))
+    }
+
+    fn create_turret(
+        world: &mut World,
+        owner_id: EntityId<Local>,
+        preferred_target: Option<EntityId<Local>>,
+    ) -> Entity {
+        let char_id = create_char(world, Team::Left, v2(0.0, 0.0));
+        world
+            .write_storage::<TurretComponent>()
+            .insert(
+                char_id.into(),
+                TurretComponent {
+                    owner_entity_id: owner_id,
+                    preferred_target,
+                },
+            )
+            .unwrap();
+        world
+            .create_entity()
+            .with(ControllerComponent::new(char_id))
+            .with(TurretControllerComponent)
+            .build()
+    }
+
+    #[test]
+    fn turret_attacks_the_preferred_target_in_range() {
+        let mut world = create_world();
+        let owner_id = create_char(&mut world, Team::Left, v2(1.0, 0.0));
+        let _closest_enemy_id = create_char(&mut world, Team::Right, v2(2.0, 0.0));
+        let preferred_id = create_char(&mut world, Team::Right, v2(8.0, 0.0));
+        let turret = create_turret(&mut world, owner_id, Some(preferred_id));
+
+        TurretAiSystem.run_now(&world);
+
+        match intention_of(&world, turret) {
+            Some(PlayerIntention::Attack(target_id)) => assert_eq!(target_id, preferred_id),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn turret_attacks_the_closest_enemy_when_the_preferred_is_out_of_range() {
+        let mut world = create_world();
+        let owner_id = create_char(&mut world, Team::Left, v2(1.0, 0.0));
+        let closest_enemy_id = create_char(&mut world, Team::Right, v2(2.0, 0.0));
+        let preferred_id = create_char(&mut world, Team::Right, v2(12.0, 0.0));
+        let turret = create_turret(&mut world, owner_id, Some(preferred_id));
+
+        TurretAiSystem.run_now(&world);
+
+        match intention_of(&world, turret) {
+            Some(PlayerIntention::Attack(target_id)) => assert_eq!(target_id, closest_enemy_id),
+            other => panic!("{:?}", other),
+        }
+    }
+}
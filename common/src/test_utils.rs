@@ -0,0 +1,76 @@
+//! Fixtures of the system tests, the server tests use them through the `test_utils` feature
+use specs::prelude::*;
+
+use crate::attack::BasicAttackType;
+use crate::char_attr::CharAttributes;
+use crate::common::{Local, Vec2};
+use crate::components::char::{
+    CharOutlook, CharType, EntityId, JobId, LocalCharStateComp, Sex, StaticCharDataComponent, Team,
+};
+use crate::components::controller::{ControllerComponent, PlayerIntention};
+use crate::components::job_ids::JobSpriteId;
+
+/// The storages of the characters and their controllers, the systems under test
+/// register the rest
+pub fn create_world() -> World {
+    let mut world = World::new();
+    world.register::<LocalCharStateComp<Local>>();
+    world.register::<StaticCharDataComponent>();
+    world.register::<ControllerComponent>();
+    world
+}
+
+/// 100 hp, everything else is the default
+pub fn attributes() -> CharAttributes {
+    CharAttributes {
+        max_hp: 100,
+        ..Default::default()
+    }
+}
+
+pub fn static_data(typ: CharType, team: Team) -> StaticCharDataComponent {
+    StaticCharDataComponent {
+        name: "Test".to_owned(),
+        team,
+        basic_attack_type: BasicAttackType::MeleeSimple,
+        typ,
+        outlook: CharOutlook::Human {
+            job_sprite_id: JobSpriteId::NOVICE,
+            head_index: 0,
+            sex: Sex::Male,
+        },
+        job_id: JobId::SWORDMAN,
+    }
+}
+
+pub fn create_char(world: &mut World, typ: CharType, team: Team, pos: Vec2) -> EntityId<Local> {
+    create_char_from(
+        world,
+        LocalCharStateComp::new(pos, attributes()),
+        static_data(typ, team),
+    )
+}
+
+pub fn create_char_from(
+    world: &mut World,
+    state: LocalCharStateComp<Local>,
+    static_data: StaticCharDataComponent,
+) -> EntityId<Local> {
+    EntityId::new(world.create_entity().with(state).with(static_data).build())
+}
+
+pub fn intention_of(world: &World, controller_id: Entity) -> Option<PlayerIntention<Local>> {
+    world
+        .read_storage::<ControllerComponent>()
+        .get(controller_id)
+        .unwrap()
+        .intention
+        .clone()
+}
+
+pub fn assert_attacks(world: &World, controller_id: Entity, expected: EntityId<Local>) {
+    match intention_of(world, controller_id) {
+        Some(PlayerIntention::Attack(target_id)) => assert_eq!(target_id, expected),
+        other => panic!("{:?}", other),
+    }
+}
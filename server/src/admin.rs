@@ -0,0 +1,124 @@
+use rustarok_common::console::CommandArguments;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+
+const MAX_LINE_LEN: usize = 4096;
+
+struct AdminConnection {
+    stream: TcpStream,
+    addr: SocketAddr,
+    received: Vec<u8>,
+    closed: bool,
+}
+
+/// Line based protocol on a localhost port, e.g. `nc localhost 6970`.
+/// Every command is answered with its output followed by an `OK` line,
+/// or with a single `ERROR: <reason>` line.
+pub struct AdminConsole {
+    listener: TcpListener,
+    connections: Vec<AdminConnection>,
+}
+
+impl AdminConsole {
+    /// Only local connections are accepted, there is no authentication
+    pub fn bind(port: u16) -> std::io::Result<AdminConsole> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        listener.set_nonblocking(true)?;
+        Ok(AdminConsole {
+            listener,
+            connections: Vec::with_capacity(4),
+        })
+    }
+
+    /// Executes the commands which have arrived since the last call
+    pub fn process(&mut self, mut execute: impl FnMut(CommandArguments) -> Result<String, String>) {
+        self.accept_connections();
+        for connection in &mut self.connections {
+            for line in connection.read_lines() {
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                log::info!("Admin command from {}: {}", connection.addr, line);
+                let reply = match execute(CommandArguments::new(line)) {
+                    Ok(output) if output.is_empty() => "OK\n".to_owned(),
+                    Ok(output) => format!("{}\nOK\n", output),
+                    Err(reason) => format!("ERROR: {}\n", reason),
+                };
+                connection.write(&reply);
+            }
+        }
+        self.connections.retain(|it| !it.closed);
+    }
+
+    fn accept_connections(&mut self) {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, addr)) => {
+                    if let Err(e) = stream.set_nonblocking(true) {
+                        log::error!("Admin connection from {} is refused: {}", addr, e);
+                        continue;
+                    }
+                    log::info!("Admin has connected from {}", addr);
+                    self.connections.push(AdminConnection {
+                        stream,
+                        addr,
+                        received: Vec::with_capacity(256),
+                        closed: false,
+                    });
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    log::error!("Could not accept admin connection: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl AdminConnection {
+    fn read_lines(&mut self) -> Vec<String> {
+        let mut buf = [0u8; 512];
+        loop {
+            match self.stream.read(&mut buf) {
+                Ok(0) => {
+                    self.close("closed by the admin");
+                    break;
+                }
+                Ok(len) => self.received.extend_from_slice(&buf[..len]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    self.close(&e.to_string());
+                    break;
+                }
+            }
+        }
+        let mut lines = Vec::new();
+        while let Some(end) = self.received.iter().position(|it| *it == b'\n') {
+            let line: Vec<u8> = self.received.drain(..=end).collect();
+            lines.push(String::from_utf8_lossy(&line).into_owned());
+        }
+        if self.received.len() > MAX_LINE_LEN {
+            self.close("too long line");
+        }
+        lines
+    }
+
+    fn write(&mut self, text: &str) {
+        if let Err(e) = self.stream.write_all(text.as_bytes()) {
+            self.close(&e.to_string());
+        }
+    }
+
+    fn close(&mut self, reason: &str) {
+        if !self.closed {
+            log::info!(
+                "Admin connection from {} has been closed: {}",
+                self.addr,
+                reason
+            );
+            self.closed = true;
+        }
+    }
+}
@@ -3,8 +3,12 @@ use rustarok_common::attack::{
     ApplyForceComponent, AreaAttackComponent, HpModificationRequest, HpModificationResult,
     HpModificationResultType, HpModificationType,
 };
+use rustarok_common::collision::CHAR_COLLISION_RADIUS;
 use rustarok_common::common::{EngineTime, GameTime, Local, SimulationTick};
-use rustarok_common::components::char::{EntityId, LocalCharStateComp, StaticCharDataComponent};
+use rustarok_common::components::char::{
+    CharAttacked, EntityId, LocalCharStateComp, StaticCharDataComponent,
+};
+use rustarok_common::components::status::status::Statuses;
 use rustarok_common::config::CommonConfigs;
 use rustarok_common::packets::from_server::FromServerPacket;
 use specs::prelude::Join;
@@ -19,6 +23,7 @@ impl<'a> specs::System<'a> for AttackSystem {
         specs::Entities<'a>,
         WriteStorage<'a, LocalCharStateComp<Local>>,
         WriteStorage<'a, StaticCharDataComponent>,
+        WriteStorage<'a, Statuses>,
         ReadExpect<'a, EngineTime>,
         ReadExpect<'a, SimulationTick>,
         WriteExpect<'a, Vec<HpModificationRequest>>,
@@ -27,6 +32,7 @@ impl<'a> specs::System<'a> for AttackSystem {
         WriteExpect<'a, CommonConfigs>,
         specs::Write<'a, specs::LazyUpdate>,
         WriteExpect<'a, OutPacketCollector>,
+        WriteExpect<'a, Vec<CharAttacked>>,
         // Option<specs::Write<'a, Vec<SystemEvent>>>,
     );
 
@@ -35,32 +41,49 @@ impl<'a> specs::System<'a> for AttackSystem {
         (
             entities,
             mut auth_char_state_storage,
-            mut static_char_data_storage,
+            static_char_data_storage,
+            mut statuses_storage,
             time,
-            tick,
+            _tick,
             mut hp_mod_requests,
             mut area_hp_mod_requests,
             mut pushes,
-            dev_configs,
-            mut updater,
+            _dev_configs,
+            _updater,
             mut packet_sender,
+            mut attacks,
         ): Self::SystemData,
     ) {
+        // the AIs have already reacted to the attacks of the previous frame
+        attacks.clear();
         {
-            let mut new_hp_mod_reqs = area_hp_mod_requests
-                .iter()
-                .map(|area_hp_mod| {
-                    AttackCalculation::apply_hp_mod_on_area(
-                        &entities,
-                        &auth_char_state_storage,
-                        &area_hp_mod,
-                    )
-                })
-                .flatten();
+            let new_hp_mod_reqs = area_hp_mod_requests.iter().flat_map(|area_hp_mod| {
+                AttackCalculation::apply_hp_mod_on_area(
+                    &entities,
+                    &auth_char_state_storage,
+                    area_hp_mod,
+                )
+            });
             hp_mod_requests.extend(new_hp_mod_reqs);
             area_hp_mod_requests.clear();
         }
 
+        for push in pushes.drain(..) {
+            let allowed = statuses_storage
+                .get(push.dst_entity.into())
+                .map(|statuses| statuses.allow_push(&push))
+                .unwrap_or(true);
+            if !allowed {
+                log::trace!("Push was denied {:?}", push);
+                continue;
+            }
+            if let Some(char_state) = auth_char_state_storage.get_mut(push.dst_entity.into()) {
+                char_state.apply_force(&push, time.now());
+            }
+        }
+
+        // the requests caused by the statuses (e.g. reflected damages) are applied in the next frame
+        let mut triggered_hp_mod_reqs = Vec::new();
         for hp_mod_req in hp_mod_requests.drain(..) {
             // TODO: char_state.cannot_control_until should be defined by this code
             // TODO: enemies can cause damages over a period of time, while they can die and be removed,
@@ -73,7 +96,7 @@ impl<'a> specs::System<'a> for AttackSystem {
             let hp_mod_req_results = if let Some(src_char_state) =
                 auth_char_state_storage.get(hp_mod_req.src_entity.into())
             {
-                let src_auth_state = auth_char_state_storage
+                let _src_auth_state = auth_char_state_storage
                     .get(hp_mod_req.src_entity.into())
                     .unwrap();
                 if let Some(dst_char_state) =
@@ -117,14 +140,13 @@ impl<'a> specs::System<'a> for AttackSystem {
             log::trace!("Attack outcomes: {:?}", hp_mod_req_results);
 
             for hp_mod_req_result in hp_mod_req_results.into_iter() {
-                let (hp_mod_req_result, char_pos) = {
-                    // let attacked_entity_state =
-                    //     char_state_storage.get_mut(attacked_id.into()).unwrap();
-                    // let hp_mod_req_result = AttackCalculation::alter_requests_by_attacked_statuses(
-                    //     hp_mod_req_result,
-                    //     attacked_entity_state,
-                    //     &mut hp_mod_requests,
-                    // );
+                let (hp_mod_req_result, _char_pos) = {
+                    let hp_mod_req_result =
+                        if let Some(statuses) = statuses_storage.get_mut(attacked_id.into()) {
+                            statuses.hp_mod_is_calculated_but_not_applied_yet(hp_mod_req_result)
+                        } else {
+                            hp_mod_req_result
+                        };
 
                     let attacked_entity_auth_state =
                         auth_char_state_storage.get_mut(attacked_id.into()).unwrap();
@@ -133,14 +155,22 @@ impl<'a> specs::System<'a> for AttackSystem {
                         &hp_mod_req_result,
                         time.now(),
                     );
+                    match hp_mod_req_result.typ {
+                        HpModificationResultType::Ok(HpModificationType::Heal(_)) => {}
+                        HpModificationResultType::Ok(_) => attacks.push(CharAttacked {
+                            attacker_id,
+                            attacked_id,
+                        }),
+                        HpModificationResultType::Blocked | HpModificationResultType::Absorbed => {}
+                    }
 
-                    // attacked_entity_state
-                    //     .statuses
-                    //     .hp_mod_has_been_applied_on_me(
-                    //         attacked_id,
-                    //         &hp_mod_req_result,
-                    //         &mut hp_mod_requests,
-                    //     );
+                    if let Some(statuses) = statuses_storage.get_mut(attacked_id.into()) {
+                        statuses.hp_mod_has_been_applied_on_me(
+                            attacked_id,
+                            &hp_mod_req_result,
+                            &mut triggered_hp_mod_reqs,
+                        );
+                    }
                     // TODO: rather than this, create a common component which
                     // contains all the necessary info from which an other system will be able to
                     // generate the render and audio commands
@@ -149,16 +179,12 @@ impl<'a> specs::System<'a> for AttackSystem {
                     (hp_mod_req_result, attacked_entity_auth_state.pos())
                 };
 
-                {
-                    // let attacker_entity_state =
-                    //     char_state_storage.get_mut(attacker_id.into()).unwrap();
-                    // attacker_entity_state
-                    //     .statuses
-                    //     .hp_mod_has_been_applied_on_enemy(
-                    //         attacker_id,
-                    //         &hp_mod_req_result,
-                    //         &mut hp_mod_requests,
-                    //     );
+                if let Some(statuses) = statuses_storage.get_mut(attacker_id.into()) {
+                    statuses.hp_mod_has_been_applied_on_enemy(
+                        attacker_id,
+                        &hp_mod_req_result,
+                        &mut triggered_hp_mod_reqs,
+                    );
                 }
 
                 send_packet(
@@ -201,6 +227,7 @@ impl<'a> specs::System<'a> for AttackSystem {
                 // }
             }
         }
+        hp_mod_requests.extend(triggered_hp_mod_reqs);
     }
 }
 
@@ -298,23 +325,18 @@ impl AttackCalculation {
                 continue;
             }
 
-            // for optimized, shape-specific queries
-            // ncollide2d::query::distance_internal::
-            // TODO2
-            //            let coll_result = ncollide2d::query::proximity(
-            //                &area_hpmod_req.area_isom,
-            //                &*area_hpmod_req.area_shape,
-            //                &Isometry2::new(char_state.pos(), 0.0),
-            //                &ncollide2d::shape::Ball::new(1.0),
-            //                0.1,
-            //            );
-            //            if coll_result == Proximity::Intersecting {
-            //                result_attacks.push(HpModificationRequest {
-            //                    src_entity: area_hpmod_req.source_entity_id,
-            //                    dst_entity: target_entity_id,
-            //                    typ: area_hpmod_req.typ,
-            //                });
-            //            }
+            // the team validity is checked later for each requests
+            if char_state.state().is_alive()
+                && area_hpmod_req
+                    .area_shape
+                    .overlaps_circle(&char_state.pos(), CHAR_COLLISION_RADIUS)
+            {
+                result_attacks.push(HpModificationRequest {
+                    src_entity: area_hpmod_req.source_entity_id,
+                    dst_entity: target_entity_id,
+                    typ: area_hpmod_req.typ,
+                });
+            }
         }
         return result_attacks;
     }
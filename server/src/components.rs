@@ -1,3 +1 @@
-use rustarok_common::packets::{SocketBuffer, SocketId};
-use specs::prelude::*;
-use std::net::{SocketAddr, TcpStream};
+
@@ -1,9 +1,59 @@
-use rustarok_common::config::{CommonConfigs, DevConfigStats, SkillsConfig};
+use rustarok_common::common::{v2, Vec2};
+use rustarok_common::components::char::{JobId, Team};
+use rustarok_common::components::guardian::GuardianConfig;
+use rustarok_common::components::minion::{MinionConfig, MinionLane};
+use rustarok_common::config::CommonConfigs;
 use serde::Deserialize;
 use serde::Serialize;
 
-#[derive(Debug, Deserialize)]
-pub struct ServerConfig {}
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServerConfig {
+    /// Clients get information only about the entities inside this radius
+    pub view_radius: f32,
+    /// The intentions are evaluated against the positions the client saw at most this long ago
+    pub max_lag_compensation_ms: usize,
+    /// The corpses of the non-player characters are removed after this
+    pub corpse_removal_seconds: f32,
+    pub spawn_points: Vec<SpawnPoint>,
+    pub minions: MinionConfig,
+    /// The lanes of every map, only the ones of the current map are used
+    pub lanes: Vec<MinionLane>,
+    pub guardians: GuardianConfig,
+    /// The structures of every map, only the ones of the current map are built for the matches
+    pub structures: Vec<StructureDefinition>,
+}
+
+/// The players of the team start the match here, and respawn here after they died
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpawnPoint {
+    pub team: Team,
+    pub x: f32,
+    pub y: f32,
+    /// 0 disables the respawning, the dead players remain dead until the end of the match
+    pub respawn_seconds: f32,
+}
+
+impl SpawnPoint {
+    pub fn pos(&self) -> Vec2 {
+        v2(self.x, self.y)
+    }
+}
+
+/// A tower or a core, the job determines its attributes and whether it attacks
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StructureDefinition {
+    pub map_name: String,
+    pub team: Team,
+    pub job_id: JobId,
+    pub x: f32,
+    pub y: f32,
+}
+
+impl StructureDefinition {
+    pub fn pos(&self) -> Vec2 {
+        v2(self.x, self.y)
+    }
+}
 
 impl ServerConfig {
     pub fn new(filename: &str) -> Result<Self, config::ConfigError> {
@@ -11,6 +61,10 @@ impl ServerConfig {
         s.merge(config::File::with_name(filename))?;
         return s.try_into();
     }
+
+    pub fn spawn_point(&self, team: Team) -> Option<&SpawnPoint> {
+        self.spawn_points.iter().find(|it| it.team == team)
+    }
 }
 
 pub fn load_common_configs(filename: &str) -> Result<CommonConfigs, config::ConfigError> {
@@ -0,0 +1,54 @@
+use crossbeam_channel::Receiver;
+use notify::{Op, RawEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use std::ffi::OsString;
+use std::path::Path;
+
+/// Notices the modifications of a file, e.g. when a designer saves `config-runtime.toml`
+pub struct ConfigWatcher {
+    // the watching stops when it is dropped
+    _watcher: RecommendedWatcher,
+    events: Receiver<RawEvent>,
+    file_name: Option<OsString>,
+}
+
+impl ConfigWatcher {
+    /// The directory of the file is watched, because many editors replace the file
+    /// instead of writing into it
+    pub fn new(path: &str) -> notify::Result<ConfigWatcher> {
+        let path = Path::new(path);
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let (tx, events) = crossbeam_channel::unbounded();
+        let mut watcher: RecommendedWatcher = Watcher::new_immediate(tx)?;
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        Ok(ConfigWatcher {
+            _watcher: watcher,
+            events,
+            file_name: path.file_name().map(|it| it.to_owned()),
+        })
+    }
+
+    /// An editor usually generates several events for a single save,
+    /// they are merged into one until the next call
+    pub fn take_change(&self) -> bool {
+        let mut changed = false;
+        for event in self.events.try_iter() {
+            match event.op {
+                Ok(op) => {
+                    let is_write =
+                        op.intersects(Op::CREATE | Op::WRITE | Op::CLOSE_WRITE | Op::RENAME);
+                    if is_write
+                        && event.path.as_ref().and_then(|it| it.file_name())
+                            == self.file_name.as_deref()
+                    {
+                        changed = true;
+                    }
+                }
+                Err(e) => log::error!("Config watcher error: {}", e),
+            }
+        }
+        changed
+    }
+}
@@ -1,43 +1,66 @@
+use crate::minion::create_minion;
+use crate::prepare_entity_id_for_sending;
 use crate::server_config::load_common_configs;
+use crate::structure::{create_guard, create_structure};
 use crate::OutPacketCollector;
 use crate::PacketTarget;
-use rand::Rng;
-use rustarok_common::char_attr::CharAttributes;
-use rustarok_common::common::{v2, v3_to_v2, Local, Vec2};
+use rustarok_common::attack::{HpModificationRequest, HpModificationType};
+use rustarok_common::common::{percentage, v2, EngineTime, GameTime, Local, Vec2};
 use rustarok_common::components::char::{
     CharOutlook, CharType, ControllerEntityId, EntityId, JobId, LocalCharStateComp, MonsterId, Sex,
     StaticCharDataComponent, Team,
 };
 use rustarok_common::components::controller::ControllerComponent;
 use rustarok_common::components::job_ids::JobSpriteId;
-use rustarok_common::config::CommonConfigs;
-use rustarok_common::console::CommandArguments;
-use rustarok_common::map::{CellType, MapWalkingInfo};
+use rustarok_common::components::minion::MinionLanes;
+use rustarok_common::components::skills::absorb_shield::AbsorbStatus;
+use rustarok_common::components::status::attack_heal_status::AttackHealStatus;
+use rustarok_common::components::status::attrib_mod::ArmorModifierStatus;
+use rustarok_common::components::status::reflect_damage_status::ReflectDamageStatus;
+use rustarok_common::components::status::status::{
+    ApplyStatusComponent, PoisonStatus, StatusEnum, Statuses,
+};
+use rustarok_common::config::{CommonConfigs, ConfigSection};
+use rustarok_common::console::{find_server_command, CommandArguments, SPAWNABLE_TYPES};
+use rustarok_common::map::MapWalkingInfo;
 use rustarok_common::packets::from_server::FromServerPacket;
-use rustarok_common::packets::SocketId;
 use specs::world::Builder;
 use specs::world::WorldExt;
 use specs::Join;
 use std::str::FromStr;
 
+/// The result of these commands depends on the files on the disk, they are not executed during
+/// the playback of a replay, their effect is recorded separately (e.g. `ReplayEvent::ConfigsChanged`)
+pub fn reads_files(args: &CommandArguments) -> bool {
+    args.get_command_name() == Some("reload_configs")
+}
+
+/// Executes the commands of `SERVER_COMMANDS`, they modify the simulation, so they are
+/// recorded in the replays as well. The caller is responsible for the permission check.
 pub fn execute_console_cmd(
     controller_id: Option<ControllerEntityId>,
     args: CommandArguments,
     ecs_world: &mut specs::World,
-) {
-    match args.get_command_name() {
-        Some("kill_all") => {
-            cmd_kill_all(controller_id, args, ecs_world);
-        }
-        Some("reload_configs") => {
-            cmd_reload_configs(controller_id, args, ecs_world);
-        }
-        Some("spawn_entity") => {
-            cmd_spawn_entity(controller_id, args, ecs_world);
-        }
-        _ => {
-            log::error!("Unknown command: {:?}", args.get_command_name());
-        }
+) -> Result<(), String> {
+    let command_def = args
+        .get_command_name()
+        .and_then(find_server_command)
+        .ok_or_else(|| format!("Unknown command: {:?}", args.get_command_name()))?;
+    command_def.validate(&args)?;
+    match command_def.name {
+        "kill_all" => cmd_kill_all(controller_id, args, ecs_world),
+        "reload_configs" => cmd_reload_configs(controller_id, args, ecs_world),
+        "spawn_entity" => cmd_spawn_entity(controller_id, args, ecs_world),
+        "set_job" => cmd_set_job(controller_id, args, ecs_world),
+        "set_outlook" => cmd_set_outlook(controller_id, args, ecs_world),
+        "set_team" => cmd_set_team(controller_id, args, ecs_world),
+        "heal" => cmd_heal(controller_id, args, ecs_world),
+        "add_status" => cmd_add_status(controller_id, args, ecs_world),
+        "resurrect" => cmd_resurrect(controller_id, args, ecs_world),
+        "set_pos" => cmd_set_pos(controller_id, args, ecs_world),
+        "goto" => cmd_goto(controller_id, args, ecs_world),
+        "clone" => cmd_clone_char(controller_id, args, ecs_world),
+        name => Err(format!("'{}' is not implemented by the server", name)),
     }
 }
 
@@ -53,71 +76,391 @@ fn get_client_char_id(
     })
 }
 
+fn find_char_by_name(name: &str, ecs_world: &specs::World) -> Result<EntityId<Local>, String> {
+    (
+        &ecs_world.entities(),
+        &ecs_world.read_storage::<StaticCharDataComponent>(),
+    )
+        .join()
+        .find(|(_entity_id, static_data)| static_data.name == name)
+        .map(|(entity_id, _static_data)| EntityId::from(entity_id))
+        .ok_or_else(|| format!("There is no character named '{}'", name))
+}
+
+/// The character of the caller if the name is omitted
+fn get_target_char_id(
+    controller_id: Option<ControllerEntityId>,
+    name: Option<&str>,
+    ecs_world: &mut specs::World,
+) -> Result<EntityId<Local>, String> {
+    match name {
+        Some(name) => find_char_by_name(name, ecs_world),
+        None => get_client_char_id(controller_id, ecs_world)
+            .ok_or_else(|| "The name of the character is missing".to_owned()),
+    }
+}
+
+fn parse_team(name: Option<&str>) -> Result<Team, String> {
+    match name {
+        Some("left") => Ok(Team::Left),
+        Some("right") => Ok(Team::Right),
+        _ => Err("The team must be 'left' or 'right'".to_owned()),
+    }
+}
+
+/// The clients got the static data only once, in `NewEntity`
+fn modify_static_data(
+    char_id: EntityId<Local>,
+    ecs_world: &mut specs::World,
+    modify: impl FnOnce(&mut StaticCharDataComponent),
+) {
+    let mut static_data_storage = ecs_world.write_storage::<StaticCharDataComponent>();
+    if let Some(static_data) = static_data_storage.get_mut(char_id.into()) {
+        modify(static_data);
+        ecs_world.write_resource::<OutPacketCollector>().push((
+            PacketTarget::All,
+            FromServerPacket::StaticDataChanged {
+                id: prepare_entity_id_for_sending(char_id),
+                data: static_data.clone(),
+            },
+        ));
+    }
+}
+
+fn cmd_set_job(
+    controller_id: Option<ControllerEntityId>,
+    args: CommandArguments,
+    ecs_world: &mut specs::World,
+) -> Result<(), String> {
+    let job_id = JobId::from_str(args.as_str(0).unwrap()).map_err(|_e| "Invalid JobId")?;
+    let char_id = get_target_char_id(controller_id, args.as_str(1), ecs_world)?;
+    let job = ecs_world
+        .read_resource::<CommonConfigs>()
+        .job(job_id)
+        .clone();
+    modify_static_data(char_id, ecs_world, |static_data| {
+        static_data.job_id = job_id;
+        static_data.basic_attack_type = job.basic_attack_type();
+        if let (CharOutlook::Human { job_sprite_id, .. }, Some(sprite)) =
+            (&mut static_data.outlook, job.sprite)
+        {
+            *job_sprite_id = sprite;
+        }
+    });
+    let configs = &ecs_world.read_resource::<CommonConfigs>();
+    if let (Some(char_state), Some(statuses)) = (
+        ecs_world
+            .write_storage::<LocalCharStateComp<Local>>()
+            .get_mut(char_id.into()),
+        ecs_world
+            .write_storage::<Statuses>()
+            .get_mut(char_id.into()),
+    ) {
+        char_state.recalc_attribs_based_on_statuses(job_id, statuses, configs);
+        char_state.hp = char_state.hp.min(char_state.calculated_attribs().max_hp);
+    }
+    Ok(())
+}
+
+fn cmd_set_outlook(
+    controller_id: Option<ControllerEntityId>,
+    args: CommandArguments,
+    ecs_world: &mut specs::World,
+) -> Result<(), String> {
+    let char_id = get_target_char_id(controller_id, args.as_str(1), ecs_world)?;
+    let outlook = {
+        let static_data_storage = ecs_world.read_storage::<StaticCharDataComponent>();
+        let current_outlook = static_data_storage
+            .get(char_id.into())
+            .map(|it| &it.outlook);
+        get_outlook(args.as_str(0).unwrap(), current_outlook)
+            .ok_or_else(|| "Invalid JobId/MonsterId".to_owned())?
+    };
+    modify_static_data(char_id, ecs_world, |static_data| {
+        static_data.outlook = outlook
+    });
+    Ok(())
+}
+
+fn cmd_set_team(
+    controller_id: Option<ControllerEntityId>,
+    args: CommandArguments,
+    ecs_world: &mut specs::World,
+) -> Result<(), String> {
+    let team = parse_team(args.as_str(0))?;
+    let char_id = get_target_char_id(controller_id, args.as_str(1), ecs_world)?;
+    modify_static_data(char_id, ecs_world, |static_data| static_data.team = team);
+    Ok(())
+}
+
+fn cmd_heal(
+    controller_id: Option<ControllerEntityId>,
+    args: CommandArguments,
+    ecs_world: &mut specs::World,
+) -> Result<(), String> {
+    let value = args.as_int(0).unwrap().max(0);
+    let char_id = get_target_char_id(controller_id, args.as_str(1), ecs_world)?;
+    let src_entity = get_client_char_id(controller_id, ecs_world).unwrap_or(char_id);
+    ecs_world
+        .write_resource::<Vec<HpModificationRequest>>()
+        .push(HpModificationRequest {
+            src_entity,
+            dst_entity: char_id,
+            typ: HpModificationType::Heal(value as u32),
+        });
+    Ok(())
+}
+
+fn create_status_payload(
+    name: &str,
+    caster_id: EntityId<Local>,
+    now: GameTime<Local>,
+    time: i32,
+    value: i32,
+) -> Result<StatusEnum, String> {
+    let duration = time.max(0) as f32 / 1000.0;
+    match name {
+        "absorb" => Ok(StatusEnum::AbsorbStatus(AbsorbStatus::new(
+            caster_id, now, duration,
+        ))),
+        "poison" => Ok(StatusEnum::PoisonStatus(PoisonStatus {
+            poison_caster_entity_id: caster_id,
+            started: now,
+            until: now.add_seconds(duration),
+            next_damage_at: now,
+            damage: value.max(1) as u32,
+        })),
+        "armor" => {
+            let mut status = ArmorModifierStatus::new(now, percentage(value));
+            status.until = now.add_seconds(duration);
+            Ok(StatusEnum::ArmorModifierStatus(status))
+        }
+        "reflect" => Ok(StatusEnum::ReflectDamageStatus(ReflectDamageStatus::new(
+            percentage(value),
+            now,
+            duration,
+        ))),
+        "attack_heal" => Ok(StatusEnum::AttackHealStatus(AttackHealStatus::new(
+            percentage(value),
+            now,
+            duration,
+        ))),
+        _ => Err("Status not found".to_owned()),
+    }
+}
+
+fn cmd_add_status(
+    controller_id: Option<ControllerEntityId>,
+    args: CommandArguments,
+    ecs_world: &mut specs::World,
+) -> Result<(), String> {
+    let char_id = get_target_char_id(controller_id, args.as_str(3), ecs_world)?;
+    let source_entity_id = get_client_char_id(controller_id, ecs_world).unwrap_or(char_id);
+    let now = ecs_world.read_resource::<EngineTime>().now();
+    let status = create_status_payload(
+        args.as_str(0).unwrap(),
+        source_entity_id,
+        now,
+        args.as_int(1).unwrap(),
+        args.as_int(2).unwrap_or(0),
+    )?;
+    ecs_world
+        .write_resource::<Vec<ApplyStatusComponent>>()
+        .push(ApplyStatusComponent {
+            source_entity_id,
+            target_entity_id: char_id,
+            status,
+        });
+    Ok(())
+}
+
+fn cmd_resurrect(
+    controller_id: Option<ControllerEntityId>,
+    args: CommandArguments,
+    ecs_world: &mut specs::World,
+) -> Result<(), String> {
+    let char_id = get_target_char_id(controller_id, args.as_str(0), ecs_world)?;
+    // the dead characters can not have statuses
+    if let Some(statuses) = ecs_world
+        .write_storage::<Statuses>()
+        .get_mut(char_id.into())
+    {
+        statuses.remove_all();
+    }
+    let mut char_state_storage = ecs_world.write_storage::<LocalCharStateComp<Local>>();
+    let char_state = char_state_storage
+        .get_mut(char_id.into())
+        .ok_or_else(|| "The character has no state".to_owned())?;
+    if char_state.state().is_alive() {
+        return Err("The character is alive".to_owned());
+    }
+    char_state.resurrect();
+    Ok(())
+}
+
+fn set_char_pos(
+    char_id: EntityId<Local>,
+    pos: Vec2,
+    ecs_world: &mut specs::World,
+) -> Result<(), String> {
+    if !ecs_world
+        .read_resource::<MapWalkingInfo>()
+        .is_walkable_pos(pos)
+    {
+        return Err("The position is not walkable".to_owned());
+    }
+    let mut char_state_storage = ecs_world.write_storage::<LocalCharStateComp<Local>>();
+    let char_state = char_state_storage
+        .get_mut(char_id.into())
+        .ok_or_else(|| "The character has no state".to_owned())?;
+    char_state.set_pos(pos);
+    // it would walk back to its old target
+    char_state.target = None;
+    Ok(())
+}
+
+fn cmd_set_pos(
+    controller_id: Option<ControllerEntityId>,
+    args: CommandArguments,
+    ecs_world: &mut specs::World,
+) -> Result<(), String> {
+    let pos = v2(
+        args.as_int(0).unwrap() as f32,
+        args.as_int(1).unwrap() as f32,
+    );
+    let char_id = get_target_char_id(controller_id, args.as_str(2), ecs_world)?;
+    set_char_pos(char_id, pos, ecs_world)
+}
+
+fn cmd_goto(
+    controller_id: Option<ControllerEntityId>,
+    args: CommandArguments,
+    ecs_world: &mut specs::World,
+) -> Result<(), String> {
+    let self_char_id = get_client_char_id(controller_id, ecs_world)
+        .ok_or_else(|| "The command requires a controlled character".to_owned())?;
+    let target_char_id = find_char_by_name(args.as_str(0).unwrap(), ecs_world)?;
+    let target_pos = ecs_world
+        .read_storage::<LocalCharStateComp<Local>>()
+        .get(target_char_id.into())
+        .map(|it| it.pos())
+        .ok_or_else(|| "The character has no state".to_owned())?;
+    set_char_pos(self_char_id, target_pos, ecs_world)
+}
+
+fn cmd_clone_char(
+    controller_id: Option<ControllerEntityId>,
+    args: CommandArguments,
+    ecs_world: &mut specs::World,
+) -> Result<(), String> {
+    let char_id = get_target_char_id(controller_id, args.as_str(0), ecs_world)?;
+    let (pos, static_data) = {
+        let char_state_storage = ecs_world.read_storage::<LocalCharStateComp<Local>>();
+        let static_data_storage = ecs_world.read_storage::<StaticCharDataComponent>();
+        match (
+            char_state_storage.get(char_id.into()),
+            static_data_storage.get(char_id.into()),
+        ) {
+            (Some(char_state), Some(static_data)) => (char_state.pos(), static_data.clone()),
+            _ => return Err("The character has no state".to_owned()),
+        }
+    };
+    let (base_attributes, clone_static_data) = {
+        let dev_configs = &ecs_world.read_resource::<CommonConfigs>();
+        let job = dev_configs.job(static_data.job_id);
+        (
+            job.attributes.clone(),
+            StaticCharDataComponent::new(
+                "Clone".to_owned(),
+                static_data.team,
+                CharType::Minion,
+                job,
+                static_data.outlook,
+            ),
+        )
+    };
+    ecs_world
+        .create_entity()
+        .with(LocalCharStateComp::new(pos, base_attributes))
+        .with(Statuses::new())
+        .with(clone_static_data)
+        .build();
+    Ok(())
+}
+
 fn cmd_spawn_entity(
-    _controller_id: Option<ControllerEntityId>,
+    controller_id: Option<ControllerEntityId>,
     args: CommandArguments,
     ecs_world: &mut specs::World,
 ) -> Result<(), String> {
     let type_name = args.as_str(0).unwrap();
-    let team = match args.as_str(1).unwrap() {
-        "left" => Team::Left,
-        _ => Team::Right,
-    };
+    if !SPAWNABLE_TYPES.contains(&type_name) {
+        return Err(format!("Unknown entity type: {}", type_name));
+    }
+    let team = parse_team(args.as_str(1))?;
     let count = args.as_int(2).unwrap_or(1);
     let pos2d = match (args.as_int(3), args.as_int(4)) {
-        (Some(x), Some(y)) => v2(x as f32, y as f32),
-        _ => {
-            let gat = &ecs_world.read_resource::<MapWalkingInfo>();
-            let hero_pos = ecs_world.read_resource::<LocalCharStateComp<Local>>().pos();
-            //            let mut rng = rand::thread_rng();
-            //            let (x, y) = loop {
-            //                let x: f32 = rng.gen_range(hero_pos.x - 10.0, hero_pos.x + 10.0);
-            //                let y: f32 = rng.gen_range(hero_pos.y - 10.0, hero_pos.y + 10.0).abs();
-            //                let index =
-            //                    y.max(0.0) as usize * gat.width as usize + x.max(0.0) as usize;
-            //                let walkable =
-            //                    (map_render_data.gat.cells[index].cell_type & CellType::Walkable as u8) != 0;
-            //                if walkable {
-            //                    break (x, y);
-            //                }
-            //            };
-            //            v2(x, -y)
-            hero_pos
+        (Some(x), Some(y)) => {
+            let pos = v2(x as f32, y as f32);
+            if !ecs_world
+                .read_resource::<MapWalkingInfo>()
+                .is_walkable_pos(pos)
+            {
+                return Err("The position is not walkable".to_owned());
+            }
+            pos
         }
+        _ => get_client_char_id(controller_id, ecs_world)
+            .and_then(|char_id| {
+                ecs_world
+                    .read_storage::<LocalCharStateComp<Local>>()
+                    .get(char_id.into())
+                    .map(|it| it.pos())
+            })
+            .ok_or_else(|| "The position is missing and the caller has no character".to_owned())?,
+    };
+    let outlook = match args.as_str(5) {
+        Some(name) => {
+            Some(get_outlook(name, None).ok_or_else(|| "Invalid JobId/MonsterId".to_owned())?)
+        }
+        None => None,
     };
-    let outlook = args
-        .as_str(5)
-        .and_then(|outlook| get_outlook(outlook, None));
-    let y = args.as_f32(6).unwrap_or(0.0);
 
     for _ in 0..count {
-        match type_name {
+        let char_entity_id = match type_name {
             "minion_melee" | "minion_ranged" => {
-                // TODO asd
-                //                let job_id = if type_name == "minion_melee" {
-                //                    JobId::MeleeMinion
-                //                } else {
-                //                    JobId::RangedMinion
-                //                };
-                //                let char_entity_id =
-                //                    create_random_char_minion(ecs_world, pos2d, team, job_id, outlook.clone());
-                //                ecs_world
-                //                    .create_entity()
-                //                    .with(ControllerComponent::new(char_entity_id))
-                //                    .with(MinionComponent { fountain_up: false })
-                //                    .build();
-            }
-            "guard" => {
-                // TODO asd
-                //                let _char_entity_id = create_client_guard_entity(ecs_world, pos2d, team, y);
-            }
-            "dummy_enemy" => {
-                let _char_entity_id = create_dummy(ecs_world, pos2d, JobId::TargetDummy);
-            }
-            "dummy_ally" => {
-                let _char_entity_id = create_dummy(ecs_world, pos2d, JobId::HealingDummy);
+                let job_id = if type_name == "minion_melee" {
+                    JobId::MeleeMinion
+                } else {
+                    JobId::RangedMinion
+                };
+                // it joins the closest lane of its team
+                let lane = ecs_world
+                    .read_resource::<MinionLanes>()
+                    .closest_waypoint(team, pos2d);
+                create_minion(
+                    ecs_world,
+                    job_id,
+                    team,
+                    pos2d,
+                    lane.map(|(lane_index, _waypoint_index)| lane_index),
+                    lane.map(|(_lane_index, waypoint_index)| waypoint_index)
+                        .unwrap_or(0),
+                )
             }
-            _ => {}
+            "guard" => create_guard(ecs_world, team, pos2d),
+            "tower" => create_structure(ecs_world, JobId::Tower, team, pos2d),
+            "dummy_enemy" => create_dummy(ecs_world, pos2d, JobId::TargetDummy),
+            _ => create_dummy(ecs_world, pos2d, JobId::HealingDummy),
+        };
+        // the clients get the static data when the entity appears for them
+        if let (Some(outlook), Some(static_data)) = (
+            &outlook,
+            ecs_world
+                .write_storage::<StaticCharDataComponent>()
+                .get_mut(char_entity_id.into()),
+        ) {
+            static_data.outlook = outlook.clone();
         }
     }
     Ok(())
@@ -148,72 +491,37 @@ fn get_outlook(name: &str, current_outlook: Option<&CharOutlook>) -> Option<Char
     }
 }
 
-fn create_dummy(ecs_world: &mut specs::World, pos2d: Vec2, job_id: JobId) {
-    let base_attributes = {
-        let dev_configs = &ecs_world.read_resource::<CommonConfigs>();
-        CharAttributes::get_base_attributes(job_id, &dev_configs).clone()
-    };
-    ecs_world
-        .create_entity()
-        .with(LocalCharStateComp::new(pos2d, base_attributes))
-        .with(StaticCharDataComponent::new(
-            "Dummy".to_owned(),
-            if job_id == JobId::HealingDummy {
-                Team::AllyForAll
-            } else {
-                Team::EnemyForAll
-            },
-            CharType::Minion,
-            job_id,
-            if job_id == JobId::HealingDummy {
-                CharOutlook::Monster(MonsterId::GEFFEN_MAGE_6)
-            } else {
-                CharOutlook::Monster(MonsterId::Barricade)
-            },
-        ))
-        .build();
-}
-
-fn create_random_char_minion(
-    ecs_world: &mut specs::World,
-    pos2d: Vec2,
-    team: Team,
-    job_id: JobId,
-    outlook: Option<CharOutlook>,
-) {
-    let mut rng = rand::thread_rng();
-    let sex = if rng.gen::<usize>() % 2 == 0 {
-        Sex::Male
-    } else {
-        Sex::Female
-    };
-
-    let head_index = rng.gen::<usize>() % 5;
-
-    let base_attributes = {
+fn create_dummy(ecs_world: &mut specs::World, pos2d: Vec2, job_id: JobId) -> EntityId<Local> {
+    let (base_attributes, static_data) = {
         let dev_configs = &ecs_world.read_resource::<CommonConfigs>();
-        CharAttributes::get_base_attributes(job_id, &dev_configs).clone()
-    };
-
-    let char_id = ecs_world
-        .create_entity()
-        .with(LocalCharStateComp::new(pos2d, base_attributes))
-        .with(StaticCharDataComponent::new(
-            "Minion".to_owned(),
-            team,
-            CharType::Minion,
-            job_id,
-            CharOutlook::Human {
-                job_sprite_id: if job_id == JobId::MeleeMinion {
-                    JobSpriteId::SWORDMAN
+        let job = dev_configs.job(job_id);
+        (
+            job.attributes.clone(),
+            StaticCharDataComponent::new(
+                "Dummy".to_owned(),
+                if job_id == JobId::HealingDummy {
+                    Team::AllyForAll
                 } else {
-                    JobSpriteId::ARCHER
+                    Team::EnemyForAll
                 },
-                head_index,
-                sex,
-            },
-        ))
-        .build();
+                CharType::Minion,
+                job,
+                if job_id == JobId::HealingDummy {
+                    CharOutlook::Monster(MonsterId::GEFFEN_MAGE_6)
+                } else {
+                    CharOutlook::Monster(MonsterId::Barricade)
+                },
+            ),
+        )
+    };
+    EntityId::from(
+        ecs_world
+            .create_entity()
+            .with(LocalCharStateComp::new(pos2d, base_attributes))
+            .with(Statuses::new())
+            .with(static_data)
+            .build(),
+    )
 }
 
 fn cmd_kill_all(
@@ -268,27 +576,80 @@ fn cmd_kill_all(
 }
 
 fn cmd_reload_configs(
-    controller_id: Option<ControllerEntityId>,
-    args: CommandArguments,
+    _controller_id: Option<ControllerEntityId>,
+    _args: CommandArguments,
     ecs_world: &mut specs::World,
 ) -> Result<(), String> {
     log::info!("Reloading configs");
-    let configs = load_common_configs("config-runtime").unwrap();
+    let configs = load_common_configs("config-runtime")
+        .map_err(|e| format!("config-runtime.toml could not be parsed: {}", e))?;
+    configs
+        .validate()
+        .map_err(|e| format!("config-runtime.toml is invalid:\n{}", e))?;
 
-    *ecs_world.write_resource::<CommonConfigs>() = configs.clone();
+    let changed_sections = replace_configs(ecs_world, configs);
+    if changed_sections.is_empty() {
+        log::info!("Configs have not changed");
+    } else {
+        log::info!(
+            "Changed config sections: {}",
+            changed_sections
+                .iter()
+                .map(|it| it.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        ecs_world.write_resource::<OutPacketCollector>().push((
+            PacketTarget::All,
+            FromServerPacket::ConfigsChanged(changed_sections),
+        ));
+    }
+    Ok(())
+}
 
-    for (state, static_info) in (
+/// Recalculates the attributes of every character, returns the modified sections
+pub fn replace_configs(ecs_world: &mut specs::World, configs: CommonConfigs) -> Vec<ConfigSection> {
+    let changed_sections = ecs_world
+        .read_resource::<CommonConfigs>()
+        .changed_sections(&configs);
+    if changed_sections.is_empty() {
+        return changed_sections;
+    }
+    for (state, static_info, statuses) in (
         &mut ecs_world.write_storage::<LocalCharStateComp<Local>>(),
         &ecs_world.read_storage::<StaticCharDataComponent>(),
+        &mut ecs_world.write_storage::<Statuses>(),
     )
         .join()
     {
-        state.recalc_attribs_based_on_statuses(static_info.job_id, &configs);
+        state.recalc_attribs_based_on_statuses(static_info.job_id, statuses, &configs);
     }
+    ecs_world.insert(configs);
+    changed_sections
+}
 
-    ecs_world
-        .write_resource::<OutPacketCollector>()
-        .push((PacketTarget::All, FromServerPacket::Configs(configs)));
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(())
+    fn spawn(args: &str) -> Result<(), String> {
+        execute_console_cmd(None, CommandArguments::new(args), &mut specs::World::new())
+    }
+
+    #[test]
+    fn invalid_spawn_arguments_are_rejected() {
+        assert_eq!(
+            Err("Unknown entity type: dragon".to_owned()),
+            spawn("spawn_entity dragon left")
+        );
+        assert_eq!(
+            Err("The team must be 'left' or 'right'".to_owned()),
+            spawn("spawn_entity guard middle")
+        );
+        // without a position it would spawn at the character of the caller
+        assert_eq!(
+            Err("The position is missing and the caller has no character".to_owned()),
+            spawn("spawn_entity guard left")
+        );
+    }
 }
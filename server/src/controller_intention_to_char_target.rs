@@ -1,6 +1,8 @@
-use rustarok_common::common::Local;
-use rustarok_common::components::char::{EntityId, EntityTarget, LocalCharStateComp};
-use rustarok_common::components::controller::{ControllerComponent, PlayerIntention};
+use rustarok_common::common::{EngineTime, Local};
+use rustarok_common::components::char::{LocalCharStateComp, StaticCharDataComponent};
+use rustarok_common::components::controller::ControllerComponent;
+use rustarok_common::config::CommonConfigs;
+use rustarok_common::lag_compensation::LagCompensatedTargets;
 use rustarok_common::systems::intention_applier::ControllerIntentionToCharTarget;
 use specs::Join;
 
@@ -9,14 +11,33 @@ pub struct ControllerIntentionToCharTargetSystem;
 impl<'a> specs::System<'a> for ControllerIntentionToCharTargetSystem {
     type SystemData = (
         specs::WriteStorage<'a, LocalCharStateComp<Local>>,
+        specs::ReadStorage<'a, StaticCharDataComponent>,
         specs::WriteStorage<'a, ControllerComponent>,
+        specs::ReadExpect<'a, EngineTime>,
+        specs::ReadExpect<'a, CommonConfigs>,
+        specs::WriteExpect<'a, LagCompensatedTargets>,
     );
 
-    fn run(&mut self, (mut char_state_storage, mut controller_storage): Self::SystemData) {
-        for mut controller in (&mut controller_storage).join() {
+    fn run(
+        &mut self,
+        (
+            mut char_state_storage,
+            static_char_data_storage,
+            mut controller_storage,
+            time,
+            configs,
+            mut lag_compensation,
+        ): Self::SystemData,
+    ) {
+        lag_compensation.remove_expired(time.now());
+        for controller in (&mut controller_storage).join() {
             ControllerIntentionToCharTarget::controller_intention_to_char_target(
                 controller,
                 &mut char_state_storage,
+                &static_char_data_storage,
+                time.now(),
+                &configs,
+                Some(&mut lag_compensation),
             );
             controller.intention = None;
         }
@@ -0,0 +1,161 @@
+use rustarok_common::common::{Local, Vec2};
+use rustarok_common::components::char::{EntityId, LocalCharStateComp};
+use specs::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+type Cell = Vec<(EntityId<Local>, Vec2)>;
+
+/// Buckets the characters into square cells, so the entities around a client
+/// can be collected without iterating over the whole world.
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Cell>,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> SpatialGrid {
+        SpatialGrid {
+            cell_size,
+            cells: HashMap::with_capacity(64),
+        }
+    }
+
+    fn cell_of(&self, pos: &Vec2) -> (i32, i32) {
+        (
+            (pos.x / self.cell_size).floor() as i32,
+            (pos.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    pub fn clear(&mut self) {
+        // the cells are kept to avoid reallocation in every frame
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+    }
+
+    pub fn insert(&mut self, id: EntityId<Local>, pos: Vec2) {
+        let cell = self.cell_of(&pos);
+        self.cells
+            .entry(cell)
+            .or_insert_with(|| Vec::with_capacity(8))
+            .push((id, pos));
+    }
+
+    /// The result is ordered by entity id so it does not depend on the iteration order of the cells
+    pub fn collect_in_radius(&self, center: &Vec2, radius: f32, out: &mut Vec<EntityId<Local>>) {
+        out.clear();
+        let (min_x, min_y) = self.cell_of(&(center - Vec2::new(radius, radius)));
+        let (max_x, max_y) = self.cell_of(&(center + Vec2::new(radius, radius)));
+        for x in min_x..=max_x {
+            for y in min_y..=max_y {
+                if let Some(cell) = self.cells.get(&(x, y)) {
+                    for (id, pos) in cell {
+                        if (pos - center).magnitude() <= radius {
+                            out.push(*id);
+                        }
+                    }
+                }
+            }
+        }
+        out.sort_by_key(|id| id.as_u64());
+    }
+}
+
+pub struct SpatialGridUpdateSystem;
+
+impl<'a> System<'a> for SpatialGridUpdateSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, LocalCharStateComp<Local>>,
+        WriteExpect<'a, SpatialGrid>,
+    );
+
+    fn run(&mut self, (entities, char_state_storage, mut grid): Self::SystemData) {
+        grid.clear();
+        for (entity_id, char_state) in (&entities, &char_state_storage).join() {
+            grid.insert(EntityId::from(entity_id), char_state.pos());
+        }
+    }
+}
+
+/// The entities a client can see. The visible list keeps the order in which
+/// the entities appeared for the client, the snapshot entries are sent in this order.
+pub struct ClientInterest {
+    visible: Vec<EntityId<Local>>,
+    // the same as `visible`, for the lookups
+    visible_set: HashSet<EntityId<Local>>,
+    // the client has already received the static data (name, outlook etc) of these entities
+    known: HashSet<EntityId<Local>>,
+    // reused in every frame by `update`
+    in_range_set: HashSet<EntityId<Local>>,
+}
+
+impl ClientInterest {
+    pub fn new() -> ClientInterest {
+        ClientInterest {
+            visible: Vec::with_capacity(64),
+            visible_set: HashSet::with_capacity(64),
+            known: HashSet::with_capacity(64),
+            in_range_set: HashSet::with_capacity(64),
+        }
+    }
+
+    pub fn visible_entities(&self) -> &[EntityId<Local>] {
+        &self.visible
+    }
+
+    pub fn is_visible(&self, id: EntityId<Local>) -> bool {
+        self.visible_set.contains(&id)
+    }
+
+    /// Returns false if the client had already known the entity
+    pub fn mark_known(&mut self, id: EntityId<Local>) -> bool {
+        self.known.insert(id)
+    }
+
+    pub fn add_visible(&mut self, id: EntityId<Local>) {
+        if self.visible_set.insert(id) {
+            self.visible.push(id);
+        }
+        self.known.insert(id);
+    }
+
+    /// The entity has been deleted from the world, the client is informed about it by other means
+    pub fn forget(&mut self, id: EntityId<Local>) {
+        if self.visible_set.remove(&id) {
+            self.visible.retain(|it| *it != id);
+        }
+        self.known.remove(&id);
+    }
+
+    /// `in_range` must contain every entity inside the view radius of the client.
+    /// The newly visible entities are appended to the visible list.
+    pub fn update(
+        &mut self,
+        in_range: &[EntityId<Local>],
+        appeared: &mut Vec<EntityId<Local>>,
+        disappeared: &mut Vec<EntityId<Local>>,
+    ) {
+        appeared.clear();
+        disappeared.clear();
+        self.in_range_set.clear();
+        self.in_range_set.extend(in_range.iter().copied());
+        for id in &self.visible {
+            if !self.in_range_set.contains(id) {
+                disappeared.push(*id);
+            }
+        }
+        for id in disappeared.iter() {
+            self.visible_set.remove(id);
+        }
+        let in_range_set = &self.in_range_set;
+        self.visible.retain(|it| in_range_set.contains(it));
+        for id in in_range {
+            if self.visible_set.insert(*id) {
+                appeared.push(*id);
+                self.visible.push(*id);
+            }
+        }
+    }
+}
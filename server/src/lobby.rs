@@ -0,0 +1,226 @@
+use rustarok_common::common::{GameTime, Local};
+use rustarok_common::components::char::{JobId, Team};
+use rustarok_common::config::CommonConfigs;
+use rustarok_common::packets::from_server::{FromServerPacket, LobbyPlayerInfo};
+use rustarok_common::packets::SocketId;
+
+pub struct LobbyPlayer {
+    pub socket_id: SocketId,
+    pub name: String,
+    pub team: Team,
+    pub job_id: JobId,
+    pub ready: bool,
+}
+
+pub enum MatchState {
+    Lobby,
+    Running {
+        started_at: GameTime<Local>,
+        // only these teams can be eliminated, so a match can be tested alone as well
+        teams_at_start: Vec<Team>,
+    },
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum MatchOutcome {
+    Ongoing,
+    Won(Team),
+    Draw,
+}
+
+/// Players join the lobby after they loaded the map, choose their name, team and job,
+/// then the match starts when all of them are ready. After the match everyone gets back here.
+pub struct Lobby {
+    state: MatchState,
+    players: Vec<LobbyPlayer>,
+    next_team: Team,
+}
+
+impl Lobby {
+    pub fn new() -> Lobby {
+        Lobby {
+            state: MatchState::Lobby,
+            players: Vec::with_capacity(64),
+            next_team: Team::Left,
+        }
+    }
+
+    pub fn state(&self) -> &MatchState {
+        &self.state
+    }
+
+    pub fn players(&self) -> &[LobbyPlayer] {
+        &self.players
+    }
+
+    /// A repeated join only updates the name, e.g. when the client sent `ReadyForGame` again
+    pub fn join(&mut self, socket_id: SocketId, name: String) {
+        if let Some(player) = self.get_player_mut(socket_id) {
+            player.name = name;
+            return;
+        }
+        self.players.push(LobbyPlayer {
+            socket_id,
+            name,
+            team: self.next_team,
+            job_id: JobId::CRUSADER,
+            ready: false,
+        });
+        self.next_team = self.next_team.get_opponent_team();
+    }
+
+    pub fn leave(&mut self, socket_id: SocketId) {
+        self.players.retain(|it| it.socket_id != socket_id);
+    }
+
+    /// A reconnected player keeps the chosen team, job and ready state
+    pub fn rebind(&mut self, old_socket_id: SocketId, new_socket_id: SocketId) {
+        if let Some(player) = self.get_player_mut(old_socket_id) {
+            player.socket_id = new_socket_id;
+        }
+    }
+
+    pub fn choose(
+        &mut self,
+        socket_id: SocketId,
+        name: String,
+        team: Team,
+        job_id: JobId,
+        configs: &CommonConfigs,
+    ) -> Result<(), String> {
+        if let MatchState::Running { .. } = self.state {
+            return Err("The match has already started".to_owned());
+        }
+        if team != Team::Left && team != Team::Right {
+            return Err(format!("Team '{}' can not be chosen", team.to_str()));
+        }
+        if !configs.job(job_id).playable {
+            return Err(format!("Job '{}' can not be chosen", job_id));
+        }
+        if name.trim().is_empty() {
+            return Err("The name must not be empty".to_owned());
+        }
+        let player = self
+            .get_player_mut(socket_id)
+            .ok_or_else(|| "The player is not in the lobby".to_owned())?;
+        player.name = name;
+        player.team = team;
+        player.job_id = job_id;
+        return Ok(());
+    }
+
+    pub fn set_ready(&mut self, socket_id: SocketId, ready: bool) {
+        if let Some(player) = self.get_player_mut(socket_id) {
+            player.ready = ready;
+        }
+    }
+
+    fn get_player_mut(&mut self, socket_id: SocketId) -> Option<&mut LobbyPlayer> {
+        self.players.iter_mut().find(|it| it.socket_id == socket_id)
+    }
+
+    pub fn can_start(&self, min_players: usize) -> bool {
+        match self.state {
+            MatchState::Lobby => {
+                self.players.len() >= min_players.max(1) && self.players.iter().all(|it| it.ready)
+            }
+            MatchState::Running { .. } => false,
+        }
+    }
+
+    pub fn start(&mut self, now: GameTime<Local>) {
+        let mut teams_at_start = Vec::with_capacity(2);
+        for player in &self.players {
+            if !teams_at_start.contains(&player.team) {
+                teams_at_start.push(player.team);
+            }
+        }
+        self.state = MatchState::Running {
+            started_at: now,
+            teams_at_start,
+        };
+    }
+
+    pub fn end(&mut self) {
+        self.state = MatchState::Lobby;
+        for player in &mut self.players {
+            player.ready = false;
+        }
+    }
+
+    /// `alive_teams` contains the team of every living player character,
+    /// `destroyed_cores` the teams whose core has been destroyed, they have lost the match
+    pub fn determine_outcome(
+        &self,
+        alive_teams: &[Team],
+        destroyed_cores: &[Team],
+        now: GameTime<Local>,
+        max_duration_seconds: f32,
+    ) -> MatchOutcome {
+        let (started_at, teams_at_start) = match &self.state {
+            MatchState::Lobby => return MatchOutcome::Ongoing,
+            MatchState::Running {
+                started_at,
+                teams_at_start,
+            } => (started_at, teams_at_start),
+        };
+        match destroyed_cores {
+            [] => {}
+            // the opponent wins even if it has no players, so a match can be tested alone as well
+            [team] => return MatchOutcome::Won(team.get_opponent_team()),
+            _ => return MatchOutcome::Draw,
+        }
+        let remaining_teams: Vec<Team> = teams_at_start
+            .iter()
+            .filter(|team| alive_teams.contains(team))
+            .copied()
+            .collect();
+        if teams_at_start.len() > 1 && remaining_teams.len() == 1 {
+            MatchOutcome::Won(remaining_teams[0])
+        } else if remaining_teams.is_empty()
+            || now.elapsed_since(*started_at).as_seconds_f32() >= max_duration_seconds
+        {
+            MatchOutcome::Draw
+        } else {
+            MatchOutcome::Ongoing
+        }
+    }
+
+    pub fn state_packet(&self) -> FromServerPacket {
+        FromServerPacket::LobbyState {
+            players: self
+                .players
+                .iter()
+                .map(|it| LobbyPlayerInfo {
+                    name: it.name.clone(),
+                    team: it.team,
+                    job_id: it.job_id,
+                    ready: it.ready,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_join_updates_the_existing_player() {
+        let mut lobby = Lobby::new();
+        let socket_id = SocketId::from_usize(0);
+        lobby.join(socket_id, "first".to_owned());
+        lobby.set_ready(socket_id, true);
+        lobby.join(SocketId::from_usize(1), "other".to_owned());
+
+        lobby.join(socket_id, "renamed".to_owned());
+
+        assert_eq!(2, lobby.players().len());
+        let player = &lobby.players()[0];
+        assert_eq!("renamed", player.name);
+        assert_eq!(Team::Left, player.team);
+        assert!(player.ready);
+        assert_eq!(Team::Right, lobby.players()[1].team);
+    }
+}
@@ -11,53 +11,101 @@
 //unused_qualifications,
 //clippy::all
 //)]
+// the explicit returns, the skills::skills like modules and the long system helper signatures are the style of the code base
+#![allow(
+    clippy::needless_return,
+    clippy::module_inception,
+    clippy::too_many_arguments,
+    clippy::new_without_default
+)]
 #[macro_use]
 extern crate specs_derive;
 
 use std::fs::File;
 use std::io::BufRead;
 use std::io::BufReader;
-use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::net::{SocketAddr, TcpListener, TcpStream, UdpSocket};
 use std::str::FromStr;
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant};
 
 use log::LevelFilter;
 use serde::Deserialize;
-use specs;
 use specs::prelude::*;
-use strum;
 
 use rustarok_common::attack::{ApplyForceComponent, AreaAttackComponent, HpModificationRequest};
-use rustarok_common::char_attr::CharAttributes;
 use rustarok_common::common::{
-    measure_time, v2, EngineTime, GameTime, Local, Remote, SimulationTick,
+    measure_time, v2, EngineTime, GameTime, Local, Remote, SimulationTick, Vec2,
 };
 use rustarok_common::components::char::{
-    create_common_player_entity, CharOutlook, CharType, ControllerEntityId, EntityId, EntityTarget,
-    JobId, LocalCharStateComp, Sex, StaticCharDataComponent, Team,
+    create_common_player_entity, CharAttacked, CharDied, CharOutlook, CharType, ControllerEntityId,
+    EntityId, JobId, LocalCharStateComp, Sex, StaticCharDataComponent, Team,
 };
 use rustarok_common::components::controller::{ControllerComponent, PlayerIntention};
+use rustarok_common::components::guardian::GuardianComponent;
 use rustarok_common::components::job_ids::JobSpriteId;
+use rustarok_common::components::minion::{MinionComponent, MinionLanes};
+use rustarok_common::components::projectile::ProjectileComponent;
+use rustarok_common::components::skills::skills::{
+    execute_finished_skill_castings, FinishCast, SkillManifestationComponent,
+    SkillManifestationEnded, SkillManifested,
+};
+use rustarok_common::components::status::status::{
+    ApplyStatusComponent, ApplyStatusInAreaComponent, RemoveStatusComponent, Statuses,
+};
+use rustarok_common::components::turret::{TurretComponent, TurretControllerComponent};
 use rustarok_common::config::CommonConfigs;
-use rustarok_common::console::CommandArguments;
+use rustarok_common::console::{find_server_command, CommandArguments, CommandPermission};
 use rustarok_common::grf::asset_loader::CommonAssetLoader;
+use rustarok_common::lag_compensation::{LagCompensatedTarget, LagCompensatedTargets};
 use rustarok_common::map::MapWalkingInfo;
 use rustarok_common::packets::from_server::{FromServerPacket, ServerEntityState};
+use rustarok_common::packets::snapshot_delta::{SnapshotDelta, SnapshotHistory};
 use rustarok_common::packets::to_server::ToServerPacket;
-use rustarok_common::packets::{NetworkTrafficEvent, PacketHandlerThread, SocketId};
+use rustarok_common::packets::{
+    NetworkTrafficEvent, PacketHandlerThread, SocketId, Transport, BUILD_ID, PROTOCOL_VERSION,
+};
+use rustarok_common::path_finding::PathFindingBuffers;
+use rustarok_common::state_checksum::world_checksum;
 use rustarok_common::systems::char_state_sys::CharacterStateUpdateSystem;
+use rustarok_common::systems::collision_sys::CollisionSystem;
+use rustarok_common::systems::guardian_ai_sys::GuardianAiSystem;
+use rustarok_common::systems::minion_ai_sys::MinionAiSystem;
+use rustarok_common::systems::projectile_sys::ProjectileSystem;
+use rustarok_common::systems::skill_sys::SkillSystem;
+use rustarok_common::systems::status_sys::StatusUpdateSystem;
+use rustarok_common::systems::turret_ai_sys::TurretAiSystem;
 
+use crate::admin::AdminConsole;
 use crate::attack::AttackSystem;
-use crate::console_cmd::execute_console_cmd;
+use crate::config_watcher::ConfigWatcher;
+use crate::console_cmd::{execute_console_cmd, reads_files, replace_configs};
 use crate::controller_intention_to_char_target::ControllerIntentionToCharTargetSystem;
+use crate::interest::{ClientInterest, SpatialGrid, SpatialGridUpdateSystem};
+use crate::lobby::{Lobby, LobbyPlayer, MatchOutcome, MatchState};
+use crate::minion::{spawn_minion_waves, start_minion_waves, stop_minion_waves, MinionWaves};
+use crate::position_history::{PositionHistory, PositionHistoryRecorderSystem};
+use crate::replay::{world_state_hash, ReplayEvent, ReplayHeader, ReplayRecorder};
+use crate::respawn::{CorpseComponent, RespawnSystem};
 use crate::server_config::{load_common_configs, ServerConfig};
+use crate::session::{DisconnectedSession, Sessions};
+use crate::structure::{remove_structures, spawn_structures, MapStructures};
 
+mod admin;
 mod attack;
 mod components;
+mod config_watcher;
 mod console_cmd;
 mod controller_intention_to_char_target;
+mod interest;
+mod lobby;
+mod minion;
+mod position_history;
+mod replay;
+mod respawn;
 #[path = "config.rs"]
 mod server_config;
+mod session;
+mod structure;
 
 pub const SIMULATION_FREQ: usize = 30;
 pub const SIMULATION_DURATION_MS: usize = 1000 / SIMULATION_FREQ;
@@ -74,6 +122,14 @@ pub struct AppConfig {
     pub start_pos_y: f32,
     pub grf_paths: Vec<String>,
     pub server_port: u16,
+    pub transport: Transport,
+    pub max_packet_size: usize,
+    pub min_players_to_start: usize,
+    pub match_duration_seconds: f32,
+    pub reconnect_grace_period_seconds: f32,
+    pub replay_file: String,
+    pub admin_port: u16,
+    pub hot_reload_configs: bool,
 }
 
 impl AppConfig {
@@ -111,6 +167,15 @@ struct RemoteClient {
     last_action_tick: u64,
     last_command_id: u32,
     name: String,
+    /// the snapshots sent to the client, the deltas are encoded against the acknowledged one
+    snapshot_history: SnapshotHistory<Local>,
+    last_acked_snapshot_tick: Option<SimulationTick>,
+    interest: ClientInterest,
+    /// sent in `Init`, the client can re-attach to its character with it after a lost connection
+    session_token: u64,
+    /// granted with the `set_admin` command of the admin console,
+    /// the commands with `Admin` permission are accepted only from admins
+    is_admin: bool,
 }
 
 // only the server must implement it
@@ -130,26 +195,39 @@ fn prepare_time_for_sending(time: GameTime<Local>) -> GameTime<Remote> {
     unsafe { std::mem::transmute(time) }
 }
 
+// only the server must implement it
+fn prepare_snapshot_delta_for_sending(delta: SnapshotDelta<Local>) -> SnapshotDelta<Remote> {
+    unsafe { std::mem::transmute(delta) }
+}
+
 // only the server must implement it
 fn client_id_to_server(id: EntityId<Remote>) -> EntityId<Local> {
     unsafe { std::mem::transmute(id) }
 }
 
 fn init_connection(
-    world: &mut specs::World,
+    _world: &mut specs::World,
     socket_handler: &mut PacketHandlerThread<ToServerPacket, FromServerPacket>,
     incoming_conn: (TcpStream, SocketAddr),
-    configs: &AppConfig,
+    _configs: &AppConfig,
 ) -> RemoteClient {
     let socket_id = socket_handler.handle_socket(incoming_conn.0);
+    new_remote_client(socket_id, incoming_conn.1)
+}
 
+fn new_remote_client(socket_id: SocketId, sock_addr: SocketAddr) -> RemoteClient {
     RemoteClient {
         socket_id,
         controller_id: None,
-        sock_addr: incoming_conn.1,
+        sock_addr,
         last_action_tick: 1,
         last_command_id: 0,
         name: "unknown".to_owned(),
+        snapshot_history: SnapshotHistory::new(),
+        last_acked_snapshot_tick: None,
+        interest: ClientInterest::new(),
+        session_token: Sessions::new_token(),
+        is_admin: false,
     }
 }
 
@@ -165,12 +243,13 @@ pub enum PacketTarget {
 
 fn main() {
     log::info!("Loading config file config.toml");
-    let config = AppConfig::new("config").expect("Could not load config file ('config.toml')");
+    let mut config = AppConfig::new("config").expect("Could not load config file ('config.toml')");
 
     simple_logging::log_to_stderr(
         LevelFilter::from_str(&config.log_level)
             .expect("Unknown log level. Please set one of the following values for 'log_level' in 'config.toml': \"OFF\", \"ERROR\", \"WARN\", \"INFO\", \"DEBUG\", \"TRACE\"")
     );
+
     log::info!(">>> Loading GRF files");
     let (elapsed, asset_loader) = measure_time(|| {
         CommonAssetLoader::new(config.grf_paths.as_slice())
@@ -178,26 +257,82 @@ fn main() {
     });
     log::info!("<<< GRF loading: {}ms", elapsed.as_millis());
 
-    let mut ecs_world = create_ecs_world();
-    ecs_world.insert(Vec::<HpModificationRequest>::with_capacity(128));
-    ecs_world.insert(Vec::<AreaAttackComponent>::with_capacity(128));
-    ecs_world.insert(Vec::<ApplyForceComponent>::with_capacity(128));
-    ecs_world.insert(EngineTime::new(0));
-    ecs_world.insert(OutPacketCollector::with_capacity(128));
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(index) = args.iter().position(|it| it == "--replay") {
+        let path = args
+            .get(index + 1)
+            .expect("Usage: rustarok-server --replay <file>");
+        std::process::exit(if run_replay(path, &asset_loader) {
+            0
+        } else {
+            1
+        });
+    }
 
-    ecs_world.insert(ServerConfig::new("server-conf.toml").unwrap());
-    ecs_world.insert(load_common_configs("config-runtime").unwrap());
-    ecs_world.insert(MapWalkingInfo::new());
-    ecs_world.insert(SimulationTick::new());
+    log::info!(">>> Loading map");
+    let (elapsed, map_walking_info) = measure_time(|| {
+        load_map_walking_info(&asset_loader, &config.map_name)
+            .unwrap_or_else(|e| panic!("Could not load the map '{}': {}", config.map_name, e))
+    });
+    log::info!("<<< Loading map: {}ms", elapsed.as_millis());
 
-    let mut ecs_dispatcher = specs::DispatcherBuilder::new()
-        .with(ControllerIntentionToCharTargetSystem, "char_control", &[])
-        .with(CharacterStateUpdateSystem, "char_state", &["char_control"])
-        .with(AttackSystem, "atk_sys", &["char_state"])
-        .build();
+    let server_config = ServerConfig::new("server-conf.toml").unwrap();
+    let common_configs = load_common_configs("config-runtime").unwrap();
+    if let Err(e) = common_configs.validate() {
+        panic!("config-runtime.toml is invalid:\n{}", e);
+    }
+    let replay_recorder = if config.replay_file.is_empty() {
+        ReplayRecorder::disabled()
+    } else {
+        let header = ReplayHeader {
+            build: BUILD_ID.to_owned(),
+            map_name: config.map_name.clone(),
+            server_config: server_config.clone(),
+            configs: common_configs.clone(),
+        };
+        ReplayRecorder::new(&config.replay_file, &header).unwrap_or_else(|e| {
+            log::error!("Could not create '{}': {}", config.replay_file, e);
+            ReplayRecorder::disabled()
+        })
+    };
+    for spawn_point in &server_config.spawn_points {
+        if !map_walking_info.is_walkable_pos(spawn_point.pos()) {
+            log::warn!(
+                "The spawn point of {:?} is not walkable on {}",
+                spawn_point.team,
+                config.map_name
+            );
+        }
+    }
+    let mut ecs_world = create_simulation_world(
+        server_config,
+        common_configs,
+        &config.map_name,
+        map_walking_info,
+        replay_recorder,
+    );
+    let mut ecs_dispatcher = create_ecs_dispatcher();
 
-    let mut packet_handler_thread =
-        PacketHandlerThread::<ToServerPacket, FromServerPacket>::start_thread(64);
+    // with UDP, the packet handler thread accepts the new clients
+    let (mut socket_listener, mut packet_handler_thread) = match config.transport {
+        Transport::Tcp => (
+            Some(bind_server(config.server_port)),
+            PacketHandlerThread::<ToServerPacket, FromServerPacket>::start_thread(
+                64,
+                config.max_packet_size,
+            ),
+        ),
+        Transport::Udp => (
+            None,
+            PacketHandlerThread::<ToServerPacket, FromServerPacket>::start_udp_thread(
+                64,
+                config.max_packet_size,
+                UdpSocket::bind(format!("0.0.0.0:{}", config.server_port))
+                    .expect("Could not bind udp socket"),
+                true,
+            ),
+        ),
+    };
 
     //////// Execute init script
     {
@@ -209,21 +344,53 @@ fn main() {
             if line.starts_with("//") || line.trim().is_empty() {
                 continue;
             }
-            execute_console_cmd(None, CommandArguments::new(&line), &mut ecs_world);
+            if let Err(e) =
+                execute_recorded_console_cmd(None, CommandArguments::new(&line), &mut ecs_world)
+            {
+                log::error!("init.cmd: {}", e);
+            }
+        }
+    };
+
+    let mut admin_console = if config.admin_port == 0 {
+        None
+    } else {
+        match AdminConsole::bind(config.admin_port) {
+            Ok(admin_console) => {
+                log::info!("admin console is listening on port {}", config.admin_port);
+                Some(admin_console)
+            }
+            Err(e) => {
+                log::error!("Could not bind the admin console: {}", e);
+                None
+            }
         }
     };
 
-    let mut socket_listener = bind_server(config.server_port);
-    log::info!("bind socket on port {}", config.server_port);
+    let config_watcher = if config.hot_reload_configs {
+        ConfigWatcher::new("config-runtime.toml")
+            .map_err(|e| log::error!("config-runtime.toml can not be watched: {}", e))
+            .ok()
+    } else {
+        None
+    };
+
+    log::info!(
+        "bind {:?} socket on port {}",
+        config.transport,
+        config.server_port
+    );
 
     log::info!("waiting for incoming connections...");
     let mut tmp_vec = Vec::with_capacity(256);
     const MAX_PLAYER_NUM: usize = 64;
 
-    let mut remote_clients = Vec::<Option<RemoteClient>>::with_capacity(64);
+    let mut remote_clients = Vec::<Option<RemoteClient>>::with_capacity(MAX_PLAYER_NUM);
 
-    let mut next_player_team = Team::Left;
-    let server_started = Instant::now();
+    let mut lobby = Lobby::new();
+    let mut sessions = Sessions::new();
+    // the simulation is stopped by the `pause` admin command
+    let mut paused = false;
 
     ////////////////////////////////////////////////////
     ////////////////////////////////////////////////////
@@ -236,26 +403,74 @@ fn main() {
     loop {
         let start = Instant::now();
 
-        accept_new_connections(
-            &mut socket_listener,
-            &mut packet_handler_thread,
+        if let Some(socket_listener) = &mut socket_listener {
+            accept_new_connections(
+                socket_listener,
+                &mut packet_handler_thread,
+                &mut remote_clients,
+                &mut ecs_world,
+                &config,
+            );
+        }
+
+        process_incoming_packets(
+            &mut tmp_vec,
+            &packet_handler_thread,
             &mut remote_clients,
             &mut ecs_world,
             &config,
+            &mut lobby,
+            &mut sessions,
         );
 
-        process_incoming_packets(
-            &mut tmp_vec,
-            &packet_handler_thread,
+        if let Some(admin_console) = &mut admin_console {
+            admin_console.process(|cmd| {
+                execute_admin_cmd(
+                    cmd,
+                    &asset_loader,
+                    &packet_handler_thread,
+                    &mut remote_clients,
+                    &mut ecs_world,
+                    &mut config,
+                    &mut lobby,
+                    &sessions,
+                    &mut paused,
+                )
+            });
+        }
+
+        if config_watcher.as_ref().map(|it| it.take_change()) == Some(true) {
+            // executed as a console command, so it is recorded in the replay as well
+            if let Err(e) = execute_recorded_console_cmd(
+                None,
+                CommandArguments::new("reload_configs"),
+                &mut ecs_world,
+            ) {
+                log::error!("The modification has been rejected, {}", e);
+            }
+        }
+
+        expire_sessions(
+            &mut sessions,
             &mut remote_clients,
             &mut ecs_world,
+            &mut lobby,
             &config,
-            &mut next_player_team,
         );
 
-        run_frame(&mut ecs_world, &mut ecs_dispatcher);
+        if !paused {
+            run_frame(&mut ecs_world, &mut ecs_dispatcher);
 
-        send_snapshots(&mut remote_clients, &mut ecs_world);
+            update_match(
+                &mut lobby,
+                &mut sessions,
+                &mut remote_clients,
+                &mut ecs_world,
+                &config,
+            );
+
+            send_snapshots(&mut remote_clients, &mut ecs_world);
+        }
 
         send_packets(&mut packet_handler_thread, &mut ecs_world, &remote_clients);
 
@@ -265,37 +480,283 @@ fn main() {
             std::thread::sleep(to_sleep);
         }
 
-        ecs_world
-            .write_resource::<EngineTime>()
-            .tick(MAX_DURATION_ALLOWED_FOR_SINGLE_SIMULATION_FRAME);
+        if paused {
+            continue;
+        }
+        if ecs_world.read_resource::<ReplayRecorder>().is_recording() {
+            let event = ReplayEvent::TickEnd {
+                tick: *ecs_world.read_resource::<SimulationTick>(),
+                hash: world_state_hash(&ecs_world),
+            };
+            ecs_world.write_resource::<ReplayRecorder>().record(&event);
+        }
+        advance_tick(&mut ecs_world);
+    }
+}
 
-        ecs_world.write_resource::<SimulationTick>().inc();
+/// Re-runs a recorded match without clients and checks that every tick produces the same world state
+fn run_replay(path: &str, asset_loader: &CommonAssetLoader) -> bool {
+    let mut reader = BufReader::new(File::open(path).expect("Could not open the replay file"));
+    let header: ReplayHeader =
+        bincode::deserialize_from(&mut reader).expect("Could not read the replay header");
+    if header.build != BUILD_ID {
+        log::warn!(
+            "The replay was recorded by a different build: {}, current: {}",
+            header.build,
+            BUILD_ID
+        );
     }
+    log::info!("Replaying a match on {}", header.map_name);
+    let map_walking_info = match load_map_walking_info(asset_loader, &header.map_name) {
+        Ok(map_walking_info) => map_walking_info,
+        Err(e) => {
+            log::error!("Could not load the map '{}': {}", header.map_name, e);
+            return false;
+        }
+    };
+    let mut ecs_world = create_simulation_world(
+        header.server_config,
+        header.configs,
+        &header.map_name,
+        map_walking_info,
+        ReplayRecorder::disabled(),
+    );
+    let mut ecs_dispatcher = create_ecs_dispatcher();
+    let mut verified_ticks = 0;
+    loop {
+        let event: ReplayEvent = match bincode::deserialize_from(&mut reader) {
+            Ok(event) => event,
+            Err(e) => match e.as_ref() {
+                bincode::ErrorKind::Io(io_err)
+                    if io_err.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    break;
+                }
+                _ => {
+                    log::error!("Invalid replay event: {}", e);
+                    return false;
+                }
+            },
+        };
+        match event {
+            ReplayEvent::SpawnPlayer {
+                name,
+                job_id,
+                team,
+                pos,
+            } => {
+                create_player_char(name, job_id, team, pos, &mut ecs_world);
+            }
+            ReplayEvent::RemovePlayer { controller } => {
+                delete_controller_and_char(
+                    ControllerEntityId::new(controller.into()),
+                    &mut ecs_world,
+                );
+            }
+            ReplayEvent::Intention {
+                controller,
+                intention,
+                client_tick,
+            } => {
+                apply_intention(
+                    ControllerEntityId::new(controller.into()),
+                    intention,
+                    client_tick,
+                    &mut ecs_world,
+                );
+            }
+            ReplayEvent::ConsoleCommand { controller, cmd } if !reads_files(&cmd) => {
+                // the failed commands have been recorded as well
+                let _ = execute_console_cmd(
+                    controller.map(|it| ControllerEntityId::new(it.into())),
+                    cmd,
+                    &mut ecs_world,
+                );
+            }
+            // their effect has been recorded as a separate event
+            ReplayEvent::ConsoleCommand { .. } => {}
+            ReplayEvent::ConfigsChanged(configs) => {
+                replace_configs(&mut ecs_world, configs);
+            }
+            ReplayEvent::MapChanged(map_name) => {
+                match load_map_walking_info(asset_loader, &map_name) {
+                    Ok(map_walking_info) => insert_map(&mut ecs_world, &map_name, map_walking_info),
+                    Err(e) => {
+                        log::error!("Could not load the map '{}': {}", map_name, e);
+                        return false;
+                    }
+                }
+            }
+            ReplayEvent::MatchStarted => {
+                start_match_simulation(&mut ecs_world);
+            }
+            ReplayEvent::MatchEnded => {
+                end_match_simulation(&mut ecs_world);
+            }
+            ReplayEvent::RunFrame => {
+                run_frame(&mut ecs_world, &mut ecs_dispatcher);
+            }
+            ReplayEvent::TickEnd { tick, hash } => {
+                let current_tick = *ecs_world.read_resource::<SimulationTick>();
+                let current_hash = world_state_hash(&ecs_world);
+                if current_tick != tick || current_hash != hash {
+                    log::error!(
+                        "The replay has diverged at tick {:?} (recorded: {:?}), hash: {}, recorded hash: {}",
+                        current_tick,
+                        tick,
+                        current_hash,
+                        hash
+                    );
+                    return false;
+                }
+                verified_ticks += 1;
+                // there is no one to send them to
+                ecs_world.write_resource::<OutPacketCollector>().clear();
+                advance_tick(&mut ecs_world);
+            }
+        }
+    }
+    log::info!("The replay has been verified, ticks: {}", verified_ticks);
+    true
+}
+
+/// The GAT and its blocking rectangles, the server enforces the walkability and the collisions
+fn load_map_walking_info(
+    asset_loader: &CommonAssetLoader,
+    map_name: &str,
+) -> Result<MapWalkingInfo, String> {
+    asset_loader
+        .load_gat(map_name)
+        .map(|(gat, rectangles)| MapWalkingInfo::from_gat(&gat, &rectangles))
+}
+
+fn create_simulation_world(
+    server_config: ServerConfig,
+    configs: CommonConfigs,
+    map_name: &str,
+    map_walking_info: MapWalkingInfo,
+    replay_recorder: ReplayRecorder,
+) -> specs::World {
+    let mut ecs_world = create_ecs_world();
+    ecs_world.insert(Vec::<HpModificationRequest>::with_capacity(128));
+    ecs_world.insert(Vec::<AreaAttackComponent>::with_capacity(128));
+    ecs_world.insert(Vec::<ApplyForceComponent>::with_capacity(128));
+    ecs_world.insert(Vec::<ApplyStatusComponent>::with_capacity(128));
+    ecs_world.insert(Vec::<ApplyStatusInAreaComponent>::with_capacity(128));
+    ecs_world.insert(Vec::<RemoveStatusComponent>::with_capacity(128));
+    ecs_world.insert(Vec::<FinishCast>::with_capacity(128));
+    ecs_world.insert(Vec::<SkillManifested>::with_capacity(128));
+    ecs_world.insert(Vec::<SkillManifestationEnded>::with_capacity(128));
+    ecs_world.insert(Vec::<CharDied>::with_capacity(16));
+    ecs_world.insert(Vec::<CharAttacked>::with_capacity(128));
+    ecs_world.insert(Vec::<ProjectileComponent>::with_capacity(128));
+    ecs_world.insert(EngineTime::new(0));
+    ecs_world.insert(OutPacketCollector::with_capacity(128));
+
+    ecs_world.insert(SpatialGrid::new(server_config.view_radius));
+    ecs_world.insert(PositionHistory::new(
+        server_config.max_lag_compensation_ms / SIMULATION_DURATION_MS,
+    ));
+    ecs_world.insert(LagCompensatedTargets::new());
+    ecs_world.insert(MinionWaves { next_wave_at: None });
+    ecs_world.insert(server_config.minions.clone());
+    ecs_world.insert(server_config.guardians.clone());
+    ecs_world.insert(server_config);
+    ecs_world.insert(configs);
+    insert_map(&mut ecs_world, map_name, map_walking_info);
+    ecs_world.insert(SimulationTick::new());
+    ecs_world.insert(replay_recorder);
+    ecs_world
+}
+
+/// The walkability of the map, its minion lanes and structures from the `ServerConfig`
+fn insert_map(ecs_world: &mut specs::World, map_name: &str, map_walking_info: MapWalkingInfo) {
+    let lanes = MinionLanes::of_map(&ecs_world.read_resource::<ServerConfig>().lanes, map_name);
+    let structures = MapStructures::of_map(
+        &ecs_world.read_resource::<ServerConfig>().structures,
+        map_name,
+    );
+    for lane in &lanes.lanes {
+        for i in 0..lane.waypoints.len() {
+            if !map_walking_info.is_walkable_pos(lane.waypoint(i)) {
+                log::warn!(
+                    "The waypoint {} of the {:?} lane is not walkable on {}",
+                    i,
+                    lane.team,
+                    map_name
+                );
+            }
+        }
+    }
+    if lanes.lanes.is_empty() {
+        log::warn!("{} has no minion lanes", map_name);
+    }
+    ecs_world.insert(lanes);
+    ecs_world.insert(structures);
+    ecs_world.insert(map_walking_info);
+    ecs_world.insert(PathFindingBuffers::default());
+}
+
+fn create_ecs_dispatcher<'a, 'b>() -> specs::Dispatcher<'a, 'b> {
+    specs::DispatcherBuilder::new()
+        .with(MinionAiSystem, "minion_ai", &[])
+        .with(GuardianAiSystem, "guardian_ai", &[])
+        .with(TurretAiSystem, "turret_ai", &[])
+        .with(
+            ControllerIntentionToCharTargetSystem,
+            "char_control",
+            &["minion_ai", "guardian_ai", "turret_ai"],
+        )
+        .with(CharacterStateUpdateSystem, "char_state", &["char_control"])
+        .with(CollisionSystem, "collision_sys", &["char_state"])
+        .with(SkillSystem, "skill_sys", &["collision_sys"])
+        .with(StatusUpdateSystem, "status_sys", &["skill_sys"])
+        .with(ProjectileSystem, "projectile_sys", &["collision_sys"])
+        .with(AttackSystem, "atk_sys", &["status_sys", "projectile_sys"])
+        .with(RespawnSystem, "respawn_sys", &["atk_sys"])
+        .with(SpatialGridUpdateSystem, "spatial_grid", &["atk_sys"])
+        .with(
+            PositionHistoryRecorderSystem,
+            "position_history",
+            &["atk_sys"],
+        )
+        .build()
+}
+
+fn advance_tick(ecs_world: &mut specs::World) {
+    ecs_world
+        .write_resource::<EngineTime>()
+        .tick(MAX_DURATION_ALLOWED_FOR_SINGLE_SIMULATION_FRAME);
+
+    ecs_world.write_resource::<SimulationTick>().inc();
 }
 
 fn send_packets(
     packet_handler_thread: &mut PacketHandlerThread<ToServerPacket, FromServerPacket>,
     ecs_world: &mut specs::World,
-    remote_clients: &Vec<Option<RemoteClient>>,
+    remote_clients: &[Option<RemoteClient>],
 ) -> usize {
     let mut to_client = ecs_world.write_resource::<OutPacketCollector>();
     let sent = to_client.len();
 
     for (target, packet) in to_client.drain(..) {
         match target {
-            PacketTarget::All | PacketTarget::Area(_) => {
-                for remote_client in remote_clients.iter() {
-                    if let Some(remote_client) = remote_client {
+            PacketTarget::All => {
+                for remote_client in remote_clients.iter().flatten() {
+                    packet_handler_thread.send(remote_client.socket_id, packet.clone());
+                }
+            }
+            PacketTarget::Area(entity_id) => {
+                for remote_client in remote_clients.iter().flatten() {
+                    if remote_client.interest.is_visible(entity_id) {
                         packet_handler_thread.send(remote_client.socket_id, packet.clone());
                     }
                 }
             }
             PacketTarget::AllExcept(except_id) => {
-                for remote_client in remote_clients.iter() {
-                    if let Some(remote_client) = remote_client {
-                        if remote_client.socket_id != except_id {
-                            packet_handler_thread.send(remote_client.socket_id, packet.clone());
-                        }
+                for remote_client in remote_clients.iter().flatten() {
+                    if remote_client.socket_id != except_id {
+                        packet_handler_thread.send(remote_client.socket_id, packet.clone());
                     }
                 }
             }
@@ -303,7 +764,19 @@ fn send_packets(
                 packet_handler_thread.send(client_socket, packet);
             }
             PacketTarget::Team(team) => {
-                // TODO
+                let controller_storage = ecs_world.read_storage::<ControllerComponent>();
+                let static_data_storage = ecs_world.read_storage::<StaticCharDataComponent>();
+                for remote_client in remote_clients.iter().flatten() {
+                    let client_team = remote_client
+                        .controller_id
+                        .and_then(|it| controller_storage.get(it.into()))
+                        .and_then(|it| it.controlled_entity)
+                        .and_then(|it| static_data_storage.get(it.into()))
+                        .map(|it| it.team);
+                    if client_team == Some(team) {
+                        packet_handler_thread.send(remote_client.socket_id, packet.clone());
+                    }
+                }
             }
         }
     }
@@ -311,10 +784,80 @@ fn send_packets(
 }
 
 fn run_frame(ecs_world: &mut specs::World, ecs_dispatcher: &mut specs::Dispatcher) {
+    ecs_world
+        .write_resource::<ReplayRecorder>()
+        .record(&ReplayEvent::RunFrame);
     ecs_dispatcher.dispatch(ecs_world);
+    execute_finished_skill_castings(ecs_world);
+    replicate_skill_manifestations(ecs_world);
+    launch_fired_projectiles(ecs_world);
+    spawn_minion_waves(ecs_world);
     ecs_world.maintain();
 }
 
+/// The clients render the manifestations, their simulation runs only on the server
+fn replicate_skill_manifestations(ecs_world: &mut specs::World) {
+    let manifested = std::mem::replace(
+        &mut *ecs_world.write_resource::<Vec<SkillManifested>>(),
+        Vec::with_capacity(128),
+    );
+    let ended = std::mem::replace(
+        &mut *ecs_world.write_resource::<Vec<SkillManifestationEnded>>(),
+        Vec::with_capacity(128),
+    );
+    let mut packet_collector = ecs_world.write_resource::<OutPacketCollector>();
+    for SkillManifested {
+        manifestation_id,
+        cast,
+    } in manifested
+    {
+        send_packet(
+            &mut packet_collector,
+            PacketTarget::Area(cast.caster_entity_id),
+            FromServerPacket::SkillManifested {
+                id: prepare_entity_id_for_sending(manifestation_id),
+                skill: cast.skill,
+                caster_id: prepare_entity_id_for_sending(cast.caster_entity_id),
+                caster_pos: cast.caster_pos,
+                skill_pos: cast.skill_pos,
+                char_to_skill_dir: cast.char_to_skill_dir,
+                target_id: cast.target_entity.map(prepare_entity_id_for_sending),
+            },
+        );
+    }
+    // the manifestation might have been seen by clients who have left the area since then
+    for SkillManifestationEnded { manifestation_id } in ended {
+        send_packet(
+            &mut packet_collector,
+            PacketTarget::All,
+            FromServerPacket::SkillManifestationEnded {
+                id: prepare_entity_id_for_sending(manifestation_id),
+            },
+        );
+    }
+}
+
+/// The projectiles start flying only in the next frame, like the skill manifestations
+fn launch_fired_projectiles(ecs_world: &mut specs::World) {
+    let fired_projectiles = std::mem::replace(
+        &mut *ecs_world.write_resource::<Vec<ProjectileComponent>>(),
+        Vec::with_capacity(128),
+    );
+    for projectile in fired_projectiles {
+        send_packet(
+            &mut ecs_world.write_resource::<OutPacketCollector>(),
+            PacketTarget::Area(projectile.caster_id),
+            FromServerPacket::ProjectileFired {
+                caster_id: prepare_entity_id_for_sending(projectile.caster_id),
+                target_id: prepare_entity_id_for_sending(projectile.target_id),
+                weapon_type: projectile.weapon_type,
+                pos: projectile.pos,
+            },
+        );
+        ecs_world.create_entity().with(projectile).build();
+    }
+}
+
 fn accept_new_connections(
     socket_listener: &mut TcpListener,
     packet_handler_thread: &mut PacketHandlerThread<ToServerPacket, FromServerPacket>,
@@ -324,22 +867,25 @@ fn accept_new_connections(
 ) {
     if let Some(connecting_client) = accept_connection(socket_listener) {
         let remote_client =
-            init_connection(ecs_world, packet_handler_thread, connecting_client, &config);
-        log::info!(
-            "{:?} - {} has joined",
-            &remote_client.socket_id,
-            &remote_client.sock_addr
-        );
-        if remote_client.socket_id.as_usize() >= remote_clients.len() {
-            remote_clients.push(Some(remote_client));
-        } else {
-            let index = remote_client.socket_id.as_usize();
-            remote_clients[index] = Some(remote_client);
-        }
+            init_connection(ecs_world, packet_handler_thread, connecting_client, config);
+        add_remote_client(remote_clients, remote_client);
+    }
+}
+
+fn add_remote_client(remote_clients: &mut Vec<Option<RemoteClient>>, remote_client: RemoteClient) {
+    log::info!(
+        "{:?} - {} has joined",
+        &remote_client.socket_id,
+        &remote_client.sock_addr
+    );
+    let index = remote_client.socket_id.as_usize();
+    if index >= remote_clients.len() {
+        remote_clients.resize_with(index + 1, || None);
     }
+    remote_clients[index] = Some(remote_client);
 }
 
-fn send_snapshots(remote_clients: &mut Vec<Option<RemoteClient>>, ecs_world: &mut specs::World) {
+fn send_snapshots(remote_clients: &mut [Option<RemoteClient>], ecs_world: &mut specs::World) {
     for remote_client in remote_clients.iter_mut() {
         let remote_client = if let Some(remote_client) = remote_client {
             remote_client
@@ -355,41 +901,48 @@ fn send_snapshots(remote_clients: &mut Vec<Option<RemoteClient>>, ecs_world: &mu
                     .controlled_entity
             };
             if let Some(controlled_entity) = controlled_entity {
-                let char_state = {
-                    let auth_char_storage = ecs_world.read_storage::<LocalCharStateComp<Local>>();
-                    auth_char_storage
-                        .get(controlled_entity.into())
-                        .unwrap()
-                        .clone()
-                };
+                update_client_interest(remote_client, controlled_entity, ecs_world);
 
-                let mut entries = vec![ServerEntityState {
-                    id: prepare_entity_id_for_sending(controlled_entity),
-                    char_snapshot: prepare_charsnapshot_for_sending(char_state),
-                }];
-                {
+                let entries = {
                     let auth_char_storage = ecs_world.read_storage::<LocalCharStateComp<Local>>();
-                    for (other_char_id, other_char_state) in
-                        (&ecs_world.entities(), &auth_char_storage).join()
-                    {
-                        let other_char_id = EntityId::from(other_char_id);
-                        if other_char_id == controlled_entity.into() {
-                            continue;
-                        }
-                        entries.push(ServerEntityState {
-                            id: prepare_entity_id_for_sending(other_char_id),
-                            char_snapshot: prepare_charsnapshot_for_sending(
-                                other_char_state.clone(),
-                            ),
+                    remote_client
+                        .interest
+                        .visible_entities()
+                        .iter()
+                        .filter_map(|entity_id| {
+                            auth_char_storage
+                                .get((*entity_id).into())
+                                .map(|char_state| ServerEntityState {
+                                    id: prepare_entity_id_for_sending(*entity_id),
+                                    char_snapshot: char_state.clone(),
+                                })
                         })
-                    }
-                }
+                        .collect()
+                };
+                let tick = *ecs_world.read_resource::<SimulationTick>();
+                let baseline = remote_client
+                    .last_acked_snapshot_tick
+                    .and_then(|acked_tick| {
+                        remote_client
+                            .snapshot_history
+                            .get(acked_tick)
+                            .map(|states| (acked_tick, states))
+                    });
+                let (snapshot, sent_states) = SnapshotDelta::encode(tick, baseline, entries);
+                let checksum = world_checksum(
+                    sent_states
+                        .iter()
+                        .map(|it| (it.id.as_u64(), &it.char_snapshot)),
+                    &|id| id.as_u64(),
+                );
+                remote_client.snapshot_history.push(tick, sent_states);
                 send_packet(
                     &mut ecs_world.write_resource(),
                     PacketTarget::Client(remote_client.socket_id),
                     FromServerPacket::Ack {
                         cid: remote_client.last_command_id,
-                        entries,
+                        snapshot: prepare_snapshot_delta_for_sending(snapshot),
+                        checksum,
                     },
                 );
                 remote_client.last_action_tick += 1;
@@ -398,223 +951,584 @@ fn send_snapshots(remote_clients: &mut Vec<Option<RemoteClient>>, ecs_world: &mu
     }
 }
 
+/// Informs the client about the entities which have got into or left its view radius
+fn update_client_interest(
+    remote_client: &mut RemoteClient,
+    controlled_entity: EntityId<Local>,
+    ecs_world: &mut specs::World,
+) {
+    let mut in_range = Vec::with_capacity(64);
+    let mut appeared = Vec::with_capacity(16);
+    let mut disappeared = Vec::with_capacity(16);
+    {
+        let auth_char_storage = ecs_world.read_storage::<LocalCharStateComp<Local>>();
+        let center = match auth_char_storage.get(controlled_entity.into()) {
+            Some(char_state) => char_state.pos(),
+            None => return,
+        };
+        let view_radius = ecs_world.read_resource::<ServerConfig>().view_radius;
+        ecs_world.read_resource::<SpatialGrid>().collect_in_radius(
+            &center,
+            view_radius,
+            &mut in_range,
+        );
+    }
+    remote_client
+        .interest
+        .update(&in_range, &mut appeared, &mut disappeared);
+    if appeared.is_empty() && disappeared.is_empty() {
+        return;
+    }
+
+    {
+        let entities = ecs_world.entities();
+        let auth_char_storage = ecs_world.read_storage::<LocalCharStateComp<Local>>();
+        let static_data_storage = ecs_world.read_storage::<StaticCharDataComponent>();
+        let packet_collector = &mut ecs_world.write_resource::<OutPacketCollector>();
+        for entity_id in disappeared {
+            if !entities.is_alive(entity_id.into()) {
+                remote_client.interest.forget(entity_id);
+            }
+            send_packet(
+                packet_collector,
+                PacketTarget::Client(remote_client.socket_id),
+                FromServerPacket::EntityDisappeared {
+                    id: prepare_entity_id_for_sending(entity_id),
+                },
+            );
+        }
+        for entity_id in appeared {
+            let char_state = auth_char_storage.get(entity_id.into()).unwrap().clone();
+            let packet = if remote_client.interest.mark_known(entity_id) {
+                let static_data = static_data_storage.get(entity_id.into()).unwrap();
+                FromServerPacket::NewEntity {
+                    id: prepare_entity_id_for_sending(entity_id),
+                    name: static_data.name.clone(),
+                    team: static_data.team,
+                    typ: static_data.typ,
+                    outlook: static_data.outlook.clone(),
+                    job_id: static_data.job_id,
+                    state: prepare_charsnapshot_for_sending(char_state),
+                }
+            } else {
+                FromServerPacket::EntityAppeared {
+                    id: prepare_entity_id_for_sending(entity_id),
+                    state: prepare_charsnapshot_for_sending(char_state),
+                }
+            };
+            send_packet(
+                packet_collector,
+                PacketTarget::Client(remote_client.socket_id),
+                packet,
+            );
+        }
+    }
+}
+
+fn update_match(
+    lobby: &mut Lobby,
+    sessions: &mut Sessions,
+    remote_clients: &mut [Option<RemoteClient>],
+    ecs_world: &mut specs::World,
+    config: &AppConfig,
+) {
+    let now = ecs_world.read_resource::<EngineTime>().now();
+    let is_running = match lobby.state() {
+        MatchState::Lobby => false,
+        MatchState::Running { .. } => true,
+    };
+    if !is_running {
+        if lobby.can_start(config.min_players_to_start) {
+            log::info!("The match has started");
+            for player in lobby.players() {
+                let remote_client = remote_clients
+                    .get_mut(player.socket_id.as_usize())
+                    .and_then(|it| it.as_mut());
+                if let Some(remote_client) = remote_client {
+                    spawn_player_char(remote_client, player, ecs_world, config);
+                }
+            }
+            start_match_simulation(ecs_world);
+            lobby.start(now);
+        }
+        return;
+    }
+
+    let alive_teams: Vec<Team> = {
+        let controller_storage = ecs_world.read_storage::<ControllerComponent>();
+        let auth_char_storage = ecs_world.read_storage::<LocalCharStateComp<Local>>();
+        let static_data_storage = ecs_world.read_storage::<StaticCharDataComponent>();
+        let corpse_storage = ecs_world.read_storage::<CorpseComponent>();
+        remote_clients
+            .iter()
+            .filter_map(|it| it.as_ref()?.controller_id)
+            .chain(sessions.controller_ids())
+            .filter_map(|controller_id| controller_storage.get(controller_id.into()))
+            .filter_map(|controller| controller.controlled_entity)
+            // the dead players who are going to respawn are still in the game
+            .filter(|char_id| {
+                let is_alive = auth_char_storage
+                    .get((*char_id).into())
+                    .map(|it| it.state().is_alive())
+                    .unwrap_or(false);
+                is_alive || corpse_storage.get((*char_id).into()).is_some()
+            })
+            .filter_map(|char_id| static_data_storage.get(char_id.into()))
+            .map(|static_data| static_data.team)
+            .collect()
+    };
+    let destroyed_cores: Vec<Team> = {
+        let auth_char_storage = ecs_world.read_storage::<LocalCharStateComp<Local>>();
+        let static_data_storage = ecs_world.read_storage::<StaticCharDataComponent>();
+        let mut destroyed_cores = Vec::with_capacity(2);
+        for (auth_state, static_data) in (&auth_char_storage, &static_data_storage).join() {
+            let is_destroyed_core = static_data.typ == CharType::Structure
+                && static_data.job_id == JobId::Core
+                && auth_state.state().is_dead();
+            if is_destroyed_core && !destroyed_cores.contains(&static_data.team) {
+                destroyed_cores.push(static_data.team);
+            }
+        }
+        destroyed_cores
+    };
+    let winner = match lobby.determine_outcome(
+        &alive_teams,
+        &destroyed_cores,
+        now,
+        config.match_duration_seconds,
+    ) {
+        MatchOutcome::Ongoing => return,
+        MatchOutcome::Won(team) => Some(team),
+        MatchOutcome::Draw => None,
+    };
+    log::info!("The match has ended, winner: {:?}", winner);
+    let mut disconnected_sessions = Vec::new();
+    sessions.drain_all(&mut disconnected_sessions);
+    for session in disconnected_sessions {
+        lobby.leave(session.socket_id);
+        delete_controller_and_char(session.controller_id, ecs_world);
+    }
+    for remote_client in remote_clients.iter_mut().filter_map(|it| it.as_mut()) {
+        if let Some(controller_id) = remote_client.controller_id.take() {
+            delete_controller_and_char(controller_id, ecs_world);
+        }
+        // the clients remove every entity when the match ends
+        remote_client.interest = ClientInterest::new();
+        remote_client.snapshot_history.clear();
+        remote_client.last_acked_snapshot_tick = None;
+    }
+    end_match_simulation(ecs_world);
+    lobby.end();
+    let packet_collector = &mut ecs_world.write_resource::<OutPacketCollector>();
+    send_packet(
+        packet_collector,
+        PacketTarget::All,
+        FromServerPacket::MatchEnded { winner },
+    );
+    send_packet(packet_collector, PacketTarget::All, lobby.state_packet());
+}
+
+/// The parts of the match which are simulated without the players
+fn start_match_simulation(ecs_world: &mut specs::World) {
+    ecs_world
+        .write_resource::<ReplayRecorder>()
+        .record(&ReplayEvent::MatchStarted);
+    spawn_structures(ecs_world);
+    start_minion_waves(ecs_world);
+}
+
+fn end_match_simulation(ecs_world: &mut specs::World) {
+    ecs_world
+        .write_resource::<ReplayRecorder>()
+        .record(&ReplayEvent::MatchEnded);
+    stop_minion_waves(ecs_world);
+    remove_structures(ecs_world);
+}
+
+fn spawn_player_char(
+    remote_client: &mut RemoteClient,
+    player: &LobbyPlayer,
+    ecs_world: &mut specs::World,
+    config: &AppConfig,
+) {
+    let pos = ecs_world
+        .read_resource::<ServerConfig>()
+        .spawn_point(player.team)
+        .map(|spawn_point| spawn_point.pos())
+        .unwrap_or_else(|| v2(config.start_pos_x, config.start_pos_y));
+    let (char_id, controller_id) = create_player_char(
+        player.name.clone(),
+        player.job_id,
+        player.team,
+        pos,
+        ecs_world,
+    );
+    remote_client.controller_id = Some(controller_id);
+    send_controlled_char(remote_client, char_id, ecs_world);
+}
+
+fn create_player_char(
+    name: String,
+    job_id: JobId,
+    team: Team,
+    pos: Vec2,
+    ecs_world: &mut specs::World,
+) -> (EntityId<Local>, ControllerEntityId) {
+    ecs_world
+        .write_resource::<ReplayRecorder>()
+        .record(&ReplayEvent::SpawnPlayer {
+            name: name.clone(),
+            job_id,
+            team,
+            pos,
+        });
+    let job_sprite_id = ecs_world
+        .read_resource::<CommonConfigs>()
+        .job(job_id)
+        .sprite
+        .unwrap_or(JobSpriteId::NOVICE);
+    let char_id = EntityId::from(
+        create_common_player_entity(
+            name,
+            ecs_world,
+            CharType::Player,
+            job_id,
+            pos,
+            team,
+            CharOutlook::Human {
+                job_sprite_id,
+                head_index: 0,
+                sex: Sex::Male,
+            },
+        )
+        .build(),
+    );
+    let network_player_id = ecs_world
+        .create_entity()
+        .with(ControllerComponent::new(char_id))
+        .build();
+    (char_id, ControllerEntityId::new(network_player_id))
+}
+
+fn controller_to_entity_id(controller_id: ControllerEntityId) -> EntityId<Local> {
+    let entity: specs::Entity = controller_id.into();
+    EntityId::from(entity)
+}
+
+fn delete_controller_and_char(controller_id: ControllerEntityId, ecs_world: &mut specs::World) {
+    ecs_world
+        .write_resource::<ReplayRecorder>()
+        .record(&ReplayEvent::RemovePlayer {
+            controller: controller_to_entity_id(controller_id),
+        });
+    let controlled_entity = ecs_world
+        .read_storage::<ControllerComponent>()
+        .get(controller_id.into())
+        .and_then(|it| it.controlled_entity);
+    if let Some(controlled_entity) = controlled_entity {
+        let _ = ecs_world.delete_entity(controlled_entity.into());
+    }
+    let _ = ecs_world.delete_entity(controller_id.into());
+}
+
+/// Sends the controlled character to its player, the other entities are sent
+/// when they get into its view radius
+fn send_controlled_char(
+    remote_client: &mut RemoteClient,
+    char_id: EntityId<Local>,
+    ecs_world: &mut specs::World,
+) {
+    let char_state = {
+        let auth_char_storage = ecs_world.read_storage::<LocalCharStateComp<Local>>();
+        auth_char_storage.get(char_id.into()).unwrap().clone()
+    };
+    let static_data_storage = ecs_world.read_storage::<StaticCharDataComponent>();
+    let static_char_state = static_data_storage.get(char_id.into()).unwrap();
+    let packet_collector = &mut ecs_world.write_resource::<OutPacketCollector>();
+    send_packet(
+        packet_collector,
+        PacketTarget::Client(remote_client.socket_id),
+        FromServerPacket::NewEntity {
+            id: prepare_entity_id_for_sending(char_id),
+            name: static_char_state.name.clone(),
+            team: static_char_state.team,
+            typ: static_char_state.typ,
+            outlook: static_char_state.outlook.clone(),
+            job_id: static_char_state.job_id,
+            state: prepare_charsnapshot_for_sending(char_state),
+        },
+    );
+    send_packet(
+        packet_collector,
+        PacketTarget::Client(remote_client.socket_id),
+        FromServerPacket::MatchStarted {
+            controlled_entity: prepare_entity_id_for_sending(char_id),
+        },
+    );
+
+    remote_client.interest.add_visible(char_id);
+}
+
 fn process_incoming_packets(
     tmp_vec: &mut Vec<(SocketId, NetworkTrafficEvent<ToServerPacket>)>,
     packet_handler_thread: &PacketHandlerThread<ToServerPacket, FromServerPacket>,
     remote_clients: &mut Vec<Option<RemoteClient>>,
     ecs_world: &mut specs::World,
     config: &AppConfig,
-    next_player_team: &mut Team,
+    lobby: &mut Lobby,
+    sessions: &mut Sessions,
 ) {
     tmp_vec.clear();
     packet_handler_thread.receive_into(tmp_vec);
-    for (client_socket, packet) in tmp_vec.drain(..) {
-        match packet {
-            NetworkTrafficEvent::IncomingTraffic { received_data_len } => {
-                //
-            }
-            NetworkTrafficEvent::OutgoingTraffic { sent_data_len } => {}
-            NetworkTrafficEvent::Disconnected => {
-                log::debug!("Client({:?}) has been disconnected", client_socket);
-                disconnect_client(remote_clients, client_socket, ecs_world);
-            }
-            NetworkTrafficEvent::LocalError(e) => {
-                log::error!("Client({:?}) has been disconnected: {:?}", client_socket, e);
-                disconnect_client(remote_clients, client_socket, ecs_world);
+    for (client_socket, event) in tmp_vec.drain(..) {
+        handle_network_event(
+            client_socket,
+            event,
+            packet_handler_thread,
+            remote_clients,
+            ecs_world,
+            config,
+            lobby,
+            sessions,
+        );
+    }
+}
+
+fn handle_network_event(
+    client_socket: SocketId,
+    event: NetworkTrafficEvent<ToServerPacket>,
+    packet_handler_thread: &PacketHandlerThread<ToServerPacket, FromServerPacket>,
+    remote_clients: &mut Vec<Option<RemoteClient>>,
+    ecs_world: &mut specs::World,
+    config: &AppConfig,
+    lobby: &mut Lobby,
+    sessions: &mut Sessions,
+) {
+    match event {
+        NetworkTrafficEvent::Connected(sock_addr) => {
+            add_remote_client(remote_clients, new_remote_client(client_socket, sock_addr));
+        }
+        NetworkTrafficEvent::IncomingTraffic {
+            received_data_len: _,
+        } => {
+            //
+        }
+        NetworkTrafficEvent::OutgoingTraffic { sent_data_len: _ } => {}
+        NetworkTrafficEvent::Disconnected => {
+            log::debug!("Client({:?}) has been disconnected", client_socket);
+            disconnect_client(
+                remote_clients,
+                client_socket,
+                ecs_world,
+                lobby,
+                sessions,
+                config,
+            );
+        }
+        NetworkTrafficEvent::LocalError(e) => {
+            log::error!("Client({:?}) has been disconnected: {:?}", client_socket, e);
+            disconnect_client(
+                remote_clients,
+                client_socket,
+                ecs_world,
+                lobby,
+                sessions,
+                config,
+            );
+        }
+        NetworkTrafficEvent::Packet(p) => {
+            if remote_clients
+                .get(client_socket.as_usize())
+                .map(|it| it.is_none())
+                .unwrap_or(true)
+            {
+                // it has been rejected or kicked, but its packets were already on the way
+                log::debug!(
+                    "Dropping packet of disconnected {:?}: {:?}",
+                    client_socket,
+                    p
+                );
+                return;
             }
-            NetworkTrafficEvent::Packet(p) => {
-                match p {
-                    ToServerPacket::Welcome { name } => {
-                        log::info!("{} welcomed ^^", name);
-                        let remote_client =
-                            remote_clients[client_socket.as_usize()].as_mut().unwrap();
-                        remote_client.name = name;
-                        send_packet(
-                            &mut ecs_world.write_resource(),
-                            PacketTarget::Client(client_socket),
-                            FromServerPacket::Init {
-                                //    let map_name = "bat_a01"; // battle ground
-                                map_name: "prontera".to_string(),
-                                start_x: config.start_pos_x,
-                                start_y: config.start_pos_y,
-                            },
+            match p {
+                ToServerPacket::Welcome {
+                    protocol_version,
+                    build,
+                    name,
+                    session_token,
+                } => {
+                    if protocol_version != PROTOCOL_VERSION {
+                        let reason = format!(
+                                "Incompatible protocol version, server: {} (build {}), client: {} (build {})",
+                                PROTOCOL_VERSION, BUILD_ID, protocol_version, build
+                            );
+                        log::error!("{} has been rejected: {}", name, reason);
+                        packet_handler_thread
+                            .send(client_socket, FromServerPacket::Rejected { reason });
+                        packet_handler_thread.disconnect(client_socket);
+                        disconnect_client(
+                            remote_clients,
+                            client_socket,
+                            ecs_world,
+                            lobby,
+                            sessions,
+                            config,
                         );
-                        let configs = (*ecs_world.read_resource::<CommonConfigs>()).clone();
-                        send_packet(
-                            &mut ecs_world.write_resource(),
-                            PacketTarget::Client(client_socket),
-                            FromServerPacket::Configs(configs),
+                        return;
+                    } else if build != BUILD_ID {
+                        log::warn!(
+                            "{} uses a different build: {}, server: {}",
+                            name,
+                            build,
+                            BUILD_ID
                         );
                     }
-                    ToServerPacket::Ping => {
-                        packet_handler_thread.send(
-                            client_socket,
-                            FromServerPacket::Pong {
-                                server_time: GameTime::from(
-                                    ecs_world.read_resource::<EngineTime>().now().as_millis(),
-                                ),
-                                server_tick: *ecs_world.read_resource::<SimulationTick>(),
-                            },
-                        );
+                    let remote_client = remote_clients[client_socket.as_usize()].as_mut().unwrap();
+                    let reattached_session =
+                        session_token.and_then(|token| sessions.reattach(token));
+                    if let Some(session) = reattached_session {
+                        log::info!("{} has reconnected", session.name);
+                        lobby.rebind(session.socket_id, client_socket);
+                        remote_client.name = session.name;
+                        remote_client.controller_id = Some(session.controller_id);
+                        remote_client.session_token = session.token;
+                    } else {
+                        log::info!("{} welcomed ^^", name);
+                        remote_client.name = name;
                     }
-                    ToServerPacket::ReadyForGame => {
-                        let (connecting_char_id, connecting_char_snapshot) = {
-                            let remote_client =
-                                remote_clients[client_socket.as_usize()].as_mut().unwrap();
-                            remote_client.name =
-                                format!("{} {}", next_player_team.to_str(), remote_client.name);
-                            log::info!("{} is ready to play", remote_client.name);
-
-                            let char_id = EntityId::from(
-                                create_common_player_entity(
-                                    remote_client.name.clone(),
-                                    ecs_world,
-                                    CharType::Player,
-                                    JobId::CRUSADER,
-                                    v2(config.start_pos_x, config.start_pos_y),
-                                    *next_player_team,
-                                    CharOutlook::Human {
-                                        job_sprite_id: JobSpriteId::from_job_id(JobId::CRUSADER),
-                                        head_index: 0,
-                                        sex: Sex::Male,
-                                    },
-                                )
-                                .build(),
-                            );
-                            *next_player_team = next_player_team.get_opponent_team();
-                            let network_player_id = ecs_world
-                                .create_entity()
-                                .with(ControllerComponent::new(char_id))
-                                .build();
-                            remote_client.controller_id =
-                                Some(ControllerEntityId::new(network_player_id));
-
-                            let char_state = {
-                                let auth_char_storage =
-                                    ecs_world.read_storage::<LocalCharStateComp<Local>>();
-                                auth_char_storage.get(char_id.into()).unwrap().clone()
-                            };
-                            let static_data_storage =
-                                ecs_world.read_storage::<StaticCharDataComponent>();
-                            let static_char_state =
-                                static_data_storage.get(char_id.into()).unwrap();
-
-                            send_packet(
-                                &mut ecs_world.write_resource(),
-                                PacketTarget::Client(remote_client.socket_id),
-                                FromServerPacket::NewEntity {
-                                    id: prepare_entity_id_for_sending(char_id),
-                                    name: static_char_state.name.clone(),
-                                    team: static_char_state.team,
-                                    typ: static_char_state.typ.clone(),
-                                    outlook: static_char_state.outlook.clone(),
-                                    job_id: static_char_state.job_id,
-                                    state: prepare_charsnapshot_for_sending(char_state.clone()),
-                                },
-                            );
-
-                            // send her the player list
-                            {
-                                let auth_char_storage =
-                                    ecs_world.read_storage::<LocalCharStateComp<Local>>();
-                                for (other_char_id, other_char_state, other_static_data) in (
-                                    &ecs_world.entities(),
-                                    &auth_char_storage,
-                                    &static_data_storage,
-                                )
-                                    .join()
-                                {
-                                    let other_char_id = EntityId::new(other_char_id);
-                                    if other_char_id == char_id {
-                                        continue;
-                                    }
-                                    send_packet(
-                                        &mut ecs_world.write_resource(),
-                                        PacketTarget::Client(remote_client.socket_id),
-                                        FromServerPacket::NewEntity {
-                                            id: prepare_entity_id_for_sending(other_char_id),
-                                            name: other_static_data.name.to_owned(),
-                                            team: other_static_data.team,
-                                            typ: other_static_data.typ.clone(),
-                                            outlook: other_static_data.outlook.clone(),
-                                            job_id: other_static_data.job_id,
-                                            state: prepare_charsnapshot_for_sending(
-                                                other_char_state.clone(),
-                                            ),
-                                        },
-                                    );
-                                }
+                    send_packet(
+                        &mut ecs_world.write_resource(),
+                        PacketTarget::Client(client_socket),
+                        FromServerPacket::Init {
+                            protocol_version: PROTOCOL_VERSION,
+                            //    let map_name = "bat_a01"; // battle ground
+                            map_name: config.map_name.clone(),
+                            start_x: config.start_pos_x,
+                            start_y: config.start_pos_y,
+                            session_token: remote_client.session_token,
+                        },
+                    );
+                    let configs = (*ecs_world.read_resource::<CommonConfigs>()).clone();
+                    send_packet(
+                        &mut ecs_world.write_resource(),
+                        PacketTarget::Client(client_socket),
+                        FromServerPacket::Configs(configs),
+                    );
+                }
+                ToServerPacket::Ping => {
+                    packet_handler_thread.send(
+                        client_socket,
+                        FromServerPacket::Pong {
+                            server_time: GameTime::from(
+                                ecs_world.read_resource::<EngineTime>().now().as_millis(),
+                            ),
+                            server_tick: *ecs_world.read_resource::<SimulationTick>(),
+                        },
+                    );
+                }
+                ToServerPacket::ReadyForGame => {
+                    let remote_client = remote_clients[client_socket.as_usize()].as_mut().unwrap();
+                    if let Some(controller_id) = remote_client.controller_id {
+                        // reconnected during the match, the whole state is sent again
+                        let controlled_entity = ecs_world
+                            .read_storage::<ControllerComponent>()
+                            .get(controller_id.into())
+                            .and_then(|it| it.controlled_entity);
+                        if let Some(controlled_entity) = controlled_entity {
+                            send_controlled_char(remote_client, controlled_entity, ecs_world);
+                        }
+                    } else {
+                        log::info!("{} has joined the lobby", remote_client.name);
+                        lobby.join(client_socket, remote_client.name.clone());
+                    }
+                    send_packet(
+                        &mut ecs_world.write_resource(),
+                        PacketTarget::All,
+                        lobby.state_packet(),
+                    );
+                }
+                ToServerPacket::LobbyChoice { name, team, job_id } => {
+                    let result = lobby.choose(
+                        client_socket,
+                        name,
+                        team,
+                        job_id,
+                        &ecs_world.read_resource::<CommonConfigs>(),
+                    );
+                    if let Err(reason) = result {
+                        log::warn!("Lobby choice of {:?} is refused: {}", client_socket, reason);
+                    }
+                    send_packet(
+                        &mut ecs_world.write_resource(),
+                        PacketTarget::All,
+                        lobby.state_packet(),
+                    );
+                }
+                ToServerPacket::ReadyCheck(ready) => {
+                    lobby.set_ready(client_socket, ready);
+                    send_packet(
+                        &mut ecs_world.write_resource(),
+                        PacketTarget::All,
+                        lobby.state_packet(),
+                    );
+                }
+                ToServerPacket::Intention {
+                    cid,
+                    client_tick,
+                    intention,
+                } => {
+                    let remote_client = remote_clients[client_socket.as_usize()].as_mut().unwrap();
+                    if let Some(controller_id) = remote_client.controller_id {
+                        let intention = match intention {
+                            PlayerIntention::MoveTo(v) => PlayerIntention::MoveTo(v),
+                            PlayerIntention::MoveTowardsMouse(v) => {
+                                PlayerIntention::MoveTowardsMouse(v)
                             }
-
-                            (char_id, char_state.clone())
-                        };
-
-                        // inform others about this player
-                        let packet = {
-                            let static_data_storage =
-                                ecs_world.read_storage::<StaticCharDataComponent>();
-                            let static_data =
-                                static_data_storage.get(connecting_char_id.into()).unwrap();
-                            let remote_client =
-                                remote_clients[client_socket.as_usize()].as_mut().unwrap();
-                            FromServerPacket::NewEntity {
-                                id: prepare_entity_id_for_sending(connecting_char_id),
-                                name: remote_client.name.clone(),
-                                team: static_data.team,
-                                typ: static_data.typ.clone(),
-                                outlook: static_data.outlook.clone(),
-                                job_id: static_data.job_id,
-                                state: prepare_charsnapshot_for_sending(
-                                    connecting_char_snapshot.clone(),
-                                ),
+                            PlayerIntention::AttackTowards(v) => PlayerIntention::AttackTowards(v),
+                            PlayerIntention::Attack(id) => {
+                                PlayerIntention::Attack(client_id_to_server(id))
                             }
+                            PlayerIntention::Casting(
+                                skill,
+                                is_self_cast,
+                                mouse_world_pos,
+                                target_entity,
+                            ) => PlayerIntention::Casting(
+                                skill,
+                                is_self_cast,
+                                mouse_world_pos,
+                                target_entity.map(client_id_to_server),
+                            ),
                         };
-
-                        send_packet(
-                            &mut ecs_world.write_resource(),
-                            PacketTarget::AllExcept(client_socket),
-                            packet,
+                        remote_client.last_command_id = cid;
+                        log::debug!(
+                            "client tick: {:?}, cid: {}, intention: {:?}",
+                            client_tick,
+                            cid,
+                            &intention
                         );
+                        apply_intention(controller_id, Some(intention), client_tick, ecs_world);
+                    } else {
+                        // TODO: close connection
                     }
-                    ToServerPacket::Intention {
-                        cid,
-                        client_tick,
-                        intention,
-                    } => {
-                        let remote_client =
-                            remote_clients[client_socket.as_usize()].as_mut().unwrap();
-                        if let Some(controller_id) = remote_client.controller_id {
-                            let mut controller_storage =
-                                ecs_world.write_storage::<ControllerComponent>();
-                            let controller: &mut ControllerComponent =
-                                controller_storage.get_mut(controller_id.into()).unwrap();
-                            controller.intention = Some(match intention {
-                                PlayerIntention::MoveTo(v) => PlayerIntention::MoveTo(v),
-                                PlayerIntention::MoveTowardsMouse(v) => {
-                                    PlayerIntention::MoveTowardsMouse(v)
-                                }
-                                PlayerIntention::AttackTowards(v) => {
-                                    PlayerIntention::AttackTowards(v)
-                                }
-                                PlayerIntention::Attack(id) => {
-                                    PlayerIntention::Attack(client_id_to_server(id))
-                                }
-                            });
-                            remote_client.last_command_id = cid;
-                            log::debug!(
-                                "client tick: {:?}, cid: {}, intention: {:?}",
-                                client_tick,
-                                cid,
-                                &controller.intention
-                            );
-                        } else {
-                            // TODO: close connection
-                        }
-                    }
-                    ToServerPacket::ConsoleCommand(cmd) => {
-                        let remote_client =
-                            remote_clients[client_socket.as_usize()].as_mut().unwrap();
-                        execute_console_cmd(remote_client.controller_id, cmd, ecs_world);
+                }
+                ToServerPacket::SnapshotReceived { tick } => {
+                    let remote_client = remote_clients[client_socket.as_usize()].as_mut().unwrap();
+                    remote_client.last_acked_snapshot_tick = Some(tick);
+                }
+                ToServerPacket::ConsoleCommand(cmd) => {
+                    let remote_client = remote_clients[client_socket.as_usize()].as_mut().unwrap();
+                    let permission = cmd
+                        .get_command_name()
+                        .and_then(find_server_command)
+                        .map(|it| it.permission)
+                        .unwrap_or(CommandPermission::Admin);
+                    if permission == CommandPermission::Admin && !remote_client.is_admin {
+                        log::warn!("{} is not allowed to execute {:?}", remote_client.name, cmd);
+                    } else if let Err(e) =
+                        execute_recorded_console_cmd(remote_client.controller_id, cmd, ecs_world)
+                    {
+                        log::error!("{}: {}", remote_client.name, e);
                     }
                 }
             }
@@ -622,6 +1536,281 @@ fn process_incoming_packets(
     }
 }
 
+fn apply_intention(
+    controller_id: ControllerEntityId,
+    intention: Option<PlayerIntention<Local>>,
+    client_tick: SimulationTick,
+    ecs_world: &mut specs::World,
+) {
+    ecs_world
+        .write_resource::<ReplayRecorder>()
+        .record(&ReplayEvent::Intention {
+            controller: controller_to_entity_id(controller_id),
+            intention: intention.clone(),
+            client_tick,
+        });
+    let target_entity = match &intention {
+        Some(PlayerIntention::Attack(id)) | Some(PlayerIntention::Casting(_, _, _, Some(id))) => {
+            Some(*id)
+        }
+        _ => None,
+    };
+    let controlled_entity = {
+        let mut controller_storage = ecs_world.write_storage::<ControllerComponent>();
+        let controller = controller_storage.get_mut(controller_id.into()).unwrap();
+        controller.intention = intention;
+        controller.controlled_entity
+    };
+    if let Some(attacker) = controlled_entity {
+        // the compensation of the previous intention must not be applied to this one
+        ecs_world
+            .write_resource::<LagCompensatedTargets>()
+            .remove(attacker);
+        if let Some(target) = target_entity {
+            compensate_lag(attacker, target, client_tick, ecs_world);
+        }
+    }
+}
+
+fn execute_recorded_console_cmd(
+    controller_id: Option<ControllerEntityId>,
+    cmd: CommandArguments,
+    ecs_world: &mut specs::World,
+) -> Result<(), String> {
+    ecs_world
+        .write_resource::<ReplayRecorder>()
+        .record(&ReplayEvent::ConsoleCommand {
+            controller: controller_id.map(controller_to_entity_id),
+            cmd: cmd.clone(),
+        });
+    let reloads_configs = reads_files(&cmd);
+    let result = execute_console_cmd(controller_id, cmd, ecs_world);
+    if reloads_configs && result.is_ok() {
+        // the files might have been changed again by the time of the playback
+        let configs = (*ecs_world.read_resource::<CommonConfigs>()).clone();
+        ecs_world
+            .write_resource::<ReplayRecorder>()
+            .record(&ReplayEvent::ConfigsChanged(configs));
+    }
+    result
+}
+
+/// The commands which modify the simulation are executed as console commands,
+/// so they are recorded in the replay
+fn execute_admin_cmd(
+    args: CommandArguments,
+    asset_loader: &CommonAssetLoader,
+    packet_handler_thread: &PacketHandlerThread<ToServerPacket, FromServerPacket>,
+    remote_clients: &mut [Option<RemoteClient>],
+    ecs_world: &mut specs::World,
+    config: &mut AppConfig,
+    lobby: &mut Lobby,
+    sessions: &Sessions,
+    paused: &mut bool,
+) -> Result<String, String> {
+    match args.get_command_name() {
+        Some("list_players") => Ok(list_players(remote_clients, sessions, ecs_world)),
+        Some("kick") => {
+            let name = args
+                .as_str(0)
+                .ok_or_else(|| "Usage: kick <name> [reason]".to_owned())?;
+            let socket_id = find_client_by_name(remote_clients, name)?;
+            let reason = args.as_str(1).unwrap_or("Kicked by an admin").to_owned();
+            kick_client(
+                remote_clients,
+                socket_id,
+                reason,
+                packet_handler_thread,
+                ecs_world,
+                lobby,
+            );
+            Ok(String::new())
+        }
+        Some("set_admin") => {
+            let usage = || "Usage: set_admin <name> <true|false>".to_owned();
+            let name = args.as_str(0).ok_or_else(usage)?;
+            let is_admin = match args.as_str(1) {
+                Some("true") => true,
+                Some("false") => false,
+                _ => return Err(usage()),
+            };
+            let socket_id = find_client_by_name(remote_clients, name)?;
+            if let Some(remote_client) = &mut remote_clients[socket_id.as_usize()] {
+                remote_client.is_admin = is_admin;
+            }
+            Ok(String::new())
+        }
+        Some("change_map") => {
+            let map_name = args
+                .as_str(0)
+                .ok_or_else(|| "Usage: change_map <map_name>".to_owned())?
+                .to_owned();
+            if let MatchState::Running { .. } = lobby.state() {
+                return Err("The map can not be changed during a match".to_owned());
+            }
+            let map_walking_info = load_map_walking_info(asset_loader, &map_name)?;
+            // the clients load the map only once, after the handshake
+            let socket_ids: Vec<SocketId> = remote_clients
+                .iter()
+                .filter_map(|it| it.as_ref())
+                .map(|it| it.socket_id)
+                .collect();
+            for socket_id in &socket_ids {
+                kick_client(
+                    remote_clients,
+                    *socket_id,
+                    format!("The map has been changed to {}, please reconnect", map_name),
+                    packet_handler_thread,
+                    ecs_world,
+                    lobby,
+                );
+            }
+            insert_map(ecs_world, &map_name, map_walking_info);
+            ecs_world
+                .write_resource::<ReplayRecorder>()
+                .record(&ReplayEvent::MapChanged(map_name.clone()));
+            config.map_name = map_name;
+            Ok(format!(
+                "{} clients have been disconnected",
+                socket_ids.len()
+            ))
+        }
+        Some("pause") => {
+            *paused = true;
+            Ok(String::new())
+        }
+        Some("resume") => {
+            *paused = false;
+            Ok(String::new())
+        }
+        _ => execute_recorded_console_cmd(None, args, ecs_world).map(|_| String::new()),
+    }
+}
+
+fn list_players(
+    remote_clients: &[Option<RemoteClient>],
+    sessions: &Sessions,
+    ecs_world: &specs::World,
+) -> String {
+    let controller_storage = ecs_world.read_storage::<ControllerComponent>();
+    let auth_char_storage = ecs_world.read_storage::<LocalCharStateComp<Local>>();
+    let static_data_storage = ecs_world.read_storage::<StaticCharDataComponent>();
+    let mut lines = Vec::with_capacity(remote_clients.len());
+    for remote_client in remote_clients.iter().filter_map(|it| it.as_ref()) {
+        let char_info = remote_client
+            .controller_id
+            .and_then(|it| controller_storage.get(it.into()))
+            .and_then(|it| it.controlled_entity)
+            .and_then(|char_id| {
+                let char_state = auth_char_storage.get(char_id.into())?;
+                let static_data = static_data_storage.get(char_id.into())?;
+                Some(format!(
+                    "{}, {}, {}, pos: ({:.1}, {:.1}), hp: {}",
+                    char_id,
+                    static_data.team.to_str(),
+                    static_data.job_id,
+                    char_state.pos().x,
+                    char_state.pos().y,
+                    char_state.hp
+                ))
+            })
+            .unwrap_or_else(|| "in the lobby".to_owned());
+        lines.push(format!(
+            "{:?} {} ({}){}: {}",
+            remote_client.socket_id,
+            remote_client.name,
+            remote_client.sock_addr,
+            if remote_client.is_admin { " admin" } else { "" },
+            char_info
+        ));
+    }
+    for session in sessions.disconnected() {
+        lines.push(format!("{}: waiting for reconnection", session.name));
+    }
+    lines.join("\n")
+}
+
+fn find_client_by_name(
+    remote_clients: &[Option<RemoteClient>],
+    name: &str,
+) -> Result<SocketId, String> {
+    remote_clients
+        .iter()
+        .filter_map(|it| it.as_ref())
+        .find(|it| it.name == name)
+        .map(|it| it.socket_id)
+        .ok_or_else(|| format!("There is no player named '{}'", name))
+}
+
+/// Unlike after a lost connection, the character is removed immediately
+fn kick_client(
+    remote_clients: &mut [Option<RemoteClient>],
+    socket_id: SocketId,
+    reason: String,
+    packet_handler_thread: &PacketHandlerThread<ToServerPacket, FromServerPacket>,
+    ecs_world: &mut specs::World,
+    lobby: &mut Lobby,
+) {
+    let remote_client = match remote_clients[socket_id.as_usize()].take() {
+        Some(remote_client) => remote_client,
+        None => return,
+    };
+    log::info!("{} has been kicked: {}", remote_client.name, reason);
+    packet_handler_thread.send(socket_id, FromServerPacket::Rejected { reason });
+    packet_handler_thread.disconnect(socket_id);
+    if let Some(controller_id) = remote_client.controller_id {
+        remove_player_char(remote_clients, controller_id, ecs_world);
+    }
+    lobby.leave(socket_id);
+    send_packet(
+        &mut ecs_world.write_resource(),
+        PacketTarget::All,
+        lobby.state_packet(),
+    );
+}
+
+/// The attacker is at its current position on the screen of its player (due to prediction),
+/// but the target is where the last received snapshots showed it
+fn compensate_lag(
+    attacker: EntityId<Local>,
+    target: EntityId<Local>,
+    client_tick: SimulationTick,
+    ecs_world: &mut specs::World,
+) {
+    let now_tick = *ecs_world.read_resource::<SimulationTick>();
+    let history = ecs_world.read_resource::<PositionHistory>();
+    let oldest_tick = now_tick
+        .as_u64()
+        .saturating_sub(history.max_rewind_ticks() as u64);
+    if client_tick.as_u64() < oldest_tick {
+        log::debug!(
+            "Intention from tick {:?} is rewound only to {}",
+            client_tick,
+            oldest_tick
+        );
+    }
+    let attacker_pos = ecs_world
+        .read_storage::<LocalCharStateComp<Local>>()
+        .get(attacker.into())
+        .map(|it| it.pos());
+    let rewound_target_pos = history
+        .positions_at(client_tick)
+        .and_then(|positions| positions.get(&target).copied());
+    if let (Some(attacker_pos), Some(rewound_target_pos)) = (attacker_pos, rewound_target_pos) {
+        let now = ecs_world.read_resource::<EngineTime>().now();
+        let max_rewind_seconds =
+            history.max_rewind_ticks() as f32 * MAX_SECONDS_ALLOWED_FOR_SINGLE_SIMULATION_FRAME;
+        ecs_world.write_resource::<LagCompensatedTargets>().insert(
+            attacker,
+            LagCompensatedTarget {
+                target,
+                distance: (attacker_pos - rewound_target_pos).magnitude(),
+                valid_until: now.add_seconds(max_rewind_seconds),
+            },
+        );
+    }
+}
+
 pub fn send_packet(
     packet_collector: &mut OutPacketCollector,
     target: PacketTarget,
@@ -634,32 +1823,91 @@ fn disconnect_client(
     remote_clients: &mut [Option<RemoteClient>],
     socket_id: SocketId,
     ecs_world: &mut specs::World,
+    lobby: &mut Lobby,
+    sessions: &mut Sessions,
+    config: &AppConfig,
 ) {
-    let controller_id = remote_clients[socket_id.as_usize()]
-        .as_ref()
-        .unwrap()
-        .controller_id;
-    remote_clients[socket_id.as_usize()] = None;
-    if let Some(controller_id) = controller_id {
-        // TODO: remove its original char!
-
-        let mut controlled_entity = ecs_world
-            .read_storage::<ControllerComponent>()
-            .get(controller_id.into())
-            .unwrap()
-            .controlled_entity;
-        if let Some(controlled_entity) = controlled_entity {
-            ecs_world.delete_entity(controlled_entity.into());
-            send_packet(
-                &mut ecs_world.write_resource(),
-                PacketTarget::All,
-                FromServerPacket::PlayerDisconnected(prepare_entity_id_for_sending(
-                    controlled_entity,
-                )),
+    let remote_client = match remote_clients[socket_id.as_usize()].take() {
+        Some(remote_client) => remote_client,
+        // e.g. it has already been rejected
+        None => return,
+    };
+    if let Some(controller_id) = remote_client.controller_id {
+        if config.reconnect_grace_period_seconds > 0.0 {
+            // the character stays idle in the world until its player reconnects
+            let tick = *ecs_world.read_resource::<SimulationTick>();
+            apply_intention(controller_id, None, tick, ecs_world);
+            log::info!(
+                "{} has lost the connection, waiting {}s for reconnection",
+                remote_client.name,
+                config.reconnect_grace_period_seconds
             );
+            let now = ecs_world.read_resource::<EngineTime>().now();
+            sessions.park(DisconnectedSession {
+                token: remote_client.session_token,
+                name: remote_client.name,
+                controller_id,
+                socket_id,
+                disconnected_at: now,
+            });
+            return;
+        }
+        remove_player_char(remote_clients, controller_id, ecs_world);
+    }
+    lobby.leave(socket_id);
+    send_packet(
+        &mut ecs_world.write_resource(),
+        PacketTarget::All,
+        lobby.state_packet(),
+    );
+}
+
+fn expire_sessions(
+    sessions: &mut Sessions,
+    remote_clients: &mut [Option<RemoteClient>],
+    ecs_world: &mut specs::World,
+    lobby: &mut Lobby,
+    config: &AppConfig,
+) {
+    let now = ecs_world.read_resource::<EngineTime>().now();
+    let mut expired_sessions = Vec::new();
+    sessions.drain_expired(
+        now,
+        config.reconnect_grace_period_seconds,
+        &mut expired_sessions,
+    );
+    for session in expired_sessions {
+        log::info!("{} has not reconnected in time", session.name);
+        remove_player_char(remote_clients, session.controller_id, ecs_world);
+        lobby.leave(session.socket_id);
+        send_packet(
+            &mut ecs_world.write_resource(),
+            PacketTarget::All,
+            lobby.state_packet(),
+        );
+    }
+}
+
+fn remove_player_char(
+    remote_clients: &mut [Option<RemoteClient>],
+    controller_id: ControllerEntityId,
+    ecs_world: &mut specs::World,
+) {
+    let controlled_entity = ecs_world
+        .read_storage::<ControllerComponent>()
+        .get(controller_id.into())
+        .and_then(|it| it.controlled_entity);
+    if let Some(controlled_entity) = controlled_entity {
+        for remote_client in remote_clients.iter_mut().flatten() {
+            remote_client.interest.forget(controlled_entity);
         }
-        ecs_world.delete_entity(controller_id.into());
+        send_packet(
+            &mut ecs_world.write_resource(),
+            PacketTarget::All,
+            FromServerPacket::PlayerDisconnected(prepare_entity_id_for_sending(controlled_entity)),
+        );
     }
+    delete_controller_and_char(controller_id, ecs_world);
 }
 
 pub fn create_ecs_world() -> specs::World {
@@ -667,5 +1915,149 @@ pub fn create_ecs_world() -> specs::World {
     ecs_world.register::<LocalCharStateComp<Local>>();
     ecs_world.register::<ControllerComponent>();
     ecs_world.register::<StaticCharDataComponent>();
+    ecs_world.register::<Statuses>();
+    ecs_world.register::<SkillManifestationComponent>();
+    ecs_world.register::<TurretComponent>();
+    ecs_world.register::<TurretControllerComponent>();
+    ecs_world.register::<CorpseComponent>();
+    ecs_world.register::<ProjectileComponent>();
+    ecs_world.register::<MinionComponent>();
+    ecs_world.register::<GuardianComponent>();
     ecs_world
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A server with a single connected client, which has not sent anything yet
+    struct TestServer {
+        world: specs::World,
+        config: AppConfig,
+        lobby: Lobby,
+        sessions: Sessions,
+        remote_clients: Vec<Option<RemoteClient>>,
+        packet_handler_thread: PacketHandlerThread<ToServerPacket, FromServerPacket>,
+        socket_id: SocketId,
+        // the handler thread sends the packets of the client to it
+        _peer: UdpSocket,
+    }
+
+    impl TestServer {
+        fn new() -> TestServer {
+            let mut world = create_ecs_world();
+            world.insert(EngineTime::new(0));
+            world.insert(SimulationTick::new());
+            world.insert(OutPacketCollector::new());
+            let config = AppConfig {
+                map_name: "prontera".to_owned(),
+                log_level: "info".to_owned(),
+                start_pos_x: 0.0,
+                start_pos_y: 0.0,
+                grf_paths: vec![],
+                server_port: 0,
+                transport: Transport::Udp,
+                max_packet_size: 1024,
+                min_players_to_start: 2,
+                match_duration_seconds: 0.0,
+                reconnect_grace_period_seconds: 0.0,
+                replay_file: String::new(),
+                admin_port: 0,
+                hot_reload_configs: false,
+            };
+            let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+            let mut packet_handler_thread = PacketHandlerThread::start_udp_thread(
+                1,
+                config.max_packet_size,
+                UdpSocket::bind("127.0.0.1:0").unwrap(),
+                false,
+            );
+            let peer_addr = peer.local_addr().unwrap();
+            let socket_id = packet_handler_thread.connect_udp(peer_addr);
+            let mut remote_clients = Vec::new();
+            add_remote_client(&mut remote_clients, new_remote_client(socket_id, peer_addr));
+            TestServer {
+                world,
+                config,
+                lobby: Lobby::new(),
+                sessions: Sessions::new(),
+                remote_clients,
+                packet_handler_thread,
+                socket_id,
+                _peer: peer,
+            }
+        }
+
+        fn receive(&mut self, packet: ToServerPacket) {
+            handle_network_event(
+                self.socket_id,
+                NetworkTrafficEvent::Packet(packet),
+                &self.packet_handler_thread,
+                &mut self.remote_clients,
+                &mut self.world,
+                &self.config,
+                &mut self.lobby,
+                &mut self.sessions,
+            );
+        }
+
+        fn is_disconnected(&self) -> bool {
+            self.remote_clients[self.socket_id.as_usize()].is_none()
+                && self.lobby.players().is_empty()
+        }
+    }
+
+    /// Packets which might arrive after the disconnection of their sender
+    fn pending_packets() -> Vec<ToServerPacket> {
+        vec![
+            ToServerPacket::ReadyForGame,
+            ToServerPacket::Intention {
+                cid: 1,
+                client_tick: SimulationTick::new(),
+                intention: PlayerIntention::MoveTo(v2(1.0, 1.0)),
+            },
+            ToServerPacket::SnapshotReceived {
+                tick: SimulationTick::new(),
+            },
+            ToServerPacket::ConsoleCommand(CommandArguments::new("list_players")),
+        ]
+    }
+
+    #[test]
+    fn packets_after_the_rejection_are_dropped() {
+        let mut server = TestServer::new();
+
+        server.receive(ToServerPacket::Welcome {
+            protocol_version: PROTOCOL_VERSION + 1,
+            build: BUILD_ID.to_owned(),
+            name: "Test".to_owned(),
+            session_token: None,
+        });
+        for packet in pending_packets() {
+            server.receive(packet);
+        }
+
+        assert!(server.is_disconnected());
+    }
+
+    #[test]
+    fn packets_after_the_kick_are_dropped() {
+        let mut server = TestServer::new();
+        server.receive(ToServerPacket::ReadyForGame);
+        assert_eq!(server.lobby.players().len(), 1);
+
+        kick_client(
+            &mut server.remote_clients,
+            server.socket_id,
+            "Kicked by an admin".to_owned(),
+            &server.packet_handler_thread,
+            &mut server.world,
+            &mut server.lobby,
+        );
+        for packet in pending_packets() {
+            server.receive(packet);
+        }
+
+        assert!(server.is_disconnected());
+    }
+}
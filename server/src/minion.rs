@@ -0,0 +1,126 @@
+use rustarok_common::common::{v2, EngineTime, GameTime, Local, Vec2};
+use rustarok_common::components::char::{
+    create_common_player_entity, CharOutlook, CharType, EntityId, JobId, Sex, Team,
+};
+use rustarok_common::components::controller::ControllerComponent;
+use rustarok_common::components::job_ids::JobSpriteId;
+use rustarok_common::components::minion::{MinionComponent, MinionConfig, MinionLanes};
+use rustarok_common::config::CommonConfigs;
+use specs::prelude::*;
+
+/// The distance between the minions of a wave, they are lined up behind the first waypoint
+const MINION_SPACING: f32 = 1.0;
+
+/// When the next waves are spawned, None outside of the matches
+pub struct MinionWaves {
+    pub next_wave_at: Option<GameTime<Local>>,
+}
+
+pub fn start_minion_waves(ecs_world: &mut specs::World) {
+    let now = ecs_world.read_resource::<EngineTime>().now();
+    let first_wave_seconds = ecs_world.read_resource::<MinionConfig>().first_wave_seconds;
+    ecs_world.write_resource::<MinionWaves>().next_wave_at =
+        Some(now.add_seconds(first_wave_seconds));
+}
+
+/// No more waves come and the minions are removed, e.g. at the end of the match
+pub fn stop_minion_waves(ecs_world: &mut specs::World) {
+    ecs_world.write_resource::<MinionWaves>().next_wave_at = None;
+    let minion_entities: Vec<Entity> = (
+        &ecs_world.entities(),
+        &ecs_world.read_storage::<ControllerComponent>(),
+        &ecs_world.read_storage::<MinionComponent>(),
+    )
+        .join()
+        .flat_map(|(controller_id, controller, _minion)| {
+            std::iter::once(controller_id)
+                .chain(controller.controlled_entity.map(|char_id| char_id.into()))
+        })
+        .collect();
+    for entity in minion_entities {
+        let _ = ecs_world.delete_entity(entity);
+    }
+}
+
+/// Spawns a wave on every lane of the current map when its time has come
+pub fn spawn_minion_waves(ecs_world: &mut specs::World) {
+    let now = ecs_world.read_resource::<EngineTime>().now();
+    let is_due = ecs_world
+        .read_resource::<MinionWaves>()
+        .next_wave_at
+        .map(|it| it.has_already_passed(now))
+        .unwrap_or(false);
+    if !is_due {
+        return;
+    }
+    let config = (*ecs_world.read_resource::<MinionConfig>()).clone();
+    ecs_world.write_resource::<MinionWaves>().next_wave_at =
+        Some(now.add_seconds(config.wave_interval_seconds));
+
+    let spawns: Vec<(usize, Team, Vec2, Vec2)> = ecs_world
+        .read_resource::<MinionLanes>()
+        .lanes
+        .iter()
+        .enumerate()
+        .map(|(lane_index, lane)| {
+            let start = lane.waypoint(0);
+            let backwards = if lane.waypoints.len() > 1 {
+                (start - lane.waypoint(1)).normalize()
+            } else {
+                v2(0.0, 0.0)
+            };
+            (lane_index, lane.team, start, backwards)
+        })
+        .collect();
+    let minion_count = config.melee_minions_per_wave + config.ranged_minions_per_wave;
+    for (lane_index, team, start, backwards) in spawns {
+        // the melee ones are in the front
+        for i in 0..minion_count {
+            let job_id = if i < config.melee_minions_per_wave {
+                JobId::MeleeMinion
+            } else {
+                JobId::RangedMinion
+            };
+            let pos = start + backwards * (i as f32 * MINION_SPACING);
+            create_minion(ecs_world, job_id, team, pos, Some(lane_index), 0);
+        }
+        log::debug!("{} minions have been spawned for {:?}", minion_count, team);
+    }
+}
+
+pub fn create_minion(
+    ecs_world: &mut specs::World,
+    job_id: JobId,
+    team: Team,
+    pos: Vec2,
+    lane: Option<usize>,
+    next_waypoint: usize,
+) -> EntityId<Local> {
+    let job_sprite_id = ecs_world
+        .read_resource::<CommonConfigs>()
+        .job(job_id)
+        .sprite
+        .unwrap_or(JobSpriteId::NOVICE);
+    let char_id = EntityId::from(
+        create_common_player_entity(
+            "Minion".to_owned(),
+            ecs_world,
+            CharType::Minion,
+            job_id,
+            pos,
+            team,
+            CharOutlook::Human {
+                job_sprite_id,
+                head_index: 0,
+                sex: Sex::Male,
+            },
+        )
+        .build(),
+    );
+    ecs_world
+        .create_entity()
+        .with(ControllerComponent::new(char_id))
+        .with(MinionComponent::new(lane, next_waypoint))
+        .build();
+    char_id
+}
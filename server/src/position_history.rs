@@ -0,0 +1,63 @@
+use rustarok_common::common::{Local, SimulationTick, Vec2};
+use rustarok_common::components::char::{EntityId, LocalCharStateComp};
+use specs::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+/// The positions of the characters in the last few ticks, the targets of the
+/// intentions are rewound to the tick the client has seen.
+pub struct PositionHistory {
+    max_len: usize,
+    ticks: VecDeque<(SimulationTick, HashMap<EntityId<Local>, Vec2>)>,
+}
+
+impl PositionHistory {
+    pub fn new(max_rewind_ticks: usize) -> PositionHistory {
+        PositionHistory {
+            max_len: max_rewind_ticks + 1,
+            ticks: VecDeque::with_capacity(max_rewind_ticks + 1),
+        }
+    }
+
+    pub fn max_rewind_ticks(&self) -> usize {
+        self.max_len - 1
+    }
+
+    /// The storage of the oldest tick is reused
+    fn next_positions(&mut self, tick: SimulationTick) -> &mut HashMap<EntityId<Local>, Vec2> {
+        let mut positions = if self.ticks.len() >= self.max_len {
+            self.ticks.pop_front().unwrap().1
+        } else {
+            HashMap::with_capacity(64)
+        };
+        positions.clear();
+        self.ticks.push_back((tick, positions));
+        &mut self.ticks.back_mut().unwrap().1
+    }
+
+    /// If the tick is too old, the oldest stored positions are returned
+    pub fn positions_at(&self, tick: SimulationTick) -> Option<&HashMap<EntityId<Local>, Vec2>> {
+        self.ticks
+            .iter()
+            .find(|(stored_tick, _positions)| stored_tick.as_u64() >= tick.as_u64())
+            .or_else(|| self.ticks.back())
+            .map(|(_tick, positions)| positions)
+    }
+}
+
+pub struct PositionHistoryRecorderSystem;
+
+impl<'a> System<'a> for PositionHistoryRecorderSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, LocalCharStateComp<Local>>,
+        ReadExpect<'a, SimulationTick>,
+        WriteExpect<'a, PositionHistory>,
+    );
+
+    fn run(&mut self, (entities, char_state_storage, tick, mut history): Self::SystemData) {
+        let positions = history.next_positions(*tick);
+        for (entity_id, char_state) in (&entities, &char_state_storage).join() {
+            positions.insert(EntityId::from(entity_id), char_state.pos());
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::server_config::ServerConfig;
+use rustarok_common::common::{Local, SimulationTick, Vec2};
+use rustarok_common::components::char::{EntityId, JobId, LocalCharStateComp, Team};
+use rustarok_common::components::controller::PlayerIntention;
+use rustarok_common::config::CommonConfigs;
+use rustarok_common::console::CommandArguments;
+use rustarok_common::state_checksum::{write_exact_char_state, StableHasher};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+/// The first record of a replay file, everything which is needed to rebuild the initial world
+#[derive(Serialize, Deserialize)]
+pub struct ReplayHeader {
+    pub build: String,
+    pub map_name: String,
+    pub server_config: ServerConfig,
+    pub configs: CommonConfigs,
+}
+
+/// Every input which modifies the simulation, in the order they were applied
+#[derive(Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum ReplayEvent {
+    SpawnPlayer {
+        name: String,
+        job_id: JobId,
+        team: Team,
+        pos: Vec2,
+    },
+    RemovePlayer {
+        controller: EntityId<Local>,
+    },
+    Intention {
+        controller: EntityId<Local>,
+        intention: Option<PlayerIntention<Local>>,
+        client_tick: SimulationTick,
+    },
+    ConsoleCommand {
+        controller: Option<EntityId<Local>>,
+        cmd: CommandArguments,
+    },
+    ConfigsChanged(CommonConfigs),
+    /// The walkability and the walls of the new map are loaded from the GRF files
+    MapChanged(String),
+    /// The match has started, the structures are built and the minion waves are coming
+    MatchStarted,
+    /// The match has ended, the minions and the structures are removed
+    MatchEnded,
+    RunFrame,
+    /// The hash of the world state after the tick has been simulated
+    TickEnd {
+        tick: SimulationTick,
+        hash: u64,
+    },
+}
+
+pub struct ReplayRecorder {
+    writer: Option<BufWriter<File>>,
+}
+
+impl ReplayRecorder {
+    /// Records nothing, e.g. during playback
+    pub fn disabled() -> ReplayRecorder {
+        ReplayRecorder { writer: None }
+    }
+
+    pub fn new(path: &str, header: &ReplayHeader) -> std::io::Result<ReplayRecorder> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        bincode::serialize_into(&mut writer, header).map_err(std::io::Error::other)?;
+        Ok(ReplayRecorder {
+            writer: Some(writer),
+        })
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    pub fn record(&mut self, event: &ReplayEvent) {
+        if let Some(writer) = &mut self.writer {
+            let result = bincode::serialize_into(&mut *writer, event).and_then(|_| {
+                // so the file is usable even if the server is killed
+                if let ReplayEvent::TickEnd { .. } = event {
+                    writer.flush()?;
+                }
+                Ok(())
+            });
+            if let Err(e) = result {
+                log::error!("Replay recording has been stopped: {}", e);
+                self.writer = None;
+            }
+        }
+    }
+}
+
+/// It must be bit exact, the replayed simulation has to produce the very same states
+pub fn world_state_hash(ecs_world: &specs::World) -> u64 {
+    let entities = ecs_world.entities();
+    let char_state_storage = ecs_world.read_storage::<LocalCharStateComp<Local>>();
+    let mut states: Vec<(EntityId<Local>, &LocalCharStateComp<Local>)> =
+        (&entities, &char_state_storage)
+            .join()
+            .map(|(entity, char_state)| (EntityId::from(entity), char_state))
+            .collect();
+    states.sort_by_key(|(id, _state)| id.as_u64());
+
+    let mut hasher = StableHasher::new();
+    for (id, state) in states {
+        hasher.write_u64(id.as_u64());
+        if let Err(e) = write_exact_char_state(&mut hasher, state) {
+            log::error!("Could not serialize the state of {}: {}", id, e);
+        }
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustarok_common::common::{v2, GameTime};
+    use rustarok_common::components::char::CharType;
+    use rustarok_common::components::skills::skills::Skills;
+    use rustarok_common::test_utils::{attributes, create_char_from, create_world, static_data};
+
+    const COOLDOWNS: [(Skills, u32); 5] = [
+        (Skills::FireWall, 1000),
+        (Skills::Lightning, 2500),
+        (Skills::Heal, 4000),
+        (Skills::Poison, 1500),
+        (Skills::AbsorbShield, 8000),
+    ];
+
+    fn world_with_cooldowns<'a>(cooldowns: impl Iterator<Item = &'a (Skills, u32)>) -> World {
+        let mut world = create_world();
+        let mut state = LocalCharStateComp::new(v2(10.0, 20.0), attributes());
+        for (skill, allowed_at) in cooldowns {
+            state
+                .skill_cast_allowed_at
+                .insert(*skill, GameTime::from(*allowed_at));
+        }
+        create_char_from(&mut world, state, static_data(CharType::Player, Team::Left));
+        world
+    }
+
+    #[test]
+    fn replayed_cooldowns_have_the_recorded_hash() {
+        let recorded = world_with_cooldowns(COOLDOWNS.iter());
+        let bytes = bincode::serialize(&ReplayEvent::TickEnd {
+            tick: SimulationTick::new(),
+            hash: world_state_hash(&recorded),
+        })
+        .unwrap();
+        let recorded_hash = match bincode::deserialize(&bytes).unwrap() {
+            ReplayEvent::TickEnd { hash, .. } => hash,
+            _ => panic!("TickEnd was expected"),
+        };
+
+        // every map gets its own random seed, so the iteration orders differ
+        for _ in 0..20 {
+            let replayed = world_with_cooldowns(COOLDOWNS.iter().rev());
+            assert_eq!(recorded_hash, world_state_hash(&replayed));
+        }
+        let changed = world_with_cooldowns(COOLDOWNS.iter().skip(1));
+        assert_ne!(recorded_hash, world_state_hash(&changed));
+    }
+}
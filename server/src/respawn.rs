@@ -0,0 +1,314 @@
+use crate::server_config::ServerConfig;
+use crate::{
+    prepare_entity_id_for_sending, prepare_time_for_sending, send_packet, OutPacketCollector,
+    PacketTarget,
+};
+use rustarok_common::common::{EngineTime, GameTime, Local};
+use rustarok_common::components::char::{
+    CharDied, CharType, EntityId, LocalCharStateComp, StaticCharDataComponent,
+};
+use rustarok_common::components::status::status::Statuses;
+use rustarok_common::packets::from_server::FromServerPacket;
+use specs::prelude::*;
+
+/// A dead character, at `until` it is respawned if it is a player, otherwise it is removed.
+/// Dead players without it remain dead until the end of the match.
+#[derive(Component)]
+pub struct CorpseComponent {
+    pub until: GameTime<Local>,
+}
+
+pub struct RespawnSystem;
+
+impl<'a> System<'a> for RespawnSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, LocalCharStateComp<Local>>,
+        ReadStorage<'a, StaticCharDataComponent>,
+        WriteStorage<'a, Statuses>,
+        WriteStorage<'a, CorpseComponent>,
+        ReadExpect<'a, EngineTime>,
+        ReadExpect<'a, ServerConfig>,
+        WriteExpect<'a, Vec<CharDied>>,
+        WriteExpect<'a, OutPacketCollector>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut auth_char_storage,
+            static_char_data_storage,
+            mut statuses_storage,
+            mut corpse_storage,
+            time,
+            server_config,
+            mut deaths,
+            mut packet_sender,
+        ): Self::SystemData,
+    ) {
+        let now = time.now();
+        for death in deaths.drain(..) {
+            let static_data = match static_char_data_storage.get(death.char_id.into()) {
+                Some(static_data) => static_data,
+                None => continue,
+            };
+            if let Some(statuses) = statuses_storage.get_mut(death.char_id.into()) {
+                statuses.remove_all();
+            }
+            let respawn_at = if static_data.typ == CharType::Player {
+                server_config
+                    .spawn_point(static_data.team)
+                    .filter(|spawn_point| spawn_point.respawn_seconds > 0.0)
+                    .map(|spawn_point| now.add_seconds(spawn_point.respawn_seconds))
+            } else {
+                None
+            };
+            let corpse_until = match static_data.typ {
+                CharType::Player => respawn_at,
+                // the ruins remain until the end of the match, the destroyed cores decide it
+                CharType::Structure => None,
+                _ => Some(now.add_seconds(server_config.corpse_removal_seconds)),
+            };
+            if let Some(until) = corpse_until {
+                if let Err(e) =
+                    corpse_storage.insert(death.char_id.into(), CorpseComponent { until })
+                {
+                    log::error!("Could not mark {:?} as corpse: {}", death.char_id, e);
+                }
+            }
+            log::info!(
+                "{} ({:?}) has died, respawn at: {:?}",
+                static_data.name,
+                death.char_id,
+                respawn_at
+            );
+            send_packet(
+                &mut packet_sender,
+                PacketTarget::Area(death.char_id),
+                FromServerPacket::CharDied {
+                    id: prepare_entity_id_for_sending(death.char_id),
+                    respawn_at: respawn_at.map(prepare_time_for_sending),
+                },
+            );
+            if static_data.typ == CharType::Structure {
+                log::info!(
+                    "The {} of {:?} has been destroyed",
+                    static_data.job_id,
+                    static_data.team
+                );
+                send_packet(
+                    &mut packet_sender,
+                    PacketTarget::All,
+                    FromServerPacket::StructureDestroyed {
+                        id: prepare_entity_id_for_sending(death.char_id),
+                        team: static_data.team,
+                        job_id: static_data.job_id,
+                    },
+                );
+            }
+        }
+
+        let mut finished_corpses = Vec::new();
+        for (char_entity_id, corpse, auth_state, static_data) in (
+            &entities,
+            &corpse_storage,
+            &mut auth_char_storage,
+            &static_char_data_storage,
+        )
+            .join()
+        {
+            // e.g. it has been resurrected by a console command
+            if auth_state.state().is_alive() {
+                finished_corpses.push(char_entity_id);
+                continue;
+            }
+            if corpse.until.has_not_passed_yet(now) {
+                continue;
+            }
+            finished_corpses.push(char_entity_id);
+            if static_data.typ == CharType::Player {
+                if let Some(spawn_point) = server_config.spawn_point(static_data.team) {
+                    auth_state.set_pos(spawn_point.pos());
+                }
+                auth_state.resurrect();
+                log::info!(
+                    "{} ({:?}) has respawned",
+                    static_data.name,
+                    EntityId::from(char_entity_id)
+                );
+            } else if let Err(e) = entities.delete(char_entity_id) {
+                log::error!("Could not remove corpse {:?}: {}", char_entity_id, e);
+            }
+        }
+        for char_entity_id in finished_corpses {
+            corpse_storage.remove(char_entity_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server_config::SpawnPoint;
+    use rustarok_common::common::{percentage, v2, Vec2};
+    use rustarok_common::components::char::{CharState, Team};
+    use rustarok_common::components::guardian::GuardianConfig;
+    use rustarok_common::components::minion::MinionConfig;
+    use rustarok_common::components::status::status::{StatusEnum, StatusEnumDiscriminants};
+    use rustarok_common::test_utils::{attributes, static_data};
+    use std::time::Duration;
+
+    fn create_world(respawn_seconds: f32) -> World {
+        let mut world = crate::create_ecs_world();
+        world.insert(EngineTime::new(0));
+        world.insert(OutPacketCollector::new());
+        world.insert(Vec::<CharDied>::new());
+        world.insert(ServerConfig {
+            view_radius: 30.0,
+            max_lag_compensation_ms: 0,
+            corpse_removal_seconds: 3.0,
+            spawn_points: vec![SpawnPoint {
+                team: Team::Left,
+                x: 10.0,
+                y: 20.0,
+                respawn_seconds,
+            }],
+            minions: MinionConfig {
+                aggro_radius: 10.0,
+                chase_radius: 15.0,
+                provoked_seconds: 3.0,
+                waypoint_reached_radius: 1.0,
+                first_wave_seconds: 0.0,
+                wave_interval_seconds: 30.0,
+                melee_minions_per_wave: 0,
+                ranged_minions_per_wave: 0,
+            },
+            lanes: vec![],
+            guardians: GuardianConfig {
+                provoked_seconds: 3.0,
+            },
+            structures: vec![],
+        });
+        world
+    }
+
+    fn create_dead_char(world: &mut World, typ: CharType, team: Team) -> EntityId<Local> {
+        let mut state = LocalCharStateComp::new(v2(100.0, 100.0), attributes());
+        state.hp = 0;
+        state.set_state(CharState::Dead);
+        let mut statuses = Statuses::new();
+        statuses.add(StatusEnum::MountedStatus {
+            speedup: percentage(30),
+        });
+        let char_id = EntityId::new(
+            world
+                .create_entity()
+                .with(state)
+                .with(statuses)
+                .with(static_data(typ, team))
+                .build(),
+        );
+        world
+            .write_resource::<Vec<CharDied>>()
+            .push(CharDied { char_id });
+        char_id
+    }
+
+    fn run_for_seconds(world: &mut World, seconds: u64) {
+        world
+            .write_resource::<EngineTime>()
+            .tick(Duration::from_secs(seconds));
+        RespawnSystem.run_now(world);
+        world.maintain();
+    }
+
+    fn state_of(world: &World, id: EntityId<Local>) -> Option<LocalCharStateComp<Local>> {
+        world
+            .read_storage::<LocalCharStateComp<Local>>()
+            .get(id.into())
+            .cloned()
+    }
+
+    #[test]
+    fn player_respawns_at_the_spawn_point_of_its_team() {
+        let mut world = create_world(5.0);
+        let char_id = create_dead_char(&mut world, CharType::Player, Team::Left);
+
+        RespawnSystem.run_now(&world);
+        assert_eq!(world.read_resource::<OutPacketCollector>().len(), 1);
+        assert!(world
+            .read_storage::<Statuses>()
+            .get(char_id.into())
+            .unwrap()
+            .get_status(StatusEnumDiscriminants::MountedStatus)
+            .is_none());
+        run_for_seconds(&mut world, 4);
+        assert!(state_of(&world, char_id).unwrap().state().is_dead());
+
+        run_for_seconds(&mut world, 1);
+        let state = state_of(&world, char_id).unwrap();
+        assert!(state.state().is_alive());
+        assert_eq!(state.hp, 100);
+        assert_eq!(state.pos(), Vec2::new(10.0, 20.0));
+        assert!(world
+            .read_storage::<CorpseComponent>()
+            .get(char_id.into())
+            .is_none());
+    }
+
+    #[test]
+    fn player_remains_dead_if_respawning_is_disabled() {
+        let mut world = create_world(0.0);
+        let left = create_dead_char(&mut world, CharType::Player, Team::Left);
+        let right = create_dead_char(&mut world, CharType::Player, Team::Right);
+
+        RespawnSystem.run_now(&world);
+        run_for_seconds(&mut world, 60);
+
+        assert!(state_of(&world, left).unwrap().state().is_dead());
+        assert!(state_of(&world, right).unwrap().state().is_dead());
+    }
+
+    #[test]
+    fn npc_corpse_is_removed() {
+        let mut world = create_world(5.0);
+        let char_id = create_dead_char(&mut world, CharType::Minion, Team::Left);
+
+        RespawnSystem.run_now(&world);
+        run_for_seconds(&mut world, 2);
+        assert!(state_of(&world, char_id).is_some());
+
+        run_for_seconds(&mut world, 1);
+        assert!(state_of(&world, char_id).is_none());
+    }
+
+    #[test]
+    fn resurrected_corpse_is_not_removed() {
+        let mut world = create_world(5.0);
+        let char_id = create_dead_char(&mut world, CharType::Minion, Team::Left);
+        RespawnSystem.run_now(&world);
+
+        world
+            .write_storage::<LocalCharStateComp<Local>>()
+            .get_mut(char_id.into())
+            .unwrap()
+            .resurrect();
+        run_for_seconds(&mut world, 10);
+
+        assert!(state_of(&world, char_id).unwrap().state().is_alive());
+    }
+
+    #[test]
+    fn destroyed_structure_is_not_removed() {
+        let mut world = create_world(5.0);
+        let char_id = create_dead_char(&mut world, CharType::Structure, Team::Left);
+
+        RespawnSystem.run_now(&world);
+        // CharDied to the area and StructureDestroyed to everyone
+        assert_eq!(world.read_resource::<OutPacketCollector>().len(), 2);
+        run_for_seconds(&mut world, 60);
+
+        assert!(state_of(&world, char_id).unwrap().state().is_dead());
+    }
+}
@@ -0,0 +1,70 @@
+use rustarok_common::common::{GameTime, Local};
+use rustarok_common::components::char::ControllerEntityId;
+use rustarok_common::packets::SocketId;
+
+/// A player who has lost the connection during a match. The character remains
+/// in the world until the grace period expires.
+pub struct DisconnectedSession {
+    pub token: u64,
+    pub name: String,
+    pub controller_id: ControllerEntityId,
+    // the lobby still refers to the player by the old socket
+    pub socket_id: SocketId,
+    pub disconnected_at: GameTime<Local>,
+}
+
+/// The token is issued in `Init`, a reconnecting client sends it back in `Welcome`
+/// to re-attach to the controller of its character.
+pub struct Sessions {
+    disconnected: Vec<DisconnectedSession>,
+}
+
+impl Sessions {
+    pub fn new() -> Sessions {
+        Sessions {
+            disconnected: Vec::with_capacity(16),
+        }
+    }
+
+    pub fn new_token() -> u64 {
+        rand::random::<u64>()
+    }
+
+    pub fn park(&mut self, session: DisconnectedSession) {
+        self.disconnected.push(session);
+    }
+
+    pub fn reattach(&mut self, token: u64) -> Option<DisconnectedSession> {
+        let index = self.disconnected.iter().position(|it| it.token == token)?;
+        Some(self.disconnected.swap_remove(index))
+    }
+
+    pub fn disconnected(&self) -> &[DisconnectedSession] {
+        &self.disconnected
+    }
+
+    pub fn controller_ids<'a>(&'a self) -> impl Iterator<Item = ControllerEntityId> + 'a {
+        self.disconnected.iter().map(|it| it.controller_id)
+    }
+
+    pub fn drain_expired(
+        &mut self,
+        now: GameTime<Local>,
+        grace_period_seconds: f32,
+        out: &mut Vec<DisconnectedSession>,
+    ) {
+        let mut i = 0;
+        while i < self.disconnected.len() {
+            let session = &self.disconnected[i];
+            if now.elapsed_since(session.disconnected_at).as_seconds_f32() >= grace_period_seconds {
+                out.push(self.disconnected.swap_remove(i));
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    pub fn drain_all(&mut self, out: &mut Vec<DisconnectedSession>) {
+        out.append(&mut self.disconnected);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::server_config::StructureDefinition;
+use rustarok_common::common::{Local, Vec2};
+use rustarok_common::components::char::{
+    create_common_player_entity, CharOutlook, CharType, EntityId, JobId, MonsterId,
+    StaticCharDataComponent, Team,
+};
+use rustarok_common::components::controller::ControllerComponent;
+use rustarok_common::components::guardian::GuardianComponent;
+use rustarok_common::config::CommonConfigs;
+use specs::prelude::*;
+
+/// The guards attack the enemies inside this radius around the place where they have been spawned
+const GUARD_GUARDED_RADIUS: f32 = 8.0;
+
+/// The structures of the current map, they are built when the match starts
+pub struct MapStructures {
+    pub structures: Vec<StructureDefinition>,
+}
+
+impl MapStructures {
+    pub fn of_map(structures: &[StructureDefinition], map_name: &str) -> MapStructures {
+        MapStructures {
+            structures: structures
+                .iter()
+                .filter(|structure| structure.map_name == map_name)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+pub fn spawn_structures(ecs_world: &mut specs::World) {
+    let structures = ecs_world
+        .read_resource::<MapStructures>()
+        .structures
+        .clone();
+    for structure in structures {
+        create_structure(ecs_world, structure.job_id, structure.team, structure.pos());
+    }
+}
+
+/// The structures are removed with their ruins at the end of the match, every match starts with new ones
+pub fn remove_structures(ecs_world: &mut specs::World) {
+    let structure_entities: Vec<Entity> = {
+        let entities = ecs_world.entities();
+        let controller_storage = ecs_world.read_storage::<ControllerComponent>();
+        let guardian_storage = ecs_world.read_storage::<GuardianComponent>();
+        let static_char_data_storage = ecs_world.read_storage::<StaticCharDataComponent>();
+        let is_structure = |entity: Entity| {
+            static_char_data_storage
+                .get(entity)
+                .map(|it| it.typ == CharType::Structure)
+                .unwrap_or(false)
+        };
+        let controller_entities = (&entities, &controller_storage, &guardian_storage)
+            .join()
+            .filter(|(_controller_id, controller, _guardian)| {
+                controller
+                    .controlled_entity
+                    .map(|char_id| is_structure(char_id.into()))
+                    .unwrap_or(false)
+            })
+            .map(|(controller_id, _controller, _guardian)| controller_id);
+        let char_entities = (&entities, &static_char_data_storage)
+            .join()
+            .filter(|(_char_id, static_data)| static_data.typ == CharType::Structure)
+            .map(|(char_id, _static_data)| char_id);
+        controller_entities.chain(char_entities).collect()
+    };
+    for entity in structure_entities {
+        let _ = ecs_world.delete_entity(entity);
+    }
+}
+
+/// Only the structures which can attack get an AI, e.g. the towers but not the cores
+pub fn create_structure(
+    ecs_world: &mut specs::World,
+    job_id: JobId,
+    team: Team,
+    pos: Vec2,
+) -> EntityId<Local> {
+    let outlook = match job_id {
+        JobId::Core => CharOutlook::Monster(MonsterId::Barricade),
+        _ => CharOutlook::Monster(MonsterId::Dimik),
+    };
+    let char_id = EntityId::from(
+        create_common_player_entity(
+            job_id.to_string(),
+            ecs_world,
+            CharType::Structure,
+            job_id,
+            pos,
+            team,
+            outlook,
+        )
+        .build(),
+    );
+    let can_attack = ecs_world
+        .read_resource::<CommonConfigs>()
+        .job(job_id)
+        .attributes
+        .attack_range
+        .is_not_zero();
+    if can_attack {
+        ecs_world
+            .create_entity()
+            .with(ControllerComponent::new(char_id))
+            .with(GuardianComponent::new(pos, None))
+            .build();
+    }
+    char_id
+}
+
+pub fn create_guard(ecs_world: &mut specs::World, team: Team, pos: Vec2) -> EntityId<Local> {
+    let outlook = if team == Team::Left {
+        CharOutlook::Monster(MonsterId::GEFFEN_MAGE_9) // blue
+    } else {
+        CharOutlook::Monster(MonsterId::GEFFEN_MAGE_12)
+    };
+    let char_id = EntityId::from(
+        create_common_player_entity(
+            "Guard".to_owned(),
+            ecs_world,
+            CharType::Guard,
+            JobId::Guard,
+            pos,
+            team,
+            outlook,
+        )
+        .build(),
+    );
+    ecs_world
+        .create_entity()
+        .with(ControllerComponent::new(char_id))
+        .with(GuardianComponent::new(pos, Some(GUARD_GUARDED_RADIUS)))
+        .build();
+    char_id
+}